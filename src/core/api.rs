@@ -9,37 +9,33 @@ use log::{error, info};
 use r2r::{geometry_msgs::msg::PoseStamped, QosProfile};
 use std::sync::{Arc, Mutex};
 use super::{localization::PoseConfidence, perception::Snapshot, state::Mode, Core};
+use crate::ros_interface::Publisher;
 
 // API struct: Wraps Core for external and internal access
 #[derive(Clone)]
 pub struct Api {
     core: Arc<Mutex<Core>>,
-    ros_node: Arc<r2r::Node>,
-    publisher: r2r::Publisher<PoseStamped>,
+    publisher: Arc<Publisher<PoseStamped>>,
 }
 
 impl Api {
     /// Initializes API with Core and ROS 2 publisher
-    pub fn new(core: Core, ros_node: &r2r::Node) -> Result<Self, Box<dyn std::error::Error>> {
-        let publisher = ros_node.create_publisher::<PoseStamped>(
-            "/cmd_pose",
-            QosProfile::default(),
-        )?;
+    pub fn new(core: Core, ros_node: &mut r2r::Node) -> Result<Self, Box<dyn std::error::Error>> {
+        let publisher = Publisher::new(ros_node, "/cmd_pose", QosProfile::default())?;
 
         Ok(Api {
             core: Arc::new(Mutex::new(core)),
-            ros_node: Arc::new(ros_node.clone()),
-            publisher,
+            publisher: Arc::new(publisher),
         })
     }
 
     /// Starts navigation to a goal pose
     pub fn start_navigation(&self, goal: PoseStamped) -> Result<(), Box<dyn std::error::Error>> {
-        let mut core = self.core.lock().unwrap();
+        let core = self.core.lock().unwrap();
 
         if core.get_mode() == Mode::Idle || core.get_mode() == Mode::Recovering {
             self.publisher.publish(&goal)?;
-            core.state.lock().unwrap().current_mode = Mode::Navigating;
+            core.set_goal_active(true);
 
             info!(
                 "Started navigation to x={}, y={}",
@@ -54,8 +50,7 @@ impl Api {
 
     /// Stops the robot
     pub fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut core = self.core.lock().unwrap();
-        core.state.lock().unwrap().emergency_stop();
+        self.core.lock().unwrap().emergency_stop();
         info!("Robot stopped");
         Ok(())
     }
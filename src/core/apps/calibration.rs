@@ -0,0 +1,105 @@
+use r2r::sensor_msgs::msg::LaserScan;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+/// Half-width of the forward window used to find the calibration wall (radians)
+const WALL_WINDOW: f32 = 0.5;
+/// Fewest returns needed in the window for a usable wall fit
+const MIN_WALL_POINTS: usize = 5;
+
+/// Rover sensor calibration
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationData {
+    pub lidar_calibration: [f32; 6], // (x, y, z, roll, pitch, yaw) mount offset
+    pub camera_calibration: [f32; 9],
+    pub imu_calibration: [f32; 12],
+}
+
+/// Drone sensor calibration
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DroneCalibrationData {
+    pub lidar_calibration: [f32; 6], // (x, y, z, roll, pitch, yaw) mount offset
+    pub camera_calibration: [f32; 9],
+    pub imu_calibration: [f32; 12],
+    pub barometer_calibration: f32,
+}
+
+/// Indoor robot sensor calibration
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndoorCalibrationData {
+    pub lidar_calibration: [f32; 6], // (x, y, z, roll, pitch, yaw) mount offset
+    pub camera_calibration: [f32; 9],
+    pub microphone_calibration: f32,
+}
+
+/// Errors reading or writing a calibration file
+#[derive(Debug, Error)]
+pub enum CalibrationError {
+    /// Calibration file could not be read or written
+    #[error("Calibration file I/O failed: {0}")]
+    Io(std::io::Error),
+    /// Calibration file is not valid JSON for this robot
+    #[error("Calibration file is invalid: {0}")]
+    Json(serde_json::Error),
+}
+
+impl From<std::io::Error> for CalibrationError {
+    fn from(err: std::io::Error) -> Self {
+        CalibrationError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CalibrationError {
+    fn from(err: serde_json::Error) -> Self {
+        CalibrationError::Json(err)
+    }
+}
+
+/// Write calibration data to a JSON file
+pub fn save_calibration<T: Serialize>(data: &T, path: &str) -> Result<(), CalibrationError> {
+    let json = serde_json::to_string_pretty(data)?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+/// Read calibration data from a JSON file
+pub fn load_calibration<T: DeserializeOwned>(path: &str) -> Result<T, CalibrationError> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Estimate the LiDAR mount offset from a scan taken facing a flat wall square-on
+///
+/// Fits a line to the returns within `WALL_WINDOW` of straight ahead; a wall
+/// perpendicular to the robot's heading that appears tilted reveals the mount yaw.
+/// Returns (x, y, z, roll, pitch, yaw) with only yaw estimated, or all zeros when
+/// too few returns are in view.
+pub fn estimate_lidar_offset(scan: &LaserScan) -> [f32; 6] {
+    let points: Vec<(f32, f32)> = scan
+        .ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, range)| range.is_finite() && **range > scan.range_min && **range < scan.range_max)
+        .map(|(i, range)| (scan.angle_min + i as f32 * scan.angle_increment, *range))
+        .filter(|(angle, _)| angle.abs() <= WALL_WINDOW)
+        .map(|(angle, range)| (range * angle.cos(), range * angle.sin()))
+        .collect();
+    if points.len() < MIN_WALL_POINTS {
+        return [0.0; 6];
+    }
+
+    // Principal direction of the returns is the wall direction
+    let n = points.len() as f32;
+    let (mean_x, mean_y) = points.iter().fold((0.0, 0.0), |acc, p| (acc.0 + p.0 / n, acc.1 + p.1 / n));
+    let (sxx, syy, sxy) = points.iter().fold((0.0, 0.0, 0.0), |acc, p| {
+        let (dx, dy) = (p.0 - mean_x, p.1 - mean_y);
+        (acc.0 + dx * dx, acc.1 + dy * dy, acc.2 + dx * dy)
+    });
+    let wall_direction = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+
+    // A square-on wall runs along the sensor's y axis; a yawed mount tilts it the other way
+    let tilt = wall_direction - std::f32::consts::FRAC_PI_2;
+    // Line directions repeat every pi, so fold the tilt into (-pi/2, pi/2)
+    let yaw = -tilt.tan().atan();
+    [0.0, 0.0, 0.0, 0.0, 0.0, yaw]
+}
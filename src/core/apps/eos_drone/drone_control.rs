@@ -1,5 +1,15 @@
-use r2r::geometry_msgs::Twist;
+use r2r::geometry_msgs::msg::Twist;
 use std::time::{Duration, Instant};
+use crate::core::control::{Pid, PidGains};
+use super::DroneError;
+use super::drone_navigation::{DroneAirspaceAnalysis, WeatherConditions, Waypoint};
+use super::drone_limits::{Axis, DroneLimits};
+use super::drone_mixer::{FrameLayout, MotorMixer};
+
+/// Fastest commanded climb (m/s)
+const MAX_CLIMB_RATE: f32 = 0.5;
+/// Fastest commanded descent (m/s)
+const MAX_DESCENT_RATE: f32 = 0.3;
 
 /// Flight control system for drones with 3D movement
 pub struct DroneControl {
@@ -11,6 +21,11 @@ pub struct DroneControl {
     last_command_time: Instant,
     command_history: Vec<(Twist, Instant)>,
     hover_stability: f32,
+    mixer: MotorMixer,
+    limits: DroneLimits,
+    altitude_pid: Pid,
+    current_altitude: f32,
+    hover_altitude: Option<f32>,
 }
 
 pub struct DroneSafetyMonitor {
@@ -54,21 +69,44 @@ impl DroneControl {
             last_command_time: Instant::now(),
             command_history: Vec::with_capacity(100),
             hover_stability: 0.9,
+            mixer: MotorMixer::new(FrameLayout::QuadX),
+            limits: DroneLimits::default(),
+            altitude_pid: Pid::new(PidGains {
+                kp: 0.8,
+                ki: 0.1,
+                kd: 0.2,
+                integral_limit: 0.2,
+                derivative_filter: 0.6,
+            }),
+            current_altitude: 0.0,
+            hover_altitude: None,
         }
     }
     
+    /// Creates a controller with airframe-specific velocity limits, which must be valid
+    pub fn with_limits(limits: DroneLimits) -> Result<Self, DroneError> {
+        limits.validate()?;
+        Ok(DroneControl {
+            limits,
+            ..Self::new()
+        })
+    }
+    
     pub fn execute_flight(
         &mut self,
         waypoint: &Waypoint,
         airspace_analysis: &DroneAirspaceAnalysis,
         weather_conditions: &WeatherConditions,
         energy_level: f32,
-    ) -> Result<Twist, String> {
+    ) -> Result<Twist, DroneError> {
         // Check safety first
         if self.safety_monitor.emergency_land_triggered {
-            return Err("Emergency landing active".to_string());
+            return Err(DroneError::EmergencyLanding);
         }
         
+        // Following waypoints again ends any hover
+        self.hover_altitude = None;
+        
         // Calculate optimal velocity for this flight segment
         let optimal_velocity = self.calculate_optimal_velocity(waypoint, airspace_analysis, weather_conditions, energy_level);
         
@@ -85,7 +123,7 @@ impl DroneControl {
     }
     
     fn calculate_optimal_velocity(
-        &self,
+        &mut self,
         waypoint: &Waypoint,
         airspace: &DroneAirspaceAnalysis,
         weather: &WeatherConditions,
@@ -107,31 +145,35 @@ impl DroneControl {
             0.3
         };
         
-        velocity.linear.x = base_speed * energy_factor * self.weather_adaptation_factor;
-        velocity.linear.z = self.calculate_vertical_velocity(waypoint, airspace, weather);
+        velocity.linear.x = (base_speed * energy_factor * self.weather_adaptation_factor) as f64;
+        velocity.linear.z = self.calculate_vertical_velocity(waypoint, weather) as f64;
         
         // Apply wind compensation
-        velocity.linear.x += waypoint.wind_compensation.0;
-        velocity.linear.y += waypoint.wind_compensation.1;
-        velocity.linear.z += waypoint.wind_compensation.2;
+        velocity.linear.x += waypoint.wind_compensation.0 as f64;
+        velocity.linear.y += waypoint.wind_compensation.1 as f64;
+        velocity.linear.z += waypoint.wind_compensation.2 as f64;
         
         velocity
     }
     
-    fn calculate_vertical_velocity(
-        &self,
-        waypoint: &Waypoint,
-        airspace: &DroneAirspaceAnalysis,
-        weather: &WeatherConditions,
-    ) -> f32 {
-        // Calculate vertical velocity based on altitude difference
-        let current_altitude = 0.0; // Would come from localizer
-        let target_altitude = waypoint.position.pose.position.z;
-        let altitude_diff = target_altitude - current_altitude;
-        
-        // Base vertical speed with weather adjustment
-        let base_speed = if altitude_diff > 0.0 { 0.5 } else { -0.3 };
-        base_speed * (1.0 - weather.wind_speed * 0.05)
+    fn calculate_vertical_velocity(&mut self, waypoint: &Waypoint, weather: &WeatherConditions) -> f32 {
+        // Track the waypoint altitude, climbing and descending more gently in wind
+        let target_altitude = waypoint.position.pose.position.z as f32;
+        let wind_factor = 1.0 - weather.wind_speed * 0.05;
+        self.hold_altitude(target_altitude) * wind_factor
+    }
+    
+    /// Vertical speed from the altitude PID, within the climb and descent rates
+    fn hold_altitude(&mut self, target_altitude: f32) -> f32 {
+        let dt = self.last_command_time.elapsed().as_secs_f32();
+        self.altitude_pid
+            .update(target_altitude - self.current_altitude, dt)
+            .clamp(-MAX_DESCENT_RATE, MAX_CLIMB_RATE)
+    }
+    
+    /// Update the altitude estimate (meters) the altitude hold works from
+    pub fn set_altitude(&mut self, altitude: f32) {
+        self.current_altitude = altitude;
     }
     
     fn smooth_acceleration(&mut self, target_velocity: Twist) -> Twist {
@@ -140,26 +182,27 @@ impl DroneControl {
         
         // Calculate acceleration needed for each axis
         let accel_x = self.calculate_axis_acceleration(
-            target_velocity.linear.x,
-            self.current_velocity.linear.x,
+            target_velocity.linear.x as f32,
+            self.current_velocity.linear.x as f32,
             time_since_last
         );
         
         let accel_y = self.calculate_axis_acceleration(
-            target_velocity.linear.y,
-            self.current_velocity.linear.y,
+            target_velocity.linear.y as f32,
+            self.current_velocity.linear.y as f32,
             time_since_last
         );
         
         let accel_z = self.calculate_axis_acceleration(
-            target_velocity.linear.z,
-            self.current_velocity.linear.z,
+            target_velocity.linear.z as f32,
+            self.current_velocity.linear.z as f32,
             time_since_last
         );
         
-        command.linear.x = (self.current_velocity.linear.x + accel_x).max(-5.0).min(5.0);
-        command.linear.y = (self.current_velocity.linear.y + accel_y).max(-5.0).min(5.0);
-        command.linear.z = (self.current_velocity.linear.z + accel_z).max(-2.0).min(2.0);
+        command.linear.x = self.current_velocity.linear.x + accel_x as f64;
+        command.linear.y = self.current_velocity.linear.y + accel_y as f64;
+        command.linear.z = self.current_velocity.linear.z + accel_z as f64;
+        let command = self.limits.clamp(&command);
         
         // Update current velocity
         self.current_velocity = command.clone();
@@ -195,8 +238,7 @@ impl DroneControl {
         }
         
         // Check altitude limit
-        let current_altitude = 0.0; // Would come from localizer
-        if current_altitude > self.safety_monitor.safety_thresholds.max_altitude {
+        if self.current_altitude > self.safety_monitor.safety_thresholds.max_altitude {
             self.safety_monitor.emergency_land_triggered = true;
         }
     }
@@ -231,14 +273,43 @@ impl DroneControl {
         self.weather_adaptation_factor = factor.max(0.1).min(2.0);
     }
     
+    /// Hold the altitude the hover began at
     pub fn maintain_hover(&mut self) -> Twist {
-        // Generate hover command with stability adjustments
+        if self.hover_altitude.is_none() {
+            // New setpoint; drop the integral built up tracking waypoints
+            self.altitude_pid.reset();
+        }
+        let target = *self.hover_altitude.get_or_insert(self.current_altitude);
+        
+        // Less stable airframes get gentler corrections
         let mut hover_cmd = Twist::default();
-        hover_cmd.linear.z = 0.1 * (1.0 - self.hover_stability); // Small adjustment
+        hover_cmd.linear.z = (self.hold_altitude(target) * self.hover_stability) as f64;
+        self.last_command_time = Instant::now();
         
         hover_cmd
     }
     
+    /// Convert a velocity command into per-motor speeds for the configured frame
+    pub fn mix(&self, cmd: &Twist, attitude: (f32, f32, f32)) -> [f32; 4] {
+        self.mixer.mix(cmd, attitude)
+    }
+    
+    pub fn set_frame_layout(&mut self, layout: FrameLayout) {
+        self.mixer.layout = layout;
+    }
+    
+    /// Maximum speed the airframe is configured for along an axis
+    pub fn max_speed_for(&self, axis: Axis) -> f32 {
+        self.limits.max_speed_for(axis)
+    }
+    
+    /// Replaces the velocity limits; invalid ones are rejected and the current ones kept
+    pub fn set_limits(&mut self, limits: DroneLimits) -> Result<(), DroneError> {
+        limits.validate()?;
+        self.limits = limits;
+        Ok(())
+    }
+    
     pub fn get_command_history(&self) -> &[(Twist, Instant)] {
         &self.command_history
     }
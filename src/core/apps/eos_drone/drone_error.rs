@@ -0,0 +1,62 @@
+use thiserror::Error;
+
+use crate::core::apps::calibration::CalibrationError;
+
+/// Errors raised by the drone navigation and control modules
+#[derive(Debug, Error)]
+pub enum DroneError {
+    /// Airspace rule file could not be read
+    #[error("Failed to read airspace rules: {0}")]
+    ReadAirspaceRules(std::io::Error),
+    /// Airspace rule file is not valid JSON
+    #[error("Failed to parse airspace rules: {0}")]
+    ParseAirspaceRules(serde_json::Error),
+    /// Goal lies in restricted airspace
+    #[error("Restricted airspace: {0}")]
+    RestrictedAirspace(String),
+    /// Planned flight path exceeds the energy budget
+    #[error("Insufficient energy for flight path: needs {required:.2}, budget {budget:.2}")]
+    InsufficientEnergy {
+        /// Energy the path needs
+        required: f32,
+        /// Energy available for the path
+        budget: f32,
+    },
+    /// Updated weather pushes the flight path over the energy budget
+    #[error("Weather changes make path infeasible: needs {required:.2}, budget {budget:.2}")]
+    WeatherInfeasible {
+        /// Energy the path needs
+        required: f32,
+        /// Energy available for the path
+        budget: f32,
+    },
+    /// No path has been planned yet
+    #[error("No current flight path to adjust")]
+    NoActivePath,
+    /// Emergency landing is in progress
+    #[error("Emergency landing active")]
+    EmergencyLanding,
+    /// Velocity limit file could not be read
+    #[error("Failed to read drone limits: {0}")]
+    ReadLimits(std::io::Error),
+    /// Velocity limit file is not valid JSON
+    #[error("Failed to parse drone limits: {0}")]
+    ParseLimits(serde_json::Error),
+    /// A velocity limit is negative or not finite
+    #[error("Invalid drone limit {name}: {value}")]
+    InvalidLimit {
+        /// Name of the limit
+        name: &'static str,
+        /// Rejected value
+        value: f32,
+    },
+    /// Calibration file could not be saved or loaded
+    #[error("Calibration error: {0}")]
+    Calibration(CalibrationError),
+}
+
+impl From<CalibrationError> for DroneError {
+    fn from(err: CalibrationError) -> Self {
+        DroneError::Calibration(err)
+    }
+}
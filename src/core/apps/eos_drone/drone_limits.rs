@@ -0,0 +1,83 @@
+use r2r::geometry_msgs::msg::Twist;
+
+use super::DroneError;
+
+/// Direction a speed limit applies to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    /// Forward/backward and left/right (each limited separately)
+    Horizontal,
+    /// Upward
+    Climb,
+    /// Downward
+    Descent,
+}
+
+/// Velocity limits of an airframe
+///
+/// Climb and descent are separate because descending into your own downwash
+/// (vortex ring state) calls for a more conservative rate on many frames. Every
+/// limit must be finite and non-negative; `load` and `DroneControl` check this.
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct DroneLimits {
+    pub max_horizontal_speed: f32, // m/s per horizontal axis
+    pub max_climb_rate: f32,       // m/s
+    pub max_descent_rate: f32,     // m/s, positive
+}
+
+impl Default for DroneLimits {
+    fn default() -> Self {
+        DroneLimits {
+            max_horizontal_speed: 5.0,
+            max_climb_rate: 2.0,
+            max_descent_rate: 2.0,
+        }
+    }
+}
+
+impl DroneLimits {
+    /// Loads limits from a JSON file, rejecting negative or non-finite ones
+    pub fn load(path: &str) -> Result<Self, DroneError> {
+        let data = std::fs::read_to_string(path).map_err(DroneError::ReadLimits)?;
+        let limits: DroneLimits = serde_json::from_str(&data).map_err(DroneError::ParseLimits)?;
+        limits.validate()?;
+        Ok(limits)
+    }
+
+    /// Checks every limit is finite and non-negative, as `clamp` needs
+    pub fn validate(&self) -> Result<(), DroneError> {
+        let limits = [
+            ("max_horizontal_speed", self.max_horizontal_speed),
+            ("max_climb_rate", self.max_climb_rate),
+            ("max_descent_rate", self.max_descent_rate),
+        ];
+        for (name, value) in limits {
+            if !value.is_finite() || value < 0.0 {
+                return Err(DroneError::InvalidLimit { name, value });
+            }
+        }
+        Ok(())
+    }
+
+    /// Maximum speed allowed along an axis
+    pub fn max_speed_for(&self, axis: Axis) -> f32 {
+        match axis {
+            Axis::Horizontal => self.max_horizontal_speed,
+            Axis::Climb => self.max_climb_rate,
+            Axis::Descent => self.max_descent_rate,
+        }
+    }
+
+    /// Clamp a velocity command's linear components to the limits
+    pub fn clamp(&self, cmd: &Twist) -> Twist {
+        let horizontal = self.max_speed_for(Axis::Horizontal) as f64;
+        let mut clamped = cmd.clone();
+        clamped.linear.x = cmd.linear.x.clamp(-horizontal, horizontal);
+        clamped.linear.y = cmd.linear.y.clamp(-horizontal, horizontal);
+        clamped.linear.z = cmd.linear.z.clamp(
+            -(self.max_speed_for(Axis::Descent) as f64),
+            self.max_speed_for(Axis::Climb) as f64,
+        );
+        clamped
+    }
+}
@@ -0,0 +1,72 @@
+use r2r::geometry_msgs::msg::Twist;
+
+/// Motor arrangement of a quadrotor frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameLayout {
+    /// Motors on the diagonals: [front-left, front-right, rear-right, rear-left]
+    QuadX,
+    /// Motors on the axes: [front, right, rear, left]
+    QuadPlus,
+}
+
+/// Maps a velocity command and current attitude to normalized motor speeds
+///
+/// Conventions follow REP 103 (x forward, y left, z up): positive pitch is nose
+/// down and positive yaw is counter-clockwise. Front-left/rear-right (X) and
+/// front/rear (+) propellers spin clockwise.
+#[derive(Clone, Debug)]
+pub struct MotorMixer {
+    pub layout: FrameLayout,
+    pub hover_thrust: f32,   // Normalized thrust holding altitude
+    pub vertical_gain: f32,  // Thrust per m/s of climb
+    pub tilt_per_speed: f32, // Target tilt (rad) per m/s of lateral speed
+    pub attitude_gain: f32,  // Motor differential per rad of attitude error
+    pub yaw_gain: f32,       // Motor differential per rad/s of yaw rate
+}
+
+impl MotorMixer {
+    /// Creates a mixer with default gains for the given layout
+    pub fn new(layout: FrameLayout) -> Self {
+        MotorMixer {
+            layout,
+            hover_thrust: 0.5,
+            vertical_gain: 0.3,
+            tilt_per_speed: 0.1,
+            attitude_gain: 0.5,
+            yaw_gain: 0.2,
+        }
+    }
+
+    /// Mix thrust and roll/pitch/yaw corrections into four motor speeds in [0, 1]
+    pub fn mix(&self, cmd: &Twist, attitude: (f32, f32, f32)) -> [f32; 4] {
+        let (roll, pitch, _yaw) = attitude;
+
+        // Tilt toward the commanded lateral velocity
+        let target_pitch = self.tilt_per_speed * cmd.linear.x as f32;
+        let target_roll = -self.tilt_per_speed * cmd.linear.y as f32;
+        let roll_cmd = self.attitude_gain * (target_roll - roll);
+        let pitch_cmd = self.attitude_gain * (target_pitch - pitch);
+        let yaw_cmd = self.yaw_gain * cmd.angular.z as f32;
+
+        // Compensate lift lost to the current tilt
+        let tilt = (roll.cos() * pitch.cos()).max(0.5);
+        let thrust = (self.hover_thrust + self.vertical_gain * cmd.linear.z as f32) / tilt;
+
+        let motors = match self.layout {
+            FrameLayout::QuadX => [
+                thrust + roll_cmd - pitch_cmd + yaw_cmd, // Front-left (CW)
+                thrust - roll_cmd - pitch_cmd - yaw_cmd, // Front-right (CCW)
+                thrust - roll_cmd + pitch_cmd + yaw_cmd, // Rear-right (CW)
+                thrust + roll_cmd + pitch_cmd - yaw_cmd, // Rear-left (CCW)
+            ],
+            FrameLayout::QuadPlus => [
+                thrust - pitch_cmd + yaw_cmd, // Front (CW)
+                thrust - roll_cmd - yaw_cmd,  // Right (CCW)
+                thrust + pitch_cmd + yaw_cmd, // Rear (CW)
+                thrust + roll_cmd - yaw_cmd,  // Left (CCW)
+            ],
+        };
+
+        motors.map(|m| m.clamp(0.0, 1.0))
+    }
+}
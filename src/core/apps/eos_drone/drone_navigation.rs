@@ -1,17 +1,20 @@
-use crate::core::{Localizer, SpatialMemory};
-use r2r::geometry_msgs::{PoseStamped, Twist};
+use crate::core::{Localization, Memory};
+use r2r::geometry_msgs::msg::{PoseStamped, Twist};
 use std::collections::HashMap;
+use super::DroneError;
+use crate::core::apps::pose_stamped;
 
 /// Aerial navigation for drones with 3D path planning
 pub struct DroneNavigation {
-    localizer: Localizer,
-    spatial_memory: SpatialMemory,
+    localizer: Localization,
+    spatial_memory: Memory,
     airspace_rules: HashMap<String, AirspaceRule>,
     current_flight_path: Option<FlightPath>,
     weather_adaptation_factor: f32,
     wind_compensation: (f32, f32, f32), // (x, y, z) wind compensation
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct AirspaceRule {
     pub name: String,
     pub max_altitude: f32,
@@ -20,6 +23,7 @@ pub struct AirspaceRule {
     pub required_clearance: bool,
 }
 
+#[derive(Clone)]
 pub struct FlightPath {
     pub waypoints: Vec<Waypoint>,
     pub total_energy_estimate: f32,
@@ -27,6 +31,7 @@ pub struct FlightPath {
     pub altitude_profile: Vec<f32>,
 }
 
+#[derive(Clone)]
 pub struct Waypoint {
     pub position: PoseStamped,
     pub recommended_speed: f32,
@@ -35,7 +40,7 @@ pub struct Waypoint {
 }
 
 impl DroneNavigation {
-    pub fn new(localizer: Localizer, spatial_memory: SpatialMemory) -> Self {
+    pub fn new(localizer: Localization, spatial_memory: Memory) -> Self {
         DroneNavigation {
             localizer,
             spatial_memory,
@@ -46,12 +51,12 @@ impl DroneNavigation {
         }
     }
     
-    pub fn load_airspace_rules(&mut self, path: &str) -> Result<(), String> {
+    pub fn load_airspace_rules(&mut self, path: &str) -> Result<(), DroneError> {
         let data = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read airspace rules: {}", e))?;
+            .map_err(DroneError::ReadAirspaceRules)?;
             
         let rules: Vec<AirspaceRule> = serde_json::from_str(&data)
-            .map_err(|e| format!("Failed to parse airspace rules: {}", e))?;
+            .map_err(DroneError::ParseAirspaceRules)?;
             
         for rule in rules {
             self.airspace_rules.insert(rule.name.clone(), rule);
@@ -66,8 +71,8 @@ impl DroneNavigation {
         airspace_analysis: &DroneAirspaceAnalysis,
         weather_conditions: &WeatherConditions,
         energy_level: f32,
-    ) -> Result<FlightPath, String> {
-        let current_pose = self.localizer.get_current_pose();
+    ) -> Result<FlightPath, DroneError> {
+        let current_pose = pose_stamped(&self.localizer.get_current_pose().pose);
         let mut path = FlightPath {
             waypoints: Vec::new(),
             total_energy_estimate: 0.0,
@@ -78,19 +83,20 @@ impl DroneNavigation {
         // Check airspace restrictions
         if let Some(rule) = self.check_airspace_restrictions(&goal, airspace_analysis) {
             if rule.restricted {
-                return Err(format!("Restricted airspace: {}", rule.name));
+                return Err(DroneError::RestrictedAirspace(rule.name.clone()));
             }
         }
         
         // Plan 3D path considering weather and airspace
-        let waypoints = self.generate_waypoints(current_pose, &goal, weather_conditions, airspace_analysis);
+        let waypoints = self.generate_waypoints(current_pose, goal.clone(), weather_conditions, airspace_analysis);
         
         for wp in waypoints {
             let energy = self.calculate_energy_cost(&wp, weather_conditions);
+            let recommended_speed = self.calculate_recommended_speed(&wp, weather_conditions);
             
             path.waypoints.push(Waypoint {
                 position: wp,
-                recommended_speed: self.calculate_recommended_speed(&wp, weather_conditions),
+                recommended_speed,
                 energy_estimate: energy,
                 wind_compensation: self.calculate_wind_compensation(weather_conditions),
             });
@@ -100,7 +106,10 @@ impl DroneNavigation {
         
         // Check energy constraints
         if path.total_energy_estimate > energy_level * 0.7 {
-            return Err("Insufficient energy for flight path".to_string());
+            return Err(DroneError::InsufficientEnergy {
+                required: path.total_energy_estimate,
+                budget: energy_level * 0.7,
+            });
         }
         
         self.current_flight_path = Some(path.clone());
@@ -114,8 +123,8 @@ impl DroneNavigation {
     ) -> Option<&AirspaceRule> {
         // Check if position is in restricted airspace
         for rule in self.airspace_rules.values() {
-            if position.pose.position.z > rule.min_altitude && 
-               position.pose.position.z < rule.max_altitude {
+            let altitude = position.pose.position.z as f32;
+            if altitude > rule.min_altitude && altitude < rule.max_altitude {
                 return Some(rule);
             }
         }
@@ -166,26 +175,30 @@ impl DroneNavigation {
         &mut self,
         new_weather: &WeatherConditions,
         energy_level: f32,
-    ) -> Result<(), String> {
-        if let Some(path) = &mut self.current_flight_path {
+    ) -> Result<(), DroneError> {
+        if let Some(mut path) = self.current_flight_path.take() {
             // Recalculate energy costs and speeds
             for waypoint in &mut path.waypoints {
                 waypoint.energy_estimate = self.calculate_energy_cost(&waypoint.position, new_weather);
                 waypoint.recommended_speed = self.calculate_recommended_speed(&waypoint.position, new_weather);
                 waypoint.wind_compensation = self.calculate_wind_compensation(new_weather);
             }
+            let path = self.current_flight_path.insert(path);
             
             // Recalculate total energy
             path.total_energy_estimate = path.waypoints.iter().map(|w| w.energy_estimate).sum();
             
             // Check if still feasible
             if path.total_energy_estimate > energy_level * 0.7 {
-                return Err("Weather changes make path infeasible".to_string());
+                return Err(DroneError::WeatherInfeasible {
+                    required: path.total_energy_estimate,
+                    budget: energy_level * 0.7,
+                });
             }
             
             Ok(())
         } else {
-            Err("No current flight path to adjust".to_string())
+            Err(DroneError::NoActivePath)
         }
     }
     
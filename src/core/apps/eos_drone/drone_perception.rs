@@ -1,5 +1,13 @@
-use r2r::{sensor_msgs::LaserScan, PointCloud2};
+use r2r::sensor_msgs::msg::{LaserScan, PointCloud2};
+use r2r::sensor_msgs::msg::Imu;
 use std::collections::VecDeque;
+use crate::ros_interface::{ImuAxisRemap, ImuSample};
+use crate::core::apps::calibration::{estimate_lidar_offset, load_calibration, save_calibration};
+use super::DroneError;
+use super::drone_navigation::DroneAirspaceAnalysis;
+use crate::core::apps::SensorFusionAlgorithm;
+
+pub use crate::core::apps::calibration::DroneCalibrationData;
 
 /// Aerial perception for drones with 3D environment analysis
 pub struct DronePerception {
@@ -9,6 +17,7 @@ pub struct DronePerception {
     motion_history: VecDeque<Vec<(f32, f32, f32)>>,
     previous_scan: Option<LaserScan>,
     calibration_data: DroneCalibrationData,
+    imu_remap: ImuAxisRemap,
 }
 
 #[derive(Clone)]
@@ -20,13 +29,6 @@ pub struct AirspaceCell {
     pub confidence: f32,
 }
 
-pub struct DroneCalibrationData {
-    pub lidar_calibration: [f32; 6],
-    pub camera_calibration: [f32; 9],
-    pub imu_calibration: [f32; 12],
-    pub barometer_calibration: f32,
-}
-
 impl DronePerception {
     pub fn new() -> Self {
         DronePerception {
@@ -35,19 +37,21 @@ impl DronePerception {
             sensor_fusion_algorithm: SensorFusionAlgorithm::Bayesian,
             motion_history: VecDeque::with_capacity(10),
             previous_scan: None,
-            calibration_data: DroneCalibrationData {
-                lidar_calibration: [0.0; 6],
-                camera_calibration: [0.0; 9],
-                imu_calibration: [0.0; 12],
-                barometer_calibration: 0.0,
-            },
+            calibration_data: DroneCalibrationData::default(),
+            imu_remap: ImuAxisRemap::identity(),
         }
     }
     
-    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, imu_data: Option<&[f32]>, barometer_data: Option<f32>) -> Result<(), String> {
+    /// Set how the IMU is mounted relative to the airframe
+    pub fn set_imu_remap(&mut self, remap: ImuAxisRemap) {
+        self.imu_remap = remap;
+    }
+    
+    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, imu_data: Option<&Imu>, barometer_data: Option<f32>) -> Result<(), DroneError> {
         // Drone-specific sensor calibration
         if let Some(imu) = imu_data {
-            self.calibration_data.imu_calibration = self.calibrate_drone_imu(imu);
+            let sample = self.imu_remap.parse(imu);
+            self.calibration_data.imu_calibration = self.calibrate_drone_imu(&sample);
         }
         
         if let Some(baro) = barometer_data {
@@ -58,12 +62,28 @@ impl DronePerception {
         
         Ok(())
     }
+
+    /// Save the current calibration as JSON so it survives restarts
+    pub fn save_calibration(&self, path: &str) -> Result<(), DroneError> {
+        Ok(save_calibration(&self.calibration_data, path)?)
+    }
+    
+    /// Replace the current calibration with one saved by `save_calibration`
+    pub fn load_calibration(&mut self, path: &str) -> Result<(), DroneError> {
+        self.calibration_data = load_calibration::<DroneCalibrationData>(path)?;
+        Ok(())
+    }
+    
+    /// Current sensor calibration
+    pub fn calibration(&self) -> &DroneCalibrationData {
+        &self.calibration_data
+    }
     
     pub fn analyze_airspace(
         &mut self,
         lidar_data: &LaserScan,
         camera_data: Option<&PointCloud2>,
-        imu_data: Option<&[f32]>,
+        imu_data: Option<&Imu>,
         barometer_data: Option<f32>,
     ) -> DroneAirspaceAnalysis {
         let mut analysis = DroneAirspaceAnalysis {
@@ -82,7 +102,8 @@ impl DronePerception {
         
         // Use IMU for turbulence assessment
         if let Some(imu) = imu_data {
-            self.assess_turbulence(imu, &mut analysis);
+            let sample = self.imu_remap.parse(imu);
+            self.assess_turbulence(&sample, &mut analysis);
         }
         
         // Use barometer for air density assessment
@@ -127,12 +148,10 @@ impl DronePerception {
         analysis.obstacle_density *= 1.1; // Camera typically detects more obstacles
     }
     
-    fn assess_turbulence(&mut self, imu_data: &[f32], analysis: &mut DroneAirspaceAnalysis) {
+    fn assess_turbulence(&mut self, imu_data: &ImuSample, analysis: &mut DroneAirspaceAnalysis) {
         // Use IMU data to assess turbulence level
-        if imu_data.len() >= 6 {
-            let angular_velocity = (imu_data[3].powi(2) + imu_data[4].powi(2) + imu_data[5].powi(2)).sqrt();
-            analysis.turbulence_level = angular_velocity.min(1.0);
-        }
+        let angular_velocity = imu_data.angular_velocity.norm() as f32;
+        analysis.turbulence_level = angular_velocity.min(1.0);
     }
     
     fn assess_air_density(&mut self, barometer_data: f32, analysis: &mut DroneAirspaceAnalysis) {
@@ -169,7 +188,7 @@ impl DronePerception {
         aircraft
     }
     
-    fn calibrate_drone_imu(&self, imu_data: &[f32]) -> [f32; 12] {
+    fn calibrate_drone_imu(&self, imu_data: &ImuSample) -> [f32; 12] {
         // Drone-specific IMU calibration
         [0.0; 12] // Placeholder
     }
@@ -180,7 +199,7 @@ impl DronePerception {
     }
     
     fn calibrate_lidar(&self, lidar_data: &LaserScan) -> [f32; 6] {
-        // LiDAR calibration
-        [0.0; 6] // Placeholder
+        // LiDAR mount offset from a wall in front of the drone
+        estimate_lidar_offset(lidar_data)
     }
 }
\ No newline at end of file
@@ -0,0 +1,10 @@
+pub mod drone_control;
+pub mod drone_error;
+pub mod drone_limits;
+pub mod drone_mixer;
+pub mod drone_navigation;
+pub mod drone_perception;
+
+pub use drone_error::DroneError;
+pub use drone_limits::{Axis, DroneLimits};
+pub use drone_mixer::{FrameLayout, MotorMixer};
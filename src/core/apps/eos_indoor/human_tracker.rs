@@ -0,0 +1,144 @@
+/// A human followed across frames, with an estimated planar velocity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrackedHuman {
+    pub id: u32,
+    pub position: (f32, f32), // meters
+    pub velocity: (f32, f32), // m/s
+    missed_frames: u32,
+}
+
+impl TrackedHuman {
+    /// Constant-velocity prediction of where this human will be `dt` seconds ahead
+    pub fn predict(&self, dt: f32) -> (f32, f32) {
+        (
+            self.position.0 + self.velocity.0 * dt,
+            self.position.1 + self.velocity.1 * dt,
+        )
+    }
+
+    /// Predicted positions every `step` seconds up to `horizon`
+    pub fn predicted_positions(&self, horizon: f32, step: f32) -> Vec<(f32, f32)> {
+        let steps = (horizon / step.max(1e-3)).floor() as usize;
+        (1..=steps).map(|k| self.predict(k as f32 * step)).collect()
+    }
+}
+
+/// Nearest-neighbor tracker that turns per-frame human detections into tracks
+pub struct HumanTracker {
+    tracks: Vec<TrackedHuman>,
+    next_id: u32,
+    pub max_association_distance: f32, // meters a human may move between frames
+    pub max_missed_frames: u32,         // frames a track survives without a detection
+    pub velocity_smoothing: f32,        // weight of the newest velocity sample (0.0 - 1.0)
+}
+
+impl Default for HumanTracker {
+    fn default() -> Self {
+        HumanTracker {
+            tracks: Vec::new(),
+            next_id: 0,
+            max_association_distance: 0.8,
+            max_missed_frames: 3,
+            velocity_smoothing: 0.5,
+        }
+    }
+}
+
+impl HumanTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Associate a frame of detections with existing tracks, `dt` seconds after the last frame
+    ///
+    /// Returns the track for each detection, in detection order.
+    pub fn update(&mut self, detections: &[(f32, f32)], dt: f32) -> Vec<TrackedHuman> {
+        // Greedy global nearest neighbor: closest detection/track pairs are matched first
+        let mut pairs = Vec::new();
+        for (d, detection) in detections.iter().enumerate() {
+            for (t, track) in self.tracks.iter().enumerate() {
+                let distance = Self::distance(track.position, *detection);
+                if distance <= self.max_association_distance {
+                    pairs.push((distance, d, t));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut detection_track = vec![None; detections.len()];
+        let mut track_matched = vec![false; self.tracks.len()];
+        for (_, d, t) in pairs {
+            if detection_track[d].is_none() && !track_matched[t] {
+                detection_track[d] = Some(t);
+                track_matched[t] = true;
+            }
+        }
+
+        // Matched tracks move to their detection and blend in the observed velocity
+        for (d, t) in detection_track.iter().enumerate() {
+            if let Some(t) = *t {
+                let track = &mut self.tracks[t];
+                let position = detections[d];
+                if dt > 0.0 {
+                    let observed = (
+                        (position.0 - track.position.0) / dt,
+                        (position.1 - track.position.1) / dt,
+                    );
+                    let alpha = self.velocity_smoothing.clamp(0.0, 1.0);
+                    track.velocity = (
+                        alpha * observed.0 + (1.0 - alpha) * track.velocity.0,
+                        alpha * observed.1 + (1.0 - alpha) * track.velocity.1,
+                    );
+                }
+                track.position = position;
+                track.missed_frames = 0;
+            }
+        }
+
+        // Unmatched tracks coast on their velocity until they have been missed too long
+        for (t, track) in self.tracks.iter_mut().enumerate() {
+            if !track_matched[t] {
+                track.position = track.predict(dt);
+                track.missed_frames += 1;
+            }
+        }
+
+        // Unmatched detections start new tracks at rest
+        for (d, t) in detection_track.iter_mut().enumerate() {
+            if t.is_none() {
+                self.tracks.push(TrackedHuman {
+                    id: self.next_id,
+                    position: detections[d],
+                    velocity: (0.0, 0.0),
+                    missed_frames: 0,
+                });
+                self.next_id += 1;
+                *t = Some(self.tracks.len() - 1);
+            }
+        }
+
+        let assigned: Vec<TrackedHuman> = detection_track
+            .iter()
+            .map(|t| self.tracks[t.expect("every detection has a track")])
+            .collect();
+
+        let max_missed = self.max_missed_frames;
+        self.tracks.retain(|track| track.missed_frames <= max_missed);
+
+        assigned
+    }
+
+    /// Currently tracked humans
+    pub fn tracks(&self) -> &[TrackedHuman] {
+        &self.tracks
+    }
+
+    /// Drop all tracks
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+    }
+
+    fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+    }
+}
@@ -1,5 +1,7 @@
-use r2r::geometry_msgs::Twist;
+use r2r::geometry_msgs::msg::Twist;
 use std::time::{Duration, Instant};
+use super::IndoorError;
+use super::indoor_navigation::{HumanPresenceAnalysis, IndoorPathSegment};
 
 /// Indoor control system with social awareness and human interaction
 pub struct IndoorControl {
@@ -65,10 +67,10 @@ impl IndoorControl {
         path_segment: &IndoorPathSegment,
         human_analysis: &HumanPresenceAnalysis,
         energy_level: f32,
-    ) -> Result<Twist, String> {
+    ) -> Result<Twist, IndoorError> {
         // Check safety first
         if self.safety_monitor.emergency_stop_triggered {
-            return Err("Emergency stop active".to_string());
+            return Err(IndoorError::EmergencyStop);
         }
         
         // Calculate optimal velocity for this segment
@@ -114,21 +116,21 @@ impl IndoorControl {
             ApproachBehavior::Assertive => 1.5,
         };
         
-        velocity.linear.x = base_speed * energy_factor * behavior_factor * self.social_awareness_factor;
+        velocity.linear.x = (base_speed * energy_factor * behavior_factor * self.social_awareness_factor) as f64;
         
         velocity
     }
     
     fn smooth_acceleration(&mut self, target_velocity: Twist) -> Twist {
-        let time_since_last = self.last_command_time.elapsed().as_secs_f32();
+        let time_since_last = self.last_command_time.elapsed().as_secs_f64();
         let mut command = self.current_velocity.clone();
         
         // Calculate acceleration needed
         let speed_diff = target_velocity.linear.x - self.current_velocity.linear.x;
         let acceleration = if speed_diff > 0.0 {
-            speed_diff.min(self.max_acceleration * time_since_last)
+            speed_diff.min(self.max_acceleration as f64 * time_since_last)
         } else {
-            speed_diff.max(-self.max_deceleration * time_since_last)
+            speed_diff.max(-self.max_deceleration as f64 * time_since_last)
         };
         
         command.linear.x = (self.current_velocity.linear.x + acceleration).max(0.0).min(1.0);
@@ -161,7 +163,7 @@ impl IndoorControl {
         }
         
         // Check acceleration limits
-        if command.linear.x.abs() > self.safety_monitor.safety_thresholds.max_acceleration {
+        if command.linear.x.abs() > self.safety_monitor.safety_thresholds.max_acceleration as f64 {
             self.safety_monitor.emergency_stop_triggered = true;
         }
     }
@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+use crate::core::apps::calibration::CalibrationError;
+
+/// Errors raised by the indoor navigation and control modules
+#[derive(Debug, Error)]
+pub enum IndoorError {
+    /// Room map file could not be read
+    #[error("Failed to read room maps: {0}")]
+    ReadRoomMaps(std::io::Error),
+    /// Room map file is not valid JSON
+    #[error("Failed to parse room maps: {0}")]
+    ParseRoomMaps(serde_json::Error),
+    /// Goal lies in an occupied private zone
+    #[error("Goal is in a restricted area")]
+    RestrictedGoal,
+    /// Planned path exceeds the energy budget
+    #[error("Insufficient energy for indoor path: needs {required:.2}, budget {budget:.2}")]
+    InsufficientEnergy {
+        /// Energy the path needs
+        required: f32,
+        /// Energy available for the path
+        budget: f32,
+    },
+    /// Path passes too close to people
+    #[error("Path is not socially acceptable (acceptability {acceptability:.2})")]
+    SociallyUnacceptable {
+        /// Product of per-segment acceptability
+        acceptability: f32,
+    },
+    /// No path has been planned yet
+    #[error("No current path to adjust")]
+    NoActivePath,
+    /// Emergency stop is latched
+    #[error("Emergency stop active")]
+    EmergencyStop,
+    /// Calibration file could not be saved or loaded
+    #[error("Calibration error: {0}")]
+    Calibration(CalibrationError),
+}
+
+impl From<CalibrationError> for IndoorError {
+    fn from(err: CalibrationError) -> Self {
+        IndoorError::Calibration(err)
+    }
+}
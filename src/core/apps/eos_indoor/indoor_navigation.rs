@@ -1,17 +1,20 @@
-use crate::core::{Localizer, SpatialMemory};
-use r2r::geometry_msgs::{PoseStamped, Twist};
+use crate::core::{Localization, Memory};
+use r2r::geometry_msgs::msg::{PoseStamped, Twist};
 use std::collections::HashMap;
+use super::IndoorError;
+use crate::core::apps::pose_stamped;
 
 /// Indoor navigation with social awareness and human interaction
 pub struct IndoorNavigation {
-    localizer: Localizer,
-    spatial_memory: SpatialMemory,
+    localizer: Localization,
+    spatial_memory: Memory,
     room_maps: HashMap<String, RoomMap>,
     current_path: Option<IndoorPath>,
     social_awareness_factor: f32,
     human_interaction_mode: HumanInteractionMode,
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct RoomMap {
     pub name: String,
     pub dimensions: (f32, f32, f32), // width, depth, height
@@ -20,12 +23,14 @@ pub struct RoomMap {
     pub social_zones: Vec<SocialZone>,
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct Furniture {
     pub position: (f32, f32),
     pub dimensions: (f32, f32, f32),
     pub type_name: String,
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct SocialZone {
     pub position: (f32, f32),
     pub radius: f32,
@@ -33,6 +38,7 @@ pub struct SocialZone {
     pub privacy_level: f32,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
 pub enum SocialZoneType {
     Conversation,
     Work,
@@ -40,6 +46,7 @@ pub enum SocialZoneType {
     Private,
 }
 
+#[derive(Clone)]
 pub struct IndoorPath {
     pub segments: Vec<IndoorPathSegment>,
     pub total_energy_estimate: f32,
@@ -47,6 +54,7 @@ pub struct IndoorPath {
     pub privacy_respect: f32,
 }
 
+#[derive(Clone)]
 pub struct IndoorPathSegment {
     pub start: PoseStamped,
     pub end: PoseStamped,
@@ -64,7 +72,7 @@ pub enum HumanInteractionMode {
 }
 
 impl IndoorNavigation {
-    pub fn new(localizer: Localizer, spatial_memory: SpatialMemory) -> Self {
+    pub fn new(localizer: Localization, spatial_memory: Memory) -> Self {
         IndoorNavigation {
             localizer,
             spatial_memory,
@@ -75,12 +83,12 @@ impl IndoorNavigation {
         }
     }
     
-    pub fn load_room_maps(&mut self, path: &str) -> Result<(), String> {
+    pub fn load_room_maps(&mut self, path: &str) -> Result<(), IndoorError> {
         let data = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read room maps: {}", e))?;
+            .map_err(IndoorError::ReadRoomMaps)?;
             
         let maps: Vec<RoomMap> = serde_json::from_str(&data)
-            .map_err(|e| format!("Failed to parse room maps: {}", e))?;
+            .map_err(IndoorError::ParseRoomMaps)?;
             
         for room_map in maps {
             self.room_maps.insert(room_map.name.clone(), room_map);
@@ -95,8 +103,8 @@ impl IndoorNavigation {
         indoor_analysis: &IndoorEnvironmentAnalysis,
         human_analysis: &HumanPresenceAnalysis,
         energy_level: f32,
-    ) -> Result<IndoorPath, String> {
-        let current_pose = self.localizer.get_current_pose();
+    ) -> Result<IndoorPath, IndoorError> {
+        let current_pose = pose_stamped(&self.localizer.get_current_pose().pose);
         let mut path = IndoorPath {
             segments: Vec::new(),
             total_energy_estimate: 0.0,
@@ -107,12 +115,12 @@ impl IndoorNavigation {
         // Check if goal is in a restricted area
         if let Some(room) = self.get_room_for_position(&goal) {
             if self.is_restricted_area(room, human_analysis) {
-                return Err("Goal is in a restricted area".to_string());
+                return Err(IndoorError::RestrictedGoal);
             }
         }
         
         // Plan path considering social rules and human presence
-        let segments = self.generate_path_segments(current_pose, &goal, indoor_analysis, human_analysis);
+        let segments = self.generate_path_segments(current_pose, goal.clone(), indoor_analysis, human_analysis);
         
         for segment in segments {
             let energy = self.calculate_energy_cost(&segment, indoor_analysis);
@@ -135,12 +143,15 @@ impl IndoorNavigation {
         
         // Check energy constraints
         if path.total_energy_estimate > energy_level * 0.8 {
-            return Err("Insufficient energy for indoor path".to_string());
+            return Err(IndoorError::InsufficientEnergy {
+                required: path.total_energy_estimate,
+                budget: energy_level * 0.8,
+            });
         }
         
         // Check social acceptability
         if path.social_acceptability < 0.5 {
-            return Err("Path is not socially acceptable".to_string());
+            return Err(IndoorError::SociallyUnacceptable { acceptability: path.social_acceptability });
         }
         
         self.current_path = Some(path.clone());
@@ -159,8 +170,8 @@ impl IndoorNavigation {
     
     fn is_position_in_room(&self, position: &PoseStamped, room: &RoomMap) -> bool {
         // Check if position is within room boundaries
-        let x = position.pose.position.x;
-        let y = position.pose.position.y;
+        let x = position.pose.position.x as f32;
+        let y = position.pose.position.y as f32;
         
         x >= 0.0 && x <= room.dimensions.0 && y >= 0.0 && y <= room.dimensions.1
     }
@@ -226,7 +237,11 @@ impl IndoorNavigation {
         let mut impact = 0.0;
         
         for human in &human_analysis.humans {
-            let distance = self.distance_to_segment(&human.position, segment);
+            // Account for where a walking human is heading, not just where they are
+            let distance = std::iter::once(&human.position)
+                .chain(&human.predicted_positions)
+                .map(|p| self.distance_to_segment(p, segment))
+                .fold(f32::INFINITY, f32::min);
             if distance < 2.0 { // Close to human
                 impact += (2.0 - distance) * 0.5;
             }
@@ -253,10 +268,10 @@ impl IndoorNavigation {
     
     fn distance_to_segment(&self, point: &(f32, f32), segment: &IndoorPathSegment) -> f32 {
         // Calculate distance from point to path segment
-        let x1 = segment.start.pose.position.x;
-        let y1 = segment.start.pose.position.y;
-        let x2 = segment.end.pose.position.x;
-        let y2 = segment.end.pose.position.y;
+        let x1 = segment.start.pose.position.x as f32;
+        let y1 = segment.start.pose.position.y as f32;
+        let x2 = segment.end.pose.position.x as f32;
+        let y2 = segment.end.pose.position.y as f32;
         let x0 = point.0;
         let y0 = point.1;
         
@@ -269,13 +284,14 @@ impl IndoorNavigation {
         &mut self,
         new_human_analysis: &HumanPresenceAnalysis,
         energy_level: f32,
-    ) -> Result<(), String> {
-        if let Some(path) = &mut self.current_path {
+    ) -> Result<(), IndoorError> {
+        if let Some(mut path) = self.current_path.take() {
             // Recalculate social impact and privacy violation
             for segment in &mut path.segments {
                 segment.social_impact = self.calculate_social_impact(segment, new_human_analysis);
                 segment.privacy_violation = self.calculate_privacy_violation(segment, new_human_analysis);
             }
+            let path = self.current_path.insert(path);
             
             // Recalculate totals
             path.social_acceptability = path.segments.iter().map(|s| 1.0 - (s.social_impact * 0.1)).product();
@@ -283,12 +299,12 @@ impl IndoorNavigation {
             
             // Check if still acceptable
             if path.social_acceptability < 0.5 {
-                return Err("Path became socially unacceptable after human changes".to_string());
+                return Err(IndoorError::SociallyUnacceptable { acceptability: path.social_acceptability });
             }
             
             Ok(())
         } else {
-            Err("No current path to adjust".to_string())
+            Err(IndoorError::NoActivePath)
         }
     }
     
@@ -312,7 +328,7 @@ impl IndoorNavigation {
 pub struct IndoorEnvironmentAnalysis {
     pub floor_type: String,
     pub obstacle_density: f32,
-    room_type: String,
+    pub room_type: String,
     pub lighting_level: f32,
 }
 
@@ -326,4 +342,6 @@ pub struct Human {
     pub activity: String,
     pub attention: f32,
     pub group_size: u8,
+    pub velocity: (f32, f32),                 // m/s, estimated across frames
+    pub predicted_positions: Vec<(f32, f32)>, // constant-velocity forecast over a short horizon
 }
\ No newline at end of file
@@ -1,5 +1,17 @@
-use r2r::{sensor_msgs::LaserScan, PointCloud2};
-use std::collections::VecDeque;
+use r2r::sensor_msgs::msg::{LaserScan, PointCloud2};
+use crate::core::apps::calibration::{estimate_lidar_offset, load_calibration, save_calibration};
+use super::IndoorError;
+use super::indoor_navigation::{Human, HumanPresenceAnalysis, IndoorEnvironmentAnalysis};
+use crate::core::apps::SensorFusionAlgorithm;
+
+pub use crate::core::apps::calibration::IndoorCalibrationData;
+use super::human_tracker::HumanTracker;
+use crate::core::util::BoundedHistory;
+
+/// Assumed time between scans when they carry no usable timestamps
+const DEFAULT_FRAME_PERIOD: f32 = 0.1;
+/// Frames of human detections kept in the motion history
+const MOTION_HISTORY_LEN: usize = 10;
 
 /// Indoor perception with human detection and social cue analysis
 pub struct IndoorPerception {
@@ -7,9 +19,12 @@ pub struct IndoorPerception {
     human_map: Vec<HumanDetection>,
     social_cue_map: Vec<SocialCue>,
     sensor_fusion_algorithm: SensorFusionAlgorithm,
-    motion_history: VecDeque<Vec<(f32, f32)>>,
+    motion_history: BoundedHistory<Vec<(f32, f32)>>,
     previous_scan: Option<LaserScan>,
     calibration_data: IndoorCalibrationData,
+    human_tracker: HumanTracker,
+    pub prediction_horizon: f32, // seconds of predicted human motion
+    pub prediction_step: f32,    // seconds between predicted positions
 }
 
 #[derive(Clone)]
@@ -28,6 +43,7 @@ pub struct SocialCue {
     pub intensity: f32,
 }
 
+#[derive(Clone)]
 pub enum SocialCueType {
     Verbal,
     Gesture,
@@ -35,12 +51,6 @@ pub enum SocialCueType {
     Gaze,
 }
 
-pub struct IndoorCalibrationData {
-    pub lidar_calibration: [f32; 6],
-    pub camera_calibration: [f32; 9],
-    pub microphone_calibration: f32,
-}
-
 impl IndoorPerception {
     pub fn new() -> Self {
         IndoorPerception {
@@ -48,17 +58,16 @@ impl IndoorPerception {
             human_map: Vec::new(),
             social_cue_map: Vec::new(),
             sensor_fusion_algorithm: SensorFusionAlgorithm::Bayesian,
-            motion_history: VecDeque::with_capacity(10),
+            motion_history: BoundedHistory::new(MOTION_HISTORY_LEN),
             previous_scan: None,
-            calibration_data: IndoorCalibrationData {
-                lidar_calibration: [0.0; 6],
-                camera_calibration: [0.0; 9],
-                microphone_calibration: 0.0,
-            },
+            calibration_data: IndoorCalibrationData::default(),
+            human_tracker: HumanTracker::new(),
+            prediction_horizon: 2.0,
+            prediction_step: 0.5,
         }
     }
     
-    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, camera_data: Option<&PointCloud2>) -> Result<(), String> {
+    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, camera_data: Option<&PointCloud2>) -> Result<(), IndoorError> {
         // Indoor-specific sensor calibration
         self.calibration_data.lidar_calibration = self.calibrate_lidar(lidar_data);
         
@@ -68,6 +77,22 @@ impl IndoorPerception {
         
         Ok(())
     }
+
+    /// Save the current calibration as JSON so it survives restarts
+    pub fn save_calibration(&self, path: &str) -> Result<(), IndoorError> {
+        Ok(save_calibration(&self.calibration_data, path)?)
+    }
+    
+    /// Replace the current calibration with one saved by `save_calibration`
+    pub fn load_calibration(&mut self, path: &str) -> Result<(), IndoorError> {
+        self.calibration_data = load_calibration::<IndoorCalibrationData>(path)?;
+        Ok(())
+    }
+    
+    /// Current sensor calibration
+    pub fn calibration(&self) -> &IndoorCalibrationData {
+        &self.calibration_data
+    }
     
     pub fn analyze_indoor_environment(
         &mut self,
@@ -122,6 +147,9 @@ impl IndoorPerception {
             self.analyze_audio_humans(audio, &mut analysis);
         }
         
+        // Follow humans across frames so the planner can anticipate their motion
+        self.track_humans(lidar_data, &mut analysis);
+        
         // Calculate overall activity level
         analysis.overall_activity_level = self.calculate_activity_level(&analysis);
         
@@ -162,12 +190,44 @@ impl IndoorPerception {
                         activity: "unknown".to_string(),
                         attention: 0.5,
                         group_size: 1,
+                        velocity: (0.0, 0.0),
+                        predicted_positions: Vec::new(),
                     });
                 }
             }
         }
     }
     
+    fn track_humans(&mut self, lidar_data: &LaserScan, analysis: &mut HumanPresenceAnalysis) {
+        // Record this frame's detections
+        let frame: Vec<(f32, f32)> = analysis.humans.iter().map(|h| h.position).collect();
+        self.motion_history.push(frame);
+        
+        // Associate with the previous frame's tracks by nearest neighbor
+        let dt = self.frame_period(lidar_data);
+        let latest = self.motion_history.last().map(|f| f.as_slice()).unwrap_or(&[]);
+        let tracks = self.human_tracker.update(latest, dt);
+        
+        for (human, track) in analysis.humans.iter_mut().zip(tracks) {
+            human.velocity = track.velocity;
+            human.predicted_positions = track.predicted_positions(self.prediction_horizon, self.prediction_step);
+        }
+        
+        self.previous_scan = Some(lidar_data.clone());
+    }
+    
+    fn frame_period(&self, lidar_data: &LaserScan) -> f32 {
+        // Time between scan stamps, falling back to the nominal period
+        let seconds = |scan: &LaserScan| scan.header.stamp.sec as f64 + scan.header.stamp.nanosec as f64 * 1e-9;
+        match &self.previous_scan {
+            Some(previous) => {
+                let dt = (seconds(lidar_data) - seconds(previous)) as f32;
+                if dt > 0.0 { dt } else { DEFAULT_FRAME_PERIOD }
+            }
+            None => DEFAULT_FRAME_PERIOD,
+        }
+    }
+    
     fn is_likely_human(&self, x: f32, y: f32, lidar_data: &LaserScan) -> bool {
         // Simple heuristic for human detection
         // In real implementation, this would use machine learning
@@ -252,8 +312,8 @@ impl IndoorPerception {
     }
     
     fn calibrate_lidar(&self, lidar_data: &LaserScan) -> [f32; 6] {
-        // LiDAR calibration
-        [0.0; 6] // Placeholder
+        // LiDAR mount offset from a wall in front of the robot
+        estimate_lidar_offset(lidar_data)
     }
     
     fn calibrate_camera(&self, camera_data: &PointCloud2) -> [f32; 9] {
@@ -0,0 +1,8 @@
+pub mod human_tracker;
+pub mod indoor_control;
+pub mod indoor_error;
+pub mod indoor_navigation;
+pub mod indoor_perception;
+
+pub use human_tracker::{HumanTracker, TrackedHuman};
+pub use indoor_error::IndoorError;
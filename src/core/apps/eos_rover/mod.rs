@@ -0,0 +1,8 @@
+pub mod rover_control;
+pub mod rover_error;
+pub mod rover_navigation;
+pub mod rover_perception;
+pub mod rover_slip;
+
+pub use rover_error::RoverError;
+pub use rover_slip::{apply_slip, SlipLimiter};
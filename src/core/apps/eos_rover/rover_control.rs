@@ -1,5 +1,9 @@
-use r2r::geometry_msgs::Twist;
+use r2r::geometry_msgs::msg::Twist;
 use std::time::{Duration, Instant};
+use super::RoverError;
+use super::rover_navigation::{PathSegment, RoverTerrainAnalysis, TerrainProfile};
+use super::rover_slip::SlipLimiter;
+use crate::core::estimate_slip;
 
 /// Advanced control system for rover movement
 pub struct RoverControl {
@@ -10,6 +14,8 @@ pub struct RoverControl {
     safety_monitor: SafetyMonitor,
     last_command_time: Instant,
     command_history: Vec<(Twist, Instant)>,
+    slip_limiter: SlipLimiter,
+    slip_estimate: f32,
 }
 
 pub struct SafetyMonitor {
@@ -48,6 +54,8 @@ impl RoverControl {
             },
             last_command_time: Instant::now(),
             command_history: Vec::with_capacity(100),
+            slip_limiter: SlipLimiter::default(),
+            slip_estimate: 0.0,
         }
     }
     
@@ -56,10 +64,10 @@ impl RoverControl {
         path_segment: &PathSegment,
         terrain_analysis: &RoverTerrainAnalysis,
         energy_level: f32,
-    ) -> Result<Twist, String> {
+    ) -> Result<Twist, RoverError> {
         // Check safety first
         if self.safety_monitor.emergency_stop_triggered {
-            return Err("Emergency stop active".to_string());
+            return Err(RoverError::EmergencyStop);
         }
         
         // Calculate optimal velocity for this terrain
@@ -101,21 +109,24 @@ impl RoverControl {
             0.4
         };
         
-        velocity.linear.x = base_speed * energy_factor * self.terrain_adaptation_factor;
+        // Slow down while the wheels slip
+        let slip_factor = self.slip_speed_scale();
+        
+        velocity.linear.x = (base_speed * energy_factor * self.terrain_adaptation_factor * slip_factor) as f64;
         
         velocity
     }
     
     fn smooth_acceleration(&mut self, target_velocity: Twist) -> Twist {
-        let time_since_last = self.last_command_time.elapsed().as_secs_f32();
+        let time_since_last = self.last_command_time.elapsed().as_secs_f64();
         let mut command = self.current_velocity.clone();
         
         // Calculate acceleration needed
         let speed_diff = target_velocity.linear.x - self.current_velocity.linear.x;
         let acceleration = if speed_diff > 0.0 {
-            speed_diff.min(self.max_acceleration * time_since_last)
+            speed_diff.min(self.max_acceleration as f64 * time_since_last)
         } else {
-            speed_diff.max(-self.max_deceleration * time_since_last)
+            speed_diff.max(-self.max_deceleration as f64 * time_since_last)
         };
         
         command.linear.x = (self.current_velocity.linear.x + acceleration).max(0.0);
@@ -139,7 +150,7 @@ impl RoverControl {
         }
         
         // Check acceleration limits
-        if command.linear.x.abs() > self.safety_monitor.safety_thresholds.max_acceleration {
+        if command.linear.x.abs() > self.safety_monitor.safety_thresholds.max_acceleration as f64 {
             self.safety_monitor.emergency_stop_triggered = true;
         }
     }
@@ -172,6 +183,25 @@ impl RoverControl {
         self.terrain_adaptation_factor = factor.max(0.1).min(2.0);
     }
     
+    /// Update the slip estimate from odometry and gyro yaw rates; returns it so it can feed localization
+    pub fn update_slip(&mut self, odom_yaw_rate: f64, imu_yaw_rate: f64) -> f32 {
+        self.slip_estimate = estimate_slip(odom_yaw_rate, imu_yaw_rate);
+        self.slip_estimate
+    }
+    
+    pub fn slip_estimate(&self) -> f32 {
+        self.slip_estimate
+    }
+    
+    /// Fraction of the terrain speed allowed at the current slip estimate
+    pub fn slip_speed_scale(&self) -> f32 {
+        self.slip_limiter.speed_scale(self.slip_estimate)
+    }
+    
+    pub fn set_slip_limiter(&mut self, limiter: SlipLimiter) {
+        self.slip_limiter = limiter;
+    }
+    
     fn get_terrain_profile(&self, terrain_type: &str) -> Option<&TerrainProfile> {
         // This would reference the navigation module's terrain profiles
         // For now, return a default
@@ -0,0 +1,40 @@
+use thiserror::Error;
+
+use crate::core::apps::calibration::CalibrationError;
+
+/// Errors raised by the rover navigation and control modules
+#[derive(Debug, Error)]
+pub enum RoverError {
+    /// Terrain profile file could not be read
+    #[error("Failed to read terrain profiles: {0}")]
+    ReadTerrainProfiles(std::io::Error),
+    /// Terrain profile file is not valid JSON
+    #[error("Failed to parse terrain profiles: {0}")]
+    ParseTerrainProfiles(serde_json::Error),
+    /// Planned path exceeds the energy budget
+    #[error("Insufficient energy for path: needs {required:.2}, budget {budget:.2}")]
+    InsufficientEnergy {
+        /// Energy the path needs
+        required: f32,
+        /// Energy available for the path
+        budget: f32,
+    },
+    /// No path has been planned yet
+    #[error("No current path to adjust")]
+    NoActivePath,
+    /// Emergency stop is latched
+    #[error("Emergency stop active")]
+    EmergencyStop,
+    /// Localization update failed
+    #[error("Localization update failed: {0}")]
+    Localization(String),
+    /// Calibration file could not be saved or loaded
+    #[error("Calibration error: {0}")]
+    Calibration(CalibrationError),
+}
+
+impl From<CalibrationError> for RoverError {
+    fn from(err: CalibrationError) -> Self {
+        RoverError::Calibration(err)
+    }
+}
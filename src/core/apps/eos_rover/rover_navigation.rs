@@ -1,11 +1,13 @@
-use crate::core::{Localizer, SpatialMemory};
-use r2r::geometry_msgs::{PoseStamped, Twist};
+use crate::core::{Localization, LocalizationStatus, Memory};
+use r2r::geometry_msgs::msg::{PoseStamped, Twist};
 use std::collections::HashMap;
+use super::RoverError;
+use crate::core::apps::pose_stamped;
 
 /// Outdoor-specific navigation for rovers with terrain adaptation
 pub struct RoverNavigation {
-    localizer: Localizer,
-    spatial_memory: SpatialMemory,
+    localizer: Localization,
+    spatial_memory: Memory,
     terrain_profiles: HashMap<String, TerrainProfile>,
     current_path: Option<Path>,
     energy_efficiency_mode: bool,
@@ -20,12 +22,14 @@ pub struct TerrainProfile {
     pub recommended_speed: f32,
 }
 
+#[derive(Clone)]
 pub struct Path {
     pub segments: Vec<PathSegment>,
     pub total_energy_estimate: f32,
     pub safety_score: f32,
 }
 
+#[derive(Clone)]
 pub struct PathSegment {
     pub start: PoseStamped,
     pub end: PoseStamped,
@@ -35,7 +39,7 @@ pub struct PathSegment {
 }
 
 impl RoverNavigation {
-    pub fn new(localizer: Localizer, spatial_memory: SpatialMemory) -> Self {
+    pub fn new(localizer: Localization, spatial_memory: Memory) -> Self {
         RoverNavigation {
             localizer,
             spatial_memory,
@@ -45,12 +49,12 @@ impl RoverNavigation {
         }
     }
     
-    pub fn load_terrain_profiles(&mut self, path: &str) -> Result<(), String> {
+    pub fn load_terrain_profiles(&mut self, path: &str) -> Result<(), RoverError> {
         let data = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read terrain profiles: {}", e))?;
+            .map_err(RoverError::ReadTerrainProfiles)?;
             
         let profiles: Vec<TerrainProfile> = serde_json::from_str(&data)
-            .map_err(|e| format!("Failed to parse terrain profiles: {}", e))?;
+            .map_err(RoverError::ParseTerrainProfiles)?;
             
         for profile in profiles {
             self.terrain_profiles.insert(profile.name.clone(), profile);
@@ -64,17 +68,14 @@ impl RoverNavigation {
         goal: PoseStamped,
         terrain_analysis: &RoverTerrainAnalysis,
         energy_level: f32
-    ) -> Result<Path, String> {
-        let current_pose = self.localizer.get_current_pose();
+    ) -> Result<Path, RoverError> {
+        let current_pose = pose_stamped(&self.localizer.get_current_pose().pose);
         let mut path = Path {
             segments: Vec::new(),
             total_energy_estimate: 0.0,
             safety_score: 1.0,
         };
         
-        // Use spatial memory to recall similar paths
-        let environment = self.spatial_memory.recall_environment();
-        
         // Plan path considering terrain, energy, and past experiences
         for terrain_segment in &terrain_analysis.terrain_segments {
             if let Some(profile) = self.terrain_profiles.get(&terrain_segment.terrain_type) {
@@ -87,7 +88,10 @@ impl RoverNavigation {
                 
                 // Check if we have enough energy
                 if path.total_energy_estimate + energy_cost > energy_level * 0.8 {
-                    return Err("Insufficient energy for path".to_string());
+                    return Err(RoverError::InsufficientEnergy {
+                        required: path.total_energy_estimate + energy_cost,
+                        budget: energy_level * 0.8,
+                    });
                 }
                 
                 let segment = PathSegment {
@@ -98,9 +102,9 @@ impl RoverNavigation {
                     risk_factor: self.calculate_risk_factor(terrain_segment, profile),
                 };
                 
+                path.safety_score *= 1.0 - (segment.risk_factor * 0.1);
                 path.segments.push(segment);
                 path.total_energy_estimate += energy_cost;
-                path.safety_score *= 1.0 - (segment.risk_factor * 0.1);
             }
         }
         
@@ -110,7 +114,7 @@ impl RoverNavigation {
     
     fn calculate_risk_factor(&self, terrain: &TerrainSegment, profile: &TerrainProfile) -> f32 {
         // Complex risk calculation based on terrain and robot capabilities
-        let mut risk = 0.0;
+        let mut risk: f32 = 0.0;
         
         if terrain.slope > profile.max_slope * 0.7 {
             risk += 0.3;
@@ -131,8 +135,8 @@ impl RoverNavigation {
         &mut self,
         current_conditions: &RoverTerrainAnalysis,
         energy_level: f32
-    ) -> Result<(), String> {
-        if let Some(path) = &mut self.current_path {
+    ) -> Result<(), RoverError> {
+        if let Some(mut path) = self.current_path.take() {
             // Dynamic path adjustment based on changing conditions
             for (i, segment) in path.segments.iter_mut().enumerate() {
                 if let Some(terrain) = current_conditions.terrain_segments.get(i) {
@@ -143,21 +147,37 @@ impl RoverNavigation {
                 }
             }
             
+            let path = self.current_path.insert(path);
+            
             // Recalculate totals
             path.total_energy_estimate = path.segments.iter().map(|s| s.energy_estimate).sum();
             path.safety_score = path.segments.iter().map(|s| 1.0 - (s.risk_factor * 0.1)).product();
             
             // Check energy again
             if path.total_energy_estimate > energy_level * 0.8 {
-                return Err("Path became too energy-intensive after adjustment".to_string());
+                return Err(RoverError::InsufficientEnergy {
+                    required: path.total_energy_estimate,
+                    budget: energy_level * 0.8,
+                });
             }
             
             Ok(())
         } else {
-            Err("No current path to adjust".to_string())
+            Err(RoverError::NoActivePath)
         }
     }
     
+    /// Wheel slip estimate (0.0 - 1.0); localization grows its uncertainty faster while slipping
+    pub fn set_slip(&mut self, slip: f32) {
+        self.localizer.set_slip(slip);
+    }
+    
+    /// Runs one localization update and reports the resulting estimate
+    pub fn update_localization(&mut self) -> Result<LocalizationStatus, RoverError> {
+        self.localizer.update().map_err(|e| RoverError::Localization(e.to_string()))?;
+        Ok(self.localizer.status())
+    }
+    
     pub fn set_energy_efficiency_mode(&mut self, enabled: bool) {
         self.energy_efficiency_mode = enabled;
     }
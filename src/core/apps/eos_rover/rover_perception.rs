@@ -1,5 +1,16 @@
-use r2r::{sensor_msgs::LaserScan, PointCloud2};
+use r2r::sensor_msgs::msg::{LaserScan, PointCloud2};
+use r2r::sensor_msgs::msg::Imu;
 use std::collections::VecDeque;
+use crate::ros_interface::{ImuAxisRemap, ImuSample};
+use crate::core::apps::calibration::{estimate_lidar_offset, load_calibration, save_calibration};
+use super::RoverError;
+use crate::core::apps::SensorFusionAlgorithm;
+use super::rover_navigation::{RoverTerrainAnalysis, TerrainSegment};
+
+pub use crate::core::apps::calibration::CalibrationData;
+
+/// Standard gravity, subtracted from the accelerometer magnitude (m/s²)
+const GRAVITY: f64 = 9.81;
 
 /// Outdoor perception specialized for rover terrain analysis
 pub struct RoverPerception {
@@ -9,6 +20,7 @@ pub struct RoverPerception {
     motion_history: VecDeque<Vec<(f32, f32)>>,
     previous_scan: Option<LaserScan>,
     calibration_data: CalibrationData,
+    imu_remap: ImuAxisRemap,
 }
 
 #[derive(Clone)]
@@ -21,18 +33,6 @@ pub struct TerrainCell {
     pub confidence: f32,
 }
 
-pub enum SensorFusionAlgorithm {
-    Bayesian,
-    DempsterShafer,
-    FuzzyLogic,
-}
-
-pub struct CalibrationData {
-    pub lidar_calibration: [f32; 6],
-    pub camera_calibration: [f32; 9],
-    pub imu_calibration: [f32; 12],
-}
-
 impl RoverPerception {
     pub fn new() -> Self {
         RoverPerception {
@@ -41,30 +41,49 @@ impl RoverPerception {
             sensor_fusion_algorithm: SensorFusionAlgorithm::Bayesian,
             motion_history: VecDeque::with_capacity(10),
             previous_scan: None,
-            calibration_data: CalibrationData {
-                lidar_calibration: [0.0; 6],
-                camera_calibration: [0.0; 9],
-                imu_calibration: [0.0; 12],
-            },
+            calibration_data: CalibrationData::default(),
+            imu_remap: ImuAxisRemap::identity(),
         }
     }
     
-    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, imu_data: Option<&[f32]>) -> Result<(), String> {
+    /// Set how the IMU is mounted relative to the rover body
+    pub fn set_imu_remap(&mut self, remap: ImuAxisRemap) {
+        self.imu_remap = remap;
+    }
+    
+    pub fn calibrate_sensors(&mut self, lidar_data: &LaserScan, imu_data: Option<&Imu>) -> Result<(), RoverError> {
         // Complex sensor calibration routine
         if let Some(imu) = imu_data {
-            self.calibration_data.imu_calibration = self.calibrate_imu(imu);
+            let sample = self.imu_remap.parse(imu);
+            self.calibration_data.imu_calibration = self.calibrate_imu(&sample);
         }
         
         self.calibration_data.lidar_calibration = self.calibrate_lidar(lidar_data);
         
         Ok(())
     }
+
+    /// Save the current calibration as JSON so it survives restarts
+    pub fn save_calibration(&self, path: &str) -> Result<(), RoverError> {
+        Ok(save_calibration(&self.calibration_data, path)?)
+    }
+    
+    /// Replace the current calibration with one saved by `save_calibration`
+    pub fn load_calibration(&mut self, path: &str) -> Result<(), RoverError> {
+        self.calibration_data = load_calibration::<CalibrationData>(path)?;
+        Ok(())
+    }
+    
+    /// Current sensor calibration
+    pub fn calibration(&self) -> &CalibrationData {
+        &self.calibration_data
+    }
     
     pub fn analyze_terrain(
         &mut self,
         lidar_data: &LaserScan,
         camera_data: Option<&PointCloud2>,
-        imu_data: Option<&[f32]>,
+        imu_data: Option<&Imu>,
     ) -> RoverTerrainAnalysis {
         let mut analysis = RoverTerrainAnalysis {
             terrain_segments: Vec::new(),
@@ -81,7 +100,8 @@ impl RoverPerception {
         
         // Use IMU for stability assessment
         if let Some(imu) = imu_data {
-            self.assess_stability(imu, &mut analysis);
+            let sample = self.imu_remap.parse(imu);
+            self.assess_stability(&sample, &mut analysis);
         }
         
         // Calculate overall difficulty
@@ -150,7 +170,7 @@ impl RoverPerception {
         }
     }
     
-    fn assess_stability(&mut self, imu_data: &[f32], analysis: &mut RoverTerrainAnalysis) {
+    fn assess_stability(&mut self, imu_data: &ImuSample, analysis: &mut RoverTerrainAnalysis) {
         // Use IMU data to assess terrain stability
         let vibration_level = self.calculate_vibration(imu_data);
         
@@ -159,14 +179,20 @@ impl RoverPerception {
         }
     }
     
-    fn calculate_vibration(&self, imu_data: &[f32]) -> f32 {
-        // Calculate vibration level from IMU data
-        if imu_data.len() >= 3 {
-            let vibration = (imu_data[0].powi(2) + imu_data[1].powi(2) + imu_data[2].powi(2)).sqrt();
-            vibration.min(1.0)
-        } else {
-            0.5 // Default medium vibration
-        }
+    fn calculate_vibration(&self, imu_data: &ImuSample) -> f32 {
+        // Vibration is the accelerometer's deviation from a steady 1 g
+        let vibration = (imu_data.linear_acceleration.norm() - GRAVITY).abs();
+        (vibration as f32).min(1.0)
+    }
+    
+    fn calibrate_imu(&self, imu_data: &ImuSample) -> [f32; 12] {
+        // Rover-specific IMU calibration
+        [0.0; 12] // Placeholder
+    }
+    
+    fn calibrate_lidar(&self, lidar_data: &LaserScan) -> [f32; 6] {
+        // LiDAR mount offset from a wall in front of the rover
+        estimate_lidar_offset(lidar_data)
     }
     
     fn calculate_difficulty(&self, analysis: &RoverTerrainAnalysis) -> f32 {
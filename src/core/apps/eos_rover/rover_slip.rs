@@ -0,0 +1,43 @@
+use super::rover_control::RoverControl;
+use super::rover_navigation::RoverNavigation;
+
+/// Speed reduction for slipping wheels on loose terrain
+///
+/// Driving slower on sand or gravel lets the wheels regain traction instead of digging in.
+#[derive(Clone, Debug)]
+pub struct SlipLimiter {
+    pub slip_threshold: f32,  // slip tolerated at full speed (0.0 - 1.0)
+    pub min_speed_scale: f32, // fraction of speed still allowed at full slip
+}
+
+impl Default for SlipLimiter {
+    fn default() -> Self {
+        SlipLimiter {
+            slip_threshold: 0.2,
+            min_speed_scale: 0.3,
+        }
+    }
+}
+
+impl SlipLimiter {
+    /// Speed multiplier for a slip estimate, falling linearly from 1.0 at the threshold
+    pub fn speed_scale(&self, slip: f32) -> f32 {
+        let slip = slip.clamp(0.0, 1.0);
+        if slip <= self.slip_threshold || self.slip_threshold >= 1.0 {
+            return 1.0;
+        }
+
+        let excess = (slip - self.slip_threshold) / (1.0 - self.slip_threshold);
+        1.0 - excess * (1.0 - self.min_speed_scale.clamp(0.0, 1.0))
+    }
+}
+
+/// Per-cycle slip update from the odometry and gyro yaw rates (rad/s)
+///
+/// One estimate both slows `control` and inflates the localization uncertainty in
+/// `navigation`; returns the estimate.
+pub fn apply_slip(control: &mut RoverControl, navigation: &mut RoverNavigation, odom_yaw_rate: f64, imu_yaw_rate: f64) -> f32 {
+    let slip = control.update_slip(odom_yaw_rate, imu_yaw_rate);
+    navigation.set_slip(slip);
+    slip
+}
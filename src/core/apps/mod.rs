@@ -0,0 +1,30 @@
+// core/apps/mod.rs
+
+// Application layers built on the Eos core: ground rover, aerial drone and
+// socially-aware indoor robot.
+
+pub mod calibration;
+pub mod eos_drone;
+pub mod eos_indoor;
+pub mod eos_rover;
+
+use r2r::geometry_msgs::msg::PoseStamped;
+
+use crate::core::Pose;
+
+/// How an app perception layer combines readings from several sensors
+pub enum SensorFusionAlgorithm {
+    Bayesian,
+    DempsterShafer,
+    FuzzyLogic,
+}
+
+/// Planar pose as the stamped ROS pose the app planners work with
+pub fn pose_stamped(pose: &Pose) -> PoseStamped {
+    let mut stamped = PoseStamped::default();
+    stamped.pose.position.x = pose.x;
+    stamped.pose.position.y = pose.y;
+    stamped.pose.orientation.z = (pose.theta / 2.0).sin();
+    stamped.pose.orientation.w = (pose.theta / 2.0).cos();
+    stamped
+}
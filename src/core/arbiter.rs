@@ -0,0 +1,118 @@
+// core/arbiter.rs
+
+// Merges velocity commands from several producers (core MotionController, app controllers
+// such as IndoorControl) into the single command that gets published. Candidates carry a
+// priority; a stop from any producer always wins so one controller can't override
+// another's safety stop, and a safety slow-down caps the speed rather than taking over
+// the steering.
+
+// Dependencies
+use crate::ros_interface::MotionCommand;
+
+// Speeds at or below this count as a stop (m/s, rad/s)
+const STOP_EPSILON: f32 = 1e-3;
+
+// Candidate priority, lowest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommandPriority {
+    Nominal,    // Regular goal-seeking motion
+    SafetySlow, // Reduced speed requested by a safety check
+    Emergency,  // Emergency stop; the output is always zero
+}
+
+// One producer's proposal for this cycle
+#[derive(Clone, Debug)]
+pub struct CommandCandidate {
+    pub source: String, // Producer name, for logging
+    pub priority: CommandPriority,
+    pub command: MotionCommand,
+}
+
+impl CommandCandidate {
+    /// Whether the candidate asks the robot to stand still
+    pub fn is_stop(&self) -> bool {
+        self.priority == CommandPriority::Emergency
+            || (self.command.linear.abs() <= STOP_EPSILON && self.command.angular.abs() <= STOP_EPSILON)
+    }
+}
+
+// Arbiter: Collects candidates during a cycle and picks the command to publish
+#[derive(Default)]
+pub struct CommandArbiter {
+    candidates: Vec<CommandCandidate>,
+}
+
+impl CommandArbiter {
+    /// Creates an arbiter with no pending candidates
+    pub fn new() -> Self {
+        CommandArbiter::default()
+    }
+
+    /// Proposes a command for the current cycle
+    pub fn submit(&mut self, source: &str, priority: CommandPriority, command: MotionCommand) {
+        self.candidates.push(CommandCandidate {
+            source: source.to_string(),
+            priority,
+            command,
+        });
+    }
+
+    /// Picks the command to publish and clears the candidates for the next cycle
+    ///
+    /// Any stop (or emergency) wins. Otherwise the slowest nominal command is followed,
+    /// scaled down (keeping its curvature) to the slowest linear speed of any candidate,
+    /// so a `SafetySlow` candidate can only lower the speed; without nominal candidates
+    /// the slowest `SafetySlow` one is followed. No candidates means stop.
+    pub fn arbitrate(&mut self) -> MotionCommand {
+        let stop = MotionCommand { linear: 0.0, angular: 0.0 };
+        let candidates = std::mem::take(&mut self.candidates);
+
+        if let Some(stopper) = candidates.iter().find(|c| c.is_stop()) {
+            if candidates.len() > 1 {
+                log::info!("Arbiter: stop from '{}' overrides {} other command(s)", stopper.source, candidates.len() - 1);
+            }
+            return stop;
+        }
+
+        let cap = candidates
+            .iter()
+            .map(|c| c.command.linear.abs())
+            .fold(f32::INFINITY, f32::min);
+        candidates
+            .iter()
+            .min_by(|a, b| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then(a.command.linear.abs().total_cmp(&b.command.linear.abs()))
+            })
+            .map(|chosen| {
+                let speed = chosen.command.linear.abs();
+                if speed <= cap {
+                    return chosen.command;
+                }
+                let scale = cap / speed;
+                MotionCommand {
+                    linear: chosen.command.linear * scale,
+                    angular: chosen.command.angular * scale,
+                }
+            })
+            .unwrap_or(stop)
+    }
+
+    /// Number of candidates submitted this cycle
+    pub fn pending(&self) -> usize {
+        self.candidates.len()
+    }
+}
+
+// Weaknesses:
+// - Only speed is blended; a SafetySlow candidate's steering is ignored while a nominal
+//   command is present.
+// - Any zero command counts as a stop, so an idle producer forces the robot to halt.
+// - Candidates aren't timestamped; a stale producer must stop submitting by itself.
+
+// Current Functionality:
+// - Collects prioritized command candidates from core and app controllers each cycle.
+// - Guarantees a stop or emergency from any producer wins.
+// - Otherwise follows the most conservative nominal command, capped to the slowest
+//   speed any candidate asks for.
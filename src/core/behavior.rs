@@ -0,0 +1,288 @@
+// core/behavior.rs
+
+// Small behavior tree used as an alternative CoreState backend. Composites (sequence,
+// selector) and leaves (condition, action) are ticked against the latest pose confidence
+// and obstacle distance; the action leaf reached decides the robot's Mode. Trees are
+// assembled with BehaviorTreeBuilder so apps can customize mode logic without touching the
+// Mode enum or the FSM in state.rs.
+
+// Dependencies
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::state::{Mode, StateConfig};
+
+// Inputs a tree is evaluated against on each tick
+#[derive(Clone, Copy, Debug)]
+pub struct TickContext {
+    pub mode: Mode,              // Mode before this tick
+    pub time_in_mode: Duration,  // How long the robot has been in `mode`
+    pub confidence: f64,         // Scalar pose confidence (0.0 - 1.0)
+    pub obstacle_distance: f64,  // Distance to the nearest occupied cell (m)
+    pub battery_level: f64,      // Battery state of charge (0.0 - 1.0)
+    pub time_without_goal: Duration, // How long the robot has stayed in `mode` with no goal
+}
+
+// Result of ticking a node
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeStatus {
+    Success, // Condition held
+    Failure, // Condition failed or no child could run
+    Running, // An action is active
+}
+
+// Condition predicate over the tick inputs
+type Predicate = Box<dyn Fn(&TickContext) -> bool + Send + Sync>;
+
+// Tree node
+pub enum BehaviorNode {
+    Sequence(Vec<BehaviorNode>), // Ticks children in order until one doesn't succeed
+    Selector(Vec<BehaviorNode>), // Ticks children in order until one doesn't fail
+    Condition(String, Predicate), // Named check: Success if it holds, Failure otherwise
+    Action(Mode),                // Puts the robot in a mode; always Running
+    SafetyAction(Mode),          // Like Action, but switches without waiting out the dwell time
+}
+
+impl fmt::Debug for BehaviorNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BehaviorNode::Sequence(children) => f.debug_tuple("Sequence").field(children).finish(),
+            BehaviorNode::Selector(children) => f.debug_tuple("Selector").field(children).finish(),
+            BehaviorNode::Condition(name, _) => f.debug_tuple("Condition").field(name).finish(),
+            BehaviorNode::Action(mode) => f.debug_tuple("Action").field(mode).finish(),
+            BehaviorNode::SafetyAction(mode) => f.debug_tuple("SafetyAction").field(mode).finish(),
+        }
+    }
+}
+
+impl BehaviorNode {
+    /// Ticks the node, recording the action leaf that ends up running and whether it's a safety leaf
+    fn tick(&self, context: &TickContext, active: &mut Option<(Mode, bool)>) -> NodeStatus {
+        match self {
+            BehaviorNode::Sequence(children) => {
+                for child in children {
+                    let status = child.tick(context, active);
+                    if status != NodeStatus::Success {
+                        return status;
+                    }
+                }
+                NodeStatus::Success
+            }
+            BehaviorNode::Selector(children) => {
+                for child in children {
+                    let status = child.tick(context, active);
+                    if status != NodeStatus::Failure {
+                        return status;
+                    }
+                }
+                NodeStatus::Failure
+            }
+            BehaviorNode::Condition(_, predicate) => {
+                if predicate(context) {
+                    NodeStatus::Success
+                } else {
+                    NodeStatus::Failure
+                }
+            }
+            BehaviorNode::Action(mode) => {
+                *active = Some((*mode, false));
+                NodeStatus::Running
+            }
+            BehaviorNode::SafetyAction(mode) => {
+                *active = Some((*mode, true));
+                NodeStatus::Running
+            }
+        }
+    }
+}
+
+// Problems found while assembling a tree
+#[derive(Debug, Error, PartialEq)]
+pub enum BehaviorTreeError {
+    /// `build` was called without any node
+    #[error("Behavior tree has no nodes")]
+    Empty,
+    /// `end` was called with no open sequence or selector
+    #[error("Unbalanced end(): no open sequence or selector")]
+    UnmatchedEnd,
+    /// `build` was called with sequences or selectors still open
+    #[error("{0} sequence/selector node(s) left open")]
+    Unclosed(usize),
+    /// More than one node was added at the top level
+    #[error("Behavior tree has {0} root nodes; wrap them in a sequence or selector")]
+    MultipleRoots(usize),
+    /// A sequence or selector was closed without children
+    #[error("Empty {0} node")]
+    EmptyComposite(&'static str),
+}
+
+// Behavior tree whose active action leaf gives the robot's mode
+#[derive(Debug)]
+pub struct BehaviorTree {
+    root: BehaviorNode,
+}
+
+impl BehaviorTree {
+    /// Starts assembling a tree
+    pub fn builder() -> BehaviorTreeBuilder {
+        BehaviorTreeBuilder::default()
+    }
+
+    /// Ticks the tree; returns the mode of the action left running, or None if the tree failed
+    ///
+    /// A tree that reaches no action leaves the mode unchanged.
+    pub fn tick(&self, context: &TickContext) -> Option<Mode> {
+        self.tick_leaf(context).map(|(mode, _)| mode)
+    }
+
+    /// Like `tick`, also saying whether the running leaf is a safety action that skips the dwell time
+    pub fn tick_leaf(&self, context: &TickContext) -> Option<(Mode, bool)> {
+        let mut active = None;
+        match self.root.tick(context, &mut active) {
+            NodeStatus::Running => active,
+            NodeStatus::Success | NodeStatus::Failure => None,
+        }
+    }
+
+    /// Tree equivalent to the built-in FSM's battery, exploration, confidence, and obstacle transitions
+    ///
+    /// Dwell times stay with CoreState, which applies them to every change the tree makes
+    /// except the obstacle stop, a safety action like the FSM's.
+    pub fn standard(config: &StateConfig) -> Self {
+        let (lost, recover, restore) = (config.lost_threshold, config.recover_threshold, config.restore_threshold);
+        let (low_battery, charged) = (config.low_battery_threshold, config.charged_threshold);
+        let explore_timeout = config.explore_timeout;
+        BehaviorTree::builder()
+            .selector()
+                .sequence()
+                    .condition("battery low", move |c| c.mode != Mode::Charging && c.battery_level < low_battery)
+                    .action(Mode::Charging)
+                .end()
+                .sequence()
+                    .condition("battery charged", move |c| c.mode == Mode::Charging && c.battery_level >= charged)
+                    .action(Mode::Idle)
+                .end()
+                .sequence()
+                    .condition("idle without goal", move |c| c.mode == Mode::Idle && c.time_without_goal >= explore_timeout)
+                    .action(Mode::Exploring)
+                .end()
+                .sequence()
+                    .condition("moving near obstacle", |c| {
+                        matches!(c.mode, Mode::Navigating | Mode::Exploring) && c.obstacle_distance < 0.3
+                    })
+                    .safety_action(Mode::Recovering)
+                .end()
+                .sequence()
+                    .condition("confidence lost", move |c| {
+                        matches!(c.mode, Mode::Idle | Mode::Navigating | Mode::Mapping | Mode::Exploring) && c.confidence < lost
+                    })
+                    .action(Mode::Lost)
+                .end()
+                .sequence()
+                    .condition("confidence recovering", move |c| c.mode == Mode::Lost && c.confidence > recover)
+                    .action(Mode::Recovering)
+                .end()
+                .sequence()
+                    .condition("confidence restored", move |c| c.mode == Mode::Recovering && c.confidence > restore)
+                    .action(Mode::Navigating)
+                .end()
+            .end()
+            .build()
+            .expect("standard behavior tree is well formed")
+    }
+}
+
+// Assembles a tree depth-first: `sequence`/`selector` open a composite, `end` closes it
+#[derive(Debug, Default)]
+pub struct BehaviorTreeBuilder {
+    open: Vec<BehaviorNode>, // Composites still receiving children, innermost last
+    roots: Vec<BehaviorNode>,
+    error: Option<BehaviorTreeError>,
+}
+
+impl BehaviorTreeBuilder {
+    /// Opens a sequence; children follow until `end`
+    pub fn sequence(mut self) -> Self {
+        self.open.push(BehaviorNode::Sequence(Vec::new()));
+        self
+    }
+
+    /// Opens a selector; children follow until `end`
+    pub fn selector(mut self) -> Self {
+        self.open.push(BehaviorNode::Selector(Vec::new()));
+        self
+    }
+
+    /// Adds a named condition leaf
+    pub fn condition(self, name: &str, predicate: impl Fn(&TickContext) -> bool + Send + Sync + 'static) -> Self {
+        self.push(BehaviorNode::Condition(name.to_string(), Box::new(predicate)))
+    }
+
+    /// Adds an action leaf that puts the robot in `mode`
+    pub fn action(self, mode: Mode) -> Self {
+        self.push(BehaviorNode::Action(mode))
+    }
+
+    /// Adds an action leaf that puts the robot in `mode` as soon as it runs, skipping the dwell time
+    pub fn safety_action(self, mode: Mode) -> Self {
+        self.push(BehaviorNode::SafetyAction(mode))
+    }
+
+    /// Closes the innermost open sequence or selector
+    pub fn end(mut self) -> Self {
+        match self.open.pop() {
+            Some(node) => {
+                let empty = match &node {
+                    BehaviorNode::Sequence(children) if children.is_empty() => Some("sequence"),
+                    BehaviorNode::Selector(children) if children.is_empty() => Some("selector"),
+                    _ => None,
+                };
+                if let Some(kind) = empty {
+                    self.error.get_or_insert(BehaviorTreeError::EmptyComposite(kind));
+                }
+                self.push(node)
+            }
+            None => {
+                self.error.get_or_insert(BehaviorTreeError::UnmatchedEnd);
+                self
+            }
+        }
+    }
+
+    /// Finishes the tree, which must have exactly one root and no open composites
+    pub fn build(mut self) -> Result<BehaviorTree, BehaviorTreeError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if !self.open.is_empty() {
+            return Err(BehaviorTreeError::Unclosed(self.open.len()));
+        }
+        match self.roots.len() {
+            0 => Err(BehaviorTreeError::Empty),
+            1 => Ok(BehaviorTree { root: self.roots.remove(0) }),
+            count => Err(BehaviorTreeError::MultipleRoots(count)),
+        }
+    }
+
+    // Adds a finished node to the innermost open composite, or as a root
+    fn push(mut self, node: BehaviorNode) -> Self {
+        match self.open.last_mut() {
+            Some(BehaviorNode::Sequence(children) | BehaviorNode::Selector(children)) => children.push(node),
+            _ => self.roots.push(node),
+        }
+        self
+    }
+}
+
+// Weaknesses:
+// - Actions only select a mode; they can't run multi-tick routines with their own state.
+// - Nodes are re-evaluated from the root every tick; there is no memory of the running child.
+// - Conditions see a fixed set of inputs; new ones (e.g. semantic objects) need TickContext fields.
+
+// Current Functionality:
+// - Sequence, selector, condition, and action nodes ticked against pose confidence,
+//   obstacle distance, battery level, and time spent in the current mode and without a goal.
+// - Derives the robot's Mode from the action leaf left running; safety actions skip the dwell time.
+// - Builder assembles trees with structural checks (balanced, single root, no empty composites).
+// - Standard tree mirrors the built-in FSM's battery, exploration, confidence, and obstacle transitions.
@@ -0,0 +1,107 @@
+// core/control.rs
+
+// Feedback control building blocks shared by the navigation controller and the robot
+// apps. Pid replaces the ad-hoc proportional terms (and the bang-bang altitude logic)
+// each controller used to carry with one tested implementation.
+
+// Dependencies
+use serde::{Deserialize, Serialize};
+
+// Tuning of a PID loop
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PidGains {
+    pub kp: f32,
+    pub ki: f32,
+    pub kd: f32,
+    pub integral_limit: f32,    // Largest magnitude of the integral term's output contribution
+    pub derivative_filter: f32, // EMA weight of the previous derivative (0 = unfiltered, < 1)
+}
+
+impl PidGains {
+    /// Proportional-only gains
+    pub fn proportional(kp: f32) -> Self {
+        PidGains {
+            kp,
+            ki: 0.0,
+            kd: 0.0,
+            integral_limit: 0.0,
+            derivative_filter: 0.0,
+        }
+    }
+}
+
+// PID controller with a clamped integral and a low-pass filtered derivative
+#[derive(Clone, Debug)]
+pub struct Pid {
+    gains: PidGains,
+    integral: f32,           // Accumulated error (error·s)
+    last_error: Option<f32>, // None until the first update, so the first call has no derivative kick
+    derivative: f32,         // Filtered error rate (error/s)
+}
+
+impl Pid {
+    /// Creates a controller with no accumulated state
+    pub fn new(gains: PidGains) -> Self {
+        Pid {
+            gains,
+            integral: 0.0,
+            last_error: None,
+            derivative: 0.0,
+        }
+    }
+
+    /// Control output for the current error after `dt` seconds
+    ///
+    /// The integral is clamped so `ki · integral` stays within `integral_limit`
+    /// (anti-windup); a non-positive `dt` only applies the proportional term.
+    pub fn update(&mut self, error: f32, dt: f32) -> f32 {
+        if dt <= 0.0 || !error.is_finite() {
+            return self.gains.kp * error;
+        }
+
+        // Integral, clamped so a long-held error cannot wind it up without bound
+        self.integral += error * dt;
+        if self.gains.ki != 0.0 {
+            let limit = (self.gains.integral_limit / self.gains.ki).abs();
+            self.integral = self.integral.clamp(-limit, limit);
+        }
+
+        // Derivative of the error, smoothed against measurement noise
+        if let Some(last) = self.last_error {
+            let raw = (error - last) / dt;
+            let alpha = self.gains.derivative_filter.clamp(0.0, 0.99);
+            self.derivative = alpha * self.derivative + (1.0 - alpha) * raw;
+        }
+        self.last_error = Some(error);
+
+        self.gains.kp * error + self.gains.ki * self.integral + self.gains.kd * self.derivative
+    }
+
+    /// Clears the integral and derivative state, e.g. when the setpoint jumps
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.last_error = None;
+        self.derivative = 0.0;
+    }
+
+    /// Current contribution of the integral term
+    pub fn integral_term(&self) -> f32 {
+        self.gains.ki * self.integral
+    }
+
+    /// Gains in use
+    pub fn gains(&self) -> PidGains {
+        self.gains
+    }
+}
+
+// Weaknesses:
+// - Anti-windup only clamps the integral; there is no back-calculation from a saturated
+//   output, so callers that clamp the output still integrate while saturated (up to the limit).
+// - Derivative acts on the error, so setpoint jumps still kick it; call reset() on new setpoints.
+// - Single-input, single-output and f32 only.
+
+// Current Functionality:
+// - PID update with a caller-supplied time step.
+// - Integral clamp bounding the integral term's contribution to the output.
+// - First-order low-pass filtered derivative with no kick on the first update.
@@ -10,9 +10,41 @@ use nalgebra::{Matrix3, Vector3, Vector6};
 use r2r::{sensor_msgs::msg::Imu, sensor_msgs::msg::LaserScan, QosProfile};
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use super::util::{BoundedHistory, CycleTimer, UpdateRate};
+use crate::ros_interface::{subscribe_with, ImuAxisRemap, ImuSample};
+
+// Yaw rates below this (rad/s) are treated as noise when normalizing slip
+const MIN_YAW_RATE: f64 = 0.1;
+// Process noise multiplier at full slip
+const SLIP_COVARIANCE_GAIN: f64 = 10.0;
+// Measurement variance of a fully familiar loop-closure pose (map nodes are trusted more than dead reckoning)
+const LOOP_CLOSURE_NOISE: f64 = 0.05;
+// Familiarity floor, so a barely known node still gives a finite (if weak) correction
+const MIN_FAMILIARITY: f64 = 0.05;
+// Covariance samples used for the drift rate (2 s at 10 Hz)
+const DRIFT_WINDOW: usize = 20;
+// Expected time between updates (10 Hz), used until two updates have been timed
+const NOMINAL_UPDATE_DT: f64 = 0.1;
+// Longest time step integrated at once, so a stalled loop doesn't fling the estimate
+const MAX_UPDATE_DT: f64 = 0.5;
+// Scan-match iterations (each re-pairs points with their nearest neighbours)
+const ICP_ITERATIONS: usize = 15;
+// Point pairs farther apart than this (meters) are not treated as the same surface
+const ICP_MAX_PAIR_DISTANCE: f64 = 0.5;
+// Fewest point pairs for a scan match to be trusted
+const ICP_MIN_PAIRS: usize = 10;
+// Position variance (m², x and y summed) at which the scalar pose confidence reads 0.5
+const CONFIDENCE_HALF_POSITION_VARIANCE: f64 = 1.0;
+// Heading variance (rad²) at which the scalar pose confidence reads 0.5, about (29°)²
+const CONFIDENCE_HALF_HEADING_VARIANCE: f64 = 0.25;
+// Start-up variance per axis: about 10 cm and 6° (1σ)
+const INITIAL_VARIANCE: f64 = 0.01;
+// IMU samples kept between updates (1 s at 400 Hz); older ones are dropped if updates stall
+const MAX_IMU_BACKLOG: usize = 400;
 
 // Pose: Represents robot position (x, y, theta) and confidence
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Pose {
     pub x: f64,     // X position (meters)
     pub y: f64,     // Y position (meters)
@@ -25,15 +57,65 @@ pub struct PoseConfidence {
     pub covariance: Matrix3<f64>, // 3x3 covariance matrix
 }
 
+impl PoseConfidence {
+    /// Confidence in the pose as a single number in (0, 1], falling as the uncertainty grows
+    ///
+    /// Position (m²) and heading (rad²) variances are each scaled by the variance at
+    /// which they alone halve the confidence, so neither unit swamps the other: 1.0 for
+    /// a certain pose, 0.5 at `CONFIDENCE_HALF_POSITION_VARIANCE` or
+    /// `CONFIDENCE_HALF_HEADING_VARIANCE`, and about 0.94 for `initial_covariance`.
+    pub fn confidence_scalar(&self) -> f64 {
+        let position = (self.covariance[(0, 0)] + self.covariance[(1, 1)]).max(0.0);
+        let heading = self.covariance[(2, 2)].max(0.0);
+        let uncertainty = position / CONFIDENCE_HALF_POSITION_VARIANCE + heading / CONFIDENCE_HALF_HEADING_VARIANCE;
+        if !uncertainty.is_finite() {
+            return 0.0;
+        }
+        1.0 / (1.0 + uncertainty)
+    }
+}
+
+/// Covariance of a freshly started estimate, before any scan has been matched
+pub fn initial_covariance() -> Matrix3<f64> {
+    Matrix3::from_diagonal_element(INITIAL_VARIANCE)
+}
+
+// Localization health for operators deciding when to relocalize
+#[derive(Clone, Serialize, Debug)]
+pub struct LocalizationStatus {
+    pub pose: Pose,
+    pub covariance_trace: f64, // Total pose uncertainty
+    pub drift_rate: f64,       // Growth of covariance trace per second
+    pub slip: f32,             // Latest wheel slip estimate (0.0 - 1.0)
+    pub update_rate: UpdateRate, // Measured time step and rate of `update`
+}
+
+// Tracks how fast pose uncertainty grows from recent covariance traces
+#[derive(Clone, Debug)]
+pub struct DriftEstimator {
+    samples: BoundedHistory<(f64, f64)>, // (time in seconds, covariance trace)
+    elapsed: f64,
+}
+
+// Sensor messages since the last update, written by the subscription callbacks and consumed by `update`
+struct LatestReadings {
+    imu: BoundedHistory<ImuSample>, // Every IMU sample, in the base frame, oldest first
+    scan: Option<LaserScan>,        // Only the latest scan is matched
+}
+
 // Localization struct: Manages sensor fusion and pose estimation
 pub struct Localization {
-    ros_node: Arc<r2r::Node>,
-    imu_subscriber: r2r::Subscriber<Imu>,
-    lidar_subscriber: r2r::Subscriber<LaserScan>,
+    ros_clock: Arc<Mutex<r2r::Clock>>,
+    started: Instant, // Fallback time base while the ROS clock is unavailable
+    timer: CycleTimer,
+    latest: Arc<Mutex<LatestReadings>>,
+    last_scan: Option<(LaserScan, Pose, f64)>, // Previous scan, the pose estimated when it arrived, and when
     state: Vector6<f64>,           // [x, y, theta, vx, vy, vtheta]
     covariance: Matrix3<f64>,
     ekf: ExtendedKalmanFilter,
     config: LocalizationConfig,
+    slip: f32, // Latest wheel slip estimate (0.0 - 1.0)
+    drift: DriftEstimator,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -41,80 +123,166 @@ struct LocalizationConfig {
     imu_topic: String,
     lidar_topic: String,
     sensor_noise: f64,
-    process_noise: f64,
+    process_noise: f64, // Process noise variance per second of prediction
+    #[serde(default)]
+    imu_axis_remap: ImuAxisRemap, // How the IMU is mounted relative to the base frame
 }
 
 struct ExtendedKalmanFilter {
-    // Simplified EKF implementation
-    f: fn(Vector6<f64>, f64) -> Vector6<f64>, // State transition function
-    h: fn(Vector6<f64>) -> Vector3<f64>,      // Measurement function
-    q: Matrix3<f64>,                          // Process noise covariance
-    r: Matrix3<f64>,                          // Measurement noise covariance
+    q: Matrix3<f64>, // Process noise covariance per second
+    r: f64,          // Variance of a scan-matched pose measurement
 }
 
 impl Localization {
     /// Initializes localization with ROS 2 subscriptions and EKF
-    pub fn new(ros_node: &r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(ros_node: &mut r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let config_file = std::fs::File::open(config_path)?;
         let config: LocalizationConfig = serde_yaml::from_reader(config_file)?;
 
-        let imu_subscriber = ros_node.subscribe::<Imu>(
-            &config.imu_topic,
-            QosProfile::default(),
-            Box::new(|_| {}),
-        )?;
+        // Callbacks queue IMU samples (in the base frame) and keep the latest scan; `update` consumes them
+        let latest = Arc::new(Mutex::new(LatestReadings {
+            imu: BoundedHistory::new(MAX_IMU_BACKLOG),
+            scan: None,
+        }));
+        let imu_latest = Arc::clone(&latest);
+        let remap = config.imu_axis_remap;
+        subscribe_with(ros_node, &config.imu_topic, QosProfile::default(), move |msg: Imu| {
+            if let Ok(mut latest) = imu_latest.lock() {
+                latest.imu.push(remap.parse(&msg));
+            }
+        })?;
 
-        let lidar_subscriber = ros_node.subscribe::<LaserScan>(
-            &config.lidar_topic,
-            QosProfile::default(),
-            Box::new(|_| {}),
-        )?;
+        let scan_latest = Arc::clone(&latest);
+        subscribe_with(ros_node, &config.lidar_topic, QosProfile::default(), move |msg: LaserScan| {
+            if let Ok(mut latest) = scan_latest.lock() {
+                latest.scan = Some(msg);
+            }
+        })?;
 
         let ekf = ExtendedKalmanFilter {
-            f: |state, dt| {
-                // Simplified state transition: x' = x + v*dt
-                let mut new_state = state;
-                new_state[0] += state[3] * dt; // x += vx*dt
-                new_state[1] += state[4] * dt; // y += vy*dt
-                new_state[2] += state[5] * dt; // theta += vtheta*dt
-                new_state
-            },
-            h: |state| Vector3::new(state[0], state[1], state[2]), // Measurement: [x, y, theta]
             q: Matrix3::from_diagonal_element(config.process_noise),
-            r: Matrix3::from_diagonal_element(config.sensor_noise),
+            r: config.sensor_noise,
         };
 
         Ok(Localization {
-            ros_node: Arc::new(ros_node.clone()),
-            imu_subscriber,
-            lidar_subscriber,
+            ros_clock: ros_node.get_ros_clock(),
+            started: Instant::now(),
+            timer: CycleTimer::new(NOMINAL_UPDATE_DT, MAX_UPDATE_DT),
+            latest,
+            last_scan: None,
             state: Vector6::zeros(),
-            covariance: Matrix3::identity(),
+            covariance: initial_covariance(),
             ekf,
             config,
+            slip: 0.0,
+            drift: DriftEstimator::new(DRIFT_WINDOW),
         })
     }
 
     /// Updates pose estimate using EKF and sensor data
+    ///
+    /// Every IMU sample since the last update drives the prediction; a new LiDAR scan
+    /// matched against the previous one gives the pose measurement for the correction,
+    /// and the matched motion over the time between the scans resets the velocity.
     pub fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Simplified EKF update (predict and correct)
-        let dt = 0.1; // Assume 10 Hz update rate
-
-        self.state = (self.ekf.f)(self.state, dt);
+        let now = self.now_seconds();
+        let dt = self.timer.tick(now);
+        let (imu, scan) = {
+            let mut latest = self.latest.lock().map_err(|_| "sensor readings lock poisoned")?;
+            let imu: Vec<ImuSample> = latest.imu.iter().copied().collect();
+            latest.imu.clear();
+            (imu, latest.scan.take())
+        };
 
-        // Correct with sensor data (placeholder)
-        let measurement = Vector3::new(self.state[0], self.state[1], self.state[2]); // Mock data
-        let residual = measurement - (self.ekf.h)(self.state);
+        // Predict: integrate the IMU, or coast at the current velocity without it
+        self.state = integrate_imu(&self.state, &imu, dt);
+        // Slipping wheels make odometry less trustworthy, so grow uncertainty faster
+        self.covariance = predict_covariance(&self.covariance, &self.ekf.q, self.slip, dt);
 
-        self.covariance += self.ekf.q;
-        let kalman_gain = self.covariance * self.ekf.r.pseudo_inverse(1e-6)?;
-        self.state += Vector6::from_vec((kalman_gain * residual).data.to_vec());
-        self.covariance = (Matrix3::identity() - kalman_gain) * self.covariance;
+        // Correct: the scan-matched motion since the previous scan, applied to the pose back then
+        if let Some(scan) = scan {
+            let matched = self.last_scan.as_ref().and_then(|(previous, previous_pose, previous_time)| {
+                let measured = compose_pose(previous_pose, &scan_match(previous, &scan)?);
+                let scan_dt = scan_interval(previous, &scan).unwrap_or(now - previous_time);
+                let velocity = scan_velocity(previous_pose, &measured, scan_dt);
+                Some((measured, velocity))
+            });
+            if let Some((measured, velocity)) = matched {
+                let corrected = fuse_pose_measurement(&self.get_current_pose(), &measured, self.ekf.r);
+                self.state[0] = corrected.pose.x;
+                self.state[1] = corrected.pose.y;
+                self.state[2] = corrected.pose.theta;
+                self.covariance = corrected.covariance;
+                // IMU integration drifts the velocity; the scans' motion pins it back down
+                if let Some(velocity) = velocity {
+                    self.state.fixed_rows_mut::<3>(3).copy_from(&velocity);
+                }
+            }
+            self.last_scan = Some((scan, self.get_current_pose().pose, now));
+        }
+        self.drift.update(&self.covariance, dt);
 
         info!("Updated pose: x={}, y={}, theta={}", self.state[0], self.state[1], self.state[2]);
         Ok(())
     }
 
+    /// Seconds on the ROS clock, or since startup while it reads zero or fails
+    fn now_seconds(&self) -> f64 {
+        let ros_now = self.ros_clock.lock().ok().and_then(|mut clock| clock.get_now().ok());
+        match ros_now {
+            Some(now) if !now.is_zero() => now.as_secs_f64(),
+            _ => self.started.elapsed().as_secs_f64(),
+        }
+    }
+
+    /// Seeds the estimate with a known pose, e.g. at startup or after the robot was carried
+    ///
+    /// Velocities are zeroed and the previous scan is dropped so it is not matched
+    /// against scans from the new place.
+    pub fn set_initial_pose(&mut self, pose: Pose, covariance: Matrix3<f64>) {
+        self.state = Vector6::new(pose.x, pose.y, pose.theta, 0.0, 0.0, 0.0);
+        self.covariance = covariance;
+        self.last_scan = None;
+        info!("Initial pose set: x={}, y={}, theta={}", pose.x, pose.y, pose.theta);
+    }
+
+    /// Sets the wheel slip estimate used to inflate process noise (see `estimate_slip`)
+    pub fn set_slip(&mut self, slip: f32) {
+        self.slip = slip.clamp(0.0, 1.0);
+    }
+
+    /// Corrects accumulated drift with the stored pose of a revisited map node
+    ///
+    /// The better known the node (`familiarity`, 0.0 - 1.0), the harder the estimate is
+    /// pulled toward it; see `loop_closure_noise`.
+    pub fn apply_loop_closure(&mut self, node_pose: &Pose, familiarity: f64) {
+        let corrected = fuse_pose_measurement(&self.get_current_pose(), node_pose, loop_closure_noise(familiarity));
+        self.state[0] = corrected.pose.x;
+        self.state[1] = corrected.pose.y;
+        self.state[2] = corrected.pose.theta;
+        self.covariance = corrected.covariance;
+        info!(
+            "Loop closure correction: x={}, y={}, theta={}",
+            self.state[0], self.state[1], self.state[2]
+        );
+    }
+
+    /// Growth of pose uncertainty (covariance trace per second) over the last few seconds
+    pub fn drift_rate(&self) -> f64 {
+        self.drift.drift_rate()
+    }
+
+    /// Returns pose, uncertainty and drift for status reporting
+    pub fn status(&self) -> LocalizationStatus {
+        LocalizationStatus {
+            pose: self.get_current_pose().pose,
+            covariance_trace: self.covariance.trace(),
+            drift_rate: self.drift_rate(),
+            slip: self.slip,
+            update_rate: self.timer.rate(),
+        }
+    }
+
     /// Returns the current pose with confidence
     pub fn get_current_pose(&self) -> PoseConfidence {
         PoseConfidence {
@@ -128,17 +296,251 @@ impl Localization {
     }
 }
 
+/// EKF state prediction over `dt` seconds, driven by an IMU reading when there is one
+///
+/// The gyro's yaw rate turns the heading and the planar acceleration, rotated into the
+/// world frame, changes the velocity. Without a reading the state coasts at its current
+/// velocity. The sample must already be in the base frame (see `ImuAxisRemap::parse`).
+pub fn predict_state(state: &Vector6<f64>, imu: Option<&ImuSample>, dt: f64) -> Vector6<f64> {
+    let mut next = *state;
+    if let Some(sample) = imu {
+        let yaw_rate = sample.angular_velocity.z;
+        // Accelerate along the mid-step heading
+        let heading = state[2] + 0.5 * yaw_rate * dt;
+        let (sin, cos) = heading.sin_cos();
+        let (ax, ay) = (sample.linear_acceleration.x, sample.linear_acceleration.y);
+        let (world_ax, world_ay) = (ax * cos - ay * sin, ax * sin + ay * cos);
+
+        next[3] = state[3] + world_ax * dt;
+        next[4] = state[4] + world_ay * dt;
+        next[5] = yaw_rate;
+        next[0] = state[0] + 0.5 * (state[3] + next[3]) * dt;
+        next[1] = state[1] + 0.5 * (state[4] + next[4]) * dt;
+    } else {
+        next[0] += state[3] * dt; // x += vx*dt
+        next[1] += state[4] * dt; // y += vy*dt
+    }
+    next[2] = state[2] + next[5] * dt;
+    next
+}
+
+/// Prediction over `dt` seconds through every IMU sample received in that time, oldest first
+///
+/// Each sample covers an equal share of `dt`; with no samples the state coasts.
+pub fn integrate_imu(state: &Vector6<f64>, samples: &[ImuSample], dt: f64) -> Vector6<f64> {
+    if samples.is_empty() {
+        return predict_state(state, None, dt);
+    }
+    let step = dt / samples.len() as f64;
+    samples.iter().fold(*state, |state, sample| predict_state(&state, Some(sample), step))
+}
+
+/// World-frame velocity [vx, vy, vtheta] moving from `previous` to `current` in `dt` seconds
+///
+/// None for a non-positive `dt`. The heading change takes the short way around.
+pub fn scan_velocity(previous: &Pose, current: &Pose, dt: f64) -> Option<Vector3<f64>> {
+    if dt <= 0.0 || !dt.is_finite() {
+        return None;
+    }
+    let turn = current.theta - previous.theta;
+    Some(Vector3::new(
+        (current.x - previous.x) / dt,
+        (current.y - previous.y) / dt,
+        turn.sin().atan2(turn.cos()) / dt,
+    ))
+}
+
+/// Seconds between two scans' header stamps, None when either is unstamped or they're out of order
+fn scan_interval(previous: &LaserScan, current: &LaserScan) -> Option<f64> {
+    let seconds = |scan: &LaserScan| scan.header.stamp.sec as f64 + scan.header.stamp.nanosec as f64 * 1e-9;
+    let (previous, current) = (seconds(previous), seconds(current));
+    (previous > 0.0 && current > previous).then_some(current - previous)
+}
+
+/// Robot motion between two scans (in the earlier scan's frame), found by point-to-point ICP
+///
+/// None when too few returns overlap for a trustworthy match.
+pub fn scan_match(previous: &LaserScan, current: &LaserScan) -> Option<Pose> {
+    let reference = scan_points(previous);
+    let points = scan_points(current);
+    if reference.len() < ICP_MIN_PAIRS || points.len() < ICP_MIN_PAIRS {
+        return None;
+    }
+
+    let mut delta = Pose { x: 0.0, y: 0.0, theta: 0.0 };
+    for _ in 0..ICP_ITERATIONS {
+        // Pair each moved point with its nearest reference point
+        let (sin, cos) = delta.theta.sin_cos();
+        let pairs: Vec<((f64, f64), (f64, f64))> = points
+            .iter()
+            .map(|&(x, y)| (delta.x + x * cos - y * sin, delta.y + x * sin + y * cos))
+            .filter_map(|moved| {
+                reference
+                    .iter()
+                    .map(|&r| (r, (r.0 - moved.0).hypot(r.1 - moved.1)))
+                    .filter(|(_, distance)| *distance <= ICP_MAX_PAIR_DISTANCE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(r, _)| (moved, r))
+            })
+            .collect();
+        if pairs.len() < ICP_MIN_PAIRS {
+            return None;
+        }
+
+        // Closed-form rigid alignment of the pairs' centred coordinates
+        let n = pairs.len() as f64;
+        let (mx, my) = pairs.iter().fold((0.0, 0.0), |acc, (m, _)| (acc.0 + m.0 / n, acc.1 + m.1 / n));
+        let (rx, ry) = pairs.iter().fold((0.0, 0.0), |acc, (_, r)| (acc.0 + r.0 / n, acc.1 + r.1 / n));
+        let (dot, cross) = pairs.iter().fold((0.0, 0.0), |acc, (m, r)| {
+            let (ax, ay, bx, by) = (m.0 - mx, m.1 - my, r.0 - rx, r.1 - ry);
+            (acc.0 + ax * bx + ay * by, acc.1 + ax * by - ay * bx)
+        });
+        let rotation = cross.atan2(dot);
+        let (sin, cos) = rotation.sin_cos();
+        let step = Pose {
+            x: rx - (mx * cos - my * sin),
+            y: ry - (mx * sin + my * cos),
+            theta: rotation,
+        };
+
+        delta = compose_pose(&step, &delta);
+        if step.x.hypot(step.y) < 1e-4 && step.theta.abs() < 1e-4 {
+            break;
+        }
+    }
+    Some(delta)
+}
+
+/// Valid returns of a scan as points in the sensor frame
+fn scan_points(scan: &LaserScan) -> Vec<(f64, f64)> {
+    scan.ranges
+        .iter()
+        .enumerate()
+        .filter(|(_, range)| range.is_finite() && **range > scan.range_min && **range < scan.range_max)
+        .map(|(i, range)| {
+            let angle = (scan.angle_min + i as f32 * scan.angle_increment) as f64;
+            (*range as f64 * angle.cos(), *range as f64 * angle.sin())
+        })
+        .collect()
+}
+
+/// Pose `delta`, expressed in `base`'s frame, in the frame `base` is expressed in
+fn compose_pose(base: &Pose, delta: &Pose) -> Pose {
+    let (sin, cos) = base.theta.sin_cos();
+    let theta = base.theta + delta.theta;
+    Pose {
+        x: base.x + delta.x * cos - delta.y * sin,
+        y: base.y + delta.x * sin + delta.y * cos,
+        theta: theta.sin().atan2(theta.cos()),
+    }
+}
+
+/// Measurement variance of a loop-closure pose from a node of the given familiarity (0.0 - 1.0)
+///
+/// A fully familiar node measures with `LOOP_CLOSURE_NOISE`; the variance grows inversely
+/// with familiarity so unfamiliar nodes only nudge the estimate.
+pub fn loop_closure_noise(familiarity: f64) -> f64 {
+    LOOP_CLOSURE_NOISE / familiarity.clamp(MIN_FAMILIARITY, 1.0)
+}
+
+/// Estimates wheel slip (0.0 - 1.0) from the yaw rates implied by wheel odometry and the IMU gyro
+///
+/// The gyro is trusted as ground truth; slip is their disagreement relative to the larger
+/// rate, so 0.0 means the wheels agree and 1.0 means odometry is unrelated to the real turn.
+pub fn estimate_slip(odom_yaw_rate: f64, imu_yaw_rate: f64) -> f32 {
+    let scale = odom_yaw_rate.abs().max(imu_yaw_rate.abs()).max(MIN_YAW_RATE);
+    ((odom_yaw_rate - imu_yaw_rate).abs() / scale).min(1.0) as f32
+}
+
+/// EKF prediction of the covariance over `dt` seconds, with process noise (per second)
+/// inflated by wheel slip (0.0 - 1.0)
+pub fn predict_covariance(covariance: &Matrix3<f64>, process_noise: &Matrix3<f64>, slip: f32, dt: f64) -> Matrix3<f64> {
+    covariance + process_noise * dt.max(0.0) * (1.0 + SLIP_COVARIANCE_GAIN * slip.clamp(0.0, 1.0) as f64)
+}
+
+impl DriftEstimator {
+    /// Creates an estimator averaging over the last `window` samples
+    pub fn new(window: usize) -> Self {
+        DriftEstimator {
+            samples: BoundedHistory::new(window.max(2)),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Records the covariance after a filter step taking `dt` seconds
+    pub fn update(&mut self, covariance: &Matrix3<f64>, dt: f64) {
+        self.elapsed += dt;
+        self.samples.push((self.elapsed, covariance.trace()));
+    }
+
+    /// Covariance trace growth per second across the window; 0.0 until two samples exist
+    ///
+    /// Negative while corrections shrink the uncertainty.
+    pub fn drift_rate(&self) -> f64 {
+        match (self.samples.iter().next(), self.samples.last()) {
+            (Some(first), Some(last)) if last.0 > first.0 => (last.1 - first.1) / (last.0 - first.0),
+            _ => 0.0,
+        }
+    }
+}
+
+/// EKF correction of a pose estimate with a direct pose measurement of the given variance
+///
+/// The heading residual is wrapped to [-pi, pi] so a measurement across the wrap-around
+/// pulls the estimate the short way round, and the corrected heading is wrapped likewise.
+pub fn fuse_pose_measurement(estimate: &PoseConfidence, measured: &Pose, noise: f64) -> PoseConfidence {
+    let innovation_covariance = estimate.covariance + Matrix3::from_diagonal_element(noise);
+    let Some(inverse) = innovation_covariance.try_inverse() else {
+        error!("Singular innovation covariance, skipping pose correction");
+        return estimate.clone();
+    };
+    let kalman_gain = estimate.covariance * inverse;
+
+    let heading_error = measured.theta - estimate.pose.theta;
+    let residual = Vector3::new(
+        measured.x - estimate.pose.x,
+        measured.y - estimate.pose.y,
+        heading_error.sin().atan2(heading_error.cos()),
+    );
+    let correction = kalman_gain * residual;
+    let theta = estimate.pose.theta + correction[2];
+
+    PoseConfidence {
+        pose: Pose {
+            x: estimate.pose.x + correction[0],
+            y: estimate.pose.y + correction[1],
+            theta: theta.sin().atan2(theta.cos()),
+        },
+        covariance: (Matrix3::identity() - kalman_gain) * estimate.covariance,
+    }
+}
+
 // Weaknesses:
-// - Simplified EKF lacks full sensor fusion (IMU, LiDAR, vision); needs real sensor data integration.
+// - Fuses IMU and LiDAR only; vision and GPS are not integrated yet.
 // Future improvement: Implement SLAM (e.g., graph-based) or particle filter for robustness.
-// - No loop closure or drift compensation; requires landmark-based corrections.
-// Future improvement: Add ORB-SLAM3 or RTAB-Map for loop closure.
-// - Hardcoded 10 Hz update rate; needs dynamic timing based on ROS 2 clock.
-// - Mock measurement data; actual sensor parsing needed for MVP demo.
+// - Scan matching is brute-force nearest-neighbour ICP; needs a k-d tree for dense scans,
+//   and only consecutive scans are matched, so its error accumulates like odometry.
+// - Accelerometer bias is not estimated, so velocity drifts between scan corrections; without
+//   a scan match it isn't corrected at all.
+// - IMU samples share the update's time step evenly; their own stamps are ignored.
+// - Loop closures correct only the current pose; past trajectory is not re-optimized.
+// Future improvement: Add ORB-SLAM3 or RTAB-Map for graph-based loop closure.
+// - Switching between the ROS clock and the startup fallback mid-run costs one nominal step.
 // - Computational cost of EKF may be high for embedded systems; optimize with fixed-point math.
+// - Slip is estimated from yaw rate only; longitudinal slip (wheels spinning in place) goes unnoticed.
 
 // Current Functionality:
 // - Initializes EKF with configurable sensor topics and noise parameters.
-// - Subscribes to IMU and LiDAR via ROS 2 for future sensor fusion.
-// - Updates pose estimate at 10 Hz with simplified predict-correct cycle.
-// - Provides pose with covariance for navigation and state modules.
+// - Subscribes to IMU and LiDAR via ROS 2, queueing every IMU sample (remapped into the base
+//   frame by `imu_axis_remap`) and keeping the latest scan for the filter.
+// - Predicts over the measured time step (ROS clock, else wall clock; capped after stalls) by
+//   integrating IMU yaw rate and planar acceleration (coasting without IMU).
+// - Reports the effective update rate in its status.
+// - Corrects with the pose from ICP scan matching of consecutive LiDAR scans, and resets the
+//   velocity to the matched motion over the time between them.
+// - Provides pose with covariance for navigation and state modules, plus a scalar confidence.
+// - Accepts an operator-seeded initial pose and covariance.
+// - Estimates wheel slip from odometry vs IMU yaw rate and inflates process noise accordingly.
+// - Pulls the estimate toward a revisited map node's pose when memory detects a loop closure,
+//   weighted by the node's familiarity.
+// - Reports the drift rate (covariance trace growth per second) for relocalization decisions.
@@ -2,58 +2,189 @@
 
 // Implements hippocampus-like navigation memory for Eos, storing topological maps,
 // recent trajectories, and loop closures to support adaptive navigation. Serializes
-// memory for persistence and supports loop closure detection for localization.
+// memory for persistence (YAML, or bincode for large maps) and supports loop closure
+// detection for localization.
 
 // Dependencies
-use log::{error, info};
-use nalgebra::Vector2;
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use log::info;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs::File;
-use std::path::Path;
+use std::io::{BufReader, BufWriter, Write};
 use super::localization::Pose;
+use super::util::BoundedHistory;
+
+// Trajectory poses kept by `Memory::new`
+const DEFAULT_TRAJECTORY_CAPACITY: usize = 100;
+
+// Distance within which a trajectory pose counts as visiting a node (m)
+const VISIT_RADIUS: f64 = 0.5;
+
+// Minimum spacing between places remembered as nodes (m)
+const NODE_SPACING: f64 = 1.0;
+
+// On-disk memory format, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MemoryFormat {
+    /// Human-readable YAML (`.yaml`, and any unrecognized extension)
+    #[default]
+    Yaml,
+    /// Compact bincode (`.bin`), much smaller and faster for large maps
+    Bincode,
+}
+
+impl MemoryFormat {
+    /// Format for a memory file path
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".bin") {
+            MemoryFormat::Bincode
+        } else {
+            MemoryFormat::Yaml
+        }
+    }
+}
 
 // Node in topological map, representing a familiar location
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct MapNode {
     id: u64,
     pose: Pose,             // Position (x, y, theta)
-    features: Vec<String>,  // Landmarks (e.g., "wall", "door")
+    features: Vec<String>,  // Landmarks seen there, repeats counted (e.g., "wall", "doorway")
     familiarity: f64,       // Confidence score [0, 1]
 }
 
+// Confirmed revisit of a remembered node
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoopClosure {
+    pub node_id: u64,          // Node being revisited
+    pub geometric_error: f64,  // Planar distance between the current pose and the node (m)
+    pub feature_overlap: f64,  // Multiset Jaccard overlap of observed and stored features [0, 1]
+}
+
+// Node waiting in the route search, ordered so the heap pops the shortest distance first
+struct RouteEntry {
+    distance: f64,
+    node: u64,
+}
+
+impl PartialEq for RouteEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RouteEntry {}
+
+impl PartialOrd for RouteEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RouteEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.total_cmp(&self.distance)
+    }
+}
+
+// Default minimum feature overlap for confirming a loop closure
+fn default_min_feature_overlap() -> f64 {
+    0.5
+}
+
+// Trajectory as stored by either the current or an older memory file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredTrajectory {
+    Bounded(BoundedHistory<Pose>),
+    Legacy(Vec<Pose>), // Plain pose sequence, from before the trajectory recorded its capacity
+}
+
+// Reads the trajectory, accepting the plain pose sequence older YAML files hold
+//
+// Only self-describing formats can tell the two shapes apart, so binary files must be current.
+fn deserialize_trajectory<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BoundedHistory<Pose>, D::Error> {
+    if !deserializer.is_human_readable() {
+        return BoundedHistory::deserialize(deserializer);
+    }
+    Ok(match StoredTrajectory::deserialize(deserializer)? {
+        StoredTrajectory::Bounded(trajectory) => trajectory,
+        StoredTrajectory::Legacy(poses) => {
+            let mut trajectory = BoundedHistory::new(poses.len().max(DEFAULT_TRAJECTORY_CAPACITY));
+            for pose in poses {
+                trajectory.push(pose);
+            }
+            trajectory
+        }
+    })
+}
+
 // Memory struct: Manages topological map, trajectory, and loop closures
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Memory {
     topological_map: HashMap<u64, MapNode>, // Familiar locations
-    trajectory: VecDeque<Pose>,             // Recent poses (last 100)
+    #[serde(deserialize_with = "deserialize_trajectory")]
+    trajectory: BoundedHistory<Pose>,       // Recent poses, up to the configured capacity
     loop_closures: Vec<(u64, u64)>,         // Pairs of revisited nodes
     node_counter: u64,                      // Incremental node IDs
+    #[serde(default)]
+    edges: HashMap<u64, Vec<u64>>,          // Traversable links between nodes (both directions)
+    #[serde(default = "default_min_feature_overlap")]
+    min_feature_overlap: f64,               // Jaccard overlap needed to confirm a loop closure
+    #[serde(default)]
+    last_visited: Option<u64>,              // Node the trajectory most recently passed through
+    #[serde(skip)]
+    closed_node: Option<u64>,               // Node of the loop closure in progress, if any
 }
 
 impl Memory {
-    /// Initializes memory with empty map and trajectory buffer
+    /// Initializes memory with empty map and a 100-pose trajectory buffer
     pub fn new() -> Self {
+        Memory::with_capacity(DEFAULT_TRAJECTORY_CAPACITY)
+    }
+
+    /// Initializes memory keeping the last `capacity` trajectory poses
+    pub fn with_capacity(capacity: usize) -> Self {
         Memory {
             topological_map: HashMap::new(),
-            trajectory: VecDeque::with_capacity(100),
+            trajectory: BoundedHistory::new(capacity),
             loop_closures: Vec::new(),
             node_counter: 0,
+            edges: HashMap::new(),
+            min_feature_overlap: default_min_feature_overlap(),
+            last_visited: None,
+            closed_node: None,
         }
     }
 
+    /// Sets the feature overlap [0, 1] a geometric match needs to count as a loop closure
+    pub fn set_min_feature_overlap(&mut self, overlap: f64) {
+        self.min_feature_overlap = overlap.clamp(0.0, 1.0);
+    }
+
     /// Adds a new pose to the trajectory buffer
+    ///
+    /// A pose within `VISIT_RADIUS` of a node other than the last one visited links the
+    /// two nodes, since the robot just travelled between them.
     pub fn add_pose(&mut self, pose: Pose) {
-        if self.trajectory.len() >= 100 {
-            self.trajectory.pop_front();
+        if let Some(node) = self.nearest_node(pose.x, pose.y, VISIT_RADIUS) {
+            if let Some(previous) = self.last_visited
+                && previous != node
+                && self.add_edge(previous, node)
+            {
+                info!("Linked map nodes {} and {} from the trajectory", previous, node);
+            }
+            self.last_visited = Some(node);
         }
-        self.trajectory.push_back(pose);
+        self.trajectory.push(pose);
     }
 
     /// Adds a new node to the topological map with detected features
     pub fn add_map_node(&mut self, pose: Pose, features: Vec<String>) -> u64 {
         let id = self.node_counter;
         self.node_counter += 1;
+        info!("Added map node {} at x={}, y={}", id, pose.x, pose.y);
         let node = MapNode {
             id,
             pose,
@@ -61,62 +192,246 @@ impl Memory {
             familiarity: 0.8, // Mock familiarity score
         };
         self.topological_map.insert(id, node);
-        info!("Added map node {} at x={}, y={}", id, pose.x, pose.y);
         id
     }
 
-    /// Checks for loop closure by comparing current pose to past nodes
-    pub fn check_loop_closure(&self, current_pose: &Pose) -> Option<u64> {
-        for (id, node) in &self.topological_map {
-            let distance = ((current_pose.x - node.pose.x).powi(2)
-                + (current_pose.y - node.pose.y).powi(2))
-                .sqrt();
-            if distance < 0.5 && (current_pose.theta - node.pose.theta).abs() < 0.1 {
-                info!("Loop closure detected with node {}", id);
-                return Some(*id);
+    /// Records that the robot can travel directly between two nodes; false if either is unknown
+    pub fn add_edge(&mut self, a: u64, b: u64) -> bool {
+        if a == b || !self.topological_map.contains_key(&a) || !self.topological_map.contains_key(&b) {
+            return false;
+        }
+        for (from, to) in [(a, b), (b, a)] {
+            let links = self.edges.entry(from).or_default();
+            if !links.contains(&to) {
+                links.push(to);
+            }
+        }
+        true
+    }
+
+    /// Remembers the place at `pose` with the features observed there
+    ///
+    /// Adds a node unless one already lies within `NODE_SPACING`, or nothing was
+    /// observed; returns the new node's id.
+    pub fn remember_place(&mut self, pose: &Pose, features: &[String]) -> Option<u64> {
+        if features.is_empty() || self.nearest_node(pose.x, pose.y, NODE_SPACING).is_some() {
+            return None;
+        }
+        Some(self.add_map_node(pose.clone(), features.to_vec()))
+    }
+
+    /// Closest node within `radius` meters of (x, y), if any
+    pub fn nearest_node(&self, x: f64, y: f64, radius: f64) -> Option<u64> {
+        self.topological_map
+            .values()
+            .map(|node| (node.id, ((node.pose.x - x).powi(2) + (node.pose.y - y).powi(2)).sqrt()))
+            .filter(|(_, distance)| *distance <= radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id)
+    }
+
+    /// Shortest chain of connected nodes from `from` to `to` (both included), by travelled distance
+    pub fn find_route(&self, from: u64, to: u64) -> Option<Vec<u64>> {
+        if !self.topological_map.contains_key(&from) || !self.topological_map.contains_key(&to) {
+            return None;
+        }
+
+        // Dijkstra over the node graph
+        let mut distance: HashMap<u64, f64> = HashMap::from([(from, 0.0)]);
+        let mut previous: HashMap<u64, u64> = HashMap::new();
+        let mut done: HashSet<u64> = HashSet::new();
+        let mut open = BinaryHeap::from([RouteEntry { distance: 0.0, node: from }]);
+        while let Some(RouteEntry { distance: current_distance, node: current }) = open.pop() {
+            if current == to {
+                break;
+            }
+            // Skip entries superseded by a shorter route found since they were queued
+            if !done.insert(current) {
+                continue;
+            }
+            let pose = &self.topological_map[&current].pose;
+            for next in self.edges.get(&current).into_iter().flatten() {
+                let next_pose = &self.topological_map[next].pose;
+                let candidate = current_distance + ((next_pose.x - pose.x).powi(2) + (next_pose.y - pose.y).powi(2)).sqrt();
+                if distance.get(next).is_none_or(|known| candidate < *known) {
+                    distance.insert(*next, candidate);
+                    previous.insert(*next, current);
+                    open.push(RouteEntry { distance: candidate, node: *next });
+                }
             }
         }
-        None
+
+        // Walk back from the destination
+        let mut path = vec![to];
+        while let Some(prev) = previous.get(path.last()?) {
+            path.push(*prev);
+        }
+        if *path.last()? != from {
+            return None;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Poses of the remembered route between the nodes nearest `start` and `goal`
+    ///
+    /// Both ends must lie within `radius` meters of a node; None when either does
+    /// not or the nodes are not connected.
+    pub fn route(&self, start: &Pose, goal: &Pose, radius: f64) -> Option<Vec<Pose>> {
+        let from = self.nearest_node(start.x, start.y, radius)?;
+        let to = self.nearest_node(goal.x, goal.y, radius)?;
+        let ids = self.find_route(from, to)?;
+        Some(ids.iter().map(|id| self.topological_map[id].pose.clone()).collect())
+    }
+
+    /// Checks for loop closure against past nodes near the current pose
+    ///
+    /// A node within 0.5 m and 0.1 rad is only confirmed when the Jaccard overlap of its
+    /// stored features and `observed_features`, counting repeated labels, reaches the
+    /// configured minimum, so similar poses in different places aren't merged. The closest confirmed node wins. Nodes and
+    /// observations without any features have no overlap.
+    pub fn check_loop_closure(&self, current_pose: &Pose, observed_features: &[String]) -> Option<LoopClosure> {
+        let closure = self
+            .topological_map
+            .values()
+            .filter_map(|node| {
+                let distance = ((current_pose.x - node.pose.x).powi(2)
+                    + (current_pose.y - node.pose.y).powi(2))
+                    .sqrt();
+                let heading_error = current_pose.theta - node.pose.theta;
+                if distance >= 0.5 || heading_error.sin().atan2(heading_error.cos()).abs() >= 0.1 {
+                    return None;
+                }
+                let overlap = feature_overlap(&node.features, observed_features);
+                if overlap < self.min_feature_overlap {
+                    info!("Rejected loop closure with node {}: feature overlap {:.2}", node.id, overlap);
+                    return None;
+                }
+                Some(LoopClosure {
+                    node_id: node.id,
+                    geometric_error: distance,
+                    feature_overlap: overlap,
+                })
+            })
+            .min_by(|a, b| a.geometric_error.total_cmp(&b.geometric_error))?;
+        info!("Loop closure detected with node {}", closure.node_id);
+        Some(closure)
     }
 
-    /// Serializes memory to a file for persistence
+    /// Stored pose of the node the current pose and features close a loop with, if any
+    pub fn loop_closure_pose(&self, current_pose: &Pose, observed_features: &[String]) -> Option<(u64, Pose)> {
+        let closure = self.check_loop_closure(current_pose, observed_features)?;
+        self.topological_map.get(&closure.node_id).map(|node| (closure.node_id, node.pose.clone()))
+    }
+
+    /// Records the node currently closing a loop (None when none does)
+    ///
+    /// Returns true only when this starts a new closure event, so a correction is applied
+    /// once per revisit rather than on every update spent near the node.
+    pub fn mark_loop_closure(&mut self, closed: Option<u64>) -> bool {
+        let started = closed.is_some() && closed != self.closed_node;
+        self.closed_node = closed;
+        started
+    }
+
+    /// Familiarity [0, 1] of a node, None if unknown
+    pub fn familiarity(&self, id: u64) -> Option<f64> {
+        self.topological_map.get(&id).map(|node| node.familiarity)
+    }
+
+    /// Records that the node `current` revisits the remembered node `previous`
+    pub fn record_loop_closure(&mut self, previous: u64, current: u64) {
+        self.loop_closures.push((previous, current));
+    }
+
+    /// Pairs of nodes recorded as the same place
+    pub fn loop_closures(&self) -> &[(u64, u64)] {
+        &self.loop_closures
+    }
+
+    /// Serializes memory to a file, as YAML or bincode depending on the extension
     pub fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let file = File::create(path)?;
-        serde_yaml::to_writer(file, self)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        match MemoryFormat::from_path(path) {
+            MemoryFormat::Yaml => serde_yaml::to_writer(&mut file, self)?,
+            MemoryFormat::Bincode => bincode::serialize_into(&mut file, self)?,
+        }
+        // Dropping the writer would swallow a failed final write
+        file.flush()?;
         info!("Saved memory to {}", path);
         Ok(())
     }
 
-    /// Loads memory from a file
+    /// Loads memory from a file, as YAML or bincode depending on the extension
     pub fn load(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let file = File::open(path)?;
-        let memory: Memory = serde_yaml::from_reader(file)?;
+        let file = BufReader::new(File::open(path)?);
+        let memory: Memory = match MemoryFormat::from_path(path) {
+            MemoryFormat::Yaml => serde_yaml::from_reader(file)?,
+            MemoryFormat::Bincode => bincode::deserialize_from(file)?,
+        };
         info!("Loaded memory from {}", path);
         Ok(memory)
     }
 
     /// Returns recent trajectory
-    pub fn get_trajectory(&self) -> &VecDeque<Pose> {
+    pub fn get_trajectory(&self) -> &BoundedHistory<Pose> {
         &self.trajectory
     }
 
+    /// Number of poses in the trajectory buffer
+    pub fn trajectory_len(&self) -> usize {
+        self.trajectory.len()
+    }
+
     /// Returns topological map
     pub fn get_topological_map(&self) -> &HashMap<u64, MapNode> {
         &self.topological_map
     }
 }
 
+/// Jaccard overlap of two feature multisets; 0 when both are empty
+///
+/// The extractor only knows a few labels, so two corners and a wall must differ from one
+/// corner and four walls: each label counts as often as it was seen.
+fn feature_overlap(a: &[String], b: &[String]) -> f64 {
+    fn count(features: &[String]) -> HashMap<&str, usize> {
+        let mut counts = HashMap::new();
+        for feature in features {
+            *counts.entry(feature.as_str()).or_default() += 1;
+        }
+        counts
+    }
+    let (a, b) = (count(a), count(b));
+    let labels: HashSet<&str> = a.keys().chain(b.keys()).copied().collect();
+    let (mut shared, mut union) = (0, 0);
+    for label in labels {
+        let (in_a, in_b) = (a.get(label).copied().unwrap_or(0), b.get(label).copied().unwrap_or(0));
+        shared += in_a.min(in_b);
+        union += in_a.max(in_b);
+    }
+    if union == 0 {
+        return 0.0;
+    }
+    shared as f64 / union as f64
+}
+
 // Weaknesses:
-// - Simplified loop closure detection (distance-based); lacks robust feature matching.
-// Future improvement: Use ORB features or SNN-based place recognition for hippocampus-like memory.
-// - Fixed-size trajectory buffer (100 poses); may need dynamic sizing for scalability.
-// - Mock familiarity scores; needs integration with perception.rs for real feature data.
-// - Serialization uses YAML, which may be slow for large maps; consider binary formats (e.g., bincode).
+// - Loop closure features are coarse labels ("corner", "wall", "doorway") compared as label
+//   counts; places with the same counts still alias. Future improvement: ORB descriptors or SNN-based place recognition.
+// - Trajectory capacity is fixed at construction; long missions must size it up front.
+// - Mock familiarity scores; nodes don't yet grow more familiar on each revisit.
+// - Binary files have no version header; a change to the node layout makes old .bin files unreadable.
 // - No multi-robot support; future versions should share maps across robots.
+// - Trajectory links assume straight travel between consecutive visited nodes; a pose jump
+//   after relocalization can link nodes that aren't directly reachable.
 
 // Current Functionality:
-// - Stores topological map with nodes (pose, features, familiarity).
-// - Maintains a recent trajectory buffer (100 poses).
-// - Detects loop closures using simple distance-based checks.
-// - Serializes/loads memory to/from YAML files.
+// - Stores topological map with nodes (pose, features, familiarity), adding one for each new
+//   place with the features perception extracted there.
+// - Maintains a recent trajectory buffer (100 poses by default, configurable with `with_capacity`).
+// - Detects loop closures from pose proximity confirmed by a minimum feature overlap, and reports
+//   the matched node's pose so localization can correct drift.
+// - Serializes/loads memory to/from YAML files, or bincode files for large maps.
 // - Provides access to trajectory and map for navigation and localization.
+// - Links nodes explicitly or as the trajectory passes between them, and finds the shortest
+//   remembered route between places (Dijkstra over inter-node distance) for the planner.
@@ -5,22 +5,68 @@
 // controlled access to critical OS functions. 
 
 // Expose submodules publicly for other Eos components (e.g., navigation, apps)
+pub mod api;
+pub mod arbiter;
+pub mod behavior;
+pub mod control;
 pub mod localization;
 pub mod perception;
+pub mod sim;
 pub mod state;
-mod memory;
+pub mod tracking;
+pub mod util;
+pub mod apps;
 pub mod memory;
 
 // Re-export key types and functions for a unified API, minimizing external dependencies
-pub use localization::{Localization, Pose, PoseConfidence};
-pub use perception::{Perception, OccupancyGrid, SemanticObject, Snapshot};
-pub use state::{CoreState, Mode};
+pub use api::Api;
+pub use arbiter::{CommandArbiter, CommandCandidate, CommandPriority};
+pub use behavior::{BehaviorNode, BehaviorTree, BehaviorTreeBuilder, BehaviorTreeError, NodeStatus, TickContext};
+pub use control::{Pid, PidGains};
+pub use localization::{
+    estimate_slip, fuse_pose_measurement, initial_covariance, integrate_imu, loop_closure_noise, predict_covariance, predict_state, scan_match, scan_velocity, DriftEstimator,
+    Localization, LocalizationStatus, Pose, PoseConfidence,
+};
+pub use memory::{LoopClosure, Memory, MemoryFormat};
+pub use perception::{Perception, OccupancyGrid, SemanticObject, Snapshot, SnapshotDiff};
+pub use sim::{LaserSimConfig, LaserSimulator, SimulatedRobot};
+pub use state::{CoreState, Mode, StateConfig, StateEvent, StateEventSender};
+pub use tracking::{DynamicObject, ObjectTracker, TrackerConfig};
+pub use util::{BoundedHistory, CycleTimer, UpdateRate};
 
 // Imports for internal use
-use log::error;
-use r2r::QosProfile;
+use log::info;
+use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
 
+/// Core system settings (the `core` section of params.yaml)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoreConfig {
+    /// Localization update rate (Hz)
+    pub localization_rate: f64,
+    /// Perception update rate (Hz)
+    pub perception_rate: f64,
+    /// State machine update rate (Hz)
+    pub state_update_rate: f64,
+    /// Seconds between memory saves
+    pub memory_persistence_interval: u64,
+    /// Maximum number of trajectory poses kept in memory
+    pub max_memory_entries: usize,
+}
+
+impl Default for CoreConfig {
+    fn default() -> Self {
+        CoreConfig {
+            localization_rate: 10.0,
+            perception_rate: 15.0,
+            state_update_rate: 5.0,
+            memory_persistence_interval: 30,
+            max_memory_entries: 1000,
+        }
+    }
+}
+
 // Core struct: Orchestrates brainstem functionality, integrating localization,
 // perception, and state management
 #[derive(Clone)]
@@ -28,40 +74,80 @@ pub struct Core {
     localization: Arc<Mutex<Localization>>,
     perception: Arc<Mutex<Perception>>,
     state: Arc<Mutex<CoreState>>,
+    memory: Arc<Mutex<Memory>>,
+    battery_level: Arc<Mutex<f64>>, // Latest reported state of charge (0.0 - 1.0)
 }
 
 impl Core {
     /// Initializes the brainstem with ROS 2 node, SNN, and configuration
-    pub fn new(ros_node: &r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(ros_node: &mut r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let localization = Arc::new(Mutex::new(Localization::new(ros_node, config_path)?));
         let perception = Arc::new(Mutex::new(Perception::new(ros_node, config_path)?));
         let state = Arc::new(Mutex::new(CoreState::new()));
+        let memory = Arc::new(Mutex::new(Memory::new()));
 
         Ok(Core {
             localization,
             perception,
             state,
+            memory,
+            battery_level: Arc::new(Mutex::new(1.0)),
         })
     }
 
+    /// Reports the battery state of charge (0.0 - 1.0) used for the Charging mode
+    pub fn set_battery_level(&self, level: f64) {
+        *self.battery_level.lock().unwrap() = level;
+    }
+
+    /// Returns a handle other modules can clone to queue state events
+    pub fn state_events(&self) -> StateEventSender {
+        self.state.lock().unwrap().event_sender()
+    }
+
+    /// Reports whether navigation has a goal, which keeps an idle robot from exploring
+    pub fn set_goal_active(&self, active: bool) {
+        self.state.lock().unwrap().set_goal_active(active);
+    }
+
     /// Updates the core state based on new sensor data and mode
     pub fn update(&self) -> Result<(), Box<dyn std::error::Error>> {
         let mut localization = self.localization.lock().unwrap();
         let mut perception = self.perception.lock().unwrap();
         let mut state = self.state.lock().unwrap();
+        let mut memory = self.memory.lock().unwrap();
 
         // Update localization with latest sensor data
         localization.update()?;
-        // Update perception with new sensor snapshot
-        perception.update()?;
-        // Update state based on localization and perception
-        state.update(&localization.get_current_pose(), &perception.get_snapshot())?;
+        // Update perception with new sensor snapshot, extracting this scan's features
+        let pose = localization.get_current_pose().pose;
+        perception.update(&pose)?;
+        // Revisiting a known place pulls the drifted estimate back toward the stored node pose,
+        // once per revisit; a new place is remembered with what was seen there
+        let closure = memory.loop_closure_pose(&pose, perception.observed_features());
+        let new_closure = memory.mark_loop_closure(closure.as_ref().map(|(id, _)| *id));
+        match closure {
+            Some((id, node_pose)) if new_closure => {
+                info!("Correcting pose with loop closure to node {}", id);
+                localization.apply_loop_closure(&node_pose, memory.familiarity(id).unwrap_or(0.0));
+            }
+            Some(_) => {}
+            None => {
+                memory.remember_place(&pose, perception.observed_features());
+            }
+        }
+        // The trajectory links the places it passes between
+        memory.add_pose(localization.get_current_pose().pose);
+        // Update state based on localization, perception, and battery
+        let battery_level = *self.battery_level.lock().unwrap();
+        state.update(&localization.get_current_pose(), &perception.get_snapshot(), battery_level)?;
+        state.report_drift_rate(localization.drift_rate());
 
         Ok(())
     }
 
-    /// Returns the current robot pose
-    pub fn get_pose(&self) -> Pose {
+    /// Returns the current robot pose with its covariance
+    pub fn get_pose(&self) -> PoseConfidence {
         self.localization.lock().unwrap().get_current_pose()
     }
 
@@ -70,6 +156,11 @@ impl Core {
         self.perception.lock().unwrap().get_snapshot()
     }
 
+    /// Stops the robot, leaving it Idle until it gets a new goal
+    pub fn emergency_stop(&self) {
+        self.state.lock().unwrap().emergency_stop();
+    }
+
     /// Returns the current mode (navigate, relocalize, idle, panic)
     pub fn get_mode(&self) -> Mode {
         self.state.lock().unwrap().get_mode()
@@ -87,4 +178,7 @@ impl Core {
 // - Initializes localization, perception, and state with ROS 2 integration.
 // - Provides a unified API for pose, snapshot, and mode queries.
 // - Updates all subsystems in a single call, ensuring consistency.
+// - Feeds loop closures detected by memory back into localization as pose corrections, matched
+//   against the features perception extracted from the same cycle's scan.
+// - Records each updated pose in memory's trajectory, linking the places it passes between.
 // - Thread-safe for concurrent access by navigation or apps.
@@ -5,12 +5,22 @@
 // environment, including landmarks, walls, and tagged objects (e.g., people, cars).
 
 // Dependencies
-use log::{error, info};
-use nalgebra::{Matrix2, Vector2};
-use r2r::{sensor_msgs::msg::LaserScan, QosProfile};
+use log::{info, warn};
+use nalgebra::Vector2;
+use r2r::{sensor_msgs::msg::{LaserScan, PointCloud2, PointField}, QosProfile};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use super::localization::Pose;
+use super::tracking::{DynamicObject, ObjectTracker, TrackerConfig};
+use super::util::CycleTimer;
+use crate::ros_interface::subscribe_with;
+
+/// Log-odds above which a cell counts as occupied (probability 0.7)
+const OCCUPIED_LOG_ODDS: f64 = 0.85;
+/// Log-odds below which a cell counts as free (probability 0.3)
+const FREE_LOG_ODDS: f64 = -0.85;
 
 // Occupancy grid: 2D grid representing free/occupied/unknown spaces
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -18,7 +28,297 @@ pub struct OccupancyGrid {
     width: usize,
     height: usize,
     resolution: f64, // Meters per cell
+    #[serde(default)]
+    origin: (f64, f64), // World position of the (0, 0) cell corner
     data: Vec<i8>,   // -1: unknown, 0: free, 1: occupied
+    #[serde(default)]
+    log_odds: Vec<f64>, // Occupancy belief per cell; empty until the first Bayesian update
+}
+
+impl OccupancyGrid {
+    /// Creates an all-unknown grid whose (0, 0) cell corner sits at `origin`
+    pub fn new(width: usize, height: usize, resolution: f64, origin: (f64, f64)) -> Self {
+        OccupancyGrid {
+            width,
+            height,
+            resolution,
+            origin,
+            data: vec![-1; width * height],
+            log_odds: vec![0.0; width * height],
+        }
+    }
+
+    /// Creates a square grid centered on a world position (e.g., the start pose)
+    pub fn centered(size: usize, resolution: f64, center: (f64, f64)) -> Self {
+        let half_extent = size as f64 * resolution / 2.0;
+        Self::new(size, size, resolution, (center.0 - half_extent, center.1 - half_extent))
+    }
+
+    /// Returns the cell edge length in meters
+    pub fn resolution(&self) -> f64 {
+        self.resolution
+    }
+
+    /// Returns the world position of the (0, 0) cell corner
+    pub fn origin(&self) -> (f64, f64) {
+        self.origin
+    }
+
+    /// Returns the number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Maps a world position to its (column, row) cell, or None outside the grid
+    pub fn world_to_cell(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let col = ((x - self.origin.0) / self.resolution).floor();
+        let row = ((y - self.origin.1) / self.resolution).floor();
+        // Written so NaN fails every comparison and lands outside
+        let inside = col >= 0.0 && row >= 0.0 && col < self.width as f64 && row < self.height as f64;
+        inside.then_some((col as usize, row as usize))
+    }
+
+    /// Returns the value of a cell (-1 unknown, 0 free, 1 occupied), or None outside the grid
+    pub fn get_cell(&self, col: usize, row: usize) -> Option<i8> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+        Some(self.data[row * self.width + col])
+    }
+
+    /// Iterates over the (column, row) of every occupied cell
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == 1)
+            .map(|(i, _)| (i % self.width, i / self.width))
+    }
+
+    /// Sets the value of a cell; ignored outside the grid
+    ///
+    /// The cell's occupancy belief is reset to the threshold for that value.
+    pub fn set_cell(&mut self, col: usize, row: usize, value: i8) {
+        if col < self.width && row < self.height {
+            self.data[row * self.width + col] = value;
+            let log_odds = match value {
+                1 => OCCUPIED_LOG_ODDS,
+                0 => FREE_LOG_ODDS,
+                _ => 0.0,
+            };
+            let index = row * self.width + col;
+            self.log_odds_mut()[index] = log_odds;
+        }
+    }
+
+    /// Returns the probability that a cell is occupied (0.5 if never observed), or None outside the grid
+    pub fn probability(&self, col: usize, row: usize) -> Option<f64> {
+        if col >= self.width || row >= self.height {
+            return None;
+        }
+        let log_odds = self.log_odds.get(row * self.width + col).copied().unwrap_or(0.0);
+        Some(1.0 - 1.0 / (1.0 + log_odds.exp()))
+    }
+
+    /// Adds an observation's log-odds to a cell, clamped to `[min, max]`; ignored outside the grid
+    ///
+    /// The cell reads occupied or free once its belief passes the matching threshold,
+    /// and unknown while it lies between them.
+    pub fn update_log_odds(&mut self, col: usize, row: usize, delta: f64, min: f64, max: f64) {
+        if col >= self.width || row >= self.height {
+            return;
+        }
+        let index = row * self.width + col;
+        let log_odds = (self.log_odds_mut()[index] + delta).clamp(min, max);
+        self.log_odds[index] = log_odds;
+        self.data[index] = if log_odds >= OCCUPIED_LOG_ODDS {
+            1
+        } else if log_odds <= FREE_LOG_ODDS {
+            0
+        } else {
+            -1
+        };
+    }
+
+    /// Cells crossed by the segment from one world position to another, in order
+    ///
+    /// Includes the cells of both ends; parts outside the grid are skipped.
+    pub fn cells_along(&self, from: (f64, f64), to: (f64, f64)) -> Vec<(usize, usize)> {
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length / (self.resolution * 0.5)).ceil() as usize;
+        let mut cells: Vec<(usize, usize)> = Vec::with_capacity(steps + 1);
+        for step in 0..=steps {
+            let t = if steps == 0 { 1.0 } else { step as f64 / steps as f64 };
+            if let Some(cell) = self.world_to_cell(from.0 + t * (to.0 - from.0), from.1 + t * (to.1 - from.1))
+                && cells.last() != Some(&cell)
+            {
+                cells.push(cell);
+            }
+        }
+        cells
+    }
+
+    /// Converts the grid into a ROS map message, e.g. for RViz
+    ///
+    /// Observed cells carry their occupancy probability as 0-100; cells never observed
+    /// are -1. Rows run along +y from `origin`, as the message expects.
+    pub fn to_ros_occupancy_grid(&self, frame_id: &str, stamp: Duration) -> r2r::nav_msgs::msg::OccupancyGrid {
+        let stamp = r2r::builtin_interfaces::msg::Time {
+            sec: stamp.as_secs() as i32,
+            nanosec: stamp.subsec_nanos(),
+        };
+        let data = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| match self.log_odds.get(i) {
+                Some(&log_odds) if value != -1 || log_odds != 0.0 => {
+                    (100.0 - 100.0 / (1.0 + log_odds.exp())).round() as i8
+                }
+                // Grids without beliefs (or never-observed cells) report their state as is
+                _ => match value {
+                    1 => 100,
+                    0 => 0,
+                    _ => -1,
+                },
+            })
+            .collect();
+
+        r2r::nav_msgs::msg::OccupancyGrid {
+            header: r2r::std_msgs::msg::Header {
+                stamp: stamp.clone(),
+                frame_id: frame_id.to_string(),
+            },
+            info: r2r::nav_msgs::msg::MapMetaData {
+                map_load_time: stamp,
+                resolution: self.resolution as f32,
+                width: self.width as u32,
+                height: self.height as u32,
+                origin: r2r::geometry_msgs::msg::Pose {
+                    position: r2r::geometry_msgs::msg::Point {
+                        x: self.origin.0,
+                        y: self.origin.1,
+                        z: 0.0,
+                    },
+                    orientation: r2r::geometry_msgs::msg::Quaternion {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                        w: 1.0,
+                    },
+                },
+            },
+            data,
+        }
+    }
+
+    // Beliefs sized to the grid (grids saved before log-odds existed start at 0)
+    fn log_odds_mut(&mut self) -> &mut Vec<f64> {
+        if self.log_odds.len() != self.data.len() {
+            self.log_odds = vec![0.0; self.data.len()];
+        }
+        &mut self.log_odds
+    }
+
+    /// Returns the world position of a cell's center
+    pub fn cell_to_world(&self, col: usize, row: usize) -> (f64, f64) {
+        (
+            self.origin.0 + (col as f64 + 0.5) * self.resolution,
+            self.origin.1 + (row as f64 + 0.5) * self.resolution,
+        )
+    }
+
+    /// Enlarges the grid so a world position lies at least `margin` cells inside it
+    ///
+    /// Each side that is too short grows by at least half the current extent so a robot
+    /// driving steadily outwards does not reallocate every update. An axis that grows is
+    /// kept to `max_size` cells by recycling the cells on its far side, forgetting the map
+    /// furthest from the position. Existing cells keep their world position. Returns
+    /// whether the grid was reallocated.
+    pub fn grow_to_include(&mut self, x: f64, y: f64, margin: usize, max_size: usize) -> bool {
+        let col = ((x - self.origin.0) / self.resolution).floor();
+        let row = ((y - self.origin.1) / self.resolution).floor();
+        if !col.is_finite() || !row.is_finite() {
+            return false;
+        }
+
+        // Cells missing on each side, rounded up to a useful growth step
+        let margin = margin as f64;
+        let step = |missing: f64, extent: usize| -> usize {
+            if missing <= 0.0 {
+                0
+            } else {
+                (missing.ceil() as usize).max(extent / 2)
+            }
+        };
+        let left = step(margin - col, self.width);
+        let right = step(col + 1.0 + margin - self.width as f64, self.width);
+        let bottom = step(margin - row, self.height);
+        let top = step(row + 1.0 + margin - self.height as f64, self.height);
+        if left + right + bottom + top == 0 {
+            return false;
+        }
+
+        // Cell ranges of the new grid, in the old grid's indices
+        let need = |index: f64| (index - margin) as i64..(index + 1.0 + margin) as i64;
+        let (col_start, col_end) = capped_span(left, right, self.width, need(col), max_size);
+        let (row_start, row_end) = capped_span(bottom, top, self.height, need(row), max_size);
+
+        // Copy the old cells still covered into place in the new grid
+        let width = (col_end - col_start) as usize;
+        let height = (row_end - row_start) as usize;
+        let mut data = vec![-1; width * height];
+        let mut log_odds = vec![0.0; width * height];
+        let old_log_odds = std::mem::take(self.log_odds_mut());
+        let (first_col, last_col) = (col_start.max(0), col_end.min(self.width as i64));
+        for old_row in row_start.max(0)..row_end.min(self.height as i64) {
+            if first_col >= last_col {
+                break;
+            }
+            let len = (last_col - first_col) as usize;
+            let src = old_row as usize * self.width + first_col as usize;
+            let dst = (old_row - row_start) as usize * width + (first_col - col_start) as usize;
+            data[dst..dst + len].copy_from_slice(&self.data[src..src + len]);
+            log_odds[dst..dst + len].copy_from_slice(&old_log_odds[src..src + len]);
+        }
+
+        self.origin = (
+            self.origin.0 + col_start as f64 * self.resolution,
+            self.origin.1 + row_start as f64 * self.resolution,
+        );
+        self.width = width;
+        self.height = height;
+        self.data = data;
+        self.log_odds = log_odds;
+        true
+    }
+}
+
+/// Cell range `[start, end)` of one grid axis after growing `low` cells below and `high`
+/// above its `extent`, in the old indices
+///
+/// A range that grew past `max_size` cells is cut back on the side away from the growth,
+/// never so far that it stops covering `need`.
+fn capped_span(low: usize, high: usize, extent: usize, need: std::ops::Range<i64>, max_size: usize) -> (i64, i64) {
+    let start = -(low as i64);
+    let end = (extent + high) as i64;
+    let max_size = (max_size as i64).max(need.end - need.start);
+    if end - start <= max_size {
+        (start, end)
+    } else if low > 0 {
+        let start = start.max(need.end - max_size);
+        (start, start + max_size)
+    } else if high > 0 {
+        let end = end.min(need.start + max_size);
+        (end - max_size, end)
+    } else {
+        (start, end)
+    }
 }
 
 // Semantic object: Represents recognized features (e.g., wall, person)
@@ -34,6 +334,112 @@ pub struct SemanticObject {
 pub struct Snapshot {
     pub(crate) grid: OccupancyGrid,
     objects: HashMap<u64, SemanticObject>,
+    #[serde(default)]
+    dynamic_objects: Vec<DynamicObject>, // Tracked objects with velocities and predicted positions
+}
+
+// Snapshot diff: Changes between two perception snapshots
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapshotDiff {
+    pub flipped_cells: Vec<(usize, usize)>, // (col, row) cells that switched free <-> occupied
+    pub appeared: Vec<u64>,                 // Object IDs only in the newer snapshot
+    pub disappeared: Vec<u64>,              // Object IDs only in the older snapshot
+    pub moved: Vec<u64>,                    // Object IDs whose position changed
+}
+
+impl SemanticObject {
+    /// Creates a semantic object of the given class at a position
+    pub fn new(id: u64, class: &str, position: Vector2<f64>) -> Self {
+        SemanticObject {
+            id,
+            class: class.to_string(),
+            position,
+        }
+    }
+
+    /// Object class, e.g. "person"
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    /// Object position in the map frame (meters)
+    pub fn position(&self) -> Vector2<f64> {
+        self.position
+    }
+}
+
+impl Snapshot {
+    /// Creates a snapshot from a grid and a set of objects
+    pub fn new(grid: OccupancyGrid, objects: Vec<SemanticObject>) -> Self {
+        Snapshot {
+            grid,
+            objects: objects.into_iter().map(|object| (object.id, object)).collect(),
+            dynamic_objects: Vec::new(),
+        }
+    }
+
+    /// Returns the tracked objects with their estimated motion
+    pub fn dynamic_objects(&self) -> &[DynamicObject] {
+        &self.dynamic_objects
+    }
+
+    /// Returns the snapshot's occupancy grid
+    pub fn grid(&self) -> &OccupancyGrid {
+        &self.grid
+    }
+
+    /// Reports what changed from `self` (older) to `other` (newer)
+    ///
+    /// Cells are compared index by index over the overlapping area; unknown
+    /// cells becoming known are exploration, not change, and are skipped.
+    pub fn diff(&self, other: &Snapshot) -> SnapshotDiff {
+        const MOVE_TOLERANCE: f64 = 0.1; // Displacement counted as a move (m)
+
+        let mut diff = SnapshotDiff::default();
+
+        // Cells that flipped between free and occupied
+        for row in 0..self.grid.height.min(other.grid.height) {
+            for col in 0..self.grid.width.min(other.grid.width) {
+                let before = self.grid.data[row * self.grid.width + col];
+                let after = other.grid.data[row * other.grid.width + col];
+                if before >= 0 && after >= 0 && before != after {
+                    diff.flipped_cells.push((col, row));
+                }
+            }
+        }
+
+        // Objects that appeared, disappeared, or moved
+        for (id, object) in &other.objects {
+            match self.objects.get(id) {
+                None => diff.appeared.push(*id),
+                Some(previous) if (object.position - previous.position).norm() > MOVE_TOLERANCE => {
+                    diff.moved.push(*id)
+                }
+                Some(_) => {}
+            }
+        }
+        diff.disappeared = self
+            .objects
+            .keys()
+            .filter(|id| !other.objects.contains_key(id))
+            .copied()
+            .collect();
+
+        diff.appeared.sort_unstable();
+        diff.disappeared.sort_unstable();
+        diff.moved.sort_unstable();
+        diff
+    }
+}
+
+impl SnapshotDiff {
+    /// True when nothing changed between the snapshots
+    pub fn is_empty(&self) -> bool {
+        self.flipped_cells.is_empty()
+            && self.appeared.is_empty()
+            && self.disappeared.is_empty()
+            && self.moved.is_empty()
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -41,49 +447,117 @@ struct PerceptionConfig {
     lidar_topic: String,
     grid_resolution: f64,
     grid_size: usize,
+    #[serde(default)]
+    grid_origin: (f64, f64),
+    #[serde(default = "default_floor_height")]
+    floor_height: f64, // Points at or below this height (m) are ground
+    #[serde(default = "default_ceiling_height")]
+    ceiling_height: f64, // Points at or above this height (m) are overhead and ignored
+    #[serde(default)]
+    grow_on_edge: bool, // Enlarge the grid instead of dropping points beyond its edge
+    #[serde(default = "default_max_grid_size")]
+    max_grid_size: usize, // Cells per side growth stops at; the map farthest away is recycled
+    #[serde(default = "default_log_odds_hit")]
+    log_odds_hit: f64, // Added to the cell a laser return ends in
+    #[serde(default = "default_log_odds_miss")]
+    log_odds_miss: f64, // Added to cells a laser ray passes through
+    #[serde(default = "default_log_odds_min")]
+    log_odds_min: f64, // Floor on a cell's belief, so freed cells can still become occupied
+    #[serde(default = "default_log_odds_max")]
+    log_odds_max: f64, // Ceiling on a cell's belief, so moved obstacles can still clear
+    #[serde(default)]
+    tracking: TrackerConfig,
+}
+
+/// Cells kept between the robot and the grid edge when growing the grid
+const EDGE_MARGIN: usize = 20;
+/// Expected time between updates (10 Hz), used until two updates have been timed
+const NOMINAL_UPDATE_DT: f64 = 0.1;
+/// Longest time step tracks are predicted over at once, so a stall doesn't fling them
+const MAX_UPDATE_DT: f64 = 0.5;
+
+fn default_floor_height() -> f64 {
+    0.05
+}
+
+fn default_ceiling_height() -> f64 {
+    1.8
+}
+
+fn default_max_grid_size() -> usize {
+    2000 // 200 m at 0.1 m cells
+}
+
+fn default_log_odds_hit() -> f64 {
+    0.7 // Return probability 0.67
+}
+
+fn default_log_odds_miss() -> f64 {
+    -0.4 // Pass-through probability 0.4
+}
+
+fn default_log_odds_min() -> f64 {
+    -2.0
 }
 
+fn default_log_odds_max() -> f64 {
+    3.5
+}
+
+// Scan received since the last update, written by the LiDAR subscription callback
+type LatestScan = Arc<Mutex<Option<LaserScan>>>;
+
 pub struct Perception {
-    ros_node: Arc<r2r::Node>,
-    lidar_subscriber: r2r::Subscriber<LaserScan>,
+    latest_scan: LatestScan,
     grid: OccupancyGrid,
     objects: HashMap<u64, SemanticObject>,
+    features: Vec<String>, // Landmark labels extracted from the last scan
+    tracker: ObjectTracker,
+    started: Instant, // Time base for measuring update steps
+    timer: CycleTimer,
     config: PerceptionConfig,
 }
 
 impl Perception {
     /// Initializes perception with ROS 2 subscriptions and occupancy grid
-    pub fn new(ros_node: &r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(ros_node: &mut r2r::Node, config_path: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let config_file = std::fs::File::open(config_path)?;
         let config: PerceptionConfig = serde_yaml::from_reader(config_file)?;
 
-        let lidar_subscriber = ros_node.subscribe::<LaserScan>(
-            &config.lidar_topic,
-            QosProfile::default(),
-            Box::new(|_| {}),
-        )?;
-
-        let grid = OccupancyGrid {
-            width: config.grid_size,
-            height: config.grid_size,
-            resolution: config.grid_resolution,
-            data: vec![-1; config.grid_size * config.grid_size],
-        };
+        // The callback only keeps the latest scan; `update` consumes it
+        let latest_scan: LatestScan = Arc::new(Mutex::new(None));
+        let callback_scan = Arc::clone(&latest_scan);
+        subscribe_with(ros_node, &config.lidar_topic, QosProfile::default(), move |msg: LaserScan| {
+            if let Ok(mut latest) = callback_scan.lock() {
+                *latest = Some(msg);
+            }
+        })?;
+
+        let grid = OccupancyGrid::new(
+            config.grid_size,
+            config.grid_size,
+            config.grid_resolution,
+            config.grid_origin,
+        );
 
         Ok(Perception {
-            ros_node: Arc::new(ros_node.clone()),
-            lidar_subscriber,
+            latest_scan,
             grid,
             objects: HashMap::new(),
+            features: Vec::new(),
+            tracker: ObjectTracker::new(config.tracking.clone()),
+            started: Instant::now(),
+            timer: CycleTimer::new(NOMINAL_UPDATE_DT, MAX_UPDATE_DT),
             config,
         })
     }
 
-    /// Updates occupancy grid and semantic objects from sensor data
-    pub fn update(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Simplified update: Mock LiDAR data processing
-        for i in 0..self.grid.data.len() {
-            self.grid.data[i] = if i % 10 == 0 { 1 } else { 0 }; // Mock occupied cells
+    /// Updates occupancy grid and semantic objects from sensor data taken at `pose`
+    pub fn update(&mut self, pose: &Pose) -> Result<(), Box<dyn std::error::Error>> {
+        let scan = self.latest_scan.lock().map_err(|_| "LiDAR scan lock poisoned")?.take();
+        if let Some(scan) = scan {
+            self.integrate_scan(&scan, pose);
+            self.features = self.extract_features(&scan);
         }
 
         // Mock semantic object detection
@@ -95,32 +569,314 @@ impl Perception {
                 position: Vector2::new(2.0, 3.0),
             },
         );
+        let dt = self.timer.tick(self.started.elapsed().as_secs_f64());
+        let detections: Vec<SemanticObject> = self.objects.values().cloned().collect();
+        self.track_objects(&detections, dt);
 
         info!("Updated perception: {} objects detected", self.objects.len());
         Ok(())
     }
 
+    /// Matches this update's detections to the tracked objects, `dt` seconds after the last
+    pub fn track_objects(&mut self, detections: &[SemanticObject], dt: f64) {
+        self.tracker.update(detections, dt);
+    }
+
+    /// Ray-casts a laser scan taken at `pose` into the occupancy grid
+    ///
+    /// Cells along each beam become more likely free and the cell its return ends in
+    /// more likely occupied, so repeated observations build confidence and one-off
+    /// returns fade. Beams without a return clear up to the maximum range. Each cell
+    /// is updated at most once per scan, with a return taking precedence.
+    pub fn integrate_scan(&mut self, scan: &LaserScan, pose: &Pose) {
+        let range_max = scan.range_max as f64;
+
+        // Beam end points in the world frame, flagged by whether they hit something
+        let mut beams = Vec::with_capacity(scan.ranges.len());
+        for (i, &range) in scan.ranges.iter().enumerate() {
+            if range.is_nan() || range < scan.range_min {
+                continue;
+            }
+            let hit = range.is_finite() && range < scan.range_max;
+            let range = if hit { range as f64 } else { range_max };
+            if !range.is_finite() || range <= 0.0 {
+                continue;
+            }
+            let angle = pose.theta + (scan.angle_min + i as f32 * scan.angle_increment) as f64;
+            beams.push(((pose.x + range * angle.cos(), pose.y + range * angle.sin()), hit));
+        }
+
+        // Grow before indexing so every cell refers to the final layout; the robot goes
+        // last so recycling for a far return never drops the cells around it
+        if self.config.grow_on_edge {
+            let max_size = self.config.max_grid_size;
+            let mut grown = false;
+            for ((x, y), hit) in &beams {
+                if *hit {
+                    grown |= self.grid.grow_to_include(*x, *y, 0, max_size);
+                }
+            }
+            grown |= self.grid.grow_to_include(pose.x, pose.y, EDGE_MARGIN, max_size);
+            if grown {
+                info!(
+                    "Grew occupancy grid to {}x{} cells at origin {:?}",
+                    self.grid.width, self.grid.height, self.grid.origin
+                );
+            }
+        }
+
+        let mut occupied = HashSet::new();
+        let mut free = HashSet::new();
+        let mut dropped = 0;
+        for (end, hit) in &beams {
+            let mut cells = self.grid.cells_along((pose.x, pose.y), *end);
+            if *hit && self.grid.world_to_cell(end.0, end.1).is_some() {
+                occupied.extend(cells.pop());
+            } else if *hit {
+                dropped += 1;
+            }
+            free.extend(cells);
+        }
+        if dropped > 0 {
+            warn!("Dropped {} laser returns beyond the occupancy grid edge", dropped);
+        }
+
+        let (min, max) = (self.config.log_odds_min, self.config.log_odds_max);
+        for (col, row) in free.difference(&occupied) {
+            self.grid.update_log_odds(*col, *row, self.config.log_odds_miss, min, max);
+        }
+        for (col, row) in &occupied {
+            self.grid.update_log_odds(*col, *row, self.config.log_odds_hit, min, max);
+        }
+    }
+
+    /// Projects a 3D point cloud (robot frame) taken at `pose` onto the occupancy grid
+    ///
+    /// Points between floor and ceiling height mark their cell occupied; ground points
+    /// mark it free unless an obstacle point from the same cloud landed there. Points
+    /// beyond the grid edge enlarge it when `grow_on_edge` is set and are dropped otherwise.
+    pub fn integrate_pointcloud(&mut self, cloud: &PointCloud2, pose: &Pose) -> Result<(), Box<dyn std::error::Error>> {
+        let points = read_xyz_points(cloud)?;
+        let (sin_theta, cos_theta) = pose.theta.sin_cos();
+
+        // Robot frame -> world frame, dropping overhead points
+        let world_points: Vec<(f64, f64, f64)> = points
+            .into_iter()
+            .filter(|(_, _, z)| *z < self.config.ceiling_height)
+            .map(|(x, y, z)| (pose.x + x * cos_theta - y * sin_theta, pose.y + x * sin_theta + y * cos_theta, z))
+            .collect();
+
+        // Grow before indexing so every cell refers to the final layout, the robot last
+        if self.config.grow_on_edge {
+            let max_size = self.config.max_grid_size;
+            let mut grown = false;
+            for (x, y, _) in &world_points {
+                grown |= self.grid.grow_to_include(*x, *y, 0, max_size);
+            }
+            grown |= self.grid.grow_to_include(pose.x, pose.y, EDGE_MARGIN, max_size);
+            if grown {
+                info!(
+                    "Grew occupancy grid to {}x{} cells at origin {:?}",
+                    self.grid.width, self.grid.height, self.grid.origin
+                );
+            }
+        }
+
+        let mut occupied = Vec::new();
+        let mut ground = Vec::new();
+        let mut dropped = 0;
+        for (x, y, z) in world_points {
+            let Some(cell) = self.grid.world_to_cell(x, y) else {
+                dropped += 1;
+                continue;
+            };
+
+            if z > self.config.floor_height {
+                occupied.push(cell);
+            } else {
+                ground.push(cell);
+            }
+        }
+        if dropped > 0 {
+            warn!("Dropped {} point cloud points beyond the occupancy grid edge", dropped);
+        }
+
+        for (col, row) in &ground {
+            if !occupied.contains(&(*col, *row)) {
+                self.grid.set_cell(*col, *row, 0);
+            }
+        }
+        for (col, row) in &occupied {
+            self.grid.set_cell(*col, *row, 1);
+        }
+
+        info!("Integrated point cloud: {} occupied, {} ground points", occupied.len(), ground.len());
+        Ok(())
+    }
+
+    /// Extracts geometric landmark labels ("corner", "wall", "doorway") from a scan
+    pub fn extract_features(&self, scan: &LaserScan) -> Vec<String> {
+        const WINDOW: usize = 3; // Beams on each side used for the contour derivative
+        const CORNER_ANGLE: f64 = std::f64::consts::FRAC_PI_4; // Min heading change for a corner
+        const MAX_JUMP: f64 = 0.3; // Range discontinuity splitting contiguous surfaces (m)
+        const MIN_WALL_LENGTH: f64 = 1.0; // Min straight extent labelled as wall (m)
+        const WALL_TOLERANCE: f64 = 0.05; // Max deviation from the wall chord (m)
+        const DOOR_WIDTH: (f64, f64) = (0.6, 1.5); // Opening width accepted as doorway (m)
+
+        let mut features = Vec::new();
+
+        // Group valid returns into contiguous surfaces
+        let mut runs: Vec<Vec<Vector2<f64>>> = Vec::new();
+        let mut current: Vec<Vector2<f64>> = Vec::new();
+        let mut last_range: Option<f64> = None;
+        for (i, &range) in scan.ranges.iter().enumerate() {
+            let valid = range.is_finite() && range > scan.range_min && range < scan.range_max;
+            let range = range as f64;
+            let continuous = valid && last_range.map_or(true, |last| (range - last).abs() <= MAX_JUMP);
+            if !continuous && !current.is_empty() {
+                runs.push(std::mem::take(&mut current));
+            }
+            if valid {
+                let angle = (scan.angle_min + i as f32 * scan.angle_increment) as f64;
+                current.push(Vector2::new(range * angle.cos(), range * angle.sin()));
+                last_range = Some(range);
+            } else {
+                last_range = None;
+            }
+        }
+        if !current.is_empty() {
+            runs.push(current);
+        }
+
+        for (run_index, run) in runs.iter().enumerate() {
+            // Heading change of the contour around each point (derivative of the scan outline)
+            let turns: Vec<f64> = (0..run.len())
+                .map(|i| {
+                    if i < WINDOW || i + WINDOW >= run.len() {
+                        return 0.0;
+                    }
+                    let incoming = run[i] - run[i - WINDOW];
+                    let outgoing = run[i + WINDOW] - run[i];
+                    let turn = outgoing.y.atan2(outgoing.x) - incoming.y.atan2(incoming.x);
+                    turn.sin().atan2(turn.cos()).abs()
+                })
+                .collect();
+
+            // Corners are peaks in the heading change
+            let mut corners = Vec::new();
+            for i in 1..turns.len().saturating_sub(1) {
+                if turns[i] > CORNER_ANGLE && turns[i] >= turns[i - 1] && turns[i] > turns[i + 1] {
+                    corners.push(i);
+                    features.push("corner".to_string());
+                }
+            }
+
+            // Straight stretches between corners are walls if long enough
+            let mut bounds = vec![0];
+            bounds.extend(&corners);
+            bounds.push(run.len() - 1);
+            for pair in bounds.windows(2) {
+                let (start, end) = (run[pair[0]], run[pair[1]]);
+                let chord = end - start;
+                let length = chord.norm();
+                if length < MIN_WALL_LENGTH {
+                    continue;
+                }
+                let straight = run[pair[0]..=pair[1]].iter().all(|p| {
+                    let offset = p - start;
+                    (chord.x * offset.y - chord.y * offset.x).abs() / length <= WALL_TOLERANCE
+                });
+                if straight {
+                    features.push("wall".to_string());
+                }
+            }
+
+            // An opening of door width between consecutive surfaces is a doorway
+            if let Some(next) = runs.get(run_index + 1) {
+                let width = (next[0] - run[run.len() - 1]).norm();
+                if width >= DOOR_WIDTH.0 && width <= DOOR_WIDTH.1 {
+                    features.push("doorway".to_string());
+                }
+            }
+        }
+
+        features
+    }
+
+    /// Landmark labels extracted from the most recent scan
+    pub fn observed_features(&self) -> &[String] {
+        &self.features
+    }
+
     /// Returns the current perception snapshot
     pub fn get_snapshot(&self) -> Snapshot {
         Snapshot {
             grid: self.grid.clone(),
             objects: self.objects.clone(),
+            dynamic_objects: self.tracker.objects(),
+        }
+    }
+}
+
+/// Reads the float32 x, y, z fields of every valid point in a cloud
+fn read_xyz_points(cloud: &PointCloud2) -> Result<Vec<(f64, f64, f64)>, Box<dyn std::error::Error>> {
+    let offset_of = |name: &str| -> Result<usize, Box<dyn std::error::Error>> {
+        cloud
+            .fields
+            .iter()
+            .find(|field| field.name == name && field.datatype == PointField::FLOAT32)
+            .map(|field| field.offset as usize)
+            .ok_or_else(|| format!("point cloud has no float32 '{}' field", name).into())
+    };
+    let (x_offset, y_offset, z_offset) = (offset_of("x")?, offset_of("y")?, offset_of("z")?);
+
+    let read = |bytes: &[u8], offset: usize| -> f64 {
+        let raw = [bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]];
+        if cloud.is_bigendian {
+            f32::from_be_bytes(raw) as f64
+        } else {
+            f32::from_le_bytes(raw) as f64
         }
+    };
+
+    let point_step = cloud.point_step as usize;
+    let count = cloud.width as usize * cloud.height as usize;
+    if point_step < x_offset.max(y_offset).max(z_offset) + 4 || cloud.data.len() < count * point_step {
+        return Err("point cloud data is shorter than its declared layout".into());
     }
+
+    Ok(cloud
+        .data
+        .chunks_exact(point_step)
+        .take(count)
+        .map(|point| (read(point, x_offset), read(point, y_offset), read(point, z_offset)))
+        .filter(|(x, y, z)| x.is_finite() && y.is_finite() && z.is_finite())
+        .collect())
 }
 
 // Weaknesses:
-// - Mock data processing; needs real LiDAR parsing and vision-based object detection.
+// - Mock object detection; needs vision-based semantic labelling.
 // Future improvement: Integrate PCL (Point Cloud Library) or YOLOv8 for semantic mapping.
-// - Simplified occupancy grid; lacks probabilistic updates (e.g., Bayesian inference).
+// - Point clouds overwrite cells instead of feeding the log-odds belief like LiDAR scans do.
 // Future improvement: Use OctoMap or Grid Map for 3D/probabilistic mapping.
-// - No dynamic object tracking; needs motion models for people/cars.
-// Future improvement: Add Kalman filter or particle filter for tracking.
+// - Ray casting samples every half cell; an exact grid traversal would be cheaper on long beams.
 // - High memory usage for large grids; optimize with sparse representations.
+// - Growing the grid copies every cell; a tiled map would only allocate new tiles.
+// - Past `max_grid_size` the far side of the map is forgotten, not saved for a later revisit.
 // - No SNN integration for perception; could enhance neuromorphic processing.
 
 // Current Functionality:
 // - Initializes a 2D occupancy grid and semantic object map.
-// - Subscribes to LiDAR via ROS 2 for future data processing.
-// - Updates grid and objects with mock data for MVP demo.
-// - Provides a snapshot for navigation and state modules.
+// - Anchors the grid at a configurable origin so it can be centered on the start pose.
+// - Optionally grows the grid when the robot nears its edge, up to a maximum size beyond which
+//   the cells farthest away are recycled; otherwise warns about dropped points.
+// - Subscribes to LiDAR via ROS 2 and ray-casts each scan into the grid with clamped
+//   log-odds Bayesian updates (free along beams, occupied at returns).
+// - Updates semantic objects with mock data for MVP demo.
+// - Projects 3D point clouds within a floor/ceiling height band onto the grid.
+// - Extracts corner, wall, and doorway landmarks from LiDAR scans for memory nodes.
+// - Tracks detected objects across updates with constant-velocity Kalman filters (tracking.rs).
+// - Converts the grid into a nav_msgs/OccupancyGrid (0-100 probabilities, -1 unknown) for RViz.
+// - Provides a snapshot, including tracked objects and their predicted positions, for
+//   navigation and state modules.
+// - Diffs snapshots to report flipped cells and appeared/disappeared/moved objects.
@@ -1,12 +1,26 @@
 // core/state.rs
 
-// Manages the robot's high-level mode (Idle, Navigating, Lost, Recovering, Mapping)
-// using a finite state machine. Handles mode transitions based on sensor/planner
-// events and provides emergency overrides for safety.
+// Manages the robot's high-level mode (Idle, Navigating, Lost, Recovering, Mapping,
+// Exploring, Charging, Panic)
+// using a finite state machine, or optionally a behavior tree (behavior.rs). Handles mode
+// transitions based on sensor/planner events, queued through an mpsc channel so momentary
+// events between updates aren't lost, and provides emergency overrides for safety.
 
 // Dependencies
-use log::{error, info};
-use super::{localization::PoseConfidence, perception::Snapshot};
+use log::{error, info, warn};
+use super::{
+    behavior::{BehaviorTree, TickContext},
+    localization::PoseConfidence,
+    perception::Snapshot,
+    util::BoundedHistory,
+};
+use std::sync::mpsc::{self, Receiver, SendError, Sender};
+use std::time::{Duration, Instant};
+
+// Mode changes kept for diagnostics
+const TRANSITION_HISTORY: usize = 16;
+// Obstacle distance that interrupts navigation or exploration (m)
+const OBSTACLE_STOP_DISTANCE: f64 = 0.3;
 
 // Robot operating modes
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -16,6 +30,64 @@ pub enum Mode {
     Lost,        // High localization uncertainty
     Recovering,  // Attempting to relocalize
     Mapping,     // Building a new map
+    Exploring,   // Wandering to map new areas while no goal is set
+    Charging,    // Battery low; docked or heading to charge
+    Panic,       // Catastrophic condition; latched hard stop until an operator resets it
+}
+
+// Momentary sensor/planner events, applied in order at the next update
+#[derive(Clone, Debug, PartialEq)]
+pub enum StateEvent {
+    ObstacleDetected { distance: f64 }, // Something came this close (m), if only briefly
+    GoalSet,                            // Navigation received a goal
+    GoalReached,                        // Navigation arrived at (or dropped) its goal
+    LocalizationLost,                   // Localization gave up on the current estimate
+    Stuck,                              // Commanded motion isn't moving the robot
+}
+
+// Cloneable handle for queueing events to a CoreState from other modules or threads
+#[derive(Clone, Debug)]
+pub struct StateEventSender {
+    sender: Sender<StateEvent>,
+}
+
+impl StateEventSender {
+    /// Queues an event; fails only once the CoreState is gone
+    pub fn send(&self, event: StateEvent) -> Result<(), SendError<StateEvent>> {
+        self.sender.send(event)
+    }
+}
+
+// State config: Confidence thresholds with hysteresis between entering and leaving Lost
+#[derive(Clone, Debug)]
+pub struct StateConfig {
+    pub lost_threshold: f64,    // Below this confidence the robot becomes Lost
+    pub recover_threshold: f64, // Above this confidence Lost moves on to Recovering
+    pub restore_threshold: f64, // Above this confidence Recovering resumes Navigating
+    pub min_mode_dwell: Duration, // How long a confidence condition must persist before switching
+    pub max_drift_rate: f64,      // Covariance trace growth per second that triggers relocalization
+    pub low_battery_threshold: f64, // Battery level (0.0 - 1.0) below which the robot goes Charging
+    pub charged_threshold: f64,     // Battery level at which Charging returns to Idle
+    pub explore_timeout: Duration,  // Idle time without a goal before the robot starts Exploring
+    pub recovery_timeout: Duration, // Time in Recovering without reaching Navigating before giving up to Idle
+    pub lost_timeout: Duration,     // Time in Lost before an emergency stop
+}
+
+impl Default for StateConfig {
+    fn default() -> Self {
+        StateConfig {
+            lost_threshold: 0.5,
+            recover_threshold: 0.8,
+            restore_threshold: 0.9,
+            min_mode_dwell: Duration::ZERO,
+            max_drift_rate: 1.0,
+            low_battery_threshold: 0.2,
+            charged_threshold: 0.9,
+            explore_timeout: Duration::from_secs(60),
+            recovery_timeout: Duration::from_secs(30),
+            lost_timeout: Duration::from_secs(60),
+        }
+    }
 }
 
 // Core state: Tracks mode and handles transitions
@@ -23,112 +95,421 @@ pub struct CoreState {
     current_mode: Mode,
     last_pose_confidence: f64,
     last_obstacle_distance: f64,
+    config: StateConfig,
+    pending_transition: Option<(Mode, Instant)>, // Candidate mode and when its condition began
+    mode_since: Instant,                         // When the current mode was entered
+    behavior: Option<BehaviorTree>,              // Replaces the FSM's transitions when set
+    last_battery_level: f64,
+    goal_active: bool,
+    goal_cleared_at: Instant, // When the last goal was reached or cancelled
+    events: Receiver<StateEvent>,
+    event_sender: Sender<StateEvent>, // Cloned into every StateEventSender
+    transitions: BoundedHistory<(Mode, Mode)>, // Recent (from, to) mode changes, oldest first
+    lost_stop: bool, // Stopped after the Lost timeout; held until relocalized or reset
 }
 
 impl CoreState {
     /// Initializes state in Idle mode
     pub fn new() -> Self {
+        Self::with_config(StateConfig::default())
+    }
+
+    /// Initializes state in Idle mode with custom thresholds and dwell time
+    pub fn with_config(config: StateConfig) -> Self {
+        let (event_sender, events) = mpsc::channel();
         CoreState {
             current_mode: Mode::Idle,
             last_pose_confidence: 1.0,         // Mock initial confidence
             last_obstacle_distance: f64::INFINITY, // No obstacles
+            config,
+            pending_transition: None,
+            mode_since: Instant::now(),
+            behavior: None,
+            last_battery_level: 1.0,
+            goal_active: false,
+            goal_cleared_at: Instant::now(),
+            events,
+            event_sender,
+            transitions: BoundedHistory::new(TRANSITION_HISTORY),
+            lost_stop: false,
         }
     }
 
-    /// Updates state based on localization and perception data
+    /// Returns a handle for queueing events, drained in order at each update
+    pub fn event_sender(&self) -> StateEventSender {
+        StateEventSender {
+            sender: self.event_sender.clone(),
+        }
+    }
+
+    /// Initializes state in Idle mode, with transitions decided by a behavior tree
+    ///
+    /// The tree replaces the confidence and obstacle transitions; dwell time (except for
+    /// safety actions), the Panic latch, and the override methods still apply.
+    pub fn with_behavior_tree(config: StateConfig, tree: BehaviorTree) -> Self {
+        CoreState {
+            behavior: Some(tree),
+            ..Self::with_config(config)
+        }
+    }
+
+    /// Updates state based on localization, perception, and battery level (0.0 - 1.0)
     pub fn update(
         &mut self,
         pose_confidence: &PoseConfidence,
         snapshot: &Snapshot,
+        battery_level: f64,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        // Extract confidence from covariance (simplified)
-        let confidence = 1.0
-            / (pose_confidence.covariance[(0, 0)] + pose_confidence.covariance[(1, 1)]).sqrt();
+        self.update_at(pose_confidence, snapshot, battery_level, Instant::now())
+    }
+
+    /// Same as `update`, with an explicit timestamp for the dwell and exploration timers
+    pub fn update_at(
+        &mut self,
+        pose_confidence: &PoseConfidence,
+        snapshot: &Snapshot,
+        battery_level: f64,
+        now: Instant,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Apply every event queued since the last update, oldest first
+        while let Ok(event) = self.events.try_recv() {
+            self.handle_event(event, now);
+        }
+
+        // Panic is latched; nothing short of an operator reset leaves it
+        if self.current_mode == Mode::Panic {
+            return Ok(());
+        }
+
+        // Don't wait forever for confidence that never comes back
+        let time_in_mode = now.saturating_duration_since(self.mode_since);
+        if self.current_mode == Mode::Recovering && time_in_mode >= self.config.recovery_timeout {
+            self.set_mode(Mode::Idle, now);
+            error!("Recovery failed: not navigating again after {:?}, now Idle", time_in_mode);
+        }
+        if self.current_mode == Mode::Lost && time_in_mode >= self.config.lost_timeout {
+            error!("Lost for {:?}, stopping until relocalized or reset", time_in_mode);
+            self.emergency_stop_at(now);
+            self.lost_stop = true;
+        }
+
+        let confidence = pose_confidence.confidence_scalar();
         self.last_pose_confidence = confidence;
+        self.last_battery_level = battery_level;
 
-        // Find nearest obstacle (simplified)
+        // The stop after the Lost timeout holds until confidence is good enough to recover
+        if self.lost_stop && confidence > self.config.recover_threshold {
+            self.lost_stop = false;
+            info!("Relocalized ({}), lost stop released", confidence);
+        }
+
+        // Find the occupied cell nearest the robot, both in the map frame
+        let robot = &pose_confidence.pose;
         self.last_obstacle_distance = snapshot
             .grid
-            .data
-            .iter()
-            .enumerate()
-            .filter(|(_, &cell)| cell == 1)
-            .map(|(i, _)| {
-                let x = (i % snapshot.grid.width) as f64 * snapshot.grid.resolution;
-                let y = (i / snapshot.grid.width) as f64 * snapshot.grid.resolution;
-                (x.powi(2) + y.powi(2)).sqrt()
+            .occupied_cells()
+            .map(|(col, row)| {
+                let (x, y) = snapshot.grid.cell_to_world(col, row);
+                (x - robot.x).hypot(y - robot.y)
             })
             .fold(f64::INFINITY, f64::min);
 
-        // Mode transition logic
-        match self.current_mode {
-            Mode::Idle => {
-                if confidence < 0.5 {
-                    self.current_mode = Mode::Lost;
-                    error!("Transitioned to Lost: low confidence ({})", confidence);
-                }
+        if self.behavior.is_some() {
+            self.tick_behavior_tree(now);
+            return Ok(());
+        }
+
+        // A low battery overrides everything but Panic; charging ends once topped up
+        if self.current_mode != Mode::Charging && battery_level < self.config.low_battery_threshold {
+            self.set_mode(Mode::Charging, now);
+            info!("Transitioned to Charging: battery low ({:.2})", battery_level);
+            return Ok(());
+        }
+        if self.current_mode == Mode::Charging {
+            if battery_level >= self.config.charged_threshold {
+                self.set_mode(Mode::Idle, now);
+                info!("Transitioned to Idle: battery charged ({:.2})", battery_level);
             }
-            Mode::Navigating => {
-                if confidence < 0.5 {
-                    self.current_mode = Mode::Lost;
-                    error!("Transitioned to Lost: low confidence ({})", confidence);
-                } else if self.last_obstacle_distance < 0.3 {
-                    self.current_mode = Mode::Recovering;
-                    error!(
-                        "Transitioned to Recovering: obstacle too close ({})",
-                        self.last_obstacle_distance
-                    );
+            return Ok(());
+        }
+
+        // With nothing to do for long enough, go map new areas
+        if self.current_mode == Mode::Idle && !self.lost_stop && self.time_without_goal(now) >= self.config.explore_timeout {
+            self.set_mode(Mode::Exploring, now);
+            info!("Transitioned to Exploring: no goal for {:?}", self.config.explore_timeout);
+            return Ok(());
+        }
+
+        // Confidence-driven candidate mode (hysteresis: separate enter/exit thresholds)
+        let candidate = match self.current_mode {
+            Mode::Idle if self.lost_stop => None,
+            Mode::Idle | Mode::Navigating | Mode::Mapping | Mode::Exploring
+                if confidence < self.config.lost_threshold => Some(Mode::Lost),
+            Mode::Lost if confidence > self.config.recover_threshold => Some(Mode::Recovering),
+            Mode::Recovering if confidence > self.config.restore_threshold => Some(Mode::Navigating),
+            Mode::Idle
+            | Mode::Navigating
+            | Mode::Mapping
+            | Mode::Exploring
+            | Mode::Lost
+            | Mode::Recovering
+            | Mode::Charging
+            | Mode::Panic => None,
+        };
+
+        // Only switch once the condition has persisted for the dwell time
+        if let Some(mode) = self.dwell(candidate, now) {
+            self.transition(mode, confidence, now);
+            return Ok(());
+        }
+
+        // Obstacle proximity is a safety condition and switches immediately
+        if matches!(self.current_mode, Mode::Navigating | Mode::Exploring) && self.last_obstacle_distance < OBSTACLE_STOP_DISTANCE {
+            self.set_mode(Mode::Recovering, now);
+            error!(
+                "Transitioned to Recovering: obstacle too close ({})",
+                self.last_obstacle_distance
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Applies one queued event
+    fn handle_event(&mut self, event: StateEvent, now: Instant) {
+        match event {
+            StateEvent::GoalSet => self.set_goal_active_at(true, now),
+            StateEvent::GoalReached => {
+                self.set_goal_active_at(false, now);
+                if self.current_mode == Mode::Navigating {
+                    self.set_mode(Mode::Idle, now);
+                    info!("Transitioned to Idle: goal reached");
                 }
             }
-            Mode::Lost => {
-                if confidence > 0.8 {
-                    self.current_mode = Mode::Recovering;
-                    info!("Transitioned to Recovering: confidence improved ({})", confidence);
+            StateEvent::ObstacleDetected { distance } => {
+                if matches!(self.current_mode, Mode::Navigating | Mode::Exploring) && distance < OBSTACLE_STOP_DISTANCE {
+                    self.set_mode(Mode::Recovering, now);
+                    error!("Transitioned to Recovering: obstacle detected ({})", distance);
                 }
             }
-            Mode::Recovering => {
-                if confidence > 0.9 {
-                    self.current_mode = Mode::Navigating;
-                    info!("Transitioned to Navigating: confidence restored ({})", confidence);
+            StateEvent::LocalizationLost => {
+                if !self.lost_stop && matches!(self.current_mode, Mode::Idle | Mode::Navigating | Mode::Mapping | Mode::Exploring) {
+                    self.set_mode(Mode::Lost, now);
+                    error!("Transitioned to Lost: localization reported lost");
                 }
             }
-            Mode::Mapping => {
-                if confidence < 0.5 {
-                    self.current_mode = Mode::Lost;
-                    error!(
-                        "Transitioned to Lost: low confidence during mapping ({})",
-                        confidence
-                    );
-                }
+            StateEvent::Stuck => self.report_stuck_at(now),
+        }
+    }
+
+    /// Ticks the behavior tree and switches to the mode of its active leaf after the dwell time
+    fn tick_behavior_tree(&mut self, now: Instant) {
+        let Some(tree) = &self.behavior else {
+            return;
+        };
+        let context = TickContext {
+            mode: self.current_mode,
+            time_in_mode: now.saturating_duration_since(self.mode_since),
+            confidence: self.last_pose_confidence,
+            obstacle_distance: self.last_obstacle_distance,
+            battery_level: self.last_battery_level,
+            time_without_goal: self.time_without_goal(now),
+        };
+        let leaf = tree
+            .tick_leaf(&context)
+            .filter(|(mode, _)| *mode != self.current_mode)
+            .filter(|(mode, _)| !(self.lost_stop && matches!(mode, Mode::Lost | Mode::Exploring)));
+        
+        // Safety leaves switch immediately, like the FSM's obstacle stop
+        if let Some((mode, true)) = leaf {
+            self.pending_transition = None;
+            self.set_mode(mode, now);
+            error!("Behavior tree safety action {:?} (obstacle {})", mode, context.obstacle_distance);
+            return;
+        }
+        if let Some(mode) = self.dwell(leaf.map(|(mode, _)| mode), now) {
+            info!(
+                "Behavior tree transitioned {:?} -> {:?} (confidence {}, obstacle {})",
+                self.current_mode, mode, context.confidence, context.obstacle_distance
+            );
+            self.set_mode(mode, now);
+        }
+    }
+
+    /// Tracks how long a candidate mode has been proposed; returns it once the dwell time has passed
+    fn dwell(&mut self, candidate: Option<Mode>, now: Instant) -> Option<Mode> {
+        let Some(mode) = candidate else {
+            self.pending_transition = None;
+            return None;
+        };
+        let since = match self.pending_transition {
+            Some((pending, since)) if pending == mode => since,
+            _ => now,
+        };
+        self.pending_transition = Some((mode, since));
+        (now.saturating_duration_since(since) >= self.config.min_mode_dwell).then_some(mode)
+    }
+
+    /// How long the robot has had no goal while staying in its current mode (zero with a goal)
+    fn time_without_goal(&self, now: Instant) -> Duration {
+        if self.goal_active {
+            return Duration::ZERO;
+        }
+        now.saturating_duration_since(self.mode_since.max(self.goal_cleared_at))
+    }
+
+    /// Enters a mode, restarting its timer and dropping any pending transition
+    fn set_mode(&mut self, mode: Mode, now: Instant) {
+        if mode != self.current_mode {
+            self.transitions.push((self.current_mode, mode));
+        }
+        self.current_mode = mode;
+        self.mode_since = now;
+        self.pending_transition = None;
+    }
+
+    /// Applies a confidence-driven mode change
+    fn transition(&mut self, mode: Mode, confidence: f64, now: Instant) {
+        let from = self.current_mode;
+        self.set_mode(mode, now);
+        match (from, mode) {
+            (Mode::Mapping, Mode::Lost) => error!(
+                "Transitioned to Lost: low confidence during mapping ({})",
+                confidence
+            ),
+            (_, Mode::Lost) => error!("Transitioned to Lost: low confidence ({})", confidence),
+            (_, Mode::Recovering) => {
+                info!("Transitioned to Recovering: confidence improved ({})", confidence)
             }
+            _ => info!("Transitioned to Navigating: confidence restored ({})", confidence),
         }
+    }
 
-        Ok(())
+    /// Goal input: Whether navigation has a goal; a new goal ends exploration
+    pub fn set_goal_active(&mut self, active: bool) {
+        self.set_goal_active_at(active, Instant::now());
+    }
+
+    fn set_goal_active_at(&mut self, active: bool, now: Instant) {
+        if self.goal_active && !active {
+            self.goal_cleared_at = now;
+        }
+        self.goal_active = active;
+        if active && self.current_mode == Mode::Exploring {
+            self.set_mode(Mode::Navigating, now);
+            info!("Transitioned to Navigating: goal set while exploring");
+        }
     }
 
     /// Emergency override: Stops robot if lost or in danger
     pub fn emergency_stop(&mut self) {
-        self.current_mode = Mode::Idle;
+        self.emergency_stop_at(Instant::now());
+    }
+
+    fn emergency_stop_at(&mut self, now: Instant) {
+        if self.current_mode == Mode::Panic {
+            return;
+        }
+        self.set_mode(Mode::Idle, now);
         error!("Emergency stop triggered");
     }
 
+    /// Stuck override: Commanded motion isn't moving the robot, so try to recover
+    pub fn report_stuck(&mut self) {
+        self.report_stuck_at(Instant::now());
+    }
+
+    fn report_stuck_at(&mut self, now: Instant) {
+        if self.current_mode != Mode::Recovering && self.current_mode != Mode::Panic {
+            self.set_mode(Mode::Recovering, now);
+            error!("Transitioned to Recovering: robot stuck");
+        }
+    }
+
+    /// Drift override: Uncertainty is growing too fast to keep navigating, so relocalize
+    pub fn report_drift_rate(&mut self, drift_rate: f64) {
+        if self.current_mode == Mode::Navigating && drift_rate > self.config.max_drift_rate {
+            self.set_mode(Mode::Recovering, Instant::now());
+            error!("Transitioned to Recovering: localization drifting ({:.3}/s)", drift_rate);
+        }
+    }
+
+    /// Operator override: Switches to `mode` right away; only `reset_panic` leaves Panic
+    pub fn override_mode(&mut self, mode: Mode) {
+        if self.current_mode == Mode::Panic {
+            if mode != Mode::Panic {
+                warn!("Ignoring switch to {:?} while in Panic; reset required", mode);
+            }
+            return;
+        }
+        self.lost_stop = false;
+        if mode != self.current_mode {
+            info!("Mode overridden from {:?} to {:?}", self.current_mode, mode);
+            self.set_mode(mode, Instant::now());
+        }
+    }
+
+    /// Panic override: Latches a hard stop that only `reset_panic` releases
+    pub fn enter_panic(&mut self, reason: &str) {
+        if self.current_mode != Mode::Panic {
+            self.set_mode(Mode::Panic, Instant::now());
+            error!("PANIC: {}", reason);
+        }
+    }
+
+    /// Operator reset: Leaves Panic for Idle; returns whether the robot was in Panic
+    pub fn reset_panic(&mut self) -> bool {
+        if self.current_mode != Mode::Panic {
+            return false;
+        }
+        self.lost_stop = false;
+        self.set_mode(Mode::Idle, Instant::now());
+        info!("Panic reset by operator, now Idle");
+        true
+    }
+
+    /// Whether the robot is held stopped after staying Lost past the timeout
+    pub fn lost_stop_latched(&self) -> bool {
+        self.lost_stop
+    }
+
     /// Returns current mode
     pub fn get_mode(&self) -> Mode {
         self.current_mode
     }
+
+    /// Recent (from, to) mode changes, oldest first
+    pub fn recent_transitions(&self) -> Vec<(Mode, Mode)> {
+        self.transitions.iter().copied().collect()
+    }
 }
 
 // Weaknesses:
-// - Simplified FSM; lacks complex transitions (e.g., timeouts, multi-step recovery).
-// Future improvement: Use hierarchical FSM or behavior trees for richer logic.
-// - Mock confidence calculation; needs robust covariance analysis.
-// - Obstacle distance is basic; integrate perception.rs semantic objects for smarter decisions.
-// - No event queue for sensor/planner inputs; risks missing transient events.
-// Future improvement: Add tokio::sync::mpsc for asynchronous event handling.
-// - Limited modes; could add Exploration, Charging, etc., for extensibility.
+// - The default FSM lacks multi-step recovery; the behavior tree backend's actions only
+//   pick a mode as well.
+// - Recovery and Lost timeouts only fire on update; a stalled update loop delays them.
+// - Confidence collapses the covariance to its scaled variances; elongated uncertainty (e.g. along a corridor) reads the same as round.
+// - Obstacle distance scans every occupied cell and ignores semantic objects; a distance
+//   transform or perception.rs semantic objects would scale better and decide smarter.
+// - Events are only applied at the next update, so their reaction waits for the update rate.
+// - Charging only tracks the battery level; docking and the path to the charger live elsewhere.
+// - Exploring has no frontier selection of its own; navigation must pick where to go.
 
 // Current Functionality:
-// - Maintains FSM with five modes (Idle, Navigating, Lost, Recovering, Mapping).
-// - Updates mode based on pose confidence and obstacle proximity.
+// - Maintains FSM with eight modes (Idle, Navigating, Lost, Recovering, Mapping, Exploring,
+//   Charging, Panic).
+// - Enters Charging below a battery threshold and returns to Idle once charged.
+// - Starts Exploring after an idle timeout without a goal; a new goal switches to Navigating.
+// - Updates mode based on pose confidence (PoseConfidence::confidence_scalar) and the distance
+//   from the robot to the nearest occupied grid cell in the map frame.
+// - Applies hysteresis thresholds and a minimum dwell time to confidence transitions.
+// - Optionally delegates transitions to a behavior tree built by apps, keeping dwell and overrides.
 // - Supports emergency stop for safety.
+// - Drains queued events (obstacle, goal, localization lost, stuck) in order at each update.
+// - Keeps a short history of mode transitions for diagnostics.
+// - Enters Recovering when navigation reports the robot stuck or localization drifts too fast.
+// - Gives up on recovery (to Idle) and on relocalizing (emergency stop) after configurable timeouts;
+//   the stop after the Lost timeout holds until confidence recovers or an operator override.
+// - Latches Panic against every automatic transition until an operator reset returns to Idle.
+// - Accepts operator mode overrides outside Panic (EosOS::set_mode).
 // - Provides mode query for navigation and API.
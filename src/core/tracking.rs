@@ -0,0 +1,212 @@
+// core/tracking.rs
+
+// Follows moving objects (people, cars) across perception updates. Detections are matched
+// to existing tracks by nearest neighbour within a gate, and each track runs a
+// constant-velocity Kalman filter over [x, y, vx, vy] so the state machine and planner can
+// reason about where an object is heading, not only where it was last seen.
+
+// Dependencies
+use nalgebra::{Matrix2, Matrix2x4, Matrix4, Vector2, Vector4};
+use serde::{Deserialize, Serialize};
+
+use super::perception::SemanticObject;
+
+// Velocity variance of a new track, whose motion is still unknown ((m/s)^2)
+const INITIAL_VELOCITY_VARIANCE: f64 = 4.0;
+
+// Tracked object with its estimated motion
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct DynamicObject {
+    pub id: u64,                          // Track ID, stable while the object stays tracked
+    pub class: String,                    // Class of the detections feeding the track
+    pub position: Vector2<f64>,           // Filtered position (m)
+    pub velocity: Vector2<f64>,           // Filtered velocity (m/s)
+    pub predicted_position: Vector2<f64>, // Expected position one prediction horizon ahead (m)
+    pub missed_updates: u32,              // Updates in a row without a matching detection
+}
+
+// Tracker tuning
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TrackerConfig {
+    pub gate_distance: f64,      // Furthest a detection may be from a track's prediction to match it (m)
+    pub process_noise: f64,      // Acceleration variance of the constant-velocity model ((m/s²)^2)
+    pub measurement_noise: f64,  // Detection position variance (m^2)
+    pub max_missed_updates: u32, // Tracks unmatched for longer are dropped
+    pub prediction_horizon: f64, // Look-ahead for `predicted_position` (s)
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        TrackerConfig {
+            gate_distance: 1.0,
+            process_noise: 0.5,
+            measurement_noise: 0.01,
+            max_missed_updates: 5,
+            prediction_horizon: 1.0,
+        }
+    }
+}
+
+// One object's filter state
+#[derive(Clone, Debug)]
+struct Track {
+    id: u64,
+    class: String,
+    state: Vector4<f64>, // [x, y, vx, vy]
+    covariance: Matrix4<f64>,
+    missed_updates: u32,
+}
+
+impl Track {
+    /// Constant-velocity prediction over `dt` seconds
+    fn predict(&mut self, dt: f64, process_noise: f64) {
+        let mut transition = Matrix4::identity();
+        transition[(0, 2)] = dt;
+        transition[(1, 3)] = dt;
+
+        // Piecewise-constant white acceleration
+        let (dt2, dt3, dt4) = (dt * dt, dt * dt * dt / 2.0, dt * dt * dt * dt / 4.0);
+        let noise = Matrix4::new(
+            dt4, 0.0, dt3, 0.0,
+            0.0, dt4, 0.0, dt3,
+            dt3, 0.0, dt2, 0.0,
+            0.0, dt3, 0.0, dt2,
+        ) * process_noise;
+
+        self.state = transition * self.state;
+        self.covariance = transition * self.covariance * transition.transpose() + noise;
+    }
+
+    /// Kalman correction with a detected position
+    fn correct(&mut self, measured: Vector2<f64>, measurement_noise: f64) {
+        let observation = Matrix2x4::new(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+        );
+        let innovation = measured - observation * self.state;
+        let innovation_covariance =
+            observation * self.covariance * observation.transpose() + Matrix2::identity() * measurement_noise;
+        let Some(inverse) = innovation_covariance.try_inverse() else {
+            return;
+        };
+        let gain = self.covariance * observation.transpose() * inverse;
+
+        self.state += gain * innovation;
+        self.covariance = (Matrix4::identity() - gain * observation) * self.covariance;
+        self.missed_updates = 0;
+    }
+
+    fn position(&self) -> Vector2<f64> {
+        Vector2::new(self.state[0], self.state[1])
+    }
+
+    fn velocity(&self) -> Vector2<f64> {
+        Vector2::new(self.state[2], self.state[3])
+    }
+}
+
+// Associates detections between perception updates and filters their motion
+#[derive(Clone, Debug)]
+pub struct ObjectTracker {
+    config: TrackerConfig,
+    tracks: Vec<Track>,
+    next_id: u64,
+}
+
+impl ObjectTracker {
+    /// Creates a tracker with no tracks
+    pub fn new(config: TrackerConfig) -> Self {
+        ObjectTracker {
+            config,
+            tracks: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Advances every track by `dt` seconds and folds in the latest detections
+    ///
+    /// Each detection updates the closest predicted track of the same class within the
+    /// gate, closest pairs first; the rest start new tracks. Tracks left unmatched for
+    /// more than `max_missed_updates` updates are dropped.
+    pub fn update(&mut self, detections: &[SemanticObject], dt: f64) {
+        for track in &mut self.tracks {
+            track.predict(dt.max(0.0), self.config.process_noise);
+        }
+
+        // Candidate pairs within the gate, closest first
+        let mut pairs = Vec::new();
+        for (detection_index, detection) in detections.iter().enumerate() {
+            for (track_index, track) in self.tracks.iter().enumerate() {
+                let distance = (detection.position() - track.position()).norm();
+                if track.class == detection.class() && distance <= self.config.gate_distance {
+                    pairs.push((distance, detection_index, track_index));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut detection_matched = vec![false; detections.len()];
+        let mut track_matched = vec![false; self.tracks.len()];
+        for (_, detection_index, track_index) in pairs {
+            if detection_matched[detection_index] || track_matched[track_index] {
+                continue;
+            }
+            detection_matched[detection_index] = true;
+            track_matched[track_index] = true;
+            self.tracks[track_index].correct(detections[detection_index].position(), self.config.measurement_noise);
+        }
+
+        // Age out tracks that went unseen
+        for (track, matched) in self.tracks.iter_mut().zip(&track_matched) {
+            if !matched {
+                track.missed_updates += 1;
+            }
+        }
+        let max_missed = self.config.max_missed_updates;
+        self.tracks.retain(|track| track.missed_updates <= max_missed);
+
+        // Unmatched detections start tracks at rest with uncertain velocity
+        for (detection, _) in detections.iter().zip(&detection_matched).filter(|(_, matched)| !**matched) {
+            let position = detection.position();
+            let mut covariance = Matrix4::identity() * INITIAL_VELOCITY_VARIANCE;
+            covariance[(0, 0)] = self.config.measurement_noise;
+            covariance[(1, 1)] = self.config.measurement_noise;
+            self.tracks.push(Track {
+                id: self.next_id,
+                class: detection.class().to_string(),
+                state: Vector4::new(position.x, position.y, 0.0, 0.0),
+                covariance,
+                missed_updates: 0,
+            });
+            self.next_id += 1;
+        }
+    }
+
+    /// Current estimate of every tracked object
+    pub fn objects(&self) -> Vec<DynamicObject> {
+        self.tracks
+            .iter()
+            .map(|track| DynamicObject {
+                id: track.id,
+                class: track.class.clone(),
+                position: track.position(),
+                velocity: track.velocity(),
+                predicted_position: track.position() + track.velocity() * self.config.prediction_horizon,
+                missed_updates: track.missed_updates,
+            })
+            .collect()
+    }
+}
+
+// Weaknesses:
+// - Greedy nearest-neighbour association; crossing objects can swap tracks. The Hungarian
+//   algorithm or JPDA would resolve such ambiguities.
+// - Constant-velocity model only; turning cars and stopping people lag behind.
+// - Detections are positions only; no size, orientation, or appearance cues for matching.
+
+// Current Functionality:
+// - Tracks objects with a per-object constant-velocity Kalman filter over position and velocity.
+// - Associates detections to predicted tracks by class and gated distance.
+// - Starts tracks for new detections and drops tracks unseen for several updates.
+// - Reports filtered positions, velocities, and positions predicted a configurable horizon ahead.
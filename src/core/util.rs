@@ -0,0 +1,163 @@
+// core/util.rs
+
+// Small shared building blocks for core, navigation and neural modules. BoundedHistory
+// replaces the hand-rolled fixed-capacity VecDeque histories (push, pop-front when full)
+// and adds window statistics over the most recent entries. CycleTimer measures the real
+// time step of periodic updates instead of assuming a fixed rate.
+
+// Dependencies
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Fixed-capacity history that evicts its oldest entry when full
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BoundedHistory<T> {
+    items: VecDeque<T>, // Oldest first
+    capacity: usize,
+}
+
+impl<T> BoundedHistory<T> {
+    /// Creates an empty history holding at most `capacity` entries
+    pub fn new(capacity: usize) -> Self {
+        BoundedHistory {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Appends an entry, evicting the oldest one if the history is full
+    pub fn push(&mut self, item: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.items.len() >= self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// Number of stored entries
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether no entries are stored
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Maximum number of stored entries
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Removes all entries
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Most recent entry
+    pub fn last(&self) -> Option<&T> {
+        self.items.back()
+    }
+
+    /// All entries, oldest first
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter()
+    }
+
+    /// The `n` most recent entries, oldest first
+    pub fn recent(&self, n: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.range(self.items.len().saturating_sub(n)..)
+    }
+
+    /// Mean of `value` over the `window` most recent entries; None when empty
+    pub fn mean_of<F: Fn(&T) -> f64>(&self, window: usize, value: F) -> Option<f64> {
+        let count = window.min(self.items.len());
+        if count == 0 {
+            return None;
+        }
+        Some(self.recent(window).map(value).sum::<f64>() / count as f64)
+    }
+
+    /// Maximum of `value` over the `window` most recent entries; None when empty
+    pub fn max_of<F: Fn(&T) -> f64>(&self, window: usize, value: F) -> Option<f64> {
+        self.recent(window).map(value).reduce(f64::max)
+    }
+}
+
+impl<T: Copy + Into<f64>> BoundedHistory<T> {
+    /// Mean of the `window` most recent values
+    pub fn mean(&self, window: usize) -> Option<f64> {
+        self.mean_of(window, |v| (*v).into())
+    }
+
+    /// Maximum of the `window` most recent values
+    pub fn max(&self, window: usize) -> Option<f64> {
+        self.max_of(window, |v| (*v).into())
+    }
+}
+
+// Update timings averaged for the effective rate
+const RATE_WINDOW: usize = 20;
+
+// Measured timing of a periodic update
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct UpdateRate {
+    pub last_dt: f64, // Seconds used for the latest update
+    pub hz: f64,      // Effective rate over recent updates
+}
+
+// Measures the time between updates of a periodic loop
+#[derive(Clone, Debug)]
+pub struct CycleTimer {
+    nominal_dt: f64, // Used before a previous update exists, or if time goes backwards
+    max_dt: f64,     // Cap so a stall doesn't turn into one huge step
+    last: Option<f64>,
+    steps: BoundedHistory<f64>,
+}
+
+impl CycleTimer {
+    /// Creates a timer for updates nominally `nominal_dt` seconds apart, capping steps at `max_dt`
+    pub fn new(nominal_dt: f64, max_dt: f64) -> Self {
+        CycleTimer {
+            nominal_dt,
+            max_dt: max_dt.max(nominal_dt),
+            last: None,
+            steps: BoundedHistory::new(RATE_WINDOW),
+        }
+    }
+
+    /// Records an update at `now` (seconds on any monotonic clock) and returns its time step
+    ///
+    /// The first update, or one not after the previous, uses the nominal step.
+    pub fn tick(&mut self, now: f64) -> f64 {
+        let dt = match self.last.replace(now) {
+            Some(previous) if now > previous => (now - previous).min(self.max_dt),
+            _ => self.nominal_dt,
+        };
+        self.steps.push(dt);
+        dt
+    }
+
+    /// Latest step and effective rate; the nominal ones before any update
+    pub fn rate(&self) -> UpdateRate {
+        let last_dt = self.steps.last().copied().unwrap_or(self.nominal_dt);
+        let mean_dt = self.steps.mean(RATE_WINDOW).unwrap_or(self.nominal_dt);
+        UpdateRate {
+            last_dt,
+            hz: if mean_dt > 0.0 { 1.0 / mean_dt } else { 0.0 },
+        }
+    }
+}
+
+// Weaknesses:
+// - Statistics are recomputed over the window on every call; fine for the 100-entry
+//   histories in use, but a running sum would be needed for large windows.
+// - Backed by VecDeque, so capacity is allocated up front even for rarely-filled histories.
+
+// Current Functionality:
+// - Fixed-capacity history with oldest-first eviction, replacing duplicated VecDeque logic.
+// - Mean and max over the most recent N entries, directly or through a value projection.
+// - Serializable so it can be persisted inside Memory.
+// - Measures real update time steps, capped after stalls, and the effective update rate.
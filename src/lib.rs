@@ -10,13 +10,15 @@ pub mod core;
 pub mod neural;
 pub mod ros_interface;
 pub mod navigation;
-pub mod apps;
+pub use core::apps;
 
 // Re-export commonly used items for easier access
-pub use core::{Localizer, SpatialMemory, PerceptionEngine, StateController, SystemAPI};
+pub use core::{Localization, Memory, Perception, CoreState, Api};
 pub use neural::{SNNEngine, NeuralConfig};
-pub use ros_interface::{RosInterface, Publisher, Subscriber};
-pub use navigation::{NavigationPlanner, MotionController};
+pub use ros_interface::{RosInterface, RobotInterface, SensorSource, CommandSink, SplitInterface, Publisher, Subscriber};
+pub use navigation::{NavigationPlanner, MotionController, MotionState};
+
+use std::time::{Duration, Instant};
 
 /// Main configuration structure for Eos OS
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -26,9 +28,18 @@ pub struct EosConfig {
     /// ROS 2 configuration
     pub ros_config: RosConfig,
     /// Navigation parameters
-    pub navigation_config: NavigationConfig,
+    pub navigation_config: navigation::NavigationConfig,
     /// Core system settings
     pub core_config: core::CoreConfig,
+    /// Control-loop rate per motion state
+    pub loop_rates: LoopRateConfig,
+    /// Behavior when sensor data or neural output is unusable
+    pub fallback: FallbackConfig,
+    /// How often perception and planning run relative to the control loop
+    pub rate_divisors: RateDivisors,
+    /// Worst-case conditions that latch the system into Panic
+    #[serde(default)]
+    pub panic: PanicConfig,
 }
 
 /// ROS 2 specific configuration
@@ -40,9 +51,24 @@ pub struct RosConfig {
     pub node_name: String,
     /// QoS settings
     pub qos_depth: usize,
+    /// QoS reliability ("best_effort" or "reliable") for published topics and `/tf`;
+    /// sensor subscriptions are always best-effort
+    #[serde(default)]
+    pub qos_reliability: ros_interface::QosReliability,
+    /// QoS durability ("volatile" or "transient_local") for published topics and `/tf`
+    #[serde(default)]
+    pub qos_durability: ros_interface::QosDurability,
+    /// Frame to report the robot pose in (e.g. "map"), via TF from odometry's frame
+    #[serde(default)]
+    pub pose_frame: Option<String>,
+    /// How the IMU is mounted relative to the base frame
+    #[serde(default)]
+    pub imu_axis_remap: ros_interface::ImuAxisRemap,
 }
 
-/// Navigation system configuration
+/// Basic navigation limits, as in older configs
+///
+/// Converts into the full `navigation::NavigationConfig`, which `EosConfig` uses.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NavigationConfig {
     /// Default maximum velocity
@@ -55,6 +81,270 @@ pub struct NavigationConfig {
     pub goal_tolerance: f32,
 }
 
+impl From<NavigationConfig> for navigation::NavigationConfig {
+    /// Takes the basic limits; angular velocity, inflation and the rest keep their defaults
+    fn from(config: NavigationConfig) -> Self {
+        navigation::NavigationConfig {
+            max_linear_velocity: config.max_velocity,
+            max_acceleration: config.max_acceleration,
+            safety_distance: config.safety_distance,
+            goal_tolerance: config.goal_tolerance,
+            ..Default::default()
+        }
+    }
+}
+
+/// Control-loop rates (Hz) for each motion state
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LoopRateConfig {
+    /// Rate while stopped or idle
+    pub stopped_hz: f32,
+    /// Rate while moving freely
+    pub moving_hz: f32,
+    /// Rate while avoiding obstacles
+    pub avoiding_hz: f32,
+    /// Rate while approaching the goal
+    pub approaching_goal_hz: f32,
+    /// Rate while emergency stopping
+    pub emergency_hz: f32,
+}
+
+impl LoopRateConfig {
+    /// Cycle period for a motion state
+    pub fn period_for(&self, state: MotionState) -> Duration {
+        let hz = match state {
+            MotionState::Stopped => self.stopped_hz,
+            MotionState::Moving => self.moving_hz,
+            MotionState::Avoiding => self.avoiding_hz,
+            MotionState::ApproachingGoal => self.approaching_goal_hz,
+            MotionState::EmergencyStopping => self.emergency_hz,
+        };
+        Duration::from_secs_f64(1.0 / hz.max(0.1) as f64)
+    }
+}
+
+impl Default for LoopRateConfig {
+    fn default() -> Self {
+        LoopRateConfig {
+            stopped_hz: 2.0,
+            moving_hz: 10.0,
+            avoiding_hz: 20.0,
+            approaching_goal_hz: 10.0,
+            emergency_hz: 20.0,
+        }
+    }
+}
+
+/// Perception and planning update rates as divisors of the control-loop rate
+///
+/// A divisor of N runs the stage every N-th cycle; control runs every cycle and
+/// reuses the last plan in between. E.g. a 20 Hz loop with perception 2 and
+/// planning 10 perceives at 10 Hz and plans at 2 Hz.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RateDivisors {
+    /// Cycles per sensor read and neural update
+    pub perception: u32,
+    /// Cycles per planning update
+    pub planning: u32,
+}
+
+impl RateDivisors {
+    /// Whether a stage with the given divisor runs on this cycle
+    fn runs_on(divisor: u32, cycle: u64) -> bool {
+        cycle.is_multiple_of(divisor.max(1) as u64)
+    }
+}
+
+impl Default for RateDivisors {
+    fn default() -> Self {
+        RateDivisors {
+            perception: 1,
+            planning: 1,
+        }
+    }
+}
+
+/// Number of times each pipeline stage has run
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CycleCounts {
+    /// Control cycles run
+    pub cycles: u64,
+    /// Perception updates (sensor read and neural processing)
+    pub perception_updates: u64,
+    /// Planning updates
+    pub plan_updates: u64,
+    /// Cycles spent stopped while Lost or Recovering, searching for or settling on a fix
+    pub relocalization_attempts: u64,
+}
+
+/// What the base does when a cycle has no usable sensor data or neural output
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// Hold position with a zero command
+    Stop,
+    /// Keep inching forward at `creep_speed`
+    CreepForward,
+    /// Turn in place at `search_turn_rate` to look for usable returns
+    RotateToSearch,
+}
+
+/// Fallback applied by `EosOS::run_cycle` instead of erroring or sending a blind zero command
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FallbackConfig {
+    /// Policy to apply
+    pub policy: FallbackPolicy,
+    /// Forward speed for `CreepForward` (m/s)
+    pub creep_speed: f32,
+    /// Turn rate for `RotateToSearch` (rad/s)
+    pub search_turn_rate: f32,
+}
+
+impl FallbackConfig {
+    /// Command to publish while the fallback is active
+    pub fn command(&self) -> ros_interface::MotionCommand {
+        let (linear, angular) = match self.policy {
+            FallbackPolicy::Stop => (0.0, 0.0),
+            FallbackPolicy::CreepForward => (self.creep_speed, 0.0),
+            FallbackPolicy::RotateToSearch => (0.0, self.search_turn_rate),
+        };
+        ros_interface::MotionCommand { linear, angular }
+    }
+
+    /// Whether a cycle's inputs are good enough to plan on
+    ///
+    /// Sensor data needs at least one in-range laser return. Neural output must be
+    /// non-empty, finite and not all zeros (what an engine without a model produces).
+    pub fn inputs_usable(&self, sensor_data: &ros_interface::SensorData, neural_output: &[f32]) -> bool {
+        let has_guidance = neural_output.iter().all(|v| v.is_finite())
+            && neural_output.iter().any(|v| *v != 0.0);
+
+        self.sensor_usable(sensor_data) && has_guidance
+    }
+
+    /// Whether the sensor data has at least one in-range laser return
+    pub fn sensor_usable(&self, sensor_data: &ros_interface::SensorData) -> bool {
+        let scan = &sensor_data.laser_scan;
+        scan.ranges
+            .iter()
+            .any(|r| r.is_finite() && *r >= scan.range_min && *r <= scan.range_max)
+    }
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        FallbackConfig {
+            policy: FallbackPolicy::Stop,
+            creep_speed: 0.05,
+            search_turn_rate: 0.3,
+        }
+    }
+}
+
+/// A worst-case condition reported by one of the subsystems
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicCause {
+    /// No usable sensor data for `sensor_loss_updates` perception updates in a row
+    SensorLoss,
+    /// An obstacle within `contact_distance` of the robot body
+    ObstacleContact,
+    /// The IMU reports the robot tilted beyond `max_tilt`
+    OverTilt,
+}
+
+/// When catastrophic conditions latch the system into Panic
+///
+/// Over-tilt alone is enough; the other causes each have a milder response on their
+/// own (the fallback, the emergency stop) and only enter Panic together.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PanicConfig {
+    /// Enter Panic at all
+    pub enabled: bool,
+    /// Consecutive perception updates without usable sensor data that count as total loss
+    pub sensor_loss_updates: u32,
+    /// Obstacle distance from the robot body that counts as contact (m)
+    pub contact_distance: f32,
+    /// Largest tilt from upright before the robot counts as tipping over (rad)
+    pub max_tilt: f64,
+}
+
+impl PanicConfig {
+    /// Whether the given simultaneous causes warrant Panic
+    pub fn triggers(&self, causes: &[PanicCause]) -> bool {
+        self.enabled && (causes.contains(&PanicCause::OverTilt) || causes.len() >= 2)
+    }
+}
+
+impl Default for PanicConfig {
+    fn default() -> Self {
+        PanicConfig {
+            enabled: true,
+            sensor_loss_updates: 3,
+            contact_distance: 0.1,
+            max_tilt: 0.7,
+        }
+    }
+}
+
+/// Tilt of the base z axis from vertical (rad), with the IMU mapped into the base frame
+/// by `remap`; 0.0 for an unset orientation
+fn imu_tilt(imu: &r2r::sensor_msgs::msg::Imu, remap: &ros_interface::ImuAxisRemap) -> f64 {
+    let q = &imu.orientation;
+    if q.x * q.x + q.y * q.y + q.z * q.z + q.w * q.w <= f64::EPSILON {
+        return 0.0;
+    }
+    // z component of the rotated z axis
+    let cos_tilt = (remap.parse(imu).orientation * nalgebra::Vector3::z()).z;
+    cos_tilt.clamp(-1.0, 1.0).acos()
+}
+
+/// Time source for the run loop, replaceable in tests
+pub trait Clock {
+    /// Current time
+    fn now(&self) -> Instant;
+    /// Block for the given duration
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Paces loop cycles at the rate configured for the current motion state
+pub struct LoopPacer {
+    rates: LoopRateConfig,
+    cycle_start: Option<Instant>,
+}
+
+impl LoopPacer {
+    /// Create a pacer for the given rate map
+    pub fn new(rates: LoopRateConfig) -> Self {
+        LoopPacer { rates, cycle_start: None }
+    }
+
+    /// Sleep out the rest of the current cycle and start the next one
+    pub fn pace<C: Clock>(&mut self, clock: &mut C, state: MotionState) {
+        let period = self.rates.period_for(state);
+        if let Some(start) = self.cycle_start {
+            let elapsed = clock.now().saturating_duration_since(start);
+            if elapsed < period {
+                clock.sleep(period - elapsed);
+            }
+        }
+        self.cycle_start = Some(clock.now());
+    }
+}
+
 impl Default for EosConfig {
     fn default() -> Self {
         EosConfig {
@@ -63,35 +353,83 @@ impl Default for EosConfig {
                 domain_id: 0,
                 node_name: "eos_robot".to_string(),
                 qos_depth: 10,
+                qos_reliability: ros_interface::QosReliability::default(),
+                qos_durability: ros_interface::QosDurability::default(),
+                pose_frame: None,
+                imu_axis_remap: ros_interface::ImuAxisRemap::identity(),
             },
-            navigation_config: NavigationConfig {
-                max_velocity: 0.5,
-                max_acceleration: 0.3,
-                safety_distance: 0.5,
-                goal_tolerance: 0.1,
-            },
+            navigation_config: navigation::NavigationConfig::default(),
             core_config: core::CoreConfig::default(),
+            loop_rates: LoopRateConfig::default(),
+            fallback: FallbackConfig::default(),
+            rate_divisors: RateDivisors::default(),
+            panic: PanicConfig::default(),
         }
     }
 }
 
 /// Primary entry point for Eos OS
-pub struct EosOS {
+pub struct EosOS<I: RobotInterface = RosInterface> {
     config: EosConfig,
-    ros_interface: RosInterface,
+    ros_interface: I,
     neural_engine: SNNEngine,
     navigation_planner: NavigationPlanner,
     motion_controller: MotionController,
     is_initialized: bool,
+    /// Run the neural engine (bypassed with zero output when false)
+    enable_neural: bool,
+    /// Run planning and control (hold position when false)
+    enable_navigation: bool,
+    /// Latest sensor data and neural output, reused between perception updates
+    last_inputs: Option<(ros_interface::SensorData, Vec<f32>)>,
+    /// Latest plan, reused by control between planning updates
+    last_plan: Option<navigation::Path>,
+    /// Mode state machine gating which stages run each cycle, driven by pose confidence and battery
+    state: core::CoreState,
+    /// Latest battery state of charge (0.0 - 1.0)
+    battery_level: f64,
+    /// Empty perception snapshot for the state machine; obstacles stay with the planner
+    no_obstacles: core::Snapshot,
+    /// Latest fused pose estimate from localization, in the configured pose frame
+    pose_estimate: Option<core::PoseConfidence>,
+    /// Occupancy grid from perception waiting to be published, in the configured pose frame
+    map: Option<core::OccupancyGrid>,
+    counts: CycleCounts,
+    /// Perception updates in a row without usable sensor data
+    sensor_loss_updates: u32,
+    /// Conditions that put the system into Panic, while in it
+    panic_causes: Vec<PanicCause>,
+    /// Changes when the interface receives sensor data; awaited by `run_cycle_async`
+    #[cfg(feature = "async")]
+    sensor_updates: Option<tokio::sync::watch::Receiver<u64>>,
 }
 
 impl EosOS {
     /// Create a new Eos OS instance with the given configuration
-    pub fn new(config: EosConfig) -> Result<Self, EosError> {
+    pub fn new(config: EosConfig) -> EosResult<Self> {
         let ros_interface = RosInterface::new(&config.ros_config)?;
+        Self::with_interface(config, ros_interface)
+    }
+}
+
+impl<S: SensorSource, C: CommandSink> EosOS<SplitInterface<S, C>> {
+    /// Create an Eos OS instance on a non-ROS sensor source and command sink
+    pub fn with_backends(config: EosConfig, source: S, sink: C) -> EosResult<Self> {
+        Self::with_interface(config, SplitInterface::new(source, sink))
+    }
+}
+
+impl<I: RobotInterface> EosOS<I> {
+    /// Create an Eos OS instance on a given robot interface (e.g. a scripted one in tests)
+    pub fn with_interface(config: EosConfig, ros_interface: I) -> EosResult<Self> {
         let neural_engine = SNNEngine::new(&config.neural_config)?;
         let navigation_planner = NavigationPlanner::new(&config.navigation_config);
         let motion_controller = MotionController::new(&config.navigation_config);
+        let mut state = core::CoreState::new();
+        state.override_mode(core::Mode::Navigating);
+        
+        #[cfg(feature = "async")]
+        let sensor_updates = ros_interface.sensor_updates();
         
         Ok(EosOS {
             config,
@@ -100,22 +438,41 @@ impl EosOS {
             navigation_planner,
             motion_controller,
             is_initialized: false,
+            enable_neural: true,
+            enable_navigation: true,
+            last_inputs: None,
+            last_plan: None,
+            state,
+            battery_level: 1.0,
+            no_obstacles: core::Snapshot::new(core::OccupancyGrid::new(0, 0, 1.0, (0.0, 0.0)), Vec::new()),
+            pose_estimate: None,
+            map: None,
+            counts: CycleCounts::default(),
+            sensor_loss_updates: 0,
+            panic_causes: Vec::new(),
+            #[cfg(feature = "async")]
+            sensor_updates,
         })
     }
     
     /// Initialize all Eos OS components
-    pub fn initialize(&mut self) -> Result<(), EosError> {
+    pub fn initialize(&mut self) -> EosResult<()> {
+        if self.is_initialized {
+            return Err(EosError::ConfigError("already initialized".to_string()));
+        }
+        
         log::info!("Initializing Eos OS...");
         
         // Initialize ROS interface
         self.ros_interface.initialize()?;
         
-        // Initialize neural engine
-        self.neural_engine.initialize()?;
-        
-        // Load any pre-trained models
-        self.neural_engine.load_model("models/default_snn.json")
-            .map_err(|e| EosError::NeuralError(e.to_string()))?;
+        // Shut the interface back down if the rest fails, so initialize can be retried
+        if let Err(e) = self.initialize_neural_engine() {
+            if let Err(shutdown_error) = self.ros_interface.shutdown() {
+                log::warn!("Failed to shut down ROS interface after failed initialization: {}", shutdown_error);
+            }
+            return Err(e);
+        }
             
         self.is_initialized = true;
         log::info!("Eos OS initialized successfully");
@@ -123,41 +480,555 @@ impl EosOS {
         Ok(())
     }
     
+    /// Initialize the neural engine and load any pre-trained model, shutting the engine
+    /// back down if loading fails
+    fn initialize_neural_engine(&mut self) -> EosResult<()> {
+        self.neural_engine.initialize()?;
+        
+        let loaded = match &self.config.neural_config.model_path {
+            Some(model_path) => self.neural_engine.load_model(model_path),
+            None => Ok(()),
+        };
+        if let Err(e) = loaded {
+            self.neural_engine.shutdown()?;
+            return Err(e.into());
+        }
+        
+        Ok(())
+    }
+    
     /// Main execution loop for Eos OS
-    pub fn run_cycle(&mut self) -> Result<(), EosError> {
+    pub fn run_cycle(&mut self) -> EosResult<()> {
         if !self.is_initialized {
             return Err(EosError::NotInitialized);
         }
         
-        // Get sensor data from ROS
-        let sensor_data = self.ros_interface.get_sensor_data()?;
+        let cycle = self.counts.cycles;
+        self.counts.cycles += 1;
         
-        // Process sensor data with neural network
-        let neural_output = self.neural_engine.process(&sensor_data)?;
+        // Panic is a latched hard stop: keep commanding zero until an operator resets it
+        if self.mode() == core::Mode::Panic {
+            return self.hold_panic_stop();
+        }
         
-        // Plan navigation based on neural output
-        let navigation_plan = self.navigation_planner.plan(
-            &sensor_data, 
-            &neural_output,
-            self.ros_interface.get_current_pose()
-        )?;
+        // Perception: refresh sensor data and neural output at the perception rate
+        let perceived = RateDivisors::runs_on(self.config.rate_divisors.perception, cycle) || self.last_inputs.is_none();
+        if perceived {
+            // Get sensor data from ROS; a failed read counts toward total sensor loss
+            let sensor_data = match self.ros_interface.get_sensor_data() {
+                Ok(sensor_data) => sensor_data,
+                Err(e) => {
+                    self.sensor_loss_updates += 1;
+                    if self.check_panic(None) {
+                        return self.hold_panic_stop();
+                    }
+                    return Err(e.into());
+                }
+            };
+            self.counts.perception_updates += 1;
+            
+            if self.config.fallback.sensor_usable(&sensor_data) {
+                self.sensor_loss_updates = 0;
+            } else {
+                self.sensor_loss_updates += 1;
+            }
+            if self.check_panic(Some(&sensor_data)) {
+                return self.hold_panic_stop();
+            }
+            self.update_state(&sensor_data);
+            
+            // Process sensor data with neural network, or pass zeros through when bypassed
+            let neural_output = if self.enable_neural {
+                match self.neural_engine.process(&sensor_data) {
+                    Ok(output) => Some(output),
+                    Err(e) => {
+                        log::warn!("Neural processing failed: {}", e);
+                        None
+                    }
+                }
+            } else {
+                Some(vec![0.0; self.config.neural_config.output_size])
+            };
+            
+            // Keep only inputs good enough to plan on; a bypassed engine gives no guidance to check
+            self.last_inputs = match neural_output {
+                Some(output) if self.enable_neural && self.config.fallback.inputs_usable(&sensor_data, &output) => {
+                    Some((sensor_data, output))
+                }
+                Some(output) if !self.enable_neural && self.config.fallback.sensor_usable(&sensor_data) => {
+                    Some((sensor_data, output))
+                }
+                _ => None,
+            };
+        }
         
-        // Execute the motion plan
-        let motion_command = self.motion_controller.execute_plan(&navigation_plan)?;
+        // Publish a new map from perception once; maps are large, so unchanged ones aren't resent
+        if let Some(grid) = self.map.take() {
+            let frame_id = self.config.ros_config.pose_frame.as_deref().unwrap_or("odom");
+            let stamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default();
+            if let Err(e) = self.ros_interface.publish_map(&grid.to_ros_occupancy_grid(frame_id, stamp)) {
+                log::warn!("Map publish failed: {}", e);
+            }
+        }
+        
+        // Keep a recent copy of the model on disk; a failed save shouldn't stop the robot
+        if let Err(e) = self.neural_engine.checkpoint_if_due(Instant::now()) {
+            log::warn!("Neural checkpoint failed: {}", e);
+        }
+        
+        // With navigation disabled, hold position whatever the inputs
+        if !self.enable_navigation {
+            self.last_plan = None;
+            let command = ros_interface::MotionCommand { linear: 0.0, angular: 0.0 };
+            self.ros_interface.publish_command(&command)?;
+            return Ok(());
+        }
+        
+        // Commanded motion that odometry doesn't show for too long means the robot is stuck;
+        // other modes are already stopping for their own reasons
+        let driving = matches!(self.mode(), core::Mode::Navigating | core::Mode::Mapping | core::Mode::Exploring);
+        if let Some((sensor_data, _)) = self.last_inputs.as_ref().filter(|_| driving) {
+            let twist = &sensor_data.odom_data.twist.twist;
+            let measured = ros_interface::MotionCommand { linear: twist.linear.x as f32, angular: twist.angular.z as f32 };
+            let dt = self.motion_controller.cycle_time();
+            if self.motion_controller.check_stuck(measured, dt) {
+                self.state.report_stuck();
+                self.last_plan = None;
+            }
+        }
+        
+        // Only Navigating, Mapping and Exploring drive; every other mode stops before planning
+        let mode = self.mode();
+        match mode {
+            core::Mode::Navigating | core::Mode::Mapping | core::Mode::Exploring => {}
+            core::Mode::Panic => return self.hold_panic_stop(),
+            core::Mode::Idle | core::Mode::Charging => {
+                self.last_plan = None;
+                let command = ros_interface::MotionCommand { linear: 0.0, angular: 0.0 };
+                self.ros_interface.publish_command(&command)?;
+                return Ok(());
+            }
+            core::Mode::Lost | core::Mode::Recovering => {
+                // Brake gently rather than driving on blind, unless an obstacle is too close for that
+                self.last_plan = None;
+                let pose = self.ros_interface.get_current_pose().ok();
+                let obstacle_close = self.last_inputs.as_ref().is_some_and(|(sensor_data, _)| {
+                    self.navigation_planner.obstacle_forces_stop(sensor_data, pose)
+                });
+                let stopped = self.motion_controller.last_command().linear == 0.0;
+                let command = if obstacle_close {
+                    self.motion_controller.emergency_stop()
+                } else if stopped && mode == core::Mode::Lost {
+                    // Turn in place so the scan sweeps features the localizer can match
+                    let search = ros_interface::MotionCommand { linear: 0.0, angular: self.config.fallback.search_turn_rate };
+                    self.motion_controller.execute_recovery(search)
+                } else {
+                    // Recovering holds still while the fix firms up
+                    self.motion_controller.controlled_stop()
+                };
+                self.ros_interface.publish_command(&command)?;
+                
+                if command.linear == 0.0 {
+                    self.counts.relocalization_attempts += 1;
+                    log::info!("{:?}: stopped, attempting relocalization", mode);
+                }
+                return Ok(());
+            }
+        }
+        
+        // Without usable inputs the planner has no guidance, so apply the fallback
+        let Some((sensor_data, neural_output)) = &self.last_inputs else {
+            self.last_plan = None;
+            return self.apply_fallback();
+        };
+        
+        // Steering and safety checks need the current pose; a guessed one would steer toward
+        // the wrong place, so stop until it's known
+        let pose = match self.ros_interface.get_current_pose() {
+            Ok(pose) => pose,
+            Err(e) => {
+                let command = self.motion_controller.controlled_stop();
+                self.ros_interface.publish_command(&command)?;
+                return Err(e.into());
+            }
+        };
+        self.navigation_planner.set_current_speed(self.motion_controller.last_command().linear);
+        self.motion_controller.set_pose(pose);
+        
+        // Planning: re-plan at the planning rate, or right away if there is no plan to follow;
+        // in between, each new scan is still checked against the plan being followed
+        let planning = RateDivisors::runs_on(self.config.rate_divisors.planning, cycle) || self.last_plan.is_none();
+        let checked = if planning {
+            self.last_plan = None;
+            self.navigation_planner.plan(sensor_data, neural_output, Some(pose)).map(Some)
+        } else if perceived {
+            self.navigation_planner.update(sensor_data, Some(pose)).map(|()| None)
+        } else {
+            self.navigation_planner.update_pose(pose);
+            Ok(None)
+        };
+        match checked {
+            Ok(Some(navigation_plan)) => {
+                self.counts.plan_updates += 1;
+                self.last_plan = Some(navigation_plan);
+            }
+            Ok(None) => {}
+            Err(e @ (navigation::NavigationError::EmergencyStop
+                | navigation::NavigationError::UnsafePath(_)
+                | navigation::NavigationError::NoPathError(_))) => {
+                // Stop first rather than leaving the last command in effect (hard when the
+                // emergency stop trips); once stopped, work through the recovery behaviors
+                // while replanning, since turning, backing up or clearing the map can open a way
+                self.last_plan = None;
+                let emergency = matches!(e, navigation::NavigationError::EmergencyStop);
+                let stopped = self.motion_controller.last_command() == ros_interface::MotionCommand { linear: 0.0, angular: 0.0 }
+                    && (!emergency || self.motion_controller.motion_state() == navigation::MotionState::EmergencyStopping);
+                let recovering = self.navigation_planner.recovery_state() != navigation::RecoveryState::Idle;
+                let recovery = if stopped || recovering { self.navigation_planner.recover(self.motion_controller.cycle_time()) } else { None };
+                let command = match recovery {
+                    Some(command) => self.motion_controller.execute_recovery(command),
+                    None if emergency => self.motion_controller.emergency_stop(),
+                    None => self.motion_controller.controlled_stop(),
+                };
+                self.ros_interface.publish_command(&command)?;
+                return Err(e.into());
+            }
+            Err(e) => return Err(e.into()),
+        }
+        let Some(navigation_plan) = &self.last_plan else {
+            return Err(EosError::NavigationError("No plan to execute".to_string()));
+        };
+        
+        // Control: execute the current plan every cycle
+        let motion_command = self.motion_controller.execute_plan_around(navigation_plan, &self.navigation_planner)?;
         
         // Publish motion commands to ROS
         self.ros_interface.publish_command(&motion_command)?;
         
+        // Publish our own motion estimate for downstream nodes and loggers
+        if let Some(estimate) = self.pose_confidence(sensor_data) {
+            let pose = ros_interface::Pose2D {
+                x: estimate.pose.x as f32,
+                y: estimate.pose.y as f32,
+                theta: estimate.pose.theta as f32,
+            };
+            let stamp = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default();
+            self.ros_interface.publish_odometry(&pose, &estimate.covariance, &motion_command, stamp)?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Feed this update's pose confidence, goal and battery level to the mode state machine
+    fn update_state(&mut self, sensor_data: &ros_interface::SensorData) {
+        let pose_confidence = self.pose_confidence(sensor_data).unwrap_or_else(|| core::PoseConfidence {
+            pose: core::Pose { x: 0.0, y: 0.0, theta: 0.0 },
+            covariance: ros_interface::planar_covariance(&sensor_data.odom_data.pose.covariance),
+        });
+        let before = self.mode();
+        self.state.set_goal_active(self.navigation_planner.goals_remaining() > 0);
+        if let Err(e) = self.state.update(&pose_confidence, &self.no_obstacles, self.battery_level) {
+            log::warn!("Mode update failed: {}", e);
+        }
+        if self.mode() != before {
+            log::info!("Mode changed from {:?} to {:?}", before, self.mode());
+            self.last_plan = None;
+        }
+    }
+    
+    /// Best pose estimate: the fused one from localization if set, else the interface pose
+    /// with odometry's covariance; None when neither is available
+    fn pose_confidence(&self, sensor_data: &ros_interface::SensorData) -> Option<core::PoseConfidence> {
+        if let Some(estimate) = &self.pose_estimate {
+            return Some(estimate.clone());
+        }
+        let pose = self.ros_interface.get_current_pose().ok()?;
+        Some(core::PoseConfidence {
+            pose: core::Pose { x: pose.x as f64, y: pose.y as f64, theta: pose.theta as f64 },
+            covariance: ros_interface::planar_covariance(&sensor_data.odom_data.pose.covariance),
+        })
+    }
+    
+    /// Collect this update's worst-case conditions and enter Panic if they warrant it
+    ///
+    /// `sensor_data` is None when the read itself failed.
+    fn check_panic(&mut self, sensor_data: Option<&ros_interface::SensorData>) -> bool {
+        let panic = &self.config.panic;
+        let mut causes = Vec::new();
+        if panic.sensor_loss_updates > 0 && self.sensor_loss_updates >= panic.sensor_loss_updates {
+            causes.push(PanicCause::SensorLoss);
+        }
+        if self.navigation_planner.obstacle_within(panic.contact_distance) {
+            causes.push(PanicCause::ObstacleContact);
+        }
+        if sensor_data.is_some_and(|data| imu_tilt(&data.imu_data, &self.config.ros_config.imu_axis_remap) > panic.max_tilt) {
+            causes.push(PanicCause::OverTilt);
+        }
+        
+        if !panic.triggers(&causes) {
+            return false;
+        }
+        self.state.enter_panic(&format!("{:?}; hard stop until operator reset", causes));
+        self.panic_causes = causes;
+        self.last_plan = None;
+        self.last_inputs = None;
+        true
+    }
+    
+    /// Command a hard stop for this Panic cycle
+    fn hold_panic_stop(&mut self) -> EosResult<()> {
+        let command = self.motion_controller.emergency_stop();
+        self.ros_interface.publish_command(&command)?;
+        Ok(())
+    }
+    
+    /// Operator reset out of Panic, leaving the system Idle
+    ///
+    /// Returns whether the system was in Panic. Navigation resumes only once the
+    /// mode is set again.
+    pub fn reset_panic(&mut self) -> bool {
+        if !self.state.reset_panic() {
+            return false;
+        }
+        log::info!("Panic ({:?}) reset by operator", self.panic_causes);
+        self.panic_causes.clear();
+        self.sensor_loss_updates = 0;
+        true
+    }
+    
+    /// Conditions that caused the current Panic (empty when not in Panic)
+    pub fn panic_causes(&self) -> &[PanicCause] {
+        &self.panic_causes
+    }
+    
+    /// Publish the configured fallback command for this cycle
+    fn apply_fallback(&mut self) -> EosResult<()> {
+        log::warn!("Unusable sensor data or neural output, applying {:?} fallback", self.config.fallback.policy);
+        let command = self.config.fallback.command();
+        self.ros_interface.publish_command(&command)?;
+        Ok(())
+    }
+    
+    /// Release a latched emergency stop once no obstacle is critically close
+    pub fn clear_emergency_stop(&mut self) -> EosResult<()> {
+        self.navigation_planner.clear_emergency_stop()?;
+        Ok(())
+    }
+    
+    /// Enable or bypass the neural engine without tearing it down
+    pub fn set_neural_enabled(&mut self, enabled: bool) {
+        self.enable_neural = enabled;
+        self.last_inputs = None;
+    }
+    
+    /// Enable or disable planning and control; disabled navigation holds position
+    pub fn set_navigation_enabled(&mut self, enabled: bool) {
+        self.enable_navigation = enabled;
+        self.last_plan = None;
+    }
+    
+    /// Override the high-level mode; only Navigating, Mapping and Exploring plan and drive
+    ///
+    /// Pose confidence and battery level move the mode on from here at the next
+    /// perception update. Panic can be entered this way (e.g. from an operator e-stop)
+    /// but only `reset_panic` leaves it.
+    pub fn set_mode(&mut self, mode: core::Mode) {
+        let before = self.mode();
+        self.state.override_mode(mode);
+        if self.mode() != before {
+            self.last_plan = None;
+        }
+    }
+    
+    /// Current high-level mode
+    pub fn mode(&self) -> core::Mode {
+        self.state.get_mode()
+    }
+    
+    /// Set the navigation goal, rejecting goals that cannot be reached
+    pub fn set_goal(&mut self, goal: ros_interface::Pose2D) -> EosResult<()> {
+        if self.mode() == core::Mode::Panic {
+            return Err(EosError::Panicked);
+        }
+        self.navigation_planner.set_goal(goal)?;
+        self.last_plan = None;
+        Ok(())
+    }
+    
+    /// Queue a navigation goal after the ones already set, rejecting goals that cannot be reached
+    pub fn push_goal(&mut self, goal: ros_interface::Pose2D) -> EosResult<()> {
+        if self.mode() == core::Mode::Panic {
+            return Err(EosError::Panicked);
+        }
+        let idle = self.navigation_planner.goals_remaining() == 0;
+        self.navigation_planner.push_goal(goal)?;
+        if idle {
+            self.last_plan = None;
+        }
+        Ok(())
+    }
+    
+    /// Periodically save the neural model to `path` while cycles run
+    pub fn enable_checkpointing(&mut self, path: String, every: Duration) {
+        self.neural_engine.enable_checkpointing(path, every);
+    }
+    
+    /// Whether the neural engine runs each perception update
+    pub fn is_neural_enabled(&self) -> bool {
+        self.enable_neural
+    }
+    
+    /// Whether planning and control run each cycle
+    pub fn is_navigation_enabled(&self) -> bool {
+        self.enable_navigation
+    }
+    
+    /// How many times each pipeline stage has run
+    pub fn cycle_counts(&self) -> CycleCounts {
+        self.counts
+    }
+    
+    /// The robot interface the system runs on
+    pub fn interface(&self) -> &I {
+        &self.ros_interface
+    }
+    
+    /// Report battery state of charge (0.0 - 1.0) so the base slows down when low
+    ///
+    /// Below the state machine's low-battery threshold the system goes Charging and stops.
+    pub fn set_battery_level(&mut self, soc: f32) {
+        self.battery_level = soc as f64;
+        self.motion_controller.set_energy_scale(soc);
+    }
+    
+    /// Set the fused pose estimate (e.g. `Core::get_pose`) in the configured pose frame
+    ///
+    /// Used for mode decisions and published as odometry in place of the raw odometry
+    /// pose and covariance; call it each cycle to keep it current.
+    pub fn set_pose_estimate(&mut self, estimate: core::PoseConfidence) {
+        self.pose_estimate = Some(estimate);
+    }
+    
+    /// Set the occupancy grid to publish (e.g. from `Core::get_perception_snapshot`), in the
+    /// configured pose frame
+    ///
+    /// Published once on the next cycle; set it again whenever perception updates the map.
+    pub fn set_map(&mut self, grid: core::OccupancyGrid) {
+        self.map = Some(grid);
+    }
+    
+    /// Run cycles until shutdown, adapting the rate to the motion state
+    pub fn run(&mut self) -> EosResult<()> {
+        self.run_with_clock(&mut SystemClock)
+    }
+    
+    /// Run cycles on the given clock until the system is shut down
+    pub fn run_with_clock<C: Clock>(&mut self, clock: &mut C) -> EosResult<()> {
+        let mut pacer = LoopPacer::new(self.config.loop_rates.clone());
+        // Ramp velocities over the measured cycle rather than assuming 100 ms
+        let started = clock.now();
+        let mut cycle_timer = core::CycleTimer::new(0.1, 0.5);
+        
+        while self.is_initialized {
+            let dt = cycle_timer.tick(clock.now().duration_since(started).as_secs_f64());
+            self.motion_controller.set_cycle_time(dt as f32);
+            let result = self.run_cycle();
+            self.report_cycle(result);
+            pacer.pace(clock, self.motion_controller.motion_state());
+        }
+        
+        Ok(())
+    }
+    
+    /// Awaitable `run_cycle`: waits for sensor data newer than the last cycle's, then runs it
+    ///
+    /// Waits at most one loop period, so a silent sensor still gets cycles that notice
+    /// the loss; interfaces that can't signal new data run right away.
+    #[cfg(feature = "async")]
+    pub async fn run_cycle_async(&mut self) -> EosResult<()> {
+        if let Some(updates) = &mut self.sensor_updates {
+            let period = self.config.loop_rates.period_for(self.motion_controller.motion_state());
+            if let Ok(Err(_)) = tokio::time::timeout(period, updates.changed()).await {
+                // The interface is gone and can't signal again; stop waiting on it
+                self.sensor_updates = None;
+            }
+        }
+        self.run_cycle()
+    }
+    
+    /// Async counterpart of `run`: cycles as sensor data arrives, at most at the loop rate,
+    /// pacing with tokio timers instead of blocking the thread
+    #[cfg(feature = "async")]
+    pub async fn run_async(&mut self) -> EosResult<()> {
+        let started = tokio::time::Instant::now();
+        let mut cycle_timer = core::CycleTimer::new(0.1, 0.5);
+        
+        while self.is_initialized {
+            let cycle_start = tokio::time::Instant::now();
+            let dt = cycle_timer.tick(cycle_start.duration_since(started).as_secs_f64());
+            self.motion_controller.set_cycle_time(dt as f32);
+            let result = self.run_cycle_async().await;
+            self.report_cycle(result);
+            
+            let period = self.config.loop_rates.period_for(self.motion_controller.motion_state());
+            tokio::time::sleep_until(cycle_start + period).await;
+        }
+        
+        Ok(())
+    }
+    
+    /// Log a cycle's failure and publish status, as the run loops do after every cycle
+    fn report_cycle(&self, result: EosResult<()>) {
+        // A failed cycle (e.g. no safe path) shouldn't end the loop
+        if let Err(e) = result {
+            log::warn!("Cycle failed: {}", e);
+        }
+        
+        // Report health even after a failed cycle, when it matters most
+        if let Err(e) = self.publish_status() {
+            log::warn!("Status publish failed: {}", e);
+        }
+    }
+    
+    /// Current system status as JSON, e.g. for a web dashboard
+    pub fn status_json(&self) -> String {
+        serde_json::to_string(&self.get_status()).unwrap_or_else(|e| {
+            log::error!("Failed to serialize system status: {}", e);
+            String::new()
+        })
+    }
+    
+    /// Publish the JSON system status on the robot interface
+    pub fn publish_status(&self) -> EosResult<()> {
+        if !self.is_initialized {
+            return Err(EosError::NotInitialized);
+        }
+        self.ros_interface.publish_status(&self.status_json())?;
         Ok(())
     }
     
     /// Shutdown Eos OS gracefully
-    pub fn shutdown(&mut self) -> Result<(), EosError> {
+    pub fn shutdown(&mut self) -> EosResult<()> {
+        if !self.is_initialized {
+            return Err(EosError::NotInitialized);
+        }
+        
         log::info!("Shutting down Eos OS...");
         
-        // Save neural network state
-        self.neural_engine.save_model("models/snn_state.json")
-            .map_err(|e| EosError::NeuralError(e.to_string()))?;
+        // Save neural network state in the format the model was loaded from; a failed
+        // save shouldn't leave the system half running
+        let format = self.config.neural_config.model_path
+            .as_deref()
+            .map(neural::ModelFormat::from_path)
+            .unwrap_or_default();
+        if let Err(e) = self.neural_engine.save_model(&format!("models/snn_state.{}", format.extension())) {
+            log::warn!("Failed to save neural state: {}", e);
+        }
+        self.neural_engine.shutdown()?;
             
         // Shutdown ROS interface
         self.ros_interface.shutdown()?;
@@ -173,44 +1044,93 @@ impl EosOS {
         // This would collect status from all components
         SystemStatus {
             neural: self.neural_engine.get_status(),
-            navigation: self.navigation_planner.get_status(),
+            navigation: navigation::NavigationStatus {
+                motion_state: self.motion_controller.motion_state(),
+                eta_seconds: self.navigation_planner.eta(self.motion_controller.mean_speed()),
+                ..self.navigation_planner.get_status()
+            },
             ros: self.ros_interface.get_status(),
-            operational: self.is_initialized,
+            operational: self.is_initialized && self.mode() != core::Mode::Panic,
         }
     }
 }
 
 /// Eos OS error types
-#[derive(Debug)]
+///
+/// Wrapped errors are part of the message rather than a `source`, so printing one
+/// with `{}` (as the run loop logs do) shows the cause exactly once.
+#[derive(Debug, thiserror::Error)]
 pub enum EosError {
     /// ROS interface error
+    #[error("ROS error: {0}")]
     RosError(String),
     /// Neural network error
+    #[error("Neural error: {0}")]
     NeuralError(String),
     /// Navigation error
+    #[error("Navigation error: {0}")]
     NavigationError(String),
     /// System not initialized
+    #[error("System not initialized")]
     NotInitialized,
     /// Configuration error
+    #[error("Configuration error: {0}")]
     ConfigError(String),
+    /// Request refused while latched in Panic
+    #[error("System is in panic mode; operator reset required")]
+    Panicked,
+    /// Indoor app error
+    #[error("Indoor app error: {0}")]
+    IndoorError(apps::eos_indoor::IndoorError),
+    /// Drone app error
+    #[error("Drone app error: {0}")]
+    DroneError(apps::eos_drone::DroneError),
+    /// Rover app error
+    #[error("Rover app error: {0}")]
+    RoverError(apps::eos_rover::RoverError),
 }
 
-impl std::fmt::Display for EosError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            EosError::RosError(msg) => write!(f, "ROS error: {}", msg),
-            EosError::NeuralError(msg) => write!(f, "Neural error: {}", msg),
-            EosError::NavigationError(msg) => write!(f, "Navigation error: {}", msg),
-            EosError::NotInitialized => write!(f, "System not initialized"),
-            EosError::ConfigError(msg) => write!(f, "Configuration error: {}", msg),
-        }
+impl From<ros_interface::RosError> for EosError {
+    fn from(err: ros_interface::RosError) -> Self {
+        EosError::RosError(err.to_string())
+    }
+}
+
+impl From<neural::NeuralError> for EosError {
+    fn from(err: neural::NeuralError) -> Self {
+        EosError::NeuralError(err.to_string())
+    }
+}
+
+impl From<navigation::NavigationError> for EosError {
+    fn from(err: navigation::NavigationError) -> Self {
+        EosError::NavigationError(err.to_string())
+    }
+}
+
+impl From<apps::eos_indoor::IndoorError> for EosError {
+    fn from(err: apps::eos_indoor::IndoorError) -> Self {
+        EosError::IndoorError(err)
+    }
+}
+
+impl From<apps::eos_drone::DroneError> for EosError {
+    fn from(err: apps::eos_drone::DroneError) -> Self {
+        EosError::DroneError(err)
+    }
+}
+
+impl From<apps::eos_rover::RoverError> for EosError {
+    fn from(err: apps::eos_rover::RoverError) -> Self {
+        EosError::RoverError(err)
     }
 }
 
-impl std::error::Error for EosError {}
+/// Result type used across the Eos OS API
+pub type EosResult<T> = Result<T, EosError>;
 
 /// Combined system status
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct SystemStatus {
     /// Neural engine status
     pub neural: neural::NeuralStatus,
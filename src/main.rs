@@ -7,10 +7,10 @@
 // - Eos modules: Neural, ROS interface, and navigation components.
 use r2r::{QosProfile, Node, Context};
 use log::{info, error};
-use env_logger;
-use eos_os::{
-    neural::Snn,
-    ros_interface::{Publisher, Subscriber},
+use r2r::sensor_msgs::msg::LaserScan;
+use eos::{
+    neural::snn::Snn,
+    ros_interface::{Publisher, SensorData, Subscriber},
     navigation::Planner,
 };
 use std::error::Error;
@@ -24,18 +24,18 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Create ROS 2 context and node
     let ctx = Context::create()?;
-    let mut node = Node::create(&ctx, "eos_node", "")?;
+    let mut node = Node::create(ctx, "eos_node", "")?;
     info!("ROS 2 node initialized");
 
     // Initialize SNN with 100 neurons
     let snn = Snn::new(100);
 
     // Set up subscriber for LIDAR data (/scan)
-    let subscriber = Subscriber::new(&mut node, "/scan")?;
+    let subscriber: Subscriber<LaserScan> = Subscriber::new(&mut node, "/scan", QosProfile::default())?;
     info!("Subscribed to /scan");
 
     // Set up publisher for navigation commands (/cmd_vel)
-    let publisher = Publisher::new(&mut node, "/cmd_vel")?;
+    let publisher = Publisher::new(&mut node, "/cmd_vel", QosProfile::default())?;
     info!("Publishing to /cmd_vel");
 
     // Initialize navigation planner with SNN
@@ -48,17 +48,23 @@ fn main() -> Result<(), Box<dyn Error>> {
         // Limited for MVP demo
 
         // Spin node to process ROS 2 messages
-        node.spin_once(std::time::Duration::from_millis(100))?;
+        node.spin_once(std::time::Duration::from_millis(100));
 
         // Process sensor data if available
-        if let Some(sensor_data) = subscriber.get_data() {
-            info!("Received LIDAR data: {} ranges", sensor_data.ranges.len());
+        if let Ok(laser_scan) = subscriber.get_latest() {
+            // The SNN only reads the scan
+            let sensor_data = SensorData {
+                laser_scan,
+                imu_data: Default::default(),
+                odom_data: Default::default(),
+            };
+            info!("Received LIDAR data: {} ranges", sensor_data.laser_scan.ranges.len());
 
             // Plan navigation using SNN
             let cmd = planner.plan(&sensor_data);
 
             // Publish navigation command
-            if let Err(e) = publisher.publish(&cmd) {
+            if let Err(e) = publisher.publish(&cmd.to_ros_message()) {
                 error!("Failed to publish command: {}", e);
             } else {
                 info!(
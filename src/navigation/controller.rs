@@ -1,8 +1,8 @@
 // src/navigation/controller.rs
 // Executes navigation commands by sending motor actions to the robot.
-// Imports shared NavCommand type from lib.rs.
+// Imports the shared MotionCommand type from the ROS interface.
 
-use crate::NavCommand;
+use crate::ros_interface::MotionCommand;
 use log::info;
 
 // Controller struct to handle motor commands.
@@ -18,7 +18,7 @@ impl Controller {
     // Executes a navigation command by logging it (placeholder for ROS 2 integration).
     // - cmd: Navigation command with linear/angular velocities.
     // In production, sends to /cmd_vel via Publisher.
-    pub fn execute(&self, cmd: &NavCommand) {
+    pub fn execute(&self, cmd: &MotionCommand) {
         info!(
             "Executing command: linear={}, angular={}",
             cmd.linear, cmd.angular
@@ -0,0 +1,76 @@
+//! Robot footprint geometry for collision checks
+//!
+//! A footprint is a polygon in the base frame. Moving along a straight segment
+//! sweeps it into a larger shape; clearances are measured from obstacle points
+//! to that swept shape rather than to the robot center.
+
+use crate::ros_interface::Pose2D;
+
+use super::keepout::{polygon_contains, segments_cross};
+
+/// Distance from `point` to the footprint swept straight from `start` to `end`
+///
+/// The robot is assumed to turn in place at `start` and then hold the segment
+/// heading, so a zero-length segment keeps `start.theta`. Returns 0.0 when the
+/// point lies inside the swept area.
+pub fn swept_clearance(footprint: &[(f32, f32)], start: Pose2D, end: Pose2D, point: (f32, f32)) -> f32 {
+    let dx = end.x - start.x;
+    let dy = end.y - start.y;
+    let length = (dx * dx + dy * dy).sqrt();
+    let heading = if length > 1e-6 { dy.atan2(dx) } else { start.theta };
+
+    // Express the point in the robot frame at the segment start
+    let (sin, cos) = heading.sin_cos();
+    let px = point.0 - start.x;
+    let py = point.1 - start.y;
+    let local = (px * cos + py * sin, -px * sin + py * cos);
+
+    // Sweeping the polygon forward by `length` is the same as sweeping the point backward
+    let behind = (local.0 - length, local.1);
+    polygon_segment_distance(footprint, behind, local)
+}
+
+/// Distance from the base frame origin to the farthest footprint vertex
+pub fn circumradius(footprint: &[(f32, f32)]) -> f32 {
+    footprint
+        .iter()
+        .map(|(x, y)| (x * x + y * y).sqrt())
+        .fold(0.0, f32::max)
+}
+
+/// Distance between a polygon and the segment a-b (0.0 if they overlap)
+fn polygon_segment_distance(polygon: &[(f32, f32)], a: (f32, f32), b: (f32, f32)) -> f32 {
+    if polygon.is_empty() {
+        return f32::MAX;
+    }
+    if polygon_contains(polygon, a.0, a.1) || polygon_contains(polygon, b.0, b.1) {
+        return 0.0;
+    }
+
+    let n = polygon.len();
+    (0..n)
+        .map(|i| (polygon[i], polygon[(i + 1) % n]))
+        .map(|(p, q)| {
+            if segments_cross(a, b, p, q) {
+                return 0.0;
+            }
+            point_segment_distance(a, p, q)
+                .min(point_segment_distance(b, p, q))
+                .min(point_segment_distance(p, a, b))
+                .min(point_segment_distance(q, a, b))
+        })
+        .fold(f32::MAX, f32::min)
+}
+
+/// Distance from point p to the segment a-b
+pub(super) fn point_segment_distance(p: (f32, f32), a: (f32, f32), b: (f32, f32)) -> f32 {
+    let ab = (b.0 - a.0, b.1 - a.1);
+    let length_sq = ab.0 * ab.0 + ab.1 * ab.1;
+    let t = if length_sq > 0.0 {
+        (((p.0 - a.0) * ab.0 + (p.1 - a.1) * ab.1) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let closest = (a.0 + t * ab.0, a.1 + t * ab.1);
+    ((p.0 - closest.0).powi(2) + (p.1 - closest.1).powi(2)).sqrt()
+}
@@ -0,0 +1,200 @@
+//! Cost grid and A* search for routing around obstacles
+//!
+//! The planner rasterizes inflated obstacles into a grid around the start and
+//! goal, searches it with 8-connected A*, and pulls the resulting cell path taut
+//! into a few straight legs.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Grid cell as (column, row)
+pub type Cell = (usize, usize);
+
+/// Blocked cells and extra traversal cost over a rectangular area
+#[derive(Debug, Clone)]
+pub struct CostGrid {
+    /// World position of the center of cell (0, 0)
+    origin: (f32, f32),
+    /// Edge length of a cell in meters
+    resolution: f32,
+    width: usize,
+    height: usize,
+    /// Extra cost per meter travelled through each cell (0 for free space), None where blocked
+    costs: Vec<Option<f32>>,
+}
+
+/// Open-set entry ordered so the binary heap pops the lowest estimate first
+struct Frontier {
+    estimate: f32,
+    index: usize,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl CostGrid {
+    /// Grid covering `min` to `max` at `resolution`, with each cell's cost from `cost_at`
+    ///
+    /// `cost_at` is called with every cell center and returns None for blocked cells.
+    pub fn new(min: (f32, f32), max: (f32, f32), resolution: f32, mut cost_at: impl FnMut(f32, f32) -> Option<f32>) -> Self {
+        let resolution = resolution.max(0.01);
+        let width = ((max.0 - min.0) / resolution).ceil().max(0.0) as usize + 1;
+        let height = ((max.1 - min.1) / resolution).ceil().max(0.0) as usize + 1;
+        let mut costs = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                costs.push(cost_at(min.0 + column as f32 * resolution, min.1 + row as f32 * resolution));
+            }
+        }
+        CostGrid {
+            origin: min,
+            resolution,
+            width,
+            height,
+            costs,
+        }
+    }
+
+    /// Cell containing a world point, None outside the grid
+    pub fn cell_of(&self, x: f32, y: f32) -> Option<Cell> {
+        let column = ((x - self.origin.0) / self.resolution).round();
+        let row = ((y - self.origin.1) / self.resolution).round();
+        if column < 0.0 || row < 0.0 || column >= self.width as f32 || row >= self.height as f32 {
+            return None;
+        }
+        Some((column as usize, row as usize))
+    }
+
+    /// World position of a cell's center
+    pub fn center(&self, cell: Cell) -> (f32, f32) {
+        (
+            self.origin.0 + cell.0 as f32 * self.resolution,
+            self.origin.1 + cell.1 as f32 * self.resolution,
+        )
+    }
+
+    /// Whether a cell can be entered
+    pub fn is_free(&self, cell: Cell) -> bool {
+        self.cost(cell).is_some()
+    }
+
+    fn cost(&self, cell: Cell) -> Option<f32> {
+        self.costs[cell.1 * self.width + cell.0]
+    }
+
+    /// Cheapest 8-connected cell path from `start` to `goal`, both included
+    ///
+    /// The start cell may itself be blocked so a robot already inside the inflation
+    /// can still back out of it. Diagonal steps may not cut past a blocked corner.
+    /// None when the goal is blocked or cut off from the start.
+    pub fn search(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        if start == goal {
+            return Some(vec![start]);
+        }
+        if !self.is_free(goal) {
+            return None;
+        }
+
+        let index = |cell: Cell| cell.1 * self.width + cell.0;
+        let heuristic = |cell: Cell| {
+            let dx = cell.0.abs_diff(goal.0) as f32;
+            let dy = cell.1.abs_diff(goal.1) as f32;
+            (dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy)) * self.resolution
+        };
+
+        let mut cost_so_far = vec![f32::INFINITY; self.costs.len()];
+        let mut came_from = vec![usize::MAX; self.costs.len()];
+        let mut open = BinaryHeap::new();
+        cost_so_far[index(start)] = 0.0;
+        open.push(Frontier { estimate: heuristic(start), index: index(start) });
+
+        while let Some(Frontier { estimate, index: current }) = open.pop() {
+            let cell = (current % self.width, current / self.width);
+            if cell == goal {
+                break;
+            }
+            // Skip entries superseded by a cheaper route found since they were queued
+            if estimate > cost_so_far[current] + heuristic(cell) {
+                continue;
+            }
+
+            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (-1, 1), (1, -1), (1, 1)] {
+                let Some(next) = self.offset(cell, dx, dy) else {
+                    continue;
+                };
+                let Some(extra) = self.cost(next) else {
+                    continue;
+                };
+                let diagonal = dx != 0 && dy != 0;
+                if diagonal && !(self.offset(cell, dx, 0).is_some_and(|c| self.is_free(c))
+                    && self.offset(cell, 0, dy).is_some_and(|c| self.is_free(c)))
+                {
+                    continue;
+                }
+
+                let step = if diagonal { std::f32::consts::SQRT_2 } else { 1.0 } * self.resolution;
+                let candidate = cost_so_far[current] + step * (1.0 + extra);
+                if candidate < cost_so_far[index(next)] {
+                    cost_so_far[index(next)] = candidate;
+                    came_from[index(next)] = current;
+                    open.push(Frontier { estimate: candidate + heuristic(next), index: index(next) });
+                }
+            }
+        }
+
+        // Walk back from the goal
+        if came_from[index(goal)] == usize::MAX {
+            return None;
+        }
+        let mut path = vec![goal];
+        let mut current = index(goal);
+        while came_from[current] != usize::MAX {
+            current = came_from[current];
+            path.push((current % self.width, current / self.width));
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Whether the straight line from `a` to `b` only crosses free cells
+    ///
+    /// The cell containing `a` is exempt, matching `search`'s blocked start.
+    pub fn line_clear(&self, a: (f32, f32), b: (f32, f32)) -> bool {
+        let Some(start) = self.cell_of(a.0, a.1) else {
+            return false;
+        };
+        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        let steps = (length / (self.resolution * 0.5)).ceil() as usize;
+        (0..=steps).all(|step| {
+            let t = if steps == 0 { 0.0 } else { step as f32 / steps as f32 };
+            match self.cell_of(a.0 + t * (b.0 - a.0), a.1 + t * (b.1 - a.1)) {
+                Some(cell) => cell == start || self.is_free(cell),
+                None => false,
+            }
+        })
+    }
+
+    /// Neighboring cell, None past the grid edge
+    fn offset(&self, cell: Cell, dx: isize, dy: isize) -> Option<Cell> {
+        let column = cell.0.checked_add_signed(dx)?;
+        let row = cell.1.checked_add_signed(dy)?;
+        (column < self.width && row < self.height).then_some((column, row))
+    }
+}
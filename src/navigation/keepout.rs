@@ -0,0 +1,95 @@
+//! Keep-out zones for navigation
+//!
+//! Operator-defined polygons (stairs, fragile displays) that the LiDAR cannot
+//! see. They are kept apart from the scan-derived obstacle map so they persist
+//! across cycles and can be rendered separately.
+
+use serde::Deserialize;
+
+use super::footprint::point_segment_distance;
+use super::NavigationError;
+
+/// A polygonal area the robot must never enter
+#[derive(Debug, Clone, Deserialize)]
+pub struct KeepoutZone {
+    /// Human-readable zone name
+    pub name: String,
+    /// Polygon vertices (x, y) in the map frame, in order
+    pub vertices: Vec<(f32, f32)>,
+}
+
+impl KeepoutZone {
+    /// Whether a point lies inside the polygon (even-odd rule)
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        polygon_contains(&self.vertices, x, y)
+    }
+
+    /// Whether the straight segment from `start` to `end` enters the polygon
+    pub fn intersects_segment(&self, start: (f32, f32), end: (f32, f32)) -> bool {
+        self.contains(start.0, start.1)
+            || self.contains(end.0, end.1)
+            || self.edges().any(|(a, b)| segments_cross(start, end, a, b))
+    }
+
+    /// Distance from a point to the polygon, 0.0 inside it
+    pub fn distance_to(&self, x: f32, y: f32) -> f32 {
+        if self.contains(x, y) {
+            return 0.0;
+        }
+        self.edges()
+            .map(|(a, b)| point_segment_distance((x, y), a, b))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Polygon edges, closing back to the first vertex
+    fn edges(&self) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+}
+
+/// Load keep-out zones from a JSON (`.json`) or YAML file
+pub fn load_zones(path: &str) -> Result<Vec<KeepoutZone>, NavigationError> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| NavigationError::ZoneFileError(format!("{}: {}", path, e)))?;
+
+    let zones: Vec<KeepoutZone> = if path.ends_with(".json") {
+        serde_json::from_str(&data).map_err(|e| NavigationError::ZoneFileError(e.to_string()))?
+    } else {
+        serde_yaml::from_str(&data).map_err(|e| NavigationError::ZoneFileError(e.to_string()))?
+    };
+
+    if let Some(zone) = zones.iter().find(|zone| zone.vertices.len() < 3) {
+        return Err(NavigationError::ZoneFileError(format!(
+            "zone '{}' needs at least 3 vertices",
+            zone.name
+        )));
+    }
+
+    Ok(zones)
+}
+
+/// Whether a point lies inside the polygon with the given vertices (even-odd rule)
+pub(super) fn polygon_contains(vertices: &[(f32, f32)], x: f32, y: f32) -> bool {
+    let n = vertices.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+        if (a.1 > y) != (b.1 > y) && x < a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Proper intersection test between segments p1-p2 and q1-q2
+pub(super) fn segments_cross(p1: (f32, f32), p2: (f32, f32), q1: (f32, f32), q2: (f32, f32)) -> bool {
+    let cross = |o: (f32, f32), a: (f32, f32), b: (f32, f32)| {
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+    (d1 > 0.0) != (d2 > 0.0) && (d3 > 0.0) != (d4 > 0.0)
+}
@@ -3,27 +3,134 @@
 //! This module handles path planning, obstacle avoidance, and motion control
 //! based on sensor data and neural network outputs.
 
+pub mod footprint;
+pub mod grid;
+pub mod keepout;
+pub mod planner;
+pub mod spatial_index;
+
+
 use std::collections::VecDeque;
 
+use crate::core::{Memory, Pose, SemanticObject};
+use crate::core::control::{Pid, PidGains};
+use crate::core::util::BoundedHistory;
+use crate::ros_interface::{MotionCommand, Pose2D};
+pub use grid::CostGrid;
+pub use keepout::KeepoutZone;
+pub use planner::Planner;
+pub use spatial_index::ObstacleIndex;
+
+/// Assumed time between scans that carry neither stamps nor a scan period
+const DEFAULT_SCAN_PERIOD: f32 = 0.1;
+/// Resampled waypoints closer than this to a segment end are dropped (meters)
+const RESAMPLE_EPSILON: f32 = 1e-4;
+/// Confidence of a return in the current scan; remembered obstacles fade from here
+const SCAN_RETURN_CONFIDENCE: f32 = 1.0;
+/// Controller cycle assumed until a measured one is set (seconds)
+const DEFAULT_CYCLE_TIME: f32 = 0.1;
+/// Longest cycle the controller ramps over at once, so a stall doesn't jump the velocity (seconds)
+const MAX_CYCLE_TIME: f32 = 0.5;
+/// Gentlest braking for a controlled stop, so slow creeping still ends promptly (m/s²)
+const MIN_STOP_DECELERATION: f32 = 0.1;
+/// Slack on the field-of-interest bounds, so a rounded ±π (e.g. 3.14159) keeps the rear beam (radians)
+const FOV_TOLERANCE: f32 = 1e-4;
+/// Segments scoring below this are rejected as unsafe
+const MIN_SEGMENT_SAFETY: f32 = 0.3;
+/// Cell size of the grid searched for a way around obstacles (meters)
+const GRID_RESOLUTION: f32 = 0.1;
+/// Space searched beyond the start and goal for a way around obstacles (meters)
+const GRID_MARGIN: f32 = 2.0;
+/// Extra cost per meter at the edge of an obstacle's inflation, fading out a safety
+/// distance further away, so detours keep their distance where there is room
+const CLEARANCE_COST: f32 = 2.0;
+
 /// Navigation planner for path planning and obstacle avoidance
 pub struct NavigationPlanner {
     config: NavigationConfig,
-    path_history: VecDeque<PathSegment>,
+    path_history: BoundedHistory<PathSegment>,
     obstacle_map: Vec<Obstacle>,
+    obstacle_index: ObstacleIndex,
+    external_obstacles: Vec<Obstacle>,
     current_goal: Option<Pose2D>,
+    /// Goals to pursue after the current one, in order
+    queued_goals: VecDeque<Pose2D>,
+    /// The current goal has an outcome and is only held, not pursued
+    goal_resolved: bool,
+    /// Seconds of scans since the current goal was set
+    goal_elapsed: f32,
+    /// Seconds of scans the emergency stop has held the current goal up
+    stopped_elapsed: f32,
+    /// Seconds of scans taken in by `update` since the last plan
+    unplanned_elapsed: f32,
+    last_outcome: Option<NavigationOutcome>,
     safety_monitor: SafetyMonitor,
+    last_rejection: Option<RejectionReason>,
+    current_path: Option<Path>,
+    keepout_zones: Vec<KeepoutZone>,
+    last_pose: Pose2D,
+    /// Odometry pose of the latest scan, whose base frame the obstacle map is in
+    scan_pose: Pose2D,
+    people: Vec<Pose2D>,
+    remembered_obstacles: Vec<RememberedObstacle>,
+    /// Previous scan's obstacles in the odometry frame, for velocity estimation
+    previous_scan_obstacles: Vec<Pose2D>,
+    last_scan_stamp: Option<f64>,
+    current_speed: f32,
+    /// Topological memory consulted for routes to goals near known places
+    memory: Option<Memory>,
+    recovery_state: RecoveryState,
+    /// Index into `recovery_behaviors` of the behavior running or next to run
+    recovery_step: usize,
+    /// Seconds the current recovery behavior has run
+    recovery_elapsed: f32,
+}
+
+/// Obstacle kept in the map after it left view, stored in the odometry frame
+#[derive(Debug, Clone)]
+struct RememberedObstacle {
+    obstacle: Obstacle,
+    /// Seconds since it was last observed
+    age: f32,
 }
 
 /// Motion controller for executing navigation plans
 pub struct MotionController {
     config: NavigationConfig,
     motion_profile: MotionProfile,
-    command_history: VecDeque<MotionCommand>,
+    command_history: BoundedHistory<MotionCommand>,
     safety_limits: SafetyLimits,
+    last_output: MotionCommand,
+    stuck_monitor: StuckMonitor,
+    energy_scale: f32,
+    motion_state: MotionState,
+    safety_events: BoundedHistory<SafetyEvent>,
+    /// Whether the previous command was held back by the acceleration limits
+    acceleration_limited: bool,
+    /// Braking rate of the controlled stop in progress, if any
+    stop_deceleration: Option<f32>,
+    heading_pid: Pid,
+    /// Latest robot pose in the odometry frame to follow plans from; None follows them
+    /// from where they were made
+    pose: Option<Pose2D>,
+    /// Time since the previous command (seconds)
+    cycle_time: f32,
 }
 
-/// Navigation configuration
+/// Detects a stalled robot by comparing commanded and measured velocity
 #[derive(Debug, Clone)]
+pub struct StuckMonitor {
+    /// Time allowed with commanded but no measured motion (seconds)
+    timeout: f32,
+    /// Accumulated stalled time (seconds)
+    stalled_time: f32,
+}
+
+/// Navigation configuration
+///
+/// Fields missing from a config file take their defaults.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct NavigationConfig {
     /// Maximum linear velocity
     pub max_linear_velocity: f32,
@@ -31,16 +138,161 @@ pub struct NavigationConfig {
     pub max_angular_velocity: f32,
     /// Maximum acceleration
     pub max_acceleration: f32,
-    /// Safety distance from obstacles
+    /// Maximum deceleration
+    pub max_deceleration: f32,
+    /// Safety distance from obstacles at standstill
     pub safety_distance: f32,
+    /// How the safety distance grows with speed
+    pub safety_margin_model: SafetyMarginModel,
     /// Goal tolerance
     pub goal_tolerance: f32,
+    /// Seconds allowed to reach a goal before aborting it (None never times out)
+    pub goal_timeout: Option<f32>,
+    /// Seconds an emergency stop must hold the robot before the goal is aborted as unsafe
+    pub unsafe_abort_timeout: f32,
+    /// Known map extent goals must lie within (None accepts goals anywhere)
+    pub map_bounds: Option<MapBounds>,
+    /// Distance from the goal within which the speed tapers down to `creep_speed`
+    pub approach_distance: f32,
+    /// Speed for final positioning at the goal tolerance radius
+    pub creep_speed: f32,
     /// Obstacle inflation radius
     pub obstacle_inflation: f32,
+    /// Obstacles below this confidence (e.g. faded or likely noise) never reject paths or stop the robot
+    pub min_blocking_confidence: f32,
+    /// Robot outline (x, y) in the base frame, in order; empty treats the robot as a
+    /// circle around its center covered by the safety distance
+    pub footprint: Vec<(f32, f32)>,
+    /// LiDAR mount pose in the robot base frame
+    pub lidar_mount: Pose2D,
+    /// Smallest bearing (radians, base frame) of returns added to the obstacle map
+    pub fov_min: f32,
+    /// Largest bearing (radians, base frame) of returns added to the obstacle map
+    pub fov_max: f32,
+    /// Maximum distance the planner commits to per cycle
+    pub planning_horizon: f32,
+    /// Distance from a remembered node within which the robot and goal can use a
+    /// remembered route (0 always plans directly)
+    pub memory_route_radius: f32,
+    /// Waypoint spacing the controller resamples paths to (0 disables resampling)
+    pub path_resample_spacing: f32,
+    /// EMA weight of the previous output command (0 disables smoothing)
+    pub output_smoothing_alpha: f32,
+    /// PID gains steering the heading onto the current path segment
+    pub heading_gains: PidGains,
+    /// Heading error (radians) up to which the robot drives at full speed; forward
+    /// speed falls to zero at twice this, so large turns happen in place
+    pub alignment_tolerance: f32,
+    /// Seconds of commanded motion without measured motion before declaring stuck
+    pub stuck_timeout: f32,
+    /// Behaviors `recover` works through, in order, after planning fails
+    pub recovery_behaviors: Vec<RecoveryBehavior>,
+    /// Radians turned by the rotate-in-place recovery
+    pub recovery_rotation: f32,
+    /// Meters reversed by the back-up recovery
+    pub recovery_backup_distance: f32,
+    /// Reversing speed of the back-up recovery (m/s)
+    pub recovery_backup_speed: f32,
+    /// State of charge below which velocity and acceleration limits are reduced
+    pub low_energy_threshold: f32,
+    /// Fraction of the limits still allowed at an empty battery
+    pub min_energy_scale: f32,
+    /// Largest gap between adjacent LiDAR returns of the same cluster
+    pub cluster_gap: f32,
+    /// Clusters shorter than this are small obstacles, longer ones walls
+    pub small_obstacle_extent: f32,
+    /// Fraction of the safety distance kept from isolated returns
+    pub point_clearance_scale: f32,
+    /// Fraction of the safety distance kept from small obstacles
+    pub small_clearance_scale: f32,
+    /// Distance from a person within which the robot slows down
+    pub comfort_radius: f32,
+    /// Fraction of the speed still allowed right next to a person
+    pub min_comfort_scale: f32,
+    /// Obstacle distance that trips the emergency stop
+    pub emergency_stop_distance: f32,
+    /// Keep the emergency stop engaged until `clear_emergency_stop` succeeds; otherwise
+    /// it clears on the first cycle with no obstacle within `emergency_stop_distance`
+    pub latch_emergency_stop: bool,
+    /// Distance over which the robot brakes to a stop after losing localization (0 stops at once)
+    pub lost_stop_distance: f32,
+    /// Seconds an obstacle keeps (fading) cost after it was last seen; 0 keeps only the latest scan
+    pub obstacle_persistence: f32,
+    /// Farthest an obstacle may move between scans and still be matched to estimate its
+    /// velocity (meters, 0 disables velocity estimation)
+    pub velocity_association_distance: f32,
+    /// Most recent safety interventions kept for auditing (0 disables recording)
+    pub safety_event_capacity: usize,
+    /// Dynamic Window Approach command selection
+    pub dwa: DwaConfig,
+}
+
+/// Dynamic Window Approach tuning for the motion controller
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DwaConfig {
+    /// Choose commands by simulated rollouts instead of the heading PID and speed ramp
+    pub enabled: bool,
+    /// Seconds each candidate command is simulated ahead
+    pub rollout_time: f32,
+    /// Linear velocities sampled across the window
+    pub linear_samples: usize,
+    /// Angular velocities sampled across the window
+    pub angular_samples: usize,
+    /// Weight of progress toward the path's lookahead point
+    pub progress_weight: f32,
+    /// Weight of the closest obstacle clearance along the rollout
+    pub clearance_weight: f32,
+    /// Weight of the final heading's alignment with the lookahead point
+    pub heading_weight: f32,
+}
+
+impl Default for DwaConfig {
+    fn default() -> Self {
+        DwaConfig {
+            enabled: false,
+            rollout_time: 1.5,
+            linear_samples: 7,
+            angular_samples: 15,
+            progress_weight: 1.0,
+            clearance_weight: 0.5,
+            heading_weight: 0.5,
+        }
+    }
+}
+
+/// Axis-aligned extent of the known map in meters
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MapBounds {
+    /// Smallest x in meters
+    pub min_x: f32,
+    /// Smallest y in meters
+    pub min_y: f32,
+    /// Largest x in meters
+    pub max_x: f32,
+    /// Largest y in meters
+    pub max_y: f32,
+}
+
+impl MapBounds {
+    /// Whether a point lies inside the bounds (edges included)
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+/// How the required obstacle clearance depends on the current speed
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyMarginModel {
+    /// Always keep `safety_distance`
+    Fixed,
+    /// Add the braking distance at `max_deceleration`: `safety_distance + v²/(2·max_decel)`
+    StoppingDistance,
 }
 
 /// Navigation status
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct NavigationStatus {
     /// Whether a goal is set
     pub has_goal: bool,
@@ -52,6 +304,89 @@ pub struct NavigationStatus {
     pub safety_status: SafetyStatus,
     /// Current motion state
     pub motion_state: MotionState,
+    /// Fraction of the active path already travelled (0.0 - 1.0)
+    pub progress_fraction: f32,
+    /// Estimated seconds to the goal at the current mean speed, if moving
+    pub eta_seconds: Option<f32>,
+    /// How the most recent goal ended, if one has
+    pub last_outcome: Option<NavigationOutcome>,
+    /// Progress through the recovery behaviors after a planning failure
+    pub recovery_state: RecoveryState,
+    /// Goals not yet reached, including the one being pursued
+    pub goals_remaining: usize,
+}
+
+/// Way of getting unstuck when planning fails
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryBehavior {
+    /// Turn on the spot so the scan sweeps the surroundings and refreshes the map
+    RotateInPlace,
+    /// Reverse a short distance, skipped when something is close behind
+    BackUp,
+    /// Forget scanned and remembered obstacles, keeping external ones
+    ClearObstacleMap,
+}
+
+/// Where the planner is in its recovery behaviors
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryState {
+    /// Planning is succeeding, no recovery needed
+    Idle,
+    /// Running the given behavior
+    Running(RecoveryBehavior),
+    /// Every behavior was tried without planning succeeding again
+    Exhausted,
+}
+
+/// How a navigation goal ended
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NavigationOutcome {
+    /// Robot came within the goal tolerance
+    Reached,
+    /// An emergency stop held the robot for `unsafe_abort_timeout`
+    AbortedUnsafe,
+    /// The goal was not reached within `goal_timeout`
+    AbortedTimeout,
+    /// A new goal replaced it
+    Preempted,
+    /// The goal was cleared
+    Cleared,
+}
+
+/// Structured explanation of why a path was rejected
+#[derive(Debug, Clone)]
+pub struct RejectionReason {
+    /// Position of the obstacle responsible for the rejection in the base frame, if known
+    pub obstacle: Option<Pose2D>,
+    /// Safety score computed for the rejected path
+    pub safety_score: f32,
+    /// Index of the offending path segment
+    pub segment_index: usize,
+}
+
+/// A command the motion controller changed to stay within its limits
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafetyEvent {
+    /// Why the command was changed
+    pub reason: SafetyEventReason,
+    /// Command before the intervention
+    pub original: MotionCommand,
+    /// Command after the intervention
+    pub applied: MotionCommand,
+}
+
+/// Kind of safety intervention
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafetyEventReason {
+    /// Clamped to the (energy-scaled) velocity limits
+    VelocityLimit,
+    /// Velocity change limited by the acceleration or deceleration limits
+    AccelerationLimit,
+    /// Overridden by an emergency stop
+    EmergencyStop,
 }
 
 /// Safety monitor for navigation
@@ -74,6 +409,8 @@ struct MotionProfile {
     target_velocity: MotionCommand,
     /// Acceleration limits
     acceleration_limits: MotionCommand,
+    /// Deceleration limits
+    deceleration_limits: MotionCommand,
 }
 
 /// Safety limits for motion control
@@ -89,7 +426,7 @@ struct SafetyLimits {
 
 /// Path segment for navigation
 #[derive(Debug, Clone)]
-struct PathSegment {
+pub struct PathSegment {
     /// Start pose
     start: Pose2D,
     /// End pose
@@ -102,20 +439,34 @@ struct PathSegment {
 
 /// Obstacle representation
 #[derive(Debug, Clone)]
-struct Obstacle {
+pub struct Obstacle {
     /// Position
-    position: Pose2D,
+    pub position: Pose2D,
     /// Radius
-    radius: f32,
+    pub radius: f32,
     /// Confidence
-    confidence: f32,
+    pub confidence: f32,
     /// Velocity (if moving)
-    velocity: Option<(f32, f32)>,
+    pub velocity: Option<(f32, f32)>,
+    /// Size class of the cluster the obstacle belongs to
+    pub category: ObstacleCategory,
+}
+
+/// Obstacle size class from clustering adjacent LiDAR returns
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObstacleCategory {
+    /// Isolated single return, e.g. debris or a table leg
+    Point,
+    /// Short cluster, e.g. a box or a person's legs
+    Small,
+    /// Long cluster such as a wall; also used for obstacles of unknown size
+    Large,
 }
 
 /// Safety status
-#[derive(Debug, Clone, PartialEq)]
-enum SafetyStatus {
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyStatus {
     /// Normal operation
     Normal,
     /// Warning - approaching limits
@@ -127,8 +478,9 @@ enum SafetyStatus {
 }
 
 /// Motion state
-#[derive(Debug, Clone, PartialEq)]
-enum MotionState {
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MotionState {
     /// Stopped
     Stopped,
     /// Moving
@@ -141,19 +493,102 @@ enum MotionState {
     EmergencyStopping,
 }
 
+impl Default for NavigationConfig {
+    fn default() -> Self {
+        NavigationConfig {
+            max_linear_velocity: 0.5,
+            max_angular_velocity: 1.0,
+            max_acceleration: 0.3,
+            max_deceleration: 0.5,
+            safety_distance: 0.5,
+            safety_margin_model: SafetyMarginModel::StoppingDistance,
+            goal_tolerance: 0.1,
+            goal_timeout: None,
+            unsafe_abort_timeout: 10.0,
+            map_bounds: None,
+            approach_distance: 1.0,
+            creep_speed: 0.05,
+            obstacle_inflation: 0.3,
+            min_blocking_confidence: 0.3,
+            footprint: Vec::new(),
+            lidar_mount: Pose2D { x: 0.0, y: 0.0, theta: 0.0 },
+            fov_min: -std::f32::consts::PI,
+            fov_max: std::f32::consts::PI,
+            planning_horizon: 2.0,
+            memory_route_radius: 1.0,
+            path_resample_spacing: 0.25,
+            output_smoothing_alpha: 0.0,
+            heading_gains: PidGains {
+                kp: 1.5,
+                ki: 0.1,
+                kd: 0.1,
+                integral_limit: 0.3,
+                derivative_filter: 0.5,
+            },
+            alignment_tolerance: 0.35,
+            stuck_timeout: 2.0,
+            recovery_behaviors: vec![
+                RecoveryBehavior::RotateInPlace,
+                RecoveryBehavior::BackUp,
+                RecoveryBehavior::ClearObstacleMap,
+            ],
+            recovery_rotation: std::f32::consts::TAU,
+            recovery_backup_distance: 0.3,
+            recovery_backup_speed: 0.1,
+            low_energy_threshold: 0.5,
+            min_energy_scale: 0.3,
+            cluster_gap: 0.15,
+            small_obstacle_extent: 0.3,
+            point_clearance_scale: 0.5,
+            small_clearance_scale: 0.75,
+            comfort_radius: 1.2,
+            min_comfort_scale: 0.3,
+            emergency_stop_distance: 0.2,
+            latch_emergency_stop: true,
+            lost_stop_distance: 0.3,
+            obstacle_persistence: 1.0,
+            velocity_association_distance: 0.3,
+            safety_event_capacity: 1000,
+            dwa: DwaConfig::default(),
+        }
+    }
+}
+
 impl NavigationPlanner {
     /// Create a new navigation planner
     pub fn new(config: &NavigationConfig) -> Self {
         NavigationPlanner {
             config: config.clone(),
-            path_history: VecDeque::with_capacity(100),
+            path_history: BoundedHistory::new(100),
             obstacle_map: Vec::new(),
+            obstacle_index: ObstacleIndex::new(config.safety_distance),
+            external_obstacles: Vec::new(),
             current_goal: None,
+            queued_goals: VecDeque::new(),
+            goal_resolved: false,
+            goal_elapsed: 0.0,
+            stopped_elapsed: 0.0,
+            unplanned_elapsed: 0.0,
+            last_outcome: None,
             safety_monitor: SafetyMonitor {
                 min_safe_distance: config.safety_distance,
                 emergency_stop: false,
                 safety_violations: 0,
             },
+            last_rejection: None,
+            current_path: None,
+            keepout_zones: Vec::new(),
+            last_pose: Pose2D { x: 0.0, y: 0.0, theta: 0.0 },
+            scan_pose: Pose2D { x: 0.0, y: 0.0, theta: 0.0 },
+            people: Vec::new(),
+            remembered_obstacles: Vec::new(),
+            previous_scan_obstacles: Vec::new(),
+            last_scan_stamp: None,
+            current_speed: 0.0,
+            memory: None,
+            recovery_state: RecoveryState::Idle,
+            recovery_step: 0,
+            recovery_elapsed: 0.0,
         }
     }
     
@@ -164,54 +599,584 @@ impl NavigationPlanner {
         neural_output: &[f32],
         current_pose: Option<Pose2D>,
     ) -> Result<Path, NavigationError> {
-        // Update obstacle map from sensor data
-        self.update_obstacle_map(sensor_data);
+        // Get current pose or use default
+        let current_pose = current_pose.unwrap_or(Pose2D { x: 0.0, y: 0.0, theta: 0.0 });
+        
+        // Update obstacle map from sensor data, counting scans taken in by `update` since the last plan
+        let dt = self.update_obstacle_map(sensor_data, current_pose) + std::mem::take(&mut self.unplanned_elapsed);
+        
+        // A latched stop holds until explicitly cleared; otherwise it clears itself once
+        // nothing is within the emergency stop distance
+        if self.safety_monitor.emergency_stop
+            && (self.config.latch_emergency_stop || self.clear_emergency_stop().is_err())
+        {
+            self.current_path = None;
+            self.hold_for_emergency_stop(dt);
+            return Err(NavigationError::EmergencyStop);
+        }
         
         // Apply neural network guidance
         self.apply_neural_guidance(neural_output);
         
-        // Get current pose or use default
-        let current_pose = current_pose.unwrap_or(Pose2D { x: 0.0, y: 0.0, theta: 0.0 });
+        self.last_pose = current_pose;
+        
+        // Resolve the active goal once it is reached or has taken too long
+        if let Some(goal) = self.active_goal() {
+            self.goal_elapsed += dt;
+            if self.calculate_distance(current_pose, goal) < self.config.goal_tolerance {
+                self.resolve_goal(NavigationOutcome::Reached);
+                if let Some(next) = self.queued_goals.pop_front() {
+                    self.start_goal(next);
+                }
+            } else if self.config.goal_timeout.is_some_and(|timeout| self.goal_elapsed > timeout) {
+                self.resolve_goal(NavigationOutcome::AbortedTimeout);
+            }
+        }
+        
+        // A resolved goal holds position until a new goal is set or it is cleared
+        if let (Some(goal), true) = (self.current_goal, self.goal_resolved) {
+            self.current_path = None;
+            return Ok(Path {
+                segments: Vec::new(),
+                total_length: 0.0,
+                overall_safety: 1.0,
+                speed_scale: 1.0,
+                distance_to_goal: Some(self.calculate_distance(current_pose, goal)),
+            });
+        }
         
-        // Plan path to goal
+        // Plan path to goal, committing only up to the planning horizon; goals near
+        // remembered places follow the remembered route when it is still safe
         let path = if let Some(goal) = self.current_goal {
-            self.plan_path_to_goal(current_pose, goal)
+            let route = self
+                .remembered_waypoints(current_pose, goal)
+                .map(|waypoints| self.plan_route(current_pose, &waypoints));
+            match route {
+                Some(Ok(path)) => Ok(path),
+                _ => self.plan_path_to_goal(current_pose, goal),
+            }
         } else {
             // No goal set, perform exploration
             self.plan_exploration_path(current_pose)
         };
         
         // Check safety
-        self.check_safety(&path);
-        
-        // Store path history
         if let Ok(path) = &path {
-            self.store_path_history(path);
+            self.check_safety(path);
+        }
+        if self.safety_monitor.emergency_stop {
+            self.current_path = None;
+            self.hold_for_emergency_stop(dt);
+            return Err(NavigationError::EmergencyStop);
+        }
+        self.stopped_elapsed = 0.0;
+        
+        // Slow down near people even when no collision is imminent
+        let comfort_scale = self.comfort_scale(current_pose);
+        let distance_to_goal = self.current_goal.map(|goal| self.calculate_distance(current_pose, goal));
+        let path = path.map(|mut path| {
+            path.speed_scale = comfort_scale;
+            path.distance_to_goal = distance_to_goal;
+            path
+        });
+        
+        // Store path history; a failed plan leaves no path to follow or render
+        match &path {
+            Ok(path) => {
+                self.store_path_history(path);
+                self.last_rejection = None;
+                self.current_path = Some(path.clone());
+                self.reset_recovery();
+            }
+            Err(_) => self.current_path = None,
         }
         
         path
     }
     
-    /// Set a new navigation goal
-    pub fn set_goal(&mut self, goal: Pose2D) {
+    /// Take in a scan between plans, checking the path being followed against it
+    ///
+    /// Refreshes the obstacle map and the pose used for progress, then trips the
+    /// emergency stop if the rest of the current path, followed from `current_pose`,
+    /// now runs into an obstacle. Fails with `EmergencyStop` while the stop holds,
+    /// like `plan`, so a new obstacle stops the robot without waiting for a re-plan.
+    pub fn update(
+        &mut self,
+        sensor_data: &super::ros_interface::SensorData,
+        current_pose: Option<Pose2D>,
+    ) -> Result<(), NavigationError> {
+        let current_pose = current_pose.unwrap_or(Pose2D { x: 0.0, y: 0.0, theta: 0.0 });
+        let dt = self.update_obstacle_map(sensor_data, current_pose);
+        self.last_pose = current_pose;
+        
+        if let Some(path) = &self.current_path {
+            let remaining = path.remaining_from(current_pose);
+            self.check_safety(&remaining);
+        }
+        if self.safety_monitor.emergency_stop
+            && (self.config.latch_emergency_stop || self.clear_emergency_stop().is_err())
+        {
+            self.current_path = None;
+            let elapsed = dt + std::mem::take(&mut self.unplanned_elapsed);
+            self.hold_for_emergency_stop(elapsed);
+            return Err(NavigationError::EmergencyStop);
+        }
+        self.unplanned_elapsed += dt;
+        Ok(())
+    }
+    
+    /// The path from the latest plan; None before planning and after a plan fails
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current_path.as_ref()
+    }
+    
+    /// Use a snapshot of the topological memory for routes to goals near known nodes
+    pub fn set_memory(&mut self, memory: &Memory) {
+        self.memory = Some(memory.clone());
+    }
+    
+    /// Stop consulting memory; every goal is planned directly
+    pub fn clear_memory(&mut self) {
+        self.memory = None;
+    }
+    
+    /// Waypoints of the remembered route from the robot to the goal, ending at the goal
+    ///
+    /// None unless both lie within `memory_route_radius` of connected nodes. A first
+    /// node the robot has already moved past, and a last node the goal lies short of,
+    /// are skipped so the route never doubles back.
+    fn remembered_waypoints(&self, current_pose: Pose2D, goal: Pose2D) -> Option<Vec<Pose2D>> {
+        let radius = self.config.memory_route_radius;
+        if radius <= 0.0 {
+            return None;
+        }
+        let as_pose = |p: Pose2D| Pose { x: p.x as f64, y: p.y as f64, theta: p.theta as f64 };
+        let route = self.memory.as_ref()?.route(&as_pose(current_pose), &as_pose(goal), radius as f64)?;
+        if route.len() < 2 {
+            return None;
+        }
+        
+        let mut waypoints: Vec<Pose2D> = route
+            .iter()
+            .map(|pose| Pose2D { x: pose.x as f32, y: pose.y as f32, theta: pose.theta as f32 })
+            .collect();
+        if self.calculate_distance(current_pose, waypoints[1]) < self.calculate_distance(waypoints[0], waypoints[1]) {
+            waypoints.remove(0);
+        }
+        let n = waypoints.len();
+        if n >= 2 && self.calculate_distance(goal, waypoints[n - 2]) < self.calculate_distance(waypoints[n - 1], waypoints[n - 2]) {
+            waypoints.pop();
+        }
+        waypoints.push(goal);
+        Some(waypoints)
+    }
+    
+    /// Plan through the given waypoints, committing only up to the planning horizon
+    fn plan_route(&mut self, start: Pose2D, waypoints: &[Pose2D]) -> Result<Path, NavigationError> {
+        let mut segments: Vec<PathSegment> = Vec::new();
+        let mut from = start;
+        let mut remaining = self.config.planning_horizon;
+        for waypoint in waypoints {
+            if remaining <= RESAMPLE_EPSILON {
+                break;
+            }
+            if self.calculate_distance(from, *waypoint) <= RESAMPLE_EPSILON {
+                continue;
+            }
+            let to = self.limit_distance(from, *waypoint, remaining);
+            let segment = self.plan_segment(from, to, segments.len())?;
+            remaining -= segment.length;
+            from = to;
+            segments.push(segment);
+        }
+        
+        Ok(Path::from_segments(segments))
+    }
+    
+    /// Feed semantic objects from perception; "person" objects get a comfort zone
+    pub fn set_semantic_objects(&mut self, objects: &[SemanticObject]) {
+        self.people = objects
+            .iter()
+            .filter(|object| object.class() == "person")
+            .map(|object| Pose2D {
+                x: object.position().x as f32,
+                y: object.position().y as f32,
+                theta: 0.0,
+            })
+            .collect();
+    }
+    
+    /// Speed scale from proximity to the nearest person (1.0 outside the comfort radius)
+    fn comfort_scale(&self, pose: Pose2D) -> f32 {
+        let radius = self.config.comfort_radius;
+        let nearest = self.people
+            .iter()
+            .map(|person| self.calculate_distance(*person, pose))
+            .fold(f32::MAX, f32::min);
+        
+        if radius <= 0.0 || nearest >= radius {
+            return 1.0;
+        }
+        let min_scale = self.config.min_comfort_scale;
+        min_scale + (1.0 - min_scale) * nearest / radius
+    }
+    
+    /// Release a latched emergency stop, refused while an obstacle is still critically close
+    pub fn clear_emergency_stop(&mut self) -> Result<(), NavigationError> {
+        // The obstacle map is in the base frame, so the robot sits at its origin
+        let origin = Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        if let Some((_, distance)) = self.closest_obstacle(origin, origin, self.config.emergency_stop_distance) {
+            return Err(NavigationError::UnsafePath(
+                format!("Obstacle still within emergency distance ({:.2}m)", distance)
+            ));
+        }
+        
+        self.safety_monitor.emergency_stop = false;
+        log::info!("Emergency stop cleared");
+        Ok(())
+    }
+    
+    /// Run one cycle of the recovery behaviors after planning failed
+    ///
+    /// Works through `recovery_behaviors` in order, one step per call, returning the
+    /// command to drive this cycle; the caller should retry planning every cycle,
+    /// which ends the recovery once it succeeds. Each call also releases the
+    /// emergency stop if the robot has regained clearance. While an obstacle is within
+    /// the emergency stop distance only backing away is safe: recovery skips ahead to
+    /// the back-up if the rear is clear, and otherwise passes over each moving behavior
+    /// holding still. None once every behavior has been tried. `dt` is the time since
+    /// the previous call (seconds), which times each behavior.
+    pub fn recover(&mut self, dt: f32) -> Option<MotionCommand> {
+        if self.safety_monitor.emergency_stop && self.clear_emergency_stop().is_ok() {
+            log::info!("Clearance regained during recovery");
+        }
+        
+        let contact = self.obstacle_within(self.config.emergency_stop_distance);
+        if contact && !self.obstacle_behind() {
+            let behaviors = &self.config.recovery_behaviors;
+            if let Some(offset) = behaviors.iter().skip(self.recovery_step).position(|b| *b == RecoveryBehavior::BackUp) {
+                if offset > 0 {
+                    self.recovery_step += offset;
+                    self.recovery_elapsed = 0.0;
+                }
+            }
+        }
+        
+        let Some(&behavior) = self.config.recovery_behaviors.get(self.recovery_step) else {
+            if self.recovery_state != RecoveryState::Exhausted {
+                log::warn!("Recovery behaviors exhausted");
+            }
+            self.recovery_state = RecoveryState::Exhausted;
+            return None;
+        };
+        if self.recovery_state != RecoveryState::Running(behavior) {
+            log::info!("Starting recovery behavior {:?}", behavior);
+        }
+        self.recovery_state = RecoveryState::Running(behavior);
+        
+        let (command, duration) = match behavior {
+            // Turning with something in contact could drag it along; hold and move on
+            RecoveryBehavior::RotateInPlace if contact => {
+                log::info!("Skipping rotate-in-place recovery, obstacle too close");
+                (MotionCommand { linear: 0.0, angular: 0.0 }, 0.0)
+            }
+            RecoveryBehavior::RotateInPlace => {
+                let speed = self.config.max_angular_velocity.max(RESAMPLE_EPSILON);
+                (MotionCommand { linear: 0.0, angular: speed }, self.config.recovery_rotation.abs() / speed)
+            }
+            RecoveryBehavior::BackUp if self.obstacle_behind() => {
+                log::info!("Skipping back-up recovery, obstacle behind");
+                (MotionCommand { linear: 0.0, angular: 0.0 }, 0.0)
+            }
+            RecoveryBehavior::BackUp => {
+                let speed = self.config.recovery_backup_speed.abs().max(RESAMPLE_EPSILON);
+                (MotionCommand { linear: -speed, angular: 0.0 }, self.config.recovery_backup_distance / speed)
+            }
+            RecoveryBehavior::ClearObstacleMap => {
+                self.clear_obstacle_map();
+                (MotionCommand { linear: 0.0, angular: 0.0 }, 0.0)
+            }
+        };
+        
+        self.recovery_elapsed += dt;
+        if self.recovery_elapsed >= duration {
+            self.recovery_step += 1;
+            self.recovery_elapsed = 0.0;
+        }
+        Some(command)
+    }
+    
+    /// Progress through the recovery behaviors
+    pub fn recovery_state(&self) -> RecoveryState {
+        self.recovery_state
+    }
+    
+    /// Start the recovery behaviors over from the first one next time
+    fn reset_recovery(&mut self) {
+        if self.recovery_state != RecoveryState::Idle {
+            log::info!("Recovery finished, planning succeeded");
+        }
+        self.recovery_state = RecoveryState::Idle;
+        self.recovery_step = 0;
+        self.recovery_elapsed = 0.0;
+    }
+    
+    /// Whether a blocking obstacle is within the safety distance behind the robot body
+    fn obstacle_behind(&self) -> bool {
+        let origin = Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        let limit = self.config.safety_distance;
+        let reach = limit + self.obstacle_index.max_radius() + footprint::circumradius(&self.config.footprint);
+        self.obstacle_index
+            .query(&self.obstacle_map, origin.x, origin.y, reach)
+            .filter(|obstacle| obstacle.position.x < 0.0 && self.is_blocking(obstacle))
+            .any(|obstacle| self.body_clearance(obstacle.position, origin, origin) < limit)
+    }
+    
+    /// Forget scanned and remembered obstacles; external obstacles stay until cleared
+    fn clear_obstacle_map(&mut self) {
+        self.remembered_obstacles.clear();
+        self.previous_scan_obstacles.clear();
+        self.obstacle_map = self.external_in_base_frame().collect();
+        self.obstacle_index.rebuild(&self.obstacle_map);
+        log::info!("Obstacle map cleared for recovery");
+    }
+    
+    /// Whether the emergency stop is engaged
+    pub fn is_emergency_stopped(&self) -> bool {
+        self.safety_monitor.emergency_stop
+    }
+    
+    /// Forget past safety violations so the status reads Normal again
+    pub fn reset_safety_violations(&mut self) {
+        self.safety_monitor.safety_violations = 0;
+    }
+    
+    /// Safety violations counted since the planner started or was last reset
+    pub fn safety_violations(&self) -> u32 {
+        self.safety_monitor.safety_violations
+    }
+    
+    /// Report the robot's current linear speed (m/s) for the speed-dependent safety margin
+    pub fn set_current_speed(&mut self, speed: f32) {
+        self.current_speed = speed.abs();
+    }
+    
+    /// Clearance added to every obstacle at the current speed
+    pub fn speed_margin(&self) -> f32 {
+        match self.config.safety_margin_model {
+            SafetyMarginModel::Fixed => 0.0,
+            SafetyMarginModel::StoppingDistance if self.config.max_deceleration > 0.0 => {
+                self.current_speed.powi(2) / (2.0 * self.config.max_deceleration)
+            }
+            SafetyMarginModel::StoppingDistance => 0.0,
+        }
+    }
+    
+    /// Set a new navigation goal, rejecting goals the robot could never reach
+    ///
+    /// A goal still being pursued is resolved as preempted, and queued goals are dropped.
+    pub fn set_goal(&mut self, goal: Pose2D) -> Result<(), NavigationError> {
+        if !self.is_goal_feasible(goal) {
+            log::warn!("Rejected infeasible navigation goal: {:?}", goal);
+            return Err(NavigationError::InvalidGoal);
+        }
+        
+        self.queued_goals.clear();
+        self.resolve_goal(NavigationOutcome::Preempted);
+        self.start_goal(goal);
+        Ok(())
+    }
+    
+    /// Queue a goal to pursue once the ones before it are reached
+    ///
+    /// Starts right away when no goal is being pursued. Rejects goals the robot could
+    /// never reach, like `set_goal`. Aborting the active goal drops the queue.
+    pub fn push_goal(&mut self, goal: Pose2D) -> Result<(), NavigationError> {
+        if !self.is_goal_feasible(goal) {
+            log::warn!("Rejected infeasible navigation goal: {:?}", goal);
+            return Err(NavigationError::InvalidGoal);
+        }
+        
+        if self.active_goal().is_some() {
+            self.queued_goals.push_back(goal);
+            log::info!("Navigation goal queued: {:?} ({} waiting)", goal, self.queued_goals.len());
+        } else {
+            self.start_goal(goal);
+        }
+        Ok(())
+    }
+    
+    /// Drop queued goals and clear the current one
+    pub fn clear_goals(&mut self) {
+        self.queued_goals.clear();
+        self.clear_goal();
+    }
+    
+    /// Goals not yet reached: the one being pursued plus those queued behind it
+    pub fn goals_remaining(&self) -> usize {
+        usize::from(self.active_goal().is_some()) + self.queued_goals.len()
+    }
+    
+    /// Make `goal` the one being pursued
+    fn start_goal(&mut self, goal: Pose2D) {
         self.current_goal = Some(goal);
+        self.goal_resolved = false;
+        self.goal_elapsed = 0.0;
+        self.stopped_elapsed = 0.0;
+        self.reset_recovery();
         log::info!("New navigation goal set: {:?}", goal);
     }
     
-    /// Clear the current goal
+    /// Whether the scan puts an obstacle within the emergency stop distance of `pose`
+    ///
+    /// Used when not planning (e.g. while stopping after losing localization) so a close
+    /// obstacle can still force a hard stop. Trips the emergency stop when it does.
+    pub fn obstacle_forces_stop(&mut self, sensor_data: &super::ros_interface::SensorData, pose: Option<Pose2D>) -> bool {
+        let pose = pose.unwrap_or(Pose2D { x: 0.0, y: 0.0, theta: 0.0 });
+        self.update_obstacle_map(sensor_data, pose);
+        
+        let speed_margin = self.speed_margin();
+        let limit = self.config.emergency_stop_distance + speed_margin;
+        let reach = limit + self.obstacle_index.max_radius() + footprint::circumradius(&self.config.footprint);
+        let origin = Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        let too_close = self
+            .obstacle_index
+            .query(&self.obstacle_map, origin.x, origin.y, reach)
+            .filter(|obstacle| self.is_blocking(obstacle))
+            .any(|obstacle| self.body_clearance(obstacle.position, origin, origin) < limit);
+        if too_close {
+            self.safety_monitor.emergency_stop = true;
+            log::error!("EMERGENCY STOP: obstacle dangerously close while stopping");
+        }
+        too_close
+    }
+    
+    /// Whether an obstacle in the current map is within `distance` of the robot body
+    ///
+    /// Checks the map as of the last scan, without updating it, so contact is still
+    /// noticed after the sensor has gone quiet.
+    pub fn obstacle_within(&self, distance: f32) -> bool {
+        let origin = Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        self.closest_obstacle(origin, origin, distance).is_some()
+    }
+    
+    /// Whether a goal is inside the known map and not occupied, inflated or in a keep-out zone
+    ///
+    /// Checked against the obstacle map from the most recent scan.
+    pub fn is_goal_feasible(&self, goal: Pose2D) -> bool {
+        if !(goal.x.is_finite() && goal.y.is_finite()) {
+            return false;
+        }
+        if self.config.map_bounds.is_some_and(|bounds| !bounds.contains(goal.x, goal.y)) {
+            return false;
+        }
+        if self.keepout_zones.iter().any(|zone| zone.contains(goal.x, goal.y)) {
+            return false;
+        }
+        let local = self.to_base_frame(goal);
+        self.obstacle_cost(local.x, local.y) == 0.0
+    }
+    
+    /// Clear the current goal, resolving it as cleared if it was still being pursued
     pub fn clear_goal(&mut self) {
+        self.resolve_goal(NavigationOutcome::Cleared);
         self.current_goal = None;
+        self.goal_resolved = false;
         log::info!("Navigation goal cleared");
     }
     
+    /// How the most recent goal ended, if one has
+    pub fn last_outcome(&self) -> Option<NavigationOutcome> {
+        self.last_outcome
+    }
+    
+    /// The goal being pursued, if it has no outcome yet
+    fn active_goal(&self) -> Option<Pose2D> {
+        self.current_goal.filter(|_| !self.goal_resolved)
+    }
+    
+    /// Counts another `dt` seconds of emergency stop, aborting the goal once it has held too long
+    ///
+    /// A stop that clears (a person stepping past) leaves the goal to be resumed.
+    fn hold_for_emergency_stop(&mut self, dt: f32) {
+        if self.active_goal().is_none() {
+            return;
+        }
+        self.stopped_elapsed += dt;
+        if self.stopped_elapsed >= self.config.unsafe_abort_timeout {
+            self.resolve_goal(NavigationOutcome::AbortedUnsafe);
+        }
+    }
+    
+    /// Record the outcome of the active goal; no-op without one
+    fn resolve_goal(&mut self, outcome: NavigationOutcome) {
+        if self.active_goal().is_some() {
+            self.goal_resolved = true;
+            self.last_outcome = Some(outcome);
+            log::info!("Navigation goal resolved: {:?}", outcome);
+            
+            // Later stops of an aborted mission may rely on the earlier ones; leave it to the caller
+            let aborted = matches!(outcome, NavigationOutcome::AbortedUnsafe | NavigationOutcome::AbortedTimeout);
+            if aborted && !self.queued_goals.is_empty() {
+                log::warn!("Dropping {} queued goals after the active goal was aborted", self.queued_goals.len());
+                self.queued_goals.clear();
+            }
+        }
+    }
+    
+    /// Add an obstacle from a non-LiDAR source (operator no-go mark, bumper hit)
+    ///
+    /// The position is in the odometry frame, like goals. External obstacles survive
+    /// the per-cycle obstacle map rebuild until cleared.
+    pub fn add_external_obstacle(&mut self, obstacle: Obstacle) {
+        self.obstacle_map.push(Obstacle {
+            position: self.to_base_frame(obstacle.position),
+            ..obstacle.clone()
+        });
+        self.external_obstacles.push(obstacle);
+        self.obstacle_index.rebuild(&self.obstacle_map);
+    }
+    
+    /// Remove all externally injected obstacles
+    pub fn clear_external_obstacles(&mut self) {
+        // External obstacles always sit at the tail of the obstacle map
+        let scan_count = self.obstacle_map.len() - self.external_obstacles.len();
+        self.obstacle_map.truncate(scan_count);
+        self.external_obstacles.clear();
+        self.obstacle_index.rebuild(&self.obstacle_map);
+    }
+    
+    /// Load permanent keep-out zones from a JSON or YAML file, replacing any loaded before
+    pub fn load_keepout_zones(&mut self, path: &str) -> Result<(), NavigationError> {
+        self.keepout_zones = keepout::load_zones(path)?;
+        log::info!("Loaded {} keep-out zones from {}", self.keepout_zones.len(), path);
+        Ok(())
+    }
+    
+    /// Keep-out zones, kept separate from scan obstacles for rendering
+    pub fn keepout_zones(&self) -> &[KeepoutZone] {
+        &self.keepout_zones
+    }
+    
+    /// Details of the most recent path rejection, cleared by the next successful plan
+    pub fn last_rejection(&self) -> Option<RejectionReason> {
+        self.last_rejection.clone()
+    }
+    
     /// Get current navigation status
     pub fn get_status(&self) -> NavigationStatus {
         let distance_to_goal = self.current_goal
-            .map(|goal| self.calculate_distance(goal, Pose2D { x: 0.0, y: 0.0, theta: 0.0 }))
+            .map(|goal| self.calculate_distance(goal, self.last_pose))
+            .unwrap_or(0.0);
+        
+        let progress_fraction = self.current_path
+            .as_ref()
+            .filter(|path| path.total_length > 0.0)
+            .map(|path| (path.distance_along(self.last_pose) / path.total_length).clamp(0.0, 1.0))
             .unwrap_or(0.0);
             
         NavigationStatus {
-            has_goal: self.current_goal.is_some(),
+            has_goal: self.active_goal().is_some(),
             distance_to_goal,
             obstacle_count: self.obstacle_map.len(),
             safety_status: if self.safety_monitor.emergency_stop {
@@ -222,29 +1187,279 @@ impl NavigationPlanner {
                 SafetyStatus::Normal
             },
             motion_state: MotionState::Stopped, // This would be updated by motion controller
+            progress_fraction,
+            eta_seconds: None, // Needs the controller's speed, see `eta`
+            last_outcome: self.last_outcome,
+            recovery_state: self.recovery_state,
+            goals_remaining: self.goals_remaining(),
+        }
+    }
+    
+    /// Update the robot pose used for progress tracking between plans
+    pub fn update_pose(&mut self, pose: Pose2D) {
+        self.last_pose = pose;
+    }
+    
+    /// Estimated seconds to finish the active path and reach the goal at `mean_speed`
+    pub fn eta(&self, mean_speed: f32) -> Option<f32> {
+        let path = self.current_path.as_ref()?;
+        if mean_speed <= 1e-3 {
+            return None;
+        }
+        
+        let mut remaining = (path.total_length - path.distance_along(self.last_pose)).max(0.0);
+        // The active path may stop at the planning horizon short of the goal
+        if let (Some(goal), Some(last)) = (self.current_goal, path.segments.last()) {
+            remaining += self.calculate_distance(last.end, goal);
         }
+        Some(remaining / mean_speed)
     }
     
-    /// Update obstacle map from sensor data
-    fn update_obstacle_map(&mut self, sensor_data: &super::ros_interface::SensorData) {
+    /// Update obstacle map from sensor data, returning the seconds since the previous scan
+    fn update_obstacle_map(&mut self, sensor_data: &super::ros_interface::SensorData, pose: Pose2D) -> f32 {
         self.obstacle_map.clear();
+        self.scan_pose = pose;
+        
+        // Sensor-to-base transform from the LiDAR mount pose
+        let mount = self.config.lidar_mount;
+        let (mount_sin, mount_cos) = mount.theta.sin_cos();
         
         // Process laser scan data for obstacles
         let scan = &sensor_data.laser_scan;
+        let mut cluster: Vec<Pose2D> = Vec::new();
+        let mut previous_index = None;
         for (i, range) in scan.ranges.iter().enumerate() {
             if *range < scan.range_max && *range > scan.range_min {
                 let angle = scan.angle_min + (i as f32) * scan.angle_increment;
-                let x = range * angle.cos();
-                let y = range * angle.sin();
+                let sensor_x = range * angle.cos();
+                let sensor_y = range * angle.sin();
                 
-                self.obstacle_map.push(Obstacle {
-                    position: Pose2D { x: x as f32, y: y as f32, theta: 0.0 },
-                    radius: self.config.obstacle_inflation,
-                    confidence: 0.8, // Default confidence
-                    velocity: None, // Would be calculated from multiple scans
-                });
+                // Express the return in the base frame
+                let x = mount.x + sensor_x * mount_cos - sensor_y * mount_sin;
+                let y = mount.y + sensor_x * mount_sin + sensor_y * mount_cos;
+                let point = Pose2D { x, y, theta: 0.0 };
+                
+                // Returns outside the field of interest are ignored
+                let bearing = point.y.atan2(point.x);
+                if bearing < self.config.fov_min - FOV_TOLERANCE || bearing > self.config.fov_max + FOV_TOLERANCE {
+                    continue;
+                }
+                
+                // Adjacent beams close together belong to the same surface
+                let continues = previous_index == Some(i.wrapping_sub(1))
+                    && cluster.last().is_some_and(|last| self.calculate_distance(*last, point) <= self.config.cluster_gap);
+                if !continues {
+                    self.push_cluster(&cluster);
+                    cluster.clear();
+                }
+                cluster.push(point);
+                previous_index = Some(i);
             }
         }
+        self.push_cluster(&cluster);
+        
+        // Track this scan's obstacles against the previous one
+        let dt = self.scan_period(scan);
+        self.estimate_velocities(pose, dt);
+        
+        // Keep recently seen obstacles that have left view, fading out
+        self.merge_remembered_obstacles(scan, pose, dt);
+        
+        // Merge in obstacles known from other sources
+        let external: Vec<Obstacle> = self.external_in_base_frame().collect();
+        self.obstacle_map.extend(external);
+        
+        self.obstacle_index.rebuild(&self.obstacle_map);
+        dt
+    }
+    
+    /// Seconds since the previous scan, from stamps or the scan's own period
+    fn scan_period(&mut self, scan: &r2r::sensor_msgs::msg::LaserScan) -> f32 {
+        let stamp = scan.header.stamp.sec as f64 + scan.header.stamp.nanosec as f64 * 1e-9;
+        let previous = self.last_scan_stamp.replace(stamp);
+        match previous {
+            Some(previous) if stamp > previous => (stamp - previous) as f32,
+            _ if scan.scan_time > 0.0 => scan.scan_time,
+            _ => DEFAULT_SCAN_PERIOD,
+        }
+    }
+    
+    /// Estimate each scanned obstacle's velocity from the nearest obstacle in the previous scan
+    ///
+    /// Positions are compared in the odometry frame so the robot's own motion cancels
+    /// out; the velocity is expressed in the base frame like the position. Obstacles with
+    /// no previous obstacle within `velocity_association_distance` get no velocity.
+    fn estimate_velocities(&mut self, pose: Pose2D, dt: f32) {
+        let threshold = self.config.velocity_association_distance;
+        let (sin, cos) = pose.theta.sin_cos();
+        let previous = std::mem::take(&mut self.previous_scan_obstacles);
+        
+        for obstacle in &mut self.obstacle_map {
+            let local = obstacle.position;
+            let world = Pose2D {
+                x: pose.x + local.x * cos - local.y * sin,
+                y: pose.y + local.x * sin + local.y * cos,
+                theta: 0.0,
+            };
+            self.previous_scan_obstacles.push(world);
+            
+            let nearest = previous
+                .iter()
+                .map(|before| (before, (world.x - before.x).hypot(world.y - before.y)))
+                .filter(|(_, moved)| threshold > 0.0 && *moved <= threshold)
+                .min_by(|a, b| a.1.total_cmp(&b.1));
+            obstacle.velocity = match nearest {
+                Some((before, _)) if dt > 0.0 => {
+                    let vx = (world.x - before.x) / dt;
+                    let vy = (world.y - before.y) / dt;
+                    Some((vx * cos + vy * sin, -vx * sin + vy * cos))
+                }
+                _ => None,
+            };
+        }
+    }
+    
+    /// Age remembered obstacles, add the still-fresh ones to the map and remember this scan's
+    ///
+    /// Remembered obstacles are kept in the odometry frame so they stay put as the robot
+    /// moves. Confidence and inflation fade linearly to zero over `obstacle_persistence`;
+    /// a beam that passes through one clears it right away.
+    fn merge_remembered_obstacles(&mut self, scan: &r2r::sensor_msgs::msg::LaserScan, pose: Pose2D, dt: f32) {
+        let persistence = self.config.obstacle_persistence;
+        if persistence <= 0.0 {
+            self.remembered_obstacles.clear();
+            return;
+        }
+        
+        let observed: Vec<Obstacle> = self.obstacle_map.clone();
+        let gap = self.config.cluster_gap;
+        let mount = self.config.lidar_mount;
+        let (sin, cos) = pose.theta.sin_cos();
+        
+        // Drop expired memories and those this scan observes again
+        let mut remembered = std::mem::take(&mut self.remembered_obstacles);
+        remembered.retain_mut(|memory| {
+            memory.age += dt;
+            let world = memory.obstacle.position;
+            let dx = world.x - pose.x;
+            let dy = world.y - pose.y;
+            let local = Pose2D { x: dx * cos + dy * sin, y: -dx * sin + dy * cos, theta: 0.0 };
+            let seen_again = observed
+                .iter()
+                .any(|o| (o.position.x - local.x).hypot(o.position.y - local.y) <= gap);
+            if memory.age >= persistence || seen_again || Self::seen_through(scan, mount, local, gap) {
+                return false;
+            }
+            
+            let fade = 1.0 - memory.age / persistence;
+            self.obstacle_map.push(Obstacle {
+                position: local,
+                radius: memory.obstacle.radius * fade,
+                confidence: memory.obstacle.confidence * fade,
+                ..memory.obstacle.clone()
+            });
+            true
+        });
+        
+        // Remember this scan's obstacles in the odometry frame
+        remembered.extend(observed.into_iter().map(|mut obstacle| {
+            let local = obstacle.position;
+            obstacle.position = Pose2D {
+                x: pose.x + local.x * cos - local.y * sin,
+                y: pose.y + local.x * sin + local.y * cos,
+                theta: 0.0,
+            };
+            RememberedObstacle { obstacle, age: 0.0 }
+        }));
+        self.remembered_obstacles = remembered;
+    }
+    
+    /// Whether the scan has a beam passing beyond a base-frame point, i.e. observes it as free
+    fn seen_through(scan: &r2r::sensor_msgs::msg::LaserScan, mount: Pose2D, point: Pose2D, margin: f32) -> bool {
+        if scan.angle_increment <= 0.0 {
+            return false;
+        }
+        
+        // Express the point in the sensor frame
+        let (mount_sin, mount_cos) = mount.theta.sin_cos();
+        let dx = point.x - mount.x;
+        let dy = point.y - mount.y;
+        let sensor_x = dx * mount_cos + dy * mount_sin;
+        let sensor_y = -dx * mount_sin + dy * mount_cos;
+        let distance = sensor_x.hypot(sensor_y);
+        
+        let beam = ((sensor_y.atan2(sensor_x) - scan.angle_min) / scan.angle_increment).round();
+        if beam < 0.0 || beam as usize >= scan.ranges.len() {
+            return false; // Outside the field of view
+        }
+        
+        // Invalid readings say nothing; a beam without a return saw free space to the maximum range
+        let range = scan.ranges[beam as usize];
+        if range.is_nan() || range <= scan.range_min {
+            return false;
+        }
+        range.min(scan.range_max) > distance + margin
+    }
+    
+    /// Obstacle cost at a point in the base frame (0.0 - 1.0)
+    ///
+    /// The highest confidence among obstacles whose inflation covers the point,
+    /// falling off linearly to the edge of the inflation radius.
+    pub fn obstacle_cost(&self, x: f32, y: f32) -> f32 {
+        let point = Pose2D { x, y, theta: 0.0 };
+        self.obstacles_near(x, y, self.obstacle_index.max_radius())
+            .filter(|obstacle| obstacle.radius > 0.0)
+            .map(|obstacle| {
+                let distance = self.calculate_distance(obstacle.position, point);
+                obstacle.confidence * (1.0 - distance / obstacle.radius).max(0.0)
+            })
+            .fold(0.0, f32::max)
+    }
+    
+    /// Classify a cluster of returns by extent and add its points to the obstacle map
+    fn push_cluster(&mut self, cluster: &[Pose2D]) {
+        let (Some(first), Some(last)) = (cluster.first(), cluster.last()) else {
+            return;
+        };
+        
+        let extent = self.calculate_distance(*first, *last);
+        let category = if cluster.len() == 1 {
+            ObstacleCategory::Point
+        } else if extent < self.config.small_obstacle_extent {
+            ObstacleCategory::Small
+        } else {
+            ObstacleCategory::Large
+        };
+        
+        for point in cluster {
+            self.obstacle_map.push(Obstacle {
+                position: *point,
+                radius: self.config.obstacle_inflation,
+                confidence: SCAN_RETURN_CONFIDENCE,
+                velocity: None, // Set by estimate_velocities
+                category,
+            });
+        }
+    }
+    
+    /// Clearance kept from an obstacle of the given category
+    fn clearance_for(&self, category: ObstacleCategory) -> f32 {
+        let scale = match category {
+            ObstacleCategory::Point => self.config.point_clearance_scale,
+            ObstacleCategory::Small => self.config.small_clearance_scale,
+            ObstacleCategory::Large => 1.0,
+        };
+        self.safety_monitor.min_safe_distance * scale
+    }
+    
+    /// Current obstacle map: the latest scan, remembered and external obstacles
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacle_map
+    }
+    
+    /// Iterate over obstacles within `radius` of the given point
+    pub fn obstacles_near(&self, x: f32, y: f32, radius: f32) -> impl Iterator<Item = &Obstacle> {
+        self.obstacle_index.query(&self.obstacle_map, x, y, radius)
     }
     
     /// Apply neural network guidance to navigation
@@ -266,39 +1481,164 @@ impl NavigationPlanner {
         }
     }
     
-    /// Plan a path to a specific goal
-    fn plan_path_to_goal(&self, start: Pose2D, goal: Pose2D) -> Result<Path, NavigationError> {
-        // Simple straight-line path planning with obstacle avoidance
-        // Would use more advanced algorithms in production
+    /// Plan a path to a specific goal, committing only up to the planning horizon
+    ///
+    /// A straight line is used when it keeps every obstacle outside its inflation and
+    /// stays out of keep-out zones; otherwise A* over a cost grid finds a way around
+    /// them. The route always leads to the goal itself and is clipped afterwards, so a
+    /// horizon point that happens to fall inside an inflation doesn't cut the goal off.
+    fn plan_path_to_goal(&mut self, start: Pose2D, goal: Pose2D) -> Result<Path, NavigationError> {
+        let waypoints = if self.segment_clear(start, goal) {
+            vec![goal]
+        } else if let Some(waypoints) = self.search_around_obstacles(start, goal) {
+            waypoints
+        } else {
+            // A direct line refused outright (e.g. an obstacle at the start) says why
+            let end = self.limit_distance(start, goal, self.config.planning_horizon);
+            self.plan_segment(start, end, 0)?;
+            return Err(NavigationError::NoPathError("Goal is cut off by obstacles".to_string()));
+        };
         
-        let distance = self.calculate_distance(start, goal);
-        let direction = self.calculate_direction(start, goal);
+        self.plan_route(start, &waypoints)
+    }
+    
+    /// Whether the robot body moving from `start` to `end` stays out of keep-out zones and every blocking obstacle's inflation
+    ///
+    /// `start` and `end` are in the odometry frame.
+    fn segment_clear(&self, start: Pose2D, end: Pose2D) -> bool {
+        if self.blocking_keepout_zone(start, end).is_some() {
+            return false;
+        }
+        let (start, end) = (self.to_base_frame(start), self.to_base_frame(end));
+        let body_radius = footprint::circumradius(&self.config.footprint);
+        let reach = self.calculate_distance(start, end) + self.max_inflation() + body_radius;
+        self.obstacles_near(start.x, start.y, reach)
+            .filter(|obstacle| self.is_blocking(obstacle))
+            .all(|obstacle| {
+                let point = (obstacle.position.x, obstacle.position.y);
+                let clearance = if self.config.footprint.is_empty() {
+                    footprint::point_segment_distance(point, (start.x, start.y), (end.x, end.y))
+                } else {
+                    footprint::swept_clearance(&self.config.footprint, start, end, point)
+                };
+                clearance >= self.inflation_of(obstacle)
+            })
+    }
+    
+    /// Waypoints of an A* route from `start` to `goal` around obstacles, ending at the goal
+    ///
+    /// Searches a grid in the odometry frame spanning both points plus `GRID_MARGIN`,
+    /// looking obstacles up in the scan's base frame. Cells are blocked within
+    /// an obstacle's inflation (grown by the body radius and half a cell diagonal, and at
+    /// least as far as a segment may start from an obstacle) and within the body radius
+    /// and half a cell diagonal of keep-out zones.
+    /// None when no route exists within the grid.
+    fn search_around_obstacles(&self, start: Pose2D, goal: Pose2D) -> Option<Vec<Pose2D>> {
+        let min_start_distance = MIN_SEGMENT_SAFETY * 2.0 * self.config.safety_distance;
+        let growth = footprint::circumradius(&self.config.footprint) + GRID_RESOLUTION * std::f32::consts::FRAC_1_SQRT_2;
+        let fade = self.config.safety_distance.max(GRID_RESOLUTION);
+        let reach = self.max_inflation().max(min_start_distance) + growth + fade;
+        
+        let min = (start.x.min(goal.x) - GRID_MARGIN, start.y.min(goal.y) - GRID_MARGIN);
+        let max = (start.x.max(goal.x) + GRID_MARGIN, start.y.max(goal.y) + GRID_MARGIN);
+        let grid = CostGrid::new(min, max, GRID_RESOLUTION, |x, y| {
+            if self.keepout_zones.iter().any(|zone| zone.distance_to(x, y) < growth) {
+                return None;
+            }
+            let point = self.to_base_frame(Pose2D { x, y, theta: 0.0 });
+            let mut cost: f32 = 0.0;
+            for obstacle in self.obstacles_near(point.x, point.y, reach).filter(|obstacle| self.is_blocking(obstacle)) {
+                let blocked_within = self.inflation_of(obstacle).max(min_start_distance) + growth;
+                let distance = self.calculate_distance(obstacle.position, point);
+                if distance < blocked_within {
+                    return None;
+                }
+                cost = cost.max(CLEARANCE_COST * (1.0 - (distance - blocked_within) / fade).max(0.0));
+            }
+            Some(cost)
+        });
+        
+        let cells = grid.search(grid.cell_of(start.x, start.y)?, grid.cell_of(goal.x, goal.y)?)?;
+        
+        // Cell centers in between, with the exact start and goal at the ends
+        let mut points: Vec<(f32, f32)> = cells.iter().map(|cell| grid.center(*cell)).collect();
+        if let Some(first) = points.first_mut() {
+            *first = (start.x, start.y);
+        }
+        if let Some(last) = points.last_mut() {
+            *last = (goal.x, goal.y);
+        }
+        
+        // Pull the cell path taut: from each waypoint jump to the farthest point in clear view
+        let mut waypoints = Vec::new();
+        let mut current = 0;
+        while current + 1 < points.len() {
+            let mut next = points.len() - 1;
+            while next > current + 1 && !grid.line_clear(points[current], points[next]) {
+                next -= 1;
+            }
+            let (x, y) = points[next];
+            let heading = (y - points[current].1).atan2(x - points[current].0);
+            waypoints.push(Pose2D { x, y, theta: heading });
+            current = next;
+        }
+        if let Some(last) = waypoints.last_mut() {
+            last.theta = goal.theta;
+        }
+        Some(waypoints)
+    }
+    
+    /// Distance around an obstacle the robot body must stay out of
+    fn inflation_of(&self, obstacle: &Obstacle) -> f32 {
+        self.config.obstacle_inflation.max(obstacle.radius)
+    }
+    
+    /// Largest inflation of any obstacle in the map
+    fn max_inflation(&self) -> f32 {
+        self.config.obstacle_inflation.max(self.obstacle_index.max_radius())
+    }
+    
+    /// Check one straight segment of a path, recording why it was rejected
+    fn plan_segment(&mut self, start: Pose2D, end: Pose2D, segment_index: usize) -> Result<PathSegment, NavigationError> {
+        // Keep-out zones are absolute; no safety score can justify entering one
+        if let Some(zone) = self.blocking_keepout_zone(start, end) {
+            let message = format!("Path crosses keep-out zone '{}'", zone.name);
+            self.last_rejection = Some(RejectionReason {
+                obstacle: None,
+                safety_score: 0.0,
+                segment_index,
+            });
+            return Err(NavigationError::UnsafePath(message));
+        }
         
         // Check for obstacles along the path
-        let safety_score = self.calculate_path_safety(start, goal);
+        let safety_score = self.calculate_path_safety(start, end);
         
-        if safety_score < 0.3 {
+        if safety_score < MIN_SEGMENT_SAFETY {
+            self.last_rejection = Some(RejectionReason {
+                obstacle: self
+                    .closest_obstacle(self.to_base_frame(start), self.to_base_frame(end), self.config.safety_distance * 2.0)
+                    .map(|(obstacle, _)| obstacle.position),
+                safety_score,
+                segment_index,
+            });
             return Err(NavigationError::UnsafePath(
                 format!("Path to goal is unsafe (score: {:.2})", safety_score)
             ));
         }
         
-        Ok(Path {
-            segments: vec![PathSegment {
-                start,
-                end: goal,
-                length: distance,
-                safety_score,
-            }],
-            total_length: distance,
-            overall_safety: safety_score,
+        Ok(PathSegment {
+            start,
+            end,
+            length: self.calculate_distance(start, end),
+            safety_score,
         })
     }
     
     /// Plan an exploration path
-    fn plan_exploration_path(&self, current_pose: Pose2D) -> Result<Path, NavigationError> {
-        // Simple exploration: move forward while avoiding obstacles
-        let exploration_distance = 2.0; // meters
+    fn plan_exploration_path(&mut self, current_pose: Pose2D) -> Result<Path, NavigationError> {
+        // Simple exploration: move straight ahead, leaving obstacles to the safety checks
+        let exploration_distance = self.config.planning_horizon;
         
         let goal = Pose2D {
             x: current_pose.x + exploration_distance * current_pose.theta.cos(),
@@ -306,28 +1646,97 @@ impl NavigationPlanner {
             theta: current_pose.theta,
         };
         
-        self.plan_path_to_goal(current_pose, goal)
+        let segment = self.plan_segment(current_pose, goal, 0)?;
+        Ok(Path::from_segments(vec![segment]))
+    }
+    
+    /// The point `max_distance` from start toward goal, or the goal if it is closer
+    fn limit_distance(&self, start: Pose2D, goal: Pose2D, max_distance: f32) -> Pose2D {
+        let distance = self.calculate_distance(start, goal);
+        if distance <= max_distance {
+            return goal;
+        }
+        
+        let direction = self.calculate_direction(start, goal);
+        Pose2D {
+            x: start.x + max_distance * direction.cos(),
+            y: start.y + max_distance * direction.sin(),
+            theta: direction,
+        }
     }
     
     /// Check path safety
     fn check_safety(&mut self, path: &Path) {
+        // A moving robot needs its braking distance on top of the standstill clearance
+        let speed_margin = self.speed_margin();
+        
+        // Low-confidence obstacles never count as violations
+        let min_confidence = self.config.min_blocking_confidence;
+        
+        // Only obstacles within the largest possible violation distance matter
+        let search_radius = self.safety_monitor.min_safe_distance + speed_margin + self.obstacle_index.max_radius();
+        
         for segment in &path.segments {
-            for obstacle in &self.obstacle_map {
-                let distance = self.calculate_distance(obstacle.position, segment.start);
+            // Obstacles are in the scan's base frame, the path in the odometry frame
+            let (start, end) = (self.to_base_frame(segment.start), self.to_base_frame(segment.end));
+            
+            // A footprint sweeps the whole segment, so look that much further ahead
+            let reach = if self.config.footprint.is_empty() {
+                search_radius
+            } else {
+                search_radius + segment.length + footprint::circumradius(&self.config.footprint)
+            };
+            
+            let zone = self.blocking_keepout_zone(segment.start, segment.end).map(|zone| zone.name.clone());
+            if let Some(name) = zone {
+                self.safety_monitor.safety_violations += 1;
+                log::warn!("Safety violation: segment enters keep-out zone '{}'", name);
+            }
+            
+            let nearby = self.obstacle_index.query(
+                &self.obstacle_map,
+                start.x,
+                start.y,
+                reach,
+            );
+            for obstacle in nearby.filter(|obstacle| obstacle.confidence >= min_confidence) {
+                let distance = self.body_clearance(obstacle.position, start, end);
                 
-                if distance < self.safety_monitor.min_safe_distance + obstacle.radius {
+                if distance < self.clearance_for(obstacle.category) + speed_margin + obstacle.radius {
                     self.safety_monitor.safety_violations += 1;
                     log::warn!("Safety violation: obstacle too close ({:.2}m)", distance);
                     
-                    if distance < self.config.safety_distance * 0.5 {
+                    // Stop if the obstacle is inside the braking distance of where the robot is now
+                    let current = self.body_clearance(obstacle.position, start, start);
+                    if current < self.config.emergency_stop_distance + speed_margin {
                         self.safety_monitor.emergency_stop = true;
-                        log::error!("EMERGENCY STOP: obstacle dangerously close ({:.2}m)", distance);
+                        log::error!("EMERGENCY STOP: obstacle dangerously close ({:.2}m)", current);
                     }
                 }
             }
         }
     }
     
+    /// First keep-out zone the straight segment from `start` to `end` enters
+    fn blocking_keepout_zone(&self, start: Pose2D, end: Pose2D) -> Option<&KeepoutZone> {
+        self.keepout_zones
+            .iter()
+            .find(|zone| zone.intersects_segment((start.x, start.y), (end.x, end.y)))
+    }
+    
+    /// Express an odometry-frame pose in the base frame of the latest scan, where obstacles live
+    fn to_base_frame(&self, pose: Pose2D) -> Pose2D {
+        relative_to(self.scan_pose, pose)
+    }
+    
+    /// External obstacles, kept in the odometry frame, in the base frame of the latest scan
+    fn external_in_base_frame(&self) -> impl Iterator<Item = Obstacle> + '_ {
+        self.external_obstacles.iter().map(|obstacle| Obstacle {
+            position: self.to_base_frame(obstacle.position),
+            ..obstacle.clone()
+        })
+    }
+    
     /// Calculate distance between two poses
     fn calculate_distance(&self, a: Pose2D, b: Pose2D) -> f32 {
         ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
@@ -339,31 +1748,85 @@ impl NavigationPlanner {
     }
     
     /// Calculate safety score for a path segment
+    ///
+    /// Each blocking obstacle scores distance / margin, the margin being twice the safety
+    /// distance for a fully confident obstacle and widening up to twice that as confidence
+    /// falls, since its position is less certain. Touching an obstacle always scores 0.0.
     fn calculate_path_safety(&self, start: Pose2D, end: Pose2D) -> f32 {
-        let mut min_distance = f32::MAX;
-        
-        for obstacle in &self.obstacle_map {
-            // Simple distance-based safety calculation
-            // Would use more sophisticated collision checking in production
-            let distance = self.calculate_distance(obstacle.position, start);
-            min_distance = min_distance.min(distance);
+        // Obstacles beyond their margin already yield a perfect score
+        let radius = self.config.safety_distance * 2.0;
+        let margin = |obstacle: &Obstacle| radius * (2.0 - obstacle.confidence.clamp(0.0, 1.0));
+        self.blocking_obstacles_along(self.to_base_frame(start), self.to_base_frame(end), radius * 2.0)
+            .map(|(obstacle, distance)| (distance / margin(obstacle)).min(1.0))
+            .fold(1.0, f32::min)
+    }
+    
+    /// Whether an obstacle is confident enough to reject paths and trigger stops
+    fn is_blocking(&self, obstacle: &Obstacle) -> bool {
+        obstacle.confidence >= self.config.min_blocking_confidence
+    }
+    
+    /// Distance from an obstacle to the robot body moving from `start` to `end`
+    ///
+    /// Without a footprint this is the distance to the center at `start`; with one it is
+    /// the distance to the footprint swept along the whole segment.
+    fn body_clearance(&self, obstacle: Pose2D, start: Pose2D, end: Pose2D) -> f32 {
+        if self.config.footprint.is_empty() {
+            self.calculate_distance(obstacle, start)
+        } else {
+            footprint::swept_clearance(&self.config.footprint, start, end, (obstacle.x, obstacle.y))
         }
-        
-        // Convert distance to safety score (0-1)
-        (min_distance / (self.config.safety_distance * 2.0)).min(1.0)
+    }
+    
+    /// Find the blocking obstacle closest to the robot body moving from `start` to `end`, within `radius`
+    fn closest_obstacle(&self, start: Pose2D, end: Pose2D, radius: f32) -> Option<(&Obstacle, f32)> {
+        self.blocking_obstacles_along(start, end, radius)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+    
+    /// Blocking obstacles within `radius` of the robot body moving from `start` to `end`, with their clearance
+    fn blocking_obstacles_along(&self, start: Pose2D, end: Pose2D, radius: f32) -> impl Iterator<Item = (&Obstacle, f32)> {
+        let reach = if self.config.footprint.is_empty() {
+            radius
+        } else {
+            radius + self.calculate_distance(start, end) + footprint::circumradius(&self.config.footprint)
+        };
+        self.obstacles_near(start.x, start.y, reach)
+            .filter(move |obstacle| self.is_blocking(obstacle))
+            .map(move |obstacle| (obstacle, self.body_clearance(obstacle.position, start, end)))
+            .filter(move |(_, distance)| *distance <= radius)
     }
     
     /// Store path in history
     fn store_path_history(&mut self, path: &Path) {
         for segment in &path.segments {
-            self.path_history.push_back(segment.clone());
-            if self.path_history.len() > 100 {
-                self.path_history.pop_front();
-            }
+            self.path_history.push(segment.clone());
         }
     }
 }
 
+/// `pose` expressed in the frame of `origin` (e.g. an odometry pose in the base frame at `origin`)
+fn relative_to(origin: Pose2D, pose: Pose2D) -> Pose2D {
+    let (sin, cos) = origin.theta.sin_cos();
+    let dx = pose.x - origin.x;
+    let dy = pose.y - origin.y;
+    Pose2D {
+        x: dx * cos + dy * sin,
+        y: -dx * sin + dy * cos,
+        theta: pose.theta - origin.theta,
+    }
+}
+
+/// `pose`, given in the frame of `origin`, expressed in the frame `origin` is in (the inverse of `relative_to`)
+fn absolute_from(origin: Pose2D, pose: Pose2D) -> Pose2D {
+    let (sin, cos) = origin.theta.sin_cos();
+    Pose2D {
+        x: origin.x + pose.x * cos - pose.y * sin,
+        y: origin.y + pose.x * sin + pose.y * cos,
+        theta: origin.theta + pose.theta,
+    }
+}
+
 impl MotionController {
     /// Create a new motion controller
     pub fn new(config: &NavigationConfig) -> Self {
@@ -376,8 +1839,12 @@ impl MotionController {
                     linear: config.max_acceleration, 
                     angular: config.max_acceleration,
                 },
+                deceleration_limits: MotionCommand {
+                    linear: config.max_deceleration,
+                    angular: config.max_deceleration,
+                },
             },
-            command_history: VecDeque::with_capacity(100),
+            command_history: BoundedHistory::new(100),
             safety_limits: SafetyLimits {
                 max_velocity: MotionCommand { 
                     linear: config.max_linear_velocity, 
@@ -389,66 +1856,337 @@ impl MotionController {
                 },
                 emergency_deceleration: config.max_acceleration * 2.0,
             },
+            last_output: MotionCommand { linear: 0.0, angular: 0.0 },
+            stuck_monitor: StuckMonitor::new(config.stuck_timeout),
+            energy_scale: 1.0,
+            motion_state: MotionState::Stopped,
+            safety_events: BoundedHistory::new(config.safety_event_capacity),
+            acceleration_limited: false,
+            stop_deceleration: None,
+            heading_pid: Pid::new(config.heading_gains),
+            pose: None,
+            cycle_time: DEFAULT_CYCLE_TIME,
         }
     }
     
+    /// Set the measured time since the previous command, capped after a stall
+    ///
+    /// Non-positive or non-finite values fall back to the default 100 ms cycle.
+    pub fn set_cycle_time(&mut self, dt: f32) {
+        self.cycle_time = if dt.is_finite() && dt > 0.0 { dt.min(MAX_CYCLE_TIME) } else { DEFAULT_CYCLE_TIME };
+    }
+    
+    /// Time step the velocity ramps are applied over (seconds)
+    pub fn cycle_time(&self) -> f32 {
+        self.cycle_time
+    }
+    
+    /// Set the current robot pose, in the odometry frame like plans
+    ///
+    /// Plans are then followed from here, so steering stays closed loop when a plan is
+    /// reused for several cycles; set it every cycle to keep it current.
+    pub fn set_pose(&mut self, pose: Pose2D) {
+        self.pose = Some(pose);
+    }
+    
     /// Execute a navigation plan
+    ///
+    /// The dynamic window approach, when enabled, knows of no obstacles or keep-out
+    /// zones here; use `execute_plan_around` to follow a planner's map.
     pub fn execute_plan(&mut self, plan: &Path) -> Result<MotionCommand, NavigationError> {
+        self.follow_plan(plan, None)
+    }
+    
+    /// Execute a navigation plan, keeping dynamic window rollouts clear of the planner's
+    /// obstacles and keep-out zones
+    pub fn execute_plan_around(&mut self, plan: &Path, planner: &NavigationPlanner) -> Result<MotionCommand, NavigationError> {
+        self.follow_plan(plan, Some(planner))
+    }
+    
+    /// Shared body of `execute_plan` and `execute_plan_around`
+    fn follow_plan(&mut self, plan: &Path, planner: Option<&NavigationPlanner>) -> Result<MotionCommand, NavigationError> {
+        // Following a plan again ends any controlled stop
+        self.stop_deceleration = None;
+        
         if plan.segments.is_empty() {
+            self.motion_state = MotionState::Stopped;
+            self.heading_pid.reset();
             return Ok(MotionCommand { linear: 0.0, angular: 0.0 });
         }
         
+        // Follow the plan from where the robot is now, on evenly spaced waypoints
+        // regardless of how long the planner's segments are
+        let remaining = self.pose.map(|pose| plan.remaining_from(pose));
+        let resampled = remaining.as_ref().unwrap_or(plan).resample(self.config.path_resample_spacing);
+        let plan = &resampled;
+        
         // For simplicity, use the first segment
         let segment = &plan.segments[0];
+        let approach_limit = plan.distance_to_goal.map(|distance| self.approach_speed_limit(distance));
+        
+        let smoothed_velocity = if self.config.dwa.enabled {
+            // Rollouts stay within the acceleration limits, so no further ramping is needed
+            let speed_cap = (self.safety_limits.max_velocity.linear * plan.speed_scale).min(approach_limit.unwrap_or(f32::MAX));
+            let command = self.dynamic_window_command(plan, speed_cap, planner);
+            self.motion_profile.current_velocity = command;
+            command
+        } else {
+            // Calculate desired velocity based on segment, tapering off near the goal
+            let mut desired_velocity = self.calculate_desired_velocity(segment, plan.speed_scale);
+            desired_velocity.angular = self.heading_command(segment);
+            if let Some(limit) = approach_limit {
+                desired_velocity.linear = desired_velocity.linear.min(limit);
+            }
+            
+            // Apply motion profile to smooth velocity changes
+            self.apply_motion_profile(desired_velocity)
+        };
         
-        // Calculate desired velocity based on segment
-        let desired_velocity = self.calculate_desired_velocity(segment);
+        // Enforce safety limits (e.g. lowered by a draining battery while moving fast)
+        let limited_velocity = self.clamp_to_velocity_limits(smoothed_velocity);
         
-        // Apply motion profile to smooth velocity changes
-        let smoothed_velocity = self.apply_motion_profile(desired_velocity);
+        // Filter cycle-to-cycle noise after the safety check
+        let output = self.smooth_output(limited_velocity);
         
-        // Check safety limits
-        if !self.check_velocity_limits(smoothed_velocity) {
-            return Err(NavigationError::VelocityLimitExceeded);
-        }
+        // Obstacles within the safety margin lower the segment score
+        let approaching = plan.distance_to_goal.is_some_and(|distance| distance < self.config.approach_distance);
+        self.motion_state = if segment.safety_score < 1.0 {
+            MotionState::Avoiding
+        } else if approaching {
+            MotionState::ApproachingGoal
+        } else if output.linear.abs() > 0.01 || output.angular.abs() > 0.01 {
+            MotionState::Moving
+        } else {
+            MotionState::Stopped
+        };
         
         // Store command history
-        self.command_history.push_back(smoothed_velocity);
-        if self.command_history.len() > 100 {
-            self.command_history.pop_front();
-        }
+        self.command_history.push(output);
+        
+        Ok(output)
+    }
+    
+    /// Apply the exponential moving-average output filter to a command
+    ///
+    /// The output is a blend of in-limit commands, so it never exceeds the limits.
+    pub fn smooth_output(&mut self, command: MotionCommand) -> MotionCommand {
+        let alpha = self.config.output_smoothing_alpha.clamp(0.0, 1.0);
+        
+        let output = MotionCommand {
+            linear: alpha * self.last_output.linear + (1.0 - alpha) * command.linear,
+            angular: alpha * self.last_output.angular + (1.0 - alpha) * command.angular,
+        };
+        
+        self.last_output = output;
+        output
+    }
+    
+    /// Scale velocity and acceleration limits down as the battery drains
+    ///
+    /// Full limits apply at or above `low_energy_threshold`; below it the scale
+    /// falls linearly to `min_energy_scale` at an empty battery.
+    pub fn set_energy_scale(&mut self, soc: f32) {
+        let soc = soc.clamp(0.0, 1.0);
+        let threshold = self.config.low_energy_threshold;
+        let floor = self.config.min_energy_scale.clamp(0.0, 1.0);
+        
+        self.energy_scale = if threshold <= 0.0 || soc >= threshold {
+            1.0
+        } else {
+            floor + (1.0 - floor) * soc / threshold
+        };
         
-        Ok(smoothed_velocity)
+        // Re-derive the limits from the configured maxima
+        self.safety_limits.max_velocity = MotionCommand {
+            linear: self.config.max_linear_velocity * self.energy_scale,
+            angular: self.config.max_angular_velocity * self.energy_scale,
+        };
+        self.safety_limits.max_acceleration = MotionCommand {
+            linear: self.config.max_acceleration * self.energy_scale,
+            angular: self.config.max_acceleration * self.energy_scale,
+        };
+        // Braking is left at full strength
+        self.motion_profile.acceleration_limits = self.safety_limits.max_acceleration;
+    }
+    
+    /// Current velocity limits after energy scaling
+    pub fn velocity_limits(&self) -> MotionCommand {
+        self.safety_limits.max_velocity
     }
     
     /// Calculate desired velocity for a path segment
-    fn calculate_desired_velocity(&self, segment: &PathSegment) -> MotionCommand {
+    fn calculate_desired_velocity(&self, segment: &PathSegment, speed_scale: f32) -> MotionCommand {
         // Simple velocity calculation based on segment length and safety
-        let base_speed = self.config.max_linear_velocity;
+        let base_speed = self.safety_limits.max_velocity.linear * speed_scale;
         let safety_factor = segment.safety_score;
         
+        // Hold back forward motion until the robot faces along the segment
+        let tolerance = self.config.alignment_tolerance.max(RESAMPLE_EPSILON);
+        let alignment = (2.0 - Self::heading_error(segment).abs() / tolerance).clamp(0.0, 1.0);
+        
         MotionCommand {
-            linear: base_speed * safety_factor,
-            angular: 0.0, // Would calculate based on curvature in production
+            linear: base_speed * safety_factor * alignment,
+            angular: 0.0, // Set by heading_command
+        }
+    }
+    
+    /// Angle from the robot's heading to the segment's direction, in (-pi, pi]
+    fn heading_error(segment: &PathSegment) -> f32 {
+        if segment.length <= RESAMPLE_EPSILON {
+            return 0.0;
+        }
+        let direction = (segment.end.y - segment.start.y).atan2(segment.end.x - segment.start.x);
+        // Wrap so the robot turns the short way round
+        (direction - segment.start.theta + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI
+    }
+    
+    /// Turn rate steering the current heading onto the segment's direction
+    fn heading_command(&mut self, segment: &PathSegment) -> f32 {
+        if segment.length <= RESAMPLE_EPSILON {
+            return 0.0;
         }
+        let error = Self::heading_error(segment);
+        
+        let max_angular = self.safety_limits.max_velocity.angular;
+        self.heading_pid.update(error, self.cycle_time).clamp(-max_angular, max_angular)
+    }
+    
+    /// Best command in the dynamic window, found by simulating each candidate ahead
+    ///
+    /// Candidates are reachable within one cycle from the current velocity, forward
+    /// only and capped at `speed_cap`. Rollouts that enter an obstacle's inflation or a
+    /// keep-out zone, or move too fast to brake before the closest one, are inadmissible; the rest are
+    /// scored on progress toward the lookahead point, clearance and final heading.
+    /// Brakes hardest when no candidate is admissible.
+    fn dynamic_window_command(&self, plan: &Path, speed_cap: f32, planner: Option<&NavigationPlanner>) -> MotionCommand {
+        let dwa = self.config.dwa;
+        let current = self.motion_profile.current_velocity;
+        let accel = self.motion_profile.acceleration_limits;
+        let decel = self.motion_profile.deceleration_limits;
+        let limits = self.safety_limits.max_velocity;
+        let rollout_time = dwa.rollout_time.max(0.1);
+        let lookahead = rollout_time * limits.linear;
+        let (Some(start), Some(target)) = (plan.segments.first().map(|segment| segment.start), plan.point_at(lookahead)) else {
+            return MotionCommand { linear: 0.0, angular: 0.0 };
+        };
+        
+        // The plan is in the odometry frame; roll out in the robot's frame at the plan start
+        let robot = Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        let target = relative_to(start, target);
+        
+        // Velocities reachable this cycle
+        let dt = self.cycle_time;
+        let linear_min = Self::ramp(current.linear, f32::MIN, accel.linear * dt, decel.linear * dt).max(0.0);
+        let linear_max = Self::ramp(current.linear, f32::MAX, accel.linear * dt, decel.linear * dt)
+            .min(speed_cap)
+            .max(linear_min);
+        let angular_min = Self::ramp(current.angular, f32::MIN, accel.angular * dt, decel.angular * dt).max(-limits.angular);
+        let angular_max = Self::ramp(current.angular, f32::MAX, accel.angular * dt, decel.angular * dt)
+            .min(limits.angular)
+            .max(angular_min);
+        let brake = MotionCommand {
+            linear: linear_min,
+            angular: if angular_min > 0.0 { angular_min } else { angular_max.min(0.0) },
+        };
+        
+        let clearance_cap = self.config.safety_distance * 2.0;
+        let body_radius = footprint::circumradius(&self.config.footprint);
+        let steps = (rollout_time / 0.1).round().max(1.0) as usize;
+        let mut best: Option<(f32, MotionCommand)> = None;
+        for linear in Self::window_samples(linear_min, linear_max, dwa.linear_samples) {
+            for angular in Self::window_samples(angular_min, angular_max, dwa.angular_samples) {
+                // Roll the command out as an arc, tracking the closest approach to any obstacle
+                let mut pose = robot;
+                let mut clearance = clearance_cap;
+                for _ in 0..steps {
+                    pose.x += linear * pose.theta.cos() * 0.1;
+                    pose.y += linear * pose.theta.sin() * 0.1;
+                    pose.theta += angular * 0.1;
+                    if let Some(planner) = planner {
+                        clearance = clearance.min(self.rollout_clearance(planner, start, pose, clearance_cap, body_radius));
+                    }
+                }
+                if clearance <= 0.0 || linear > (2.0 * clearance * decel.linear).sqrt() {
+                    continue;
+                }
+                
+                let remaining = (target.x - pose.x).hypot(target.y - pose.y);
+                let progress = ((target.x - robot.x).hypot(target.y - robot.y) - remaining) / lookahead.max(RESAMPLE_EPSILON);
+                let heading = if remaining > RESAMPLE_EPSILON {
+                    let error = ((target.y - pose.y).atan2(target.x - pose.x) - pose.theta + std::f32::consts::PI)
+                        .rem_euclid(std::f32::consts::TAU)
+                        - std::f32::consts::PI;
+                    1.0 - error.abs() / std::f32::consts::PI
+                } else {
+                    1.0
+                };
+                let score = dwa.progress_weight * progress
+                    + dwa.clearance_weight * clearance / clearance_cap.max(RESAMPLE_EPSILON)
+                    + dwa.heading_weight * heading;
+                if best.is_none_or(|(best_score, _)| score > best_score) {
+                    best = Some((score, MotionCommand { linear, angular }));
+                }
+            }
+        }
+        best.map_or(brake, |(_, command)| command)
+    }
+    
+    /// Closest clearance between the robot body at `pose` and any blocking obstacle's inflation
+    /// or keep-out zone, up to `cap`
+    ///
+    /// `pose` is in the robot's frame at `origin` (odometry frame). It is taken to the base
+    /// frame of the planner's latest scan for obstacles, and to the odometry frame for
+    /// keep-out zones.
+    fn rollout_clearance(&self, planner: &NavigationPlanner, origin: Pose2D, pose: Pose2D, cap: f32, body_radius: f32) -> f32 {
+        let world = absolute_from(origin, pose);
+        let pose = planner.to_base_frame(world);
+        let obstacles = planner
+            .obstacles_near(pose.x, pose.y, cap + planner.max_inflation() + body_radius)
+            .filter(|obstacle| obstacle.confidence >= self.config.min_blocking_confidence)
+            .map(|obstacle| {
+                (obstacle.position.x - pose.x).hypot(obstacle.position.y - pose.y)
+                    - self.config.obstacle_inflation.max(obstacle.radius)
+                    - body_radius
+            });
+        let zones = planner
+            .keepout_zones()
+            .iter()
+            .map(|zone| zone.distance_to(world.x, world.y) - body_radius);
+        obstacles.chain(zones).fold(cap, f32::min)
+    }
+    
+    /// `count` evenly spaced values from `min` to `max` (just `max` for a single sample)
+    fn window_samples(min: f32, max: f32, count: usize) -> impl Iterator<Item = f32> {
+        let count = count.max(1);
+        (0..count).map(move |i| {
+            if count == 1 {
+                max
+            } else {
+                min + (max - min) * i as f32 / (count - 1) as f32
+            }
+        })
     }
     
     /// Apply motion profile to smooth velocity changes
-    fn apply_motion_profile(&mut self, desired_velocity: MotionCommand) -> MotionCommand {
-        // Simple linear acceleration limiting
-        let max_delta_linear = self.motion_profile.acceleration_limits.linear * 0.1; // Assuming 100ms cycle
-        let max_delta_angular = self.motion_profile.acceleration_limits.angular * 0.1;
+    ///
+    /// Speeding up is limited by the acceleration limits and slowing down by the
+    /// (usually larger) deceleration limits.
+    pub fn apply_motion_profile(&mut self, desired_velocity: MotionCommand) -> MotionCommand {
+        let current = self.motion_profile.current_velocity;
+        let accel = self.motion_profile.acceleration_limits;
+        let decel = self.motion_profile.deceleration_limits;
         
-        let delta_linear = (desired_velocity.linear - self.motion_profile.current_velocity.linear)
-            .clamp(-max_delta_linear, max_delta_linear);
-            
-        let delta_angular = (desired_velocity.angular - self.motion_profile.current_velocity.angular)
-            .clamp(-max_delta_angular, max_delta_angular);
-            
+        let dt = self.cycle_time;
         let new_velocity = MotionCommand {
-            linear: self.motion_profile.current_velocity.linear + delta_linear,
-            angular: self.motion_profile.current_velocity.angular + delta_angular,
+            linear: Self::ramp(current.linear, desired_velocity.linear, accel.linear * dt, decel.linear * dt),
+            angular: Self::ramp(current.angular, desired_velocity.angular, accel.angular * dt, decel.angular * dt),
         };
+        // A ramp spans many cycles; record where it starts rather than every step
+        let limited = new_velocity != desired_velocity;
+        if limited && !self.acceleration_limited {
+            self.record_safety_event(SafetyEventReason::AccelerationLimit, desired_velocity, new_velocity);
+        }
+        self.acceleration_limited = limited;
         
         // Update current velocity
         self.motion_profile.current_velocity = new_velocity;
@@ -456,22 +2194,201 @@ impl MotionController {
         new_velocity
     }
     
-    /// Check if velocity is within safety limits
-    fn check_velocity_limits(&self, velocity: MotionCommand) -> bool {
-        velocity.linear.abs() <= self.safety_limits.max_velocity.linear &&
-        velocity.angular.abs() <= self.safety_limits.max_velocity.angular
+    /// Step one axis toward the desired value, choosing the limit by direction
+    fn ramp(current: f32, desired: f32, max_accel_step: f32, max_decel_step: f32) -> f32 {
+        let delta = desired - current;
+        // Moving toward zero speed (or reversing) is braking
+        let slowing = current != 0.0 && delta.signum() != current.signum();
+        let max_step = if slowing { max_decel_step } else { max_accel_step };
+        
+        current + delta.clamp(-max_step, max_step)
+    }
+    
+    /// Clamp a command to the velocity limits, recording the intervention
+    fn clamp_to_velocity_limits(&mut self, velocity: MotionCommand) -> MotionCommand {
+        let max = self.safety_limits.max_velocity;
+        let clamped = MotionCommand {
+            linear: velocity.linear.clamp(-max.linear, max.linear),
+            angular: velocity.angular.clamp(-max.angular, max.angular),
+        };
+        
+        if clamped != velocity {
+            log::warn!("Command clamped to velocity limits: {:.2} -> {:.2} m/s", velocity.linear, clamped.linear);
+            self.record_safety_event(SafetyEventReason::VelocityLimit, velocity, clamped);
+            // Continue ramping from the clamped velocity
+            self.motion_profile.current_velocity = clamped;
+        }
+        clamped
+    }
+    
+    /// Append a safety event, dropping the oldest beyond `safety_event_capacity`
+    fn record_safety_event(&mut self, reason: SafetyEventReason, original: MotionCommand, applied: MotionCommand) {
+        self.safety_events.push(SafetyEvent { reason, original, applied });
+    }
+    
+    /// Recorded safety interventions, oldest first
+    pub fn safety_events(&self) -> &BoundedHistory<SafetyEvent> {
+        &self.safety_events
+    }
+    
+    /// Compare the last output command against measured odometry velocity
+    ///
+    /// Returns true once the robot has been commanded to move without moving for
+    /// longer than `stuck_timeout`.
+    pub fn check_stuck(&mut self, measured: MotionCommand, dt: f32) -> bool {
+        self.stuck_monitor.update(self.last_output, measured, dt)
+    }
+    
+    /// Speed cap at the given distance from the goal
+    ///
+    /// Full speed outside `approach_distance`, tapering linearly to `creep_speed`
+    /// at the goal tolerance radius.
+    pub fn approach_speed_limit(&self, distance_to_goal: f32) -> f32 {
+        let max_speed = self.safety_limits.max_velocity.linear;
+        let creep = self.config.creep_speed.min(max_speed);
+        let span = self.config.approach_distance - self.config.goal_tolerance;
+        if span <= 0.0 {
+            return max_speed;
+        }
+        
+        let fraction = ((distance_to_goal - self.config.goal_tolerance) / span).clamp(0.0, 1.0);
+        creep + (max_speed - creep) * fraction
+    }
+    
+    /// Most recent command output
+    pub fn last_command(&self) -> MotionCommand {
+        self.last_output
+    }
+    
+    /// Mean commanded linear speed over the recent command history
+    pub fn mean_speed(&self) -> f32 {
+        const WINDOW: usize = 10;
+        self.command_history
+            .mean_of(WINDOW, |c| c.linear.abs() as f64)
+            .unwrap_or(0.0) as f32
+    }
+    
+    /// Motion state derived from the last executed plan
+    pub fn motion_state(&self) -> MotionState {
+        self.motion_state
+    }
+    
+    /// Brake toward a standstill, stopping within `lost_stop_distance` of where braking began
+    ///
+    /// The deceleration is fixed from the speed when the stop begins, kept within
+    /// `MIN_STOP_DECELERATION` and the deceleration limit; turning winds down at the
+    /// angular deceleration limit. Call once per cycle until it returns zero.
+    pub fn controlled_stop(&mut self) -> MotionCommand {
+        let current = self.last_output;
+        let max_decel = self.motion_profile.deceleration_limits;
+        let distance = self.config.lost_stop_distance;
+        self.heading_pid.reset();
+        let deceleration = *self.stop_deceleration.get_or_insert_with(|| {
+            if distance > 0.0 {
+                (current.linear.powi(2) / (2.0 * distance)).clamp(MIN_STOP_DECELERATION, max_decel.linear.max(MIN_STOP_DECELERATION))
+            } else {
+                f32::INFINITY
+            }
+        });
+        
+        let output = MotionCommand {
+            linear: Self::ramp(current.linear, 0.0, 0.0, deceleration * self.cycle_time),
+            angular: Self::ramp(current.angular, 0.0, 0.0, max_decel.angular * self.cycle_time),
+        };
+        
+        self.motion_profile.current_velocity = output;
+        self.last_output = output;
+        self.command_history.push(output);
+        self.motion_state = if output.linear != 0.0 || output.angular != 0.0 {
+            MotionState::Moving
+        } else {
+            MotionState::Stopped
+        };
+        
+        output
+    }
+    
+    /// Drive a recovery behavior's command within the acceleration and velocity limits
+    pub fn execute_recovery(&mut self, command: MotionCommand) -> MotionCommand {
+        self.heading_pid.reset();
+        self.stop_deceleration = None;
+        let ramped = self.apply_motion_profile(command);
+        let output = self.clamp_to_velocity_limits(ramped);
+        
+        self.last_output = output;
+        self.command_history.push(output);
+        self.motion_state = if output.linear != 0.0 || output.angular != 0.0 {
+            MotionState::Moving
+        } else {
+            MotionState::Stopped
+        };
+        output
     }
     
     /// Perform emergency stop
     pub fn emergency_stop(&mut self) -> MotionCommand {
+        let stop = MotionCommand { linear: 0.0, angular: 0.0 };
+        if self.last_output != stop {
+            self.record_safety_event(SafetyEventReason::EmergencyStop, self.last_output, stop);
+        }
+        
+        self.heading_pid.reset();
+        
         // Apply emergency deceleration
         self.motion_profile.current_velocity.linear = 0.0;
         self.motion_profile.current_velocity.angular = 0.0;
+        self.last_output = MotionCommand { linear: 0.0, angular: 0.0 };
+        self.motion_state = MotionState::EmergencyStopping;
         
         MotionCommand { linear: 0.0, angular: 0.0 }
     }
 }
 
+impl StuckMonitor {
+    /// Minimum commanded speed considered an attempt to move (m/s)
+    const MIN_COMMANDED_SPEED: f32 = 0.05;
+    /// Measured/commanded ratio below which the robot counts as stalled
+    const STALL_RATIO: f32 = 0.2;
+    
+    /// Create a monitor with the given timeout in seconds
+    pub fn new(timeout: f32) -> Self {
+        StuckMonitor {
+            timeout,
+            stalled_time: 0.0,
+        }
+    }
+    
+    /// Feed one cycle of commanded and measured velocity; returns the stuck condition
+    pub fn update(&mut self, commanded: MotionCommand, measured: MotionCommand, dt: f32) -> bool {
+        let commanded_speed = commanded.linear.abs();
+        let stalled = commanded_speed > Self::MIN_COMMANDED_SPEED
+            && measured.linear.abs() < commanded_speed * Self::STALL_RATIO;
+        
+        if stalled {
+            self.stalled_time += dt;
+        } else {
+            self.stalled_time = 0.0;
+        }
+        
+        if self.is_stuck() {
+            log::warn!("Robot stuck: commanded {:.2} m/s but measured {:.2} m/s for {:.1}s",
+                commanded.linear, measured.linear, self.stalled_time);
+        }
+        
+        self.is_stuck()
+    }
+    
+    /// Whether the stall has outlasted the timeout
+    pub fn is_stuck(&self) -> bool {
+        self.stalled_time > self.timeout
+    }
+    
+    /// Clear the accumulated stall time
+    pub fn reset(&mut self) {
+        self.stalled_time = 0.0;
+    }
+}
+
 /// Path representation for navigation
 #[derive(Debug, Clone)]
 pub struct Path {
@@ -481,6 +2398,196 @@ pub struct Path {
     total_length: f32,
     /// Overall safety score
     overall_safety: f32,
+    /// Speed reduction requested by the planner (e.g. near people)
+    speed_scale: f32,
+    /// Remaining distance to the goal when planning, if a goal is set
+    distance_to_goal: Option<f32>,
+}
+
+impl Path {
+    /// Path through the given segments, at full speed and with no goal distance yet
+    fn from_segments(segments: Vec<PathSegment>) -> Path {
+        Path {
+            total_length: segments.iter().map(|segment| segment.length).sum(),
+            overall_safety: segments.iter().map(|segment| segment.safety_score).fold(1.0, f32::min),
+            segments,
+            speed_scale: 1.0,
+            distance_to_goal: None,
+        }
+    }
+    
+    /// Distance travelled along the path up to the projection of `pose` onto it
+    pub fn distance_along(&self, pose: Pose2D) -> f32 {
+        let mut best = (f32::MAX, 0.0); // (distance from path, distance along path)
+        let mut offset = 0.0;
+        
+        for segment in &self.segments {
+            let (dx, dy) = (segment.end.x - segment.start.x, segment.end.y - segment.start.y);
+            let length_sq = dx * dx + dy * dy;
+            let t = if length_sq > 0.0 {
+                (((pose.x - segment.start.x) * dx + (pose.y - segment.start.y) * dy) / length_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let off_path = (pose.x - segment.start.x - t * dx).hypot(pose.y - segment.start.y - t * dy);
+            if off_path < best.0 {
+                best = (off_path, offset + t * segment.length);
+            }
+            offset += segment.length;
+        }
+        
+        best.1
+    }
+    
+    /// The rest of the path as seen from `pose`: the first segment runs from the robot
+    /// to the end of the segment it has reached, and the goal distance shrinks by the
+    /// distance already travelled
+    pub fn remaining_from(&self, pose: Pose2D) -> Path {
+        let travelled = self.distance_along(pose);
+        let mut offset = 0.0;
+        let mut segments = Vec::with_capacity(self.segments.len());
+        for segment in &self.segments {
+            offset += segment.length;
+            if !segments.is_empty() {
+                segments.push(segment.clone());
+            } else if offset > travelled + RESAMPLE_EPSILON {
+                let length = (segment.end.x - pose.x).hypot(segment.end.y - pose.y);
+                segments.push(PathSegment { start: pose, length, ..segment.clone() });
+            }
+        }
+        // Past the last waypoint, head for the path's end
+        if let (true, Some(last)) = (segments.is_empty(), self.segments.last()) {
+            let length = (last.end.x - pose.x).hypot(last.end.y - pose.y);
+            segments.push(PathSegment { start: pose, length, ..last.clone() });
+        }
+        
+        Path {
+            distance_to_goal: self.distance_to_goal.map(|distance| (distance - travelled).max(0.0)),
+            speed_scale: self.speed_scale,
+            ..Path::from_segments(segments)
+        }
+    }
+    
+    /// Point `distance` meters along the path, facing along it; the end if the path is shorter
+    pub fn point_at(&self, distance: f32) -> Option<Pose2D> {
+        let mut remaining = distance.max(0.0);
+        for segment in &self.segments {
+            let heading = (segment.end.y - segment.start.y).atan2(segment.end.x - segment.start.x);
+            if remaining <= segment.length {
+                let t = if segment.length > 0.0 { remaining / segment.length } else { 0.0 };
+                return Some(Pose2D {
+                    x: segment.start.x + t * (segment.end.x - segment.start.x),
+                    y: segment.start.y + t * (segment.end.y - segment.start.y),
+                    theta: heading,
+                });
+            }
+            remaining -= segment.length;
+        }
+        self.segments.last().map(|segment| segment.end)
+    }
+    
+    /// Path segments in travel order
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+    
+    /// Total path length in meters
+    pub fn total_length(&self) -> f32 {
+        self.total_length
+    }
+    
+    /// Overall safety score (0.0 - 1.0)
+    pub fn overall_safety(&self) -> f32 {
+        self.overall_safety
+    }
+    
+    /// Fraction of the normal speed to drive this path at (0.0 - 1.0)
+    pub fn speed_scale(&self) -> f32 {
+        self.speed_scale
+    }
+    
+    /// Remaining distance to the goal (beyond this path if it's horizon-limited)
+    pub fn distance_to_goal(&self) -> Option<f32> {
+        self.distance_to_goal
+    }
+    
+    /// Waypoints in travel order: every segment start, then the final end
+    pub fn waypoints(&self) -> Vec<Pose2D> {
+        let mut waypoints: Vec<Pose2D> = self.segments.iter().map(|segment| segment.start).collect();
+        waypoints.extend(self.segments.last().map(|segment| segment.end));
+        waypoints
+    }
+    
+    /// Densify the path to waypoints `spacing` meters apart along its length
+    ///
+    /// Waypoints are linearly interpolated; the last piece ends exactly at the path end
+    /// and may be shorter. Each new segment keeps the lowest safety score it overlaps.
+    pub fn resample(&self, spacing: f32) -> Path {
+        if spacing <= 0.0 || self.segments.is_empty() {
+            return self.clone();
+        }
+        
+        // Interpolated waypoints, each with the index of the original segment it lies on
+        let mut waypoints = vec![(self.segments[0].start, 0)];
+        let mut next = spacing;
+        let mut travelled = 0.0;
+        for (index, segment) in self.segments.iter().enumerate() {
+            let heading = (segment.end.y - segment.start.y).atan2(segment.end.x - segment.start.x);
+            while next < travelled + segment.length - RESAMPLE_EPSILON {
+                let t = (next - travelled) / segment.length;
+                let pose = Pose2D {
+                    x: segment.start.x + t * (segment.end.x - segment.start.x),
+                    y: segment.start.y + t * (segment.end.y - segment.start.y),
+                    theta: heading,
+                };
+                waypoints.push((pose, index));
+                next += spacing;
+            }
+            travelled += segment.length;
+        }
+        let last = self.segments.len() - 1;
+        waypoints.push((self.segments[last].end, last));
+        
+        let segments = waypoints
+            .windows(2)
+            .map(|pair| {
+                let ((start, first), (end, second)) = (pair[0], pair[1]);
+                PathSegment {
+                    start,
+                    end,
+                    length: ((end.x - start.x).powi(2) + (end.y - start.y).powi(2)).sqrt(),
+                    safety_score: self.segments[first..=second]
+                        .iter()
+                        .map(|segment| segment.safety_score)
+                        .fold(1.0, f32::min),
+                }
+            })
+            .collect();
+        
+        Path { segments, ..self.clone() }
+    }
+}
+
+impl PathSegment {
+    /// Start pose
+    pub fn start(&self) -> Pose2D {
+        self.start
+    }
+    
+    /// End pose
+    pub fn end(&self) -> Pose2D {
+        self.end
+    }
+    
+    /// Segment length in meters
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+    
+    /// Segment safety score (0.0 - 1.0)
+    pub fn safety_score(&self) -> f32 {
+        self.safety_score
+    }
 }
 
 /// Navigation error types
@@ -496,6 +2603,10 @@ pub enum NavigationError {
     InvalidGoal,
     /// Planning timeout
     PlanningTimeout,
+    /// Keep-out zone file could not be loaded
+    ZoneFileError(String),
+    /// Emergency stop engaged
+    EmergencyStop,
 }
 
 impl std::fmt::Display for NavigationError {
@@ -506,6 +2617,8 @@ impl std::fmt::Display for NavigationError {
             NavigationError::VelocityLimitExceeded => write!(f, "Velocity limit exceeded"),
             NavigationError::InvalidGoal => write!(f, "Invalid goal"),
             NavigationError::PlanningTimeout => write!(f, "Planning timeout"),
+            NavigationError::ZoneFileError(msg) => write!(f, "Keep-out zone file error: {}", msg),
+            NavigationError::EmergencyStop => write!(f, "Emergency stop engaged"),
         }
     }
 }
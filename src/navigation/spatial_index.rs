@@ -0,0 +1,80 @@
+//! Spatial hash index for obstacle lookups
+//!
+//! Buckets obstacles into a uniform grid so safety checks only visit
+//! obstacles near the query point instead of the whole obstacle map.
+
+use std::collections::HashMap;
+
+use super::Obstacle;
+
+/// Uniform grid index over an obstacle list
+#[derive(Debug, Clone)]
+pub struct ObstacleIndex {
+    /// Edge length of a grid cell in meters
+    cell_size: f32,
+    /// Obstacle indices bucketed by grid cell
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    /// Largest obstacle radius seen during the last rebuild
+    max_radius: f32,
+}
+
+impl ObstacleIndex {
+    /// Create an empty index with the given cell size
+    pub fn new(cell_size: f32) -> Self {
+        ObstacleIndex {
+            cell_size: cell_size.max(0.01),
+            cells: HashMap::new(),
+            max_radius: 0.0,
+        }
+    }
+
+    /// Rebuild the index from scratch for the given obstacles
+    pub fn rebuild(&mut self, obstacles: &[Obstacle]) {
+        self.cells.clear();
+        self.max_radius = 0.0;
+
+        for (i, obstacle) in obstacles.iter().enumerate() {
+            let cell = self.cell_of(obstacle.position.x, obstacle.position.y);
+            self.cells.entry(cell).or_default().push(i);
+            self.max_radius = self.max_radius.max(obstacle.radius);
+        }
+    }
+
+    /// Largest obstacle radius in the index
+    pub fn max_radius(&self) -> f32 {
+        self.max_radius
+    }
+
+    /// Iterate over the obstacles whose center lies within `radius` of (x, y)
+    ///
+    /// `obstacles` must be the same slice the index was last rebuilt from.
+    pub fn query<'a>(
+        &'a self,
+        obstacles: &'a [Obstacle],
+        x: f32,
+        y: f32,
+        radius: f32,
+    ) -> impl Iterator<Item = &'a Obstacle> + 'a {
+        let (min_cx, min_cy) = self.cell_of(x - radius, y - radius);
+        let (max_cx, max_cy) = self.cell_of(x + radius, y + radius);
+
+        (min_cx..=max_cx)
+            .flat_map(move |cx| (min_cy..=max_cy).map(move |cy| (cx, cy)))
+            .filter_map(move |cell| self.cells.get(&cell))
+            .flatten()
+            .map(move |&i| &obstacles[i])
+            .filter(move |obstacle| {
+                let dx = obstacle.position.x - x;
+                let dy = obstacle.position.y - y;
+                (dx * dx + dy * dy).sqrt() <= radius
+            })
+    }
+
+    /// Grid cell containing a world coordinate
+    fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+}
@@ -0,0 +1,63 @@
+//! Spike-train encoding of sensor inputs
+//!
+//! Turns laser ranges into closeness values in [0, 1] and those into binary spike
+//! trains over the simulation's time steps, so the network sees spikes rather than
+//! raw meters.
+
+use serde::{Deserialize, Serialize};
+
+/// How each network input is presented over the simulation time steps
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpikeEncoding {
+    /// Raw ranges in meters, fed straight to the network without spike trains
+    #[default]
+    Raw,
+    /// Spike count proportional to closeness, spread evenly over the train
+    Rate,
+    /// A single spike, earlier the closer the obstacle; none for nothing in range
+    Latency,
+}
+
+impl SpikeEncoding {
+    /// Spike train of `time_steps` values (1.0 spike, 0.0 silent) for a closeness in [0, 1]
+    ///
+    /// Closeness outside [0, 1] is clamped and NaN is treated as 0 (nothing seen).
+    /// `Raw` holds the clamped value at every step.
+    pub fn encode(self, closeness: f32, time_steps: usize) -> Vec<f32> {
+        let closeness = if closeness.is_nan() { 0.0 } else { closeness.clamp(0.0, 1.0) };
+        let mut train = vec![0.0; time_steps];
+        match self {
+            SpikeEncoding::Raw => train.fill(closeness),
+            SpikeEncoding::Rate => {
+                // Spike whenever the accumulated rate crosses the next whole spike
+                for (step, value) in train.iter_mut().enumerate() {
+                    let before = (step as f32 * closeness).floor();
+                    let after = ((step + 1) as f32 * closeness).floor();
+                    if after > before {
+                        *value = 1.0;
+                    }
+                }
+            }
+            SpikeEncoding::Latency => {
+                if closeness > 0.0 && time_steps > 0 {
+                    let step = ((1.0 - closeness) * (time_steps - 1) as f32).round() as usize;
+                    train[step] = 1.0;
+                }
+            }
+        }
+        train
+    }
+}
+
+/// Closeness in [0, 1] of a range reading: 1.0 at `range_min`, 0.0 at `range_max` and beyond
+///
+/// Readings outside the sensor's range are clamped to it; NaN, and any reading from
+/// a sensor without a valid range, count as nothing seen.
+pub fn closeness(range: f32, range_min: f32, range_max: f32) -> f32 {
+    if range.is_nan() || range_min.is_nan() || range_max.is_nan() || range_max <= range_min {
+        return 0.0;
+    }
+    let range = range.clamp(range_min, range_max);
+    1.0 - (range - range_min) / (range_max - range_min)
+}
@@ -4,19 +4,53 @@
 //! for sensor processing and decision making.
 pub mod snn;
 pub mod config;
+pub mod encoding;
 
+pub use encoding::SpikeEncoding;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use crate::core::util::BoundedHistory;
 
 /// SNN engine for processing sensor data
 pub struct SNNEngine {
     config: NeuralConfig,
     model: Option<NeuralModel>,
-    input_buffer: VecDeque<Vec<f32>>,
-    output_buffer: VecDeque<Vec<f32>>,
+    quantized_model: Option<QuantizedModel>,
+    input_buffer: BoundedHistory<Vec<f32>>,
+    output_buffer: BoundedHistory<Vec<f32>>,
+    mean_latency_ms: Option<f32>,
+    checkpoint: Option<Checkpoint>,
+    stdp_traces: Option<StdpTraces>,
     is_initialized: bool,
 }
 
+/// Decaying spike histories of the output layer's inputs and outputs
+struct StdpTraces {
+    pre: Vec<f32>,
+    post: Vec<f32>,
+}
+
+/// Periodic model checkpoint schedule
+struct Checkpoint {
+    path: String,
+    every: std::time::Duration,
+    last: std::time::Instant,
+    /// Saves that have failed in a row, each doubling the wait before the next try
+    failures: u32,
+}
+
+/// Most intervals a failing checkpoint waits between attempts
+const MAX_CHECKPOINT_BACKOFF: u32 = 8;
+
+/// Layer buffers reused across inferences so a batch allocates them once
+#[derive(Default)]
+struct Scratch {
+    layer: Vec<f32>,
+    next: Vec<f32>,
+}
+
 /// Neural network configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeuralConfig {
@@ -34,12 +68,108 @@ pub struct NeuralConfig {
     pub spike_threshold: f32,
     /// Simulation time steps
     pub time_steps: usize,
+    /// Run inference on i8 weights in integer arithmetic (embedded targets)
+    #[serde(default)]
+    pub quantized: bool,
+    /// Pre-trained model loaded at startup (None keeps the generated default model)
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// Seed for the generated default model's weights (None draws from entropy)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Hidden-layer activation for generated models (loaded models keep their own)
+    #[serde(default)]
+    pub activation: Activation,
+    /// How laser beams are reduced to `input_size` inputs
+    #[serde(default)]
+    pub beam_sampling: BeamSampling,
+    /// How inputs are presented over `time_steps` (raw ranges or spike trains)
+    #[serde(default)]
+    pub encoding: SpikeEncoding,
+    /// Range (min, max) STDP keeps output weights within
+    #[serde(default = "default_weight_range")]
+    pub weight_range: (f32, f32),
+}
+
+/// Range of the generated model's weights
+fn default_weight_range() -> (f32, f32) {
+    (-1.0, 1.0)
+}
+
+/// Reduction of a laser scan to the network's input size
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BeamSampling {
+    /// First `input_size` beams, zero-padded (only sees one side of a wide scan)
+    Truncate,
+    /// Closest range in each of `input_size` even bins across the full scan
+    #[default]
+    MinPool,
+}
+
+/// Nonlinearity applied to hidden-layer outputs; the output layer always spikes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// max(0, x)
+    Relu,
+    /// 1 / (1 + e^-x)
+    Sigmoid,
+    /// tanh(x)
+    Tanh,
+    /// 1 above the spike threshold, else 0
+    #[default]
+    Threshold,
+}
+
+impl Activation {
+    /// Apply the activation to a pre-activation value
+    pub fn apply(self, x: f32, spike_threshold: f32) -> f32 {
+        match self {
+            Activation::Relu => x.max(0.0),
+            Activation::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Activation::Tanh => x.tanh(),
+            Activation::Threshold => if x > spike_threshold { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+/// On-disk model format, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ModelFormat {
+    /// Human-readable JSON (`.json`, and any unrecognized extension)
+    #[default]
+    Json,
+    /// Compact bincode (`.bin`), much smaller and faster for large models
+    Bincode,
+}
+
+impl ModelFormat {
+    /// Format for a model file path
+    pub fn from_path(path: &str) -> Self {
+        if path.ends_with(".bin") {
+            ModelFormat::Bincode
+        } else {
+            ModelFormat::Json
+        }
+    }
+    
+    /// File extension for this format, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ModelFormat::Json => "json",
+            ModelFormat::Bincode => "bin",
+        }
+    }
 }
 
 /// Neural network model structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NeuralModel {
-    /// Model weights
+    /// Hidden layer weights, each indexed [input][neuron]
+    #[serde(default)]
+    hidden_weights: Vec<Vec<Vec<f32>>>,
+    /// Output layer weights, indexed [input][output]
     weights: Vec<Vec<f32>>,
     /// Neuron states
     states: Vec<f32>,
@@ -49,6 +179,74 @@ pub struct NeuralModel {
     metadata: ModelMetadata,
 }
 
+/// Bits of fractional precision for fixed-point inputs
+const INPUT_FRAC_BITS: u32 = 8;
+
+/// Number of recent runs the reported processing latency roughly averages over
+const LATENCY_WINDOW: usize = 10;
+
+/// Model format major versions this engine can run
+const SUPPORTED_MODEL_VERSIONS: std::ops::RangeInclusive<u32> = 1..=1;
+
+/// Fraction of an output neuron's membrane potential kept from one time step to the next
+const LIF_LEAK: f32 = 0.9;
+
+/// Fraction of an STDP trace left after each cycle
+const STDP_TRACE_DECAY: f32 = 0.5;
+
+/// Depression relative to potentiation, slightly above 1 so uncorrelated firing weakens weights
+const STDP_DEPRESSION_RATIO: f32 = 1.05;
+
+/// Integer copy of a model's weights for quantized inference
+#[derive(Debug, Clone)]
+struct QuantizedModel {
+    /// Hidden layers followed by the output layer
+    layers: Vec<QuantizedLayer>,
+    /// Hidden-layer activation
+    activation: Activation,
+}
+
+/// One layer of i8 weights
+#[derive(Debug, Clone)]
+struct QuantizedLayer {
+    /// Weights as i8, `weight ≈ q * weight_scale`
+    weights: Vec<Vec<i8>>,
+    /// Float value of one weight step
+    weight_scale: f32,
+}
+
+impl QuantizedLayer {
+    /// Symmetric quantization with one scale for the whole layer
+    fn from_weights(weights: &[Vec<f32>]) -> Self {
+        let max_weight = weights
+            .iter()
+            .flatten()
+            .fold(0.0f32, |max, w| max.max(w.abs()));
+        let weight_scale = if max_weight > 0.0 { max_weight / i8::MAX as f32 } else { 1.0 };
+        
+        QuantizedLayer {
+            weights: weights
+                .iter()
+                .map(|row| row.iter().map(|w| (w / weight_scale).round() as i8).collect())
+                .collect(),
+            weight_scale,
+        }
+    }
+    
+    /// Integer accumulators for fixed-point inputs, one per output neuron
+    fn accumulate(&self, input: &[i32], outputs: usize) -> Vec<i64> {
+        (0..outputs)
+            .map(|i| {
+                let mut acc: i64 = 0;
+                for j in 0..input.len().min(self.weights.len()) {
+                    acc += input[j] as i64 * self.weights[j][i] as i64;
+                }
+                acc
+            })
+            .collect()
+    }
+}
+
 /// Model metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ModelMetadata {
@@ -60,10 +258,22 @@ struct ModelMetadata {
     trained_on: String,
     /// Performance metrics
     accuracy: f32,
+    /// Hidden-layer activation the model was built with
+    #[serde(default)]
+    activation: Activation,
+}
+
+/// Network output with how decisively each output neuron fired
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeuralOutput {
+    /// Spike output per neuron (1 fired, 0 silent)
+    pub values: Vec<f32>,
+    /// Per-output confidence (0.0 - 1.0) from the potential's margin above threshold
+    pub confidence: Vec<f32>,
 }
 
 /// Neural network status
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NeuralStatus {
     /// Whether the model is loaded
     pub model_loaded: bool,
@@ -71,7 +281,7 @@ pub struct NeuralStatus {
     pub input_buffer_size: usize,
     /// Output buffer size
     pub output_buffer_size: usize,
-    /// Processing latency in milliseconds
+    /// Moving average of processing latency in milliseconds (0.0 before any run)
     pub processing_latency: f32,
     /// Model accuracy if available
     pub model_accuracy: Option<f32>,
@@ -87,6 +297,13 @@ impl Default for NeuralConfig {
             learning_rate: 0.01,
             spike_threshold: 0.5,
             time_steps: 10,
+            quantized: false,
+            model_path: Some("models/default_snn.json".to_string()),
+            seed: None,
+            activation: Activation::Threshold,
+            beam_sampling: BeamSampling::MinPool,
+            encoding: SpikeEncoding::Raw,
+            weight_range: default_weight_range(),
         }
     }
 }
@@ -97,14 +314,22 @@ impl SNNEngine {
         Ok(SNNEngine {
             config: config.clone(),
             model: None,
-            input_buffer: VecDeque::with_capacity(100),
-            output_buffer: VecDeque::with_capacity(100),
+            quantized_model: None,
+            input_buffer: BoundedHistory::new(100),
+            output_buffer: BoundedHistory::new(100),
+            mean_latency_ms: None,
+            checkpoint: None,
+            stdp_traces: None,
             is_initialized: false,
         })
     }
     
     /// Initialize the neural engine
     pub fn initialize(&mut self) -> Result<(), NeuralError> {
+        if self.is_initialized {
+            return Err(NeuralError::AlreadyInitialized);
+        }
+        
         log::info!("Initializing neural engine...");
         
         // Create a default model if none loaded
@@ -113,35 +338,130 @@ impl SNNEngine {
             log::info!("Created default neural model");
         }
         
+        if self.config.quantized && self.quantized_model.is_none() {
+            self.quantize_model()?;
+        }
+        
         self.is_initialized = true;
         log::info!("Neural engine initialized successfully");
         
         Ok(())
     }
     
-    /// Load a pre-trained model
+    /// Stop processing; the loaded model is kept for a later `initialize`
+    pub fn shutdown(&mut self) -> Result<(), NeuralError> {
+        if !self.is_initialized {
+            return Err(NeuralError::NotInitialized);
+        }
+        
+        self.is_initialized = false;
+        log::info!("Neural engine shut down");
+        
+        Ok(())
+    }
+    
+    /// Load a pre-trained model, as JSON or bincode depending on the extension
     pub fn load_model(&mut self, path: &str) -> Result<(), NeuralError> {
         log::info!("Loading neural model from: {}", path);
         
-        let model_data = std::fs::read_to_string(path)
+        let model_data = std::fs::read(path)
             .map_err(|e| NeuralError::LoadError(e.to_string()))?;
             
-        let model: NeuralModel = serde_json::from_str(&model_data)
-            .map_err(|e| NeuralError::ParseError(e.to_string()))?;
+        let model: NeuralModel = match ModelFormat::from_path(path) {
+            ModelFormat::Json => serde_json::from_slice(&model_data)
+                .map_err(|e| NeuralError::ParseError(e.to_string()))?,
+            ModelFormat::Bincode => bincode::deserialize(&model_data)
+                .map_err(|e| NeuralError::ParseError(e.to_string()))?,
+        };
             
+        self.validate_model(&model)?;
+        
         self.model = Some(model);
+        self.stdp_traces = None;
         log::info!("Neural model loaded successfully");
         
+        // A stale quantized copy would keep running the previous weights
+        self.quantized_model = None;
+        if self.config.quantized {
+            self.quantize_model()?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Check a model's layer sizes against the configured input and output sizes
+    ///
+    /// Each layer must have one weight row per neuron of the layer before it (the
+    /// inputs for the first), and the output layer `output_size` columns. An
+    /// unsupported format version only warns.
+    fn validate_model(&self, model: &NeuralModel) -> Result<(), NeuralError> {
+        let major = model.metadata.version.split('.').next().and_then(|v| v.trim().parse::<u32>().ok());
+        if !major.is_some_and(|major| SUPPORTED_MODEL_VERSIONS.contains(&major)) {
+            log::warn!(
+                "Model '{}' has version {}, supported major versions are {:?}",
+                model.metadata.name,
+                model.metadata.version,
+                SUPPORTED_MODEL_VERSIONS
+            );
+        }
+        
+        let mut expected_rows = self.config.input_size;
+        for layer in model.hidden_weights.iter().chain(std::iter::once(&model.weights)) {
+            if layer.len() != expected_rows {
+                return Err(NeuralError::DimensionMismatch { expected: expected_rows, found: layer.len() });
+            }
+            let columns = layer.first().map_or(0, |row| row.len());
+            if let Some(row) = layer.iter().find(|row| row.len() != columns) {
+                return Err(NeuralError::DimensionMismatch { expected: columns, found: row.len() });
+            }
+            expected_rows = columns;
+        }
+        if expected_rows != self.config.output_size {
+            return Err(NeuralError::DimensionMismatch { expected: self.config.output_size, found: expected_rows });
+        }
+        Ok(())
+    }
+    
+    /// Convert the loaded float model to i8 weights and switch to integer inference
+    pub fn quantize_model(&mut self) -> Result<(), NeuralError> {
+        let model = self.model.as_ref().ok_or(NeuralError::NoModelError)?;
+        
+        let layers: Vec<QuantizedLayer> = model.hidden_weights
+            .iter()
+            .chain(std::iter::once(&model.weights))
+            .map(|weights| QuantizedLayer::from_weights(weights))
+            .collect();
+        log::info!("Neural model quantized to i8 ({} layers)", layers.len());
+            
+        self.quantized_model = Some(QuantizedModel {
+            layers,
+            activation: model.metadata.activation,
+        });
+        
         Ok(())
     }
     
-    /// Save the current model
+    /// Whether inference runs on the quantized model
+    pub fn is_quantized(&self) -> bool {
+        self.quantized_model.is_some()
+    }
+    
+    /// Save the current model, as JSON or bincode depending on the extension
+    ///
+    /// Writes to a temporary file and renames it over `path`, so a crash mid-save
+    /// leaves the previous model intact.
     pub fn save_model(&self, path: &str) -> Result<(), NeuralError> {
         if let Some(model) = &self.model {
-            let model_data = serde_json::to_string_pretty(model)
-                .map_err(|e| NeuralError::SaveError(e.to_string()))?;
+            let model_data = match ModelFormat::from_path(path) {
+                ModelFormat::Json => serde_json::to_vec_pretty(model)
+                    .map_err(|e| NeuralError::SaveError(e.to_string()))?,
+                ModelFormat::Bincode => bincode::serialize(model)
+                    .map_err(|e| NeuralError::SaveError(e.to_string()))?,
+            };
                 
-            std::fs::write(path, model_data)
+            let temp_path = format!("{}.tmp", path);
+            std::fs::write(&temp_path, model_data)
+                .and_then(|_| std::fs::rename(&temp_path, path))
                 .map_err(|e| NeuralError::SaveError(e.to_string()))?;
                 
             log::info!("Neural model saved to: {}", path);
@@ -151,8 +471,116 @@ impl SNNEngine {
         }
     }
     
+    /// Save the model to `path` every `every` while running, starting one interval from now
+    pub fn enable_checkpointing(&mut self, path: String, every: std::time::Duration) {
+        log::info!("Checkpointing neural model to {} every {:?}", path, every);
+        self.checkpoint = Some(Checkpoint {
+            path,
+            every,
+            last: std::time::Instant::now(),
+            failures: 0,
+        });
+    }
+    
+    /// Stop periodic checkpoints
+    pub fn disable_checkpointing(&mut self) {
+        self.checkpoint = None;
+    }
+    
+    /// Save a checkpoint if the interval has elapsed by `now`; returns whether one was written
+    ///
+    /// After a failed save the wait doubles with each further failure, up to
+    /// `MAX_CHECKPOINT_BACKOFF` intervals, so a full or read-only disk isn't retried every cycle.
+    pub fn checkpoint_if_due(&mut self, now: std::time::Instant) -> Result<bool, NeuralError> {
+        let Some(checkpoint) = &self.checkpoint else {
+            return Ok(false);
+        };
+        let backoff = 2u32.saturating_pow(checkpoint.failures).min(MAX_CHECKPOINT_BACKOFF);
+        if now.saturating_duration_since(checkpoint.last) < checkpoint.every * backoff {
+            return Ok(false);
+        }
+        
+        let result = self.save_model(&checkpoint.path);
+        if let Some(checkpoint) = &mut self.checkpoint {
+            checkpoint.last = now;
+            checkpoint.failures = if result.is_ok() { 0 } else { checkpoint.failures.saturating_add(1) };
+        }
+        result.map(|_| true)
+    }
+    
+    /// Adapt the output weights online with spike-timing-dependent plasticity
+    ///
+    /// Call once per `process` cycle with the output layer's input spikes (`pre`, one
+    /// per weight row) and the output spikes (`post`). Within a cycle the input fires
+    /// first, so coincident spikes potentiate; input spikes after earlier output spikes
+    /// depress. Changes are scaled by `learning_rate`, and the weights they change are
+    /// clamped to `weight_range`.
+    pub fn apply_stdp(&mut self, pre_spikes: &[f32], post_spikes: &[f32]) -> Result<(), NeuralError> {
+        let model = self.model.as_mut().ok_or(NeuralError::NoModelError)?;
+        let outputs = model.weights.first().map_or(0, |row| row.len());
+        if pre_spikes.len() != model.weights.len() || post_spikes.len() != outputs {
+            return Err(NeuralError::ProcessingError(format!(
+                "STDP expects {} pre and {} post spikes, got {} and {}",
+                model.weights.len(),
+                outputs,
+                pre_spikes.len(),
+                post_spikes.len()
+            )));
+        }
+        
+        // Traces from earlier cycles, decayed to now
+        let traces = self.stdp_traces.get_or_insert_with(|| StdpTraces {
+            pre: vec![0.0; pre_spikes.len()],
+            post: vec![0.0; post_spikes.len()],
+        });
+        let rate = self.config.learning_rate;
+        let (min, max) = self.config.weight_range;
+        for (j, row) in model.weights.iter_mut().enumerate() {
+            let pre_trace = traces.pre[j] * STDP_TRACE_DECAY + pre_spikes[j];
+            for (i, weight) in row.iter_mut().enumerate() {
+                let potentiation = pre_trace * post_spikes[i];
+                let depression = STDP_DEPRESSION_RATIO * traces.post[i] * STDP_TRACE_DECAY * pre_spikes[j];
+                // Weights without spike activity keep their value, even outside the range
+                let change = rate * (potentiation - depression);
+                if change != 0.0 {
+                    *weight = (*weight + change).clamp(min, max);
+                }
+            }
+        }
+        
+        // Fold this cycle's spikes into the traces
+        for (trace, spike) in traces.pre.iter_mut().zip(pre_spikes) {
+            *trace = *trace * STDP_TRACE_DECAY + spike;
+        }
+        for (trace, spike) in traces.post.iter_mut().zip(post_spikes) {
+            *trace = *trace * STDP_TRACE_DECAY + spike;
+        }
+        
+        // Keep integer inference on the adapted weights
+        if self.quantized_model.is_some() {
+            self.quantize_model()?;
+        }
+        Ok(())
+    }
+    
+    /// Output-layer weight from input `input` to output `output`, if the model has one
+    pub fn output_weight(&self, input: usize, output: usize) -> Option<f32> {
+        self.model.as_ref()?.weights.get(input)?.get(output).copied()
+    }
+    
     /// Process sensor data through the neural network
     pub fn process(&mut self, sensor_data: &super::ros_interface::SensorData) -> Result<Vec<f32>, NeuralError> {
+        Ok(self.process_with_confidence(sensor_data)?.values)
+    }
+    
+    /// Process sensor data, also reporting how strongly each output fired
+    ///
+    /// A near-threshold flicker and a strongly driven output give the same spike;
+    /// the confidence lets navigation discount the former.
+    pub fn process_with_confidence(
+        &mut self,
+        sensor_data: &super::ros_interface::SensorData,
+    ) -> Result<NeuralOutput, NeuralError> {
         if !self.is_initialized {
             return Err(NeuralError::NotInitialized);
         }
@@ -161,10 +589,7 @@ impl SNNEngine {
         let input = self.preprocess_sensor_data(sensor_data);
         
         // Add to input buffer
-        self.input_buffer.push_back(input.clone());
-        if self.input_buffer.len() > 100 {
-            self.input_buffer.pop_front();
-        }
+        self.input_buffer.push(input.clone());
         
         // Process through neural network
         let start_time = std::time::Instant::now();
@@ -172,37 +597,104 @@ impl SNNEngine {
         let processing_time = start_time.elapsed();
         
         // Add to output buffer
-        self.output_buffer.push_back(output.clone());
-        if self.output_buffer.len() > 100 {
-            self.output_buffer.pop_front();
-        }
+        self.output_buffer.push(output.values.clone());
+        self.record_latency(processing_time.as_secs_f32() * 1000.0);
         
         log::debug!("Neural processing time: {:?}", processing_time);
         
         Ok(output)
     }
     
+    /// Run already-preprocessed inputs through the network, one output per input
+    ///
+    /// Gives the same outputs as `process` on the matching sensor data, but leaves the
+    /// input/output buffers and latency statistics alone and reuses the layer buffers
+    /// across inputs. Every input must have `input_size` values.
+    pub fn process_batch(&mut self, inputs: &[Vec<f32>]) -> Result<Vec<Vec<f32>>, NeuralError> {
+        if !self.is_initialized {
+            return Err(NeuralError::NotInitialized);
+        }
+        
+        let mut scratch = Scratch::default();
+        inputs
+            .iter()
+            .map(|input| {
+                if input.len() != self.config.input_size {
+                    return Err(NeuralError::ProcessingError(format!(
+                        "Batch input has {} values, expected {}",
+                        input.len(),
+                        self.config.input_size
+                    )));
+                }
+                Ok(self.process_input_with(input, &mut scratch)?.values)
+            })
+            .collect()
+    }
+    
+    /// Fold one processing time into the latency moving average
+    ///
+    /// An exponential moving average weighted like a `LATENCY_WINDOW`-run mean; the
+    /// first run after a reset seeds it directly.
+    fn record_latency(&mut self, latency_ms: f32) {
+        let alpha = 2.0 / (LATENCY_WINDOW as f32 + 1.0);
+        self.mean_latency_ms = Some(match self.mean_latency_ms {
+            Some(mean) => mean + alpha * (latency_ms - mean),
+            None => latency_ms,
+        });
+    }
+    
+    /// Forget the measured processing latency, e.g. before a benchmark run
+    pub fn reset_latency_stats(&mut self) {
+        self.mean_latency_ms = None;
+    }
+    
     /// Get current neural engine status
     pub fn get_status(&self) -> NeuralStatus {
         NeuralStatus {
             model_loaded: self.model.is_some(),
             input_buffer_size: self.input_buffer.len(),
             output_buffer_size: self.output_buffer.len(),
-            processing_latency: 0.0, // Would be calculated from actual timing
+            processing_latency: self.mean_latency_ms.unwrap_or(0.0),
             model_accuracy: self.model.as_ref().map(|m| m.metadata.accuracy),
         }
     }
     
-    /// Preprocess sensor data for neural network input
-    fn preprocess_sensor_data(&self, sensor_data: &super::ros_interface::SensorData) -> Vec<f32> {
+    /// Convert sensor data to the network input vector
+    pub fn preprocess_sensor_data(&self, sensor_data: &super::ros_interface::SensorData) -> Vec<f32> {
         // Simple preprocessing - would be more complex in production
         let mut input = Vec::with_capacity(self.config.input_size);
         
         // Add laser scan data
-        if sensor_data.laser_scan.ranges.len() > 0 {
-            let scan_data = &sensor_data.laser_scan.ranges;
-            for i in 0..self.config.input_size.min(scan_data.len()) {
-                input.push(scan_data[i] as f32);
+        let scan_data = &sensor_data.laser_scan.ranges;
+        if !scan_data.is_empty() {
+            match self.config.beam_sampling {
+                BeamSampling::Truncate => {
+                    input.extend(scan_data.iter().take(self.config.input_size));
+                }
+                BeamSampling::MinPool => {
+                    // Keep the closest valid return per bin so no obstacle gets sampled away;
+                    // NaN and out-of-range readings aren't returns, and a bin without any reads as clear
+                    let scan = &sensor_data.laser_scan;
+                    let has_range = scan.range_max > scan.range_min;
+                    let valid = |range: &f32| !range.is_nan() && (!has_range || (scan.range_min..=scan.range_max).contains(range));
+                    let clear = if has_range { scan.range_max } else { 0.0 };
+                    let beams = scan_data.len();
+                    let bins = self.config.input_size;
+                    for bin in 0..bins {
+                        let start = bin * beams / bins;
+                        let end = ((bin + 1) * beams / bins).max(start + 1);
+                        let closest = scan_data[start..end].iter().copied().filter(valid).reduce(f32::min);
+                        input.push(closest.unwrap_or(clear));
+                    }
+                }
+            }
+        }
+        
+        // Spike encodings work on closeness, so near obstacles drive the most spikes
+        if self.config.encoding != SpikeEncoding::Raw {
+            let scan = &sensor_data.laser_scan;
+            for value in input.iter_mut() {
+                *value = encoding::closeness(*value, scan.range_min, scan.range_max);
             }
         }
         
@@ -214,43 +706,170 @@ impl SNNEngine {
         input
     }
     
+    /// Spike trains for a preprocessed input, one frame of `input_size` values per time step
+    pub fn encode_spike_trains(&self, input: &[f32]) -> Vec<Vec<f32>> {
+        let steps = self.config.time_steps.max(1);
+        let trains: Vec<Vec<f32>> = input
+            .iter()
+            .map(|closeness| self.config.encoding.encode(*closeness, steps))
+            .collect();
+        (0..steps)
+            .map(|step| trains.iter().map(|train| train[step]).collect())
+            .collect()
+    }
+    
     /// Process input through the neural network
-    fn process_input(&self, input: &[f32]) -> Result<Vec<f32>, NeuralError> {
+    fn process_input(&self, input: &[f32]) -> Result<NeuralOutput, NeuralError> {
+        self.process_input_with(input, &mut Scratch::default())
+    }
+    
+    /// `process_input` using caller-owned layer buffers
+    fn process_input_with(&self, input: &[f32], scratch: &mut Scratch) -> Result<NeuralOutput, NeuralError> {
+        if self.config.encoding != SpikeEncoding::Raw {
+            return self.process_spike_trains(&self.encode_spike_trains(input), scratch);
+        }
+        if let Some(quantized) = &self.quantized_model {
+            return Ok(self.process_input_quantized(quantized, input));
+        }
+        
         if let Some(model) = &self.model {
             // Simple feedforward simulation - would use actual SNN in production
-            let mut output = vec![0.0; self.config.output_size];
+            // The output layer keeps spike semantics (would be spike-based in real SNN)
+            let potentials = self.forward(model, input, scratch);
             
-            for i in 0..self.config.output_size {
-                for j in 0..input.len().min(model.weights.len()) {
-                    output[i] += input[j] * model.weights[j][i];
+            Ok(NeuralOutput {
+                values: potentials
+                    .iter()
+                    .map(|x| Activation::Threshold.apply(*x, self.config.spike_threshold))
+                    .collect(),
+                confidence: potentials
+                    .iter()
+                    .map(|x| spike_confidence(*x, self.config.spike_threshold))
+                    .collect(),
+            })
+        } else {
+            Err(NeuralError::NoModelError)
+        }
+    }
+    
+    /// Run spike-train frames through the float model with leaky integrate-and-fire outputs
+    ///
+    /// Each output integrates its input current with `LIF_LEAK` per step and resets
+    /// after firing; it reports 1 if it fired at any step. Confidence comes from the
+    /// highest potential reached.
+    fn process_spike_trains(&self, frames: &[Vec<f32>], scratch: &mut Scratch) -> Result<NeuralOutput, NeuralError> {
+        let model = self.model.as_ref().ok_or(NeuralError::NoModelError)?;
+        let outputs = self.config.output_size;
+        let threshold = self.config.spike_threshold;
+        
+        let mut potentials = vec![0.0; outputs];
+        let mut peaks = vec![f32::MIN; outputs];
+        let mut fired = vec![false; outputs];
+        for frame in frames {
+            let current = self.forward(model, frame, scratch);
+            for i in 0..outputs {
+                potentials[i] = potentials[i] * LIF_LEAK + current[i];
+                peaks[i] = peaks[i].max(potentials[i]);
+                if potentials[i] > threshold {
+                    fired[i] = true;
+                    potentials[i] = 0.0;
                 }
-                // Simple activation (would be spike-based in real SNN)
-                output[i] = if output[i] > self.config.spike_threshold {
-                    1.0
-                } else {
-                    0.0
-                };
             }
+        }
+        
+        Ok(NeuralOutput {
+            values: fired.iter().map(|f| if *f { 1.0 } else { 0.0 }).collect(),
+            confidence: peaks.iter().map(|peak| spike_confidence(*peak, threshold)).collect(),
+        })
+    }
+    
+    /// Output layer potentials of the float model, with activated hidden layers
+    ///
+    /// The result lives in `scratch` and is overwritten by the next call.
+    fn forward<'a>(&self, model: &NeuralModel, input: &[f32], scratch: &'a mut Scratch) -> &'a [f32] {
+        scratch.layer.clear();
+        scratch.layer.extend_from_slice(input);
+        for weights in &model.hidden_weights {
+            let neurons = weights.first().map_or(0, |row| row.len());
+            Self::feed_forward(&scratch.layer, weights, neurons, &mut scratch.next);
+            for x in scratch.next.iter_mut() {
+                *x = model.metadata.activation.apply(*x, self.config.spike_threshold);
+            }
+            std::mem::swap(&mut scratch.layer, &mut scratch.next);
+        }
+        Self::feed_forward(&scratch.layer, &model.weights, self.config.output_size, &mut scratch.next);
+        &scratch.next
+    }
+    
+    /// Weighted sums of `input` for the first `outputs` neurons of a layer, into `output`
+    fn feed_forward(input: &[f32], weights: &[Vec<f32>], outputs: usize, output: &mut Vec<f32>) {
+        output.clear();
+        output.resize(outputs, 0.0);
+        for i in 0..outputs {
+            for j in 0..input.len().min(weights.len()) {
+                output[i] += input[j] * weights[j][i];
+            }
+        }
+    }
+    
+    /// Integer-only version of `process_input` on i8 weights and fixed-point inputs
+    ///
+    /// Hidden activations are applied on the dequantized sums and re-quantized for
+    /// the next layer; all weighted sums run in integer arithmetic.
+    fn process_input_quantized(&self, model: &QuantizedModel, input: &[f32]) -> NeuralOutput {
+        let one = (1 << INPUT_FRAC_BITS) as f32;
+        // Inputs to fixed point (saturating, so inf ranges stay bounded)
+        let to_fixed = |values: &[f32]| -> Vec<i32> {
+            values.iter().map(|x| (x * one).round() as i32).collect()
+        };
+        
+        let (output_layer, hidden_layers) = model.layers.split_last().expect("quantized model has an output layer");
+        let mut values = to_fixed(input);
+        for layer in hidden_layers {
+            let neurons = layer.weights.first().map_or(0, |row| row.len());
+            let activated: Vec<f32> = layer
+                .accumulate(&values, neurons)
+                .into_iter()
+                .map(|acc| model.activation.apply(acc as f32 * layer.weight_scale / one, self.config.spike_threshold))
+                .collect();
+            values = to_fixed(&activated);
+        }
             
-            Ok(output)
-        } else {
-            Err(NeuralError::NoModelError)
+        // Threshold in accumulator units, computed once per call
+        let threshold = (self.config.spike_threshold * one / output_layer.weight_scale) as i64;
+        
+        let sums = output_layer.accumulate(&values, self.config.output_size);
+        NeuralOutput {
+            values: sums.iter().map(|acc| if *acc > threshold { 1.0 } else { 0.0 }).collect(),
+            confidence: sums
+                .iter()
+                .map(|acc| spike_confidence(*acc as f32 * output_layer.weight_scale / one, self.config.spike_threshold))
+                .collect(),
         }
     }
     
     /// Create a default model with random weights
     fn create_default_model(&self) -> NeuralModel {
-        let mut weights = Vec::with_capacity(self.config.input_size);
+        let mut rng = match self.config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let mut random_layer = |inputs: usize, outputs: usize| -> Vec<Vec<f32>> {
+            (0..inputs)
+                .map(|_| (0..outputs).map(|_| rng.r#gen::<f32>() * 2.0 - 1.0).collect())
+                .collect()
+        };
         
-        for _ in 0..self.config.input_size {
-            let mut neuron_weights = Vec::with_capacity(self.config.output_size);
-            for _ in 0..self.config.output_size {
-                neuron_weights.push(rand::random::<f32>() * 2.0 - 1.0);
-            }
-            weights.push(neuron_weights);
+        let mut hidden_weights = Vec::with_capacity(self.config.hidden_layers);
+        let mut layer_inputs = self.config.input_size;
+        for _ in 0..self.config.hidden_layers {
+            hidden_weights.push(random_layer(layer_inputs, self.config.hidden_neurons));
+            layer_inputs = self.config.hidden_neurons;
         }
+        let weights = random_layer(layer_inputs, self.config.output_size);
         
         NeuralModel {
+            hidden_weights,
             weights,
             states: vec![0.0; self.config.input_size],
             thresholds: vec![self.config.spike_threshold; self.config.input_size],
@@ -259,16 +878,31 @@ impl SNNEngine {
                 version: "1.0".to_string(),
                 trained_on: "random_weights".to_string(),
                 accuracy: 0.0,
+                activation: self.config.activation,
             },
         }
     }
 }
 
+/// Confidence that an output fired, from its potential's margin above threshold
+///
+/// Zero at or below threshold, rising towards 1.0 as the margin grows; a margin of one
+/// threshold gives about 0.63.
+fn spike_confidence(potential: f32, threshold: f32) -> f32 {
+    let margin = potential - threshold;
+    if margin.is_nan() || margin <= 0.0 {
+        return 0.0;
+    }
+    1.0 - (-margin / threshold.abs().max(f32::EPSILON)).exp()
+}
+
 /// Neural network error types
 #[derive(Debug)]
 pub enum NeuralError {
     /// Engine not initialized
     NotInitialized,
+    /// Engine initialized twice
+    AlreadyInitialized,
     /// No model loaded
     NoModelError,
     /// Model load error
@@ -279,17 +913,28 @@ pub enum NeuralError {
     ParseError(String),
     /// Processing error
     ProcessingError(String),
+    /// Loaded model's layer size doesn't fit the configuration
+    DimensionMismatch {
+        /// Size the configuration or the neighbouring layer calls for
+        expected: usize,
+        /// Size found in the loaded model
+        found: usize,
+    },
 }
 
 impl std::fmt::Display for NeuralError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             NeuralError::NotInitialized => write!(f, "Neural engine not initialized"),
+            NeuralError::AlreadyInitialized => write!(f, "Neural engine already initialized"),
             NeuralError::NoModelError => write!(f, "No neural model loaded"),
             NeuralError::LoadError(msg) => write!(f, "Model load error: {}", msg),
             NeuralError::SaveError(msg) => write!(f, "Model save error: {}", msg),
             NeuralError::ParseError(msg) => write!(f, "Model parse error: {}", msg),
             NeuralError::ProcessingError(msg) => write!(f, "Processing error: {}", msg),
+            NeuralError::DimensionMismatch { expected, found } => {
+                write!(f, "Model dimension mismatch: expected {}, found {}", expected, found)
+            }
         }
     }
 }
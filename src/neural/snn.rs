@@ -1,8 +1,8 @@
 // src/neural/snn.rs
 // Implements a simplified spiking neural network for navigation decisions.
 
-// Imports shared types from lib.rs.
-use crate::{SensorData, NavCommand};
+// Imports shared types from the ROS interface.
+use crate::ros_interface::{MotionCommand, SensorData};
 use log::info;
 
 /// SNN struct to simulate neuromorphic processing.
@@ -23,10 +23,12 @@ impl Snn {
 
     /// Processes sensor data to produce a navigation command.
     /// - `data`: LIDAR ranges from Subscriber.
-    /// Returns a `NavCommand` based on simple obstacle avoidance logic.
-    pub fn process(&self, data: &SensorData) -> NavCommand {
+    ///
+    /// Returns a `MotionCommand` based on simple obstacle avoidance logic.
+    pub fn process(&self, data: &SensorData) -> MotionCommand {
         // Simulate SNN: Check for close obstacles
         let min_distance = data
+            .laser_scan
             .ranges
             .iter()
             .filter(|&&d| d > 0.0)
@@ -36,15 +38,9 @@ impl Snn {
 
         // Basic decision: Turn if obstacle near, else move forward
         if min_distance < 0.5 {
-            NavCommand {
-                linear: 0.0,
-                angular: 0.5,
-            } // Turn right
+            MotionCommand::from((0.0, 0.5)) // Turn right
         } else {
-            NavCommand {
-                linear: 0.2,
-                angular: 0.0,
-            } // Move forward
+            MotionCommand::from((0.2, 0.0)) // Move forward
         }
     }
 }
@@ -56,13 +52,13 @@ impl Snn {
 // - Logging: Uses env_logger for debugging, aiding development.
 //
 // Weaknesses:
-// - Placeholder Logic: No real spiking neurons or learning (e.g., STDP), limiting memory simulation.
+// - Placeholder Logic: No real spiking neurons or learning here; STDP only exists in SNNEngine::apply_stdp.
 // - Limited Realism: Simple distance-based decision doesn’t reflect true neuromorphic behavior.
 // - Scalability: Fixed weights prevent adaptive learning in MVP.
 //
 // Opportunities:
 // - Real SNN: Integrate NEST or Brian2 post-MVP for true spiking neural networks.
-// - Learning: Add synaptic plasticity for memory retention, enhancing demo impact.
+// - Learning: Extend SNNEngine's output-layer STDP to hidden layers for memory retention.
 // - Traction: Open-source SNN attracts developers, supporting 100+ GitHub stars goal.
 //
 // Threats:
@@ -0,0 +1,55 @@
+//! Tokio-based spinning for `RosInterface` (`async` feature)
+//!
+//! Instead of a thread started by `initialize`, the node is spun by a future the
+//! application runs on its tokio runtime. The spin loop waits inside rcl on the
+//! runtime's blocking pool, and the subscription callbacks bump a watch channel on
+//! every message, so tasks await fresh sensor data instead of polling for it.
+
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use super::{RosError, RosInterface};
+
+/// Longest a single spin waits for messages, bounding how long shutdown takes to be seen
+const SPIN_TIMEOUT: Duration = Duration::from_millis(100);
+
+impl RosInterface {
+    /// Future that spins the node until `shutdown`
+    ///
+    /// Call before `initialize`, which then starts no spin thread, and run the
+    /// future on the tokio runtime (e.g. `tokio::spawn(ros.spin()?)`).
+    pub fn spin(&mut self) -> Result<impl Future<Output = Result<(), RosError>> + Send + 'static, RosError> {
+        if self.spin_thread.is_some() {
+            return Err(RosError::AlreadyInitialized);
+        }
+        self.spun_async = true;
+
+        let node = self.node.clone();
+        let spinning = self.spinning.clone();
+        spinning.store(true, Ordering::SeqCst);
+
+        Ok(async move {
+            // spin_once blocks until messages are ready, so it runs off the async workers
+            tokio::task::spawn_blocking(move || {
+                while spinning.load(Ordering::SeqCst) {
+                    node.lock()
+                        .map_err(|_| RosError::InitError("node lock poisoned".to_string()))?
+                        .spin_once(SPIN_TIMEOUT);
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| RosError::InitError(format!("spin task failed: {}", e)))?
+        })
+    }
+
+    /// Receiver that changes whenever a laser scan, IMU or odometry message arrives
+    ///
+    /// Await `changed()` on it to wait for fresh sensor data.
+    pub fn sensor_updates(&self) -> watch::Receiver<u64> {
+        self.sensor_updates.subscribe()
+    }
+}
@@ -0,0 +1,147 @@
+//! IMU message parsing
+//!
+//! Turns `sensor_msgs/Imu` into a typed sample and maps it from the IMU's own
+//! axes into the robot base frame, so differently-mounted IMUs read the same.
+
+use nalgebra::{Matrix3, Rotation3, UnitQuaternion, Vector3};
+use r2r::sensor_msgs::msg::Imu;
+use serde::{Deserialize, Serialize};
+
+use super::RosError;
+
+/// One IMU reading with named fields
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImuSample {
+    /// Orientation in the world frame
+    pub orientation: UnitQuaternion<f64>,
+    /// Angular velocity (rad/s)
+    pub angular_velocity: Vector3<f64>,
+    /// Linear acceleration including gravity (m/s²)
+    pub linear_acceleration: Vector3<f64>,
+}
+
+/// Parse an IMU message as-is, in the IMU's own axes
+pub fn parse_imu(msg: &Imu) -> ImuSample {
+    let q = &msg.orientation;
+    ImuSample {
+        orientation: UnitQuaternion::from_quaternion(nalgebra::Quaternion::new(q.w, q.x, q.y, q.z)),
+        angular_velocity: Vector3::new(msg.angular_velocity.x, msg.angular_velocity.y, msg.angular_velocity.z),
+        linear_acceleration: Vector3::new(
+            msg.linear_acceleration.x,
+            msg.linear_acceleration.y,
+            msg.linear_acceleration.z,
+        ),
+    }
+}
+
+/// An IMU axis, possibly reversed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SignedAxis {
+    #[serde(rename = "+x")]
+    PosX,
+    #[serde(rename = "-x")]
+    NegX,
+    #[serde(rename = "+y")]
+    PosY,
+    #[serde(rename = "-y")]
+    NegY,
+    #[serde(rename = "+z")]
+    PosZ,
+    #[serde(rename = "-z")]
+    NegZ,
+}
+
+impl SignedAxis {
+    /// Unit vector of this axis in IMU coordinates
+    fn unit(self) -> Vector3<f64> {
+        match self {
+            SignedAxis::PosX => Vector3::x(),
+            SignedAxis::NegX => -Vector3::x(),
+            SignedAxis::PosY => Vector3::y(),
+            SignedAxis::NegY => -Vector3::y(),
+            SignedAxis::PosZ => Vector3::z(),
+            SignedAxis::NegZ => -Vector3::z(),
+        }
+    }
+}
+
+/// Which IMU axis each base-frame axis (x, y, z) reads from
+///
+/// Configured as e.g. `["+x", "-y", "-z"]` for an IMU mounted upside down.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "[SignedAxis; 3]", into = "[SignedAxis; 3]")]
+pub struct ImuAxisRemap {
+    axes: [SignedAxis; 3],
+    /// IMU-to-base rotation built from `axes`
+    rotation: Rotation3<f64>,
+}
+
+impl ImuAxisRemap {
+    /// Remap with the base x, y and z axes taken from the given IMU axes
+    ///
+    /// Fails unless the axes form a right-handed rotation (each IMU axis used once,
+    /// with an even number of sign flips and swaps).
+    pub fn new(x: SignedAxis, y: SignedAxis, z: SignedAxis) -> Result<Self, RosError> {
+        let matrix = Matrix3::from_rows(&[x.unit().transpose(), y.unit().transpose(), z.unit().transpose()]);
+        if (matrix.determinant() - 1.0).abs() > 1e-9 {
+            return Err(RosError::ConversionError(format!(
+                "IMU axis remap {:?} is not a proper rotation",
+                [x, y, z]
+            )));
+        }
+
+        Ok(ImuAxisRemap {
+            axes: [x, y, z],
+            rotation: Rotation3::from_matrix_unchecked(matrix),
+        })
+    }
+
+    /// IMU mounted flat with its axes matching the base frame
+    pub fn identity() -> Self {
+        ImuAxisRemap {
+            axes: [SignedAxis::PosX, SignedAxis::PosY, SignedAxis::PosZ],
+            rotation: Rotation3::identity(),
+        }
+    }
+
+    /// IMU mounted upside down, i.e. rolled 180° about the forward axis
+    pub fn upside_down() -> Self {
+        Self::new(SignedAxis::PosX, SignedAxis::NegY, SignedAxis::NegZ).expect("roll by 180° is a rotation")
+    }
+
+    /// Express a sample taken in IMU axes in the base frame
+    pub fn apply(&self, sample: ImuSample) -> ImuSample {
+        let imu_to_base = UnitQuaternion::from_rotation_matrix(&self.rotation);
+        ImuSample {
+            // World-from-base is world-from-IMU followed by IMU-from-base
+            orientation: sample.orientation * imu_to_base.inverse(),
+            angular_velocity: self.rotation * sample.angular_velocity,
+            linear_acceleration: self.rotation * sample.linear_acceleration,
+        }
+    }
+
+    /// Parse an IMU message straight into the base frame
+    pub fn parse(&self, msg: &Imu) -> ImuSample {
+        self.apply(parse_imu(msg))
+    }
+}
+
+impl Default for ImuAxisRemap {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl TryFrom<[SignedAxis; 3]> for ImuAxisRemap {
+    type Error = RosError;
+
+    fn try_from(axes: [SignedAxis; 3]) -> Result<Self, RosError> {
+        Self::new(axes[0], axes[1], axes[2])
+    }
+}
+
+impl From<ImuAxisRemap> for [SignedAxis; 3] {
+    fn from(remap: ImuAxisRemap) -> Self {
+        remap.axes
+    }
+}
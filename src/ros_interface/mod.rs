@@ -5,23 +5,45 @@
 //! - Subscribing to sensor data
 //! - Managing ROS nodes and topics
 
+#[cfg(feature = "async")]
+mod async_spin;
+mod imu;
 mod publisher;
+mod qos;
 mod subscriber;
+mod tf;
 
+use nalgebra::Matrix3;
 use r2r::{Context, Node, QosProfile};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+pub use imu::{parse_imu, ImuAxisRemap, ImuSample, SignedAxis};
 pub use publisher::*;
+pub use qos::{qos_profile, QosDurability, QosReliability};
 pub use subscriber::*;
+pub use tf::{TfBuffer, TfListener, DEFAULT_TF_MAX_AGE};
 
 /// ROS 2 interface manager
 pub struct RosInterface {
-    node: Arc<Node>,
+    /// Shared with the spin thread, which needs it mutably for `spin_once`
+    node: Arc<Mutex<Node>>,
     context: Context,
     publishers: RosPublishers,
-    subscribers: RosSubscribers,
+    subscribers: Arc<RosSubscribers>,
+    tf: TfListener,
+    /// Frame `get_current_pose` reports in, e.g. "map"; None for odometry's own frame
+    pose_frame: Option<String>,
     is_initialized: bool,
+    /// Keeps the spin thread (or spin future) running; cleared on shutdown
+    spinning: Arc<AtomicBool>,
+    spin_thread: Option<std::thread::JoinHandle<()>>,
+    /// Spinning is driven by the future from `spin` (async feature), so `initialize` starts no thread
+    spun_async: bool,
+    /// Counts sensor messages received, bumped by the subscription callbacks for tasks awaiting data
+    #[cfg(feature = "async")]
+    sensor_updates: Arc<tokio::sync::watch::Sender<u64>>,
 }
 
 /// Collection of all ROS publishers
@@ -32,6 +54,10 @@ pub struct RosPublishers {
     pub status: Publisher<r2r::std_msgs::msg::String>,
     /// Neural output publisher
     pub neural_output: Publisher<r2r::std_msgs::msg::Float32MultiArray>,
+    /// Estimated pose and velocity publisher (`/eos/odom`)
+    pub odom: Publisher<r2r::nav_msgs::msg::Odometry>,
+    /// Occupancy grid publisher (`/eos/map`)
+    pub map: Publisher<r2r::nav_msgs::msg::OccupancyGrid>,
 }
 
 /// Collection of all ROS subscribers
@@ -44,8 +70,95 @@ pub struct RosSubscribers {
     pub odom: Subscriber<r2r::nav_msgs::msg::Odometry>,
 }
 
+impl RosPublishers {
+    /// Advertise the command, status, neural output, odometry, and map topics
+    fn new(node: &mut Node, qos: &QosProfile) -> Result<Self, RosError> {
+        Ok(RosPublishers {
+            cmd_vel: Publisher::new(node, "/cmd_vel", qos.clone())?,
+            status: Publisher::new(node, "/eos/status", qos.clone())?,
+            neural_output: Publisher::new(node, "/eos/neural_output", qos.clone())?,
+            odom: Publisher::new(node, "/eos/odom", qos.clone())?,
+            map: Publisher::new(node, "/eos/map", qos.clone())?,
+        })
+    }
+}
+
+impl RosSubscribers {
+    /// Subscribe to the laser scan, IMU, and odometry topics, calling `notify` on every message
+    fn new<F>(node: &mut Node, qos: &QosProfile, notify: F) -> Result<Self, RosError>
+    where
+        F: Fn() + Clone + Send + 'static,
+    {
+        Ok(RosSubscribers {
+            laser_scan: Subscriber::with_notify(node, "/scan", qos.clone(), notify.clone())?,
+            imu: Subscriber::with_notify(node, "/imu", qos.clone(), notify.clone())?,
+            odom: Subscriber::with_notify(node, "/odom", qos.clone(), notify)?,
+        })
+    }
+}
+
+/// Where the run loop reads sensor data and pose from (ROS, a simulator, a replay, a vendor SDK)
+pub trait SensorSource {
+    /// Latest sensor data
+    fn get_sensor_data(&self) -> Result<SensorData, RosError>;
+    /// Current robot pose, or why it isn't known (no odometry yet, missing transform)
+    fn get_current_pose(&self) -> Result<Pose2D, RosError>;
+}
+
+/// Where the run loop sends velocity commands
+pub trait CommandSink {
+    /// Send a velocity command to the base
+    fn publish_command(&self, command: &MotionCommand) -> Result<(), RosError>;
+}
+
+impl<T: SensorSource + ?Sized> SensorSource for &T {
+    fn get_sensor_data(&self) -> Result<SensorData, RosError> {
+        (**self).get_sensor_data()
+    }
+    
+    fn get_current_pose(&self) -> Result<Pose2D, RosError> {
+        (**self).get_current_pose()
+    }
+}
+
+impl<T: CommandSink + ?Sized> CommandSink for &T {
+    fn publish_command(&self, command: &MotionCommand) -> Result<(), RosError> {
+        (**self).publish_command(command)
+    }
+}
+
+/// Sensor input and command output used by the run loop, plus lifecycle and reporting
+///
+/// Implemented by `RosInterface`; `SplitInterface` builds one from any sensor source
+/// and command sink, and tests substitute scripted implementations.
+pub trait RobotInterface: SensorSource + CommandSink {
+    /// Start communication
+    fn initialize(&mut self) -> Result<(), RosError>;
+    /// Publish the estimated pose and velocity
+    fn publish_odometry(
+        &self,
+        pose: &Pose2D,
+        covariance: &Matrix3<f64>,
+        velocity: &MotionCommand,
+        stamp: Duration,
+    ) -> Result<(), RosError>;
+    /// Publish an occupancy grid map for visualization (e.g. RViz)
+    fn publish_map(&self, map: &r2r::nav_msgs::msg::OccupancyGrid) -> Result<(), RosError>;
+    /// Publish a JSON system status report for remote monitoring
+    fn publish_status(&self, status_json: &str) -> Result<(), RosError>;
+    /// Interface status
+    fn get_status(&self) -> RosStatus;
+    /// Stop communication
+    fn shutdown(&mut self) -> Result<(), RosError>;
+    /// Receiver that changes whenever sensor data arrives; None if the interface can't signal it
+    #[cfg(feature = "async")]
+    fn sensor_updates(&self) -> Option<tokio::sync::watch::Receiver<u64>> {
+        None
+    }
+}
+
 /// ROS interface status
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct RosStatus {
     /// Node connectivity status
     pub connected: bool,
@@ -61,40 +174,72 @@ impl RosInterface {
     /// Create a new ROS interface
     pub fn new(config: &super::RosConfig) -> Result<Self, RosError> {
         let context = Context::create()?;
-        let node = Node::create(&context, &config.node_name, "")?;
+        let mut node = Node::create(context.clone(), &config.node_name, "")?;
+        
+        // Configured QoS for published topics and TF; sensor drivers publish best-effort,
+        // which a reliable subscription would never match, so sensors stay best-effort
+        let qos = qos_profile(config.qos_depth, config.qos_reliability, config.qos_durability);
+        let sensor_qos = qos_profile(config.qos_depth, QosReliability::BestEffort, QosDurability::Volatile);
         
-        // Create QoS profile
-        let qos = QosProfile::default()
-            .depth(config.qos_depth)
-            .reliability(r2r::QosReliabilityPolicy::BestEffort);
+        // Every sensor message wakes tasks awaiting `sensor_updates` (async feature)
+        #[cfg(feature = "async")]
+        let sensor_updates = Arc::new(tokio::sync::watch::channel(0u64).0);
+        #[cfg(feature = "async")]
+        let notify = {
+            let sensor_updates = sensor_updates.clone();
+            move || sensor_updates.send_modify(|count| *count += 1)
+        };
+        #[cfg(not(feature = "async"))]
+        let notify = || {};
         
         // Initialize publishers and subscribers
-        let publishers = RosPublishers::new(&node, &qos)?;
-        let subscribers = RosSubscribers::new(&node, &qos)?;
+        let publishers = RosPublishers::new(&mut node, &qos)?;
+        let subscribers = RosSubscribers::new(&mut node, &sensor_qos, notify)?;
+        let tf = TfListener::new(&mut node, &qos, DEFAULT_TF_MAX_AGE)?;
         
         Ok(RosInterface {
-            node: Arc::new(node),
+            node: Arc::new(Mutex::new(node)),
             context,
             publishers,
-            subscribers,
+            subscribers: Arc::new(subscribers),
+            tf,
+            pose_frame: config.pose_frame.clone(),
             is_initialized: false,
+            spinning: Arc::new(AtomicBool::new(false)),
+            spin_thread: None,
+            spun_async: false,
+            #[cfg(feature = "async")]
+            sensor_updates,
         })
     }
     
     /// Initialize the ROS interface
     pub fn initialize(&mut self) -> Result<(), RosError> {
+        if self.is_initialized {
+            return Err(RosError::AlreadyInitialized);
+        }
+        
         log::info!("Initializing ROS interface...");
         
-        // Spawn a thread to handle ROS spinning
-        let node_clone = self.node.clone();
-        std::thread::spawn(move || {
-            let mut executor = r2r::Executor::new();
-            executor.add_node(node_clone).unwrap();
-            
-            loop {
-                executor.spin_once(Duration::from_millis(100));
-            }
-        });
+        // Spawn a thread to handle ROS spinning, unless one is still running or a
+        // spin future drives the node instead
+        if self.spin_thread.is_none() && !self.spun_async {
+            let node_clone = self.node.clone();
+            let spinning = self.spinning.clone();
+            spinning.store(true, Ordering::SeqCst);
+            self.spin_thread = Some(std::thread::spawn(move || {
+                // Each spin fills the subscription streams the subscriber threads drain
+                while spinning.load(Ordering::SeqCst) {
+                    match node_clone.lock() {
+                        Ok(mut node) => node.spin_once(Duration::from_millis(100)),
+                        Err(_) => {
+                            log::error!("ROS spin thread stopping: node lock poisoned");
+                            return;
+                        }
+                    }
+                }
+            }));
+        }
         
         self.is_initialized = true;
         log::info!("ROS interface initialized successfully");
@@ -131,12 +276,6 @@ impl RosInterface {
         // Publish the command
         self.publishers.cmd_vel.publish(&twist_msg)?;
         
-        // Also publish status update
-        let status_msg = r2r::std_msgs::msg::String {
-            data: format!("Command published: {:?}", command),
-        };
-        self.publishers.status.publish(&status_msg)?;
-        
         Ok(())
     }
     
@@ -163,21 +302,80 @@ impl RosInterface {
         Ok(())
     }
     
+    /// Publish the estimated pose and current velocity as odometry
+    ///
+    /// The pose is taken to be in the frame `get_current_pose` reports in: the configured
+    /// pose frame, or odometry's own frame without one.
+    pub fn publish_odometry(
+        &self,
+        pose: &Pose2D,
+        covariance: &Matrix3<f64>,
+        velocity: &MotionCommand,
+        stamp: Duration,
+    ) -> Result<(), RosError> {
+        if !self.is_initialized {
+            return Err(RosError::NotInitialized);
+        }
+        
+        let odom_msg = odometry_message(&self.pose_frame_id(), pose, covariance, velocity, stamp);
+        self.publishers.odom.publish(&odom_msg)?;
+        
+        Ok(())
+    }
+    
+    /// Publish the occupancy grid on `/eos/map`
+    ///
+    /// Build the message with `OccupancyGrid::to_ros_occupancy_grid`.
+    pub fn publish_map(&self, map: &r2r::nav_msgs::msg::OccupancyGrid) -> Result<(), RosError> {
+        if !self.is_initialized {
+            return Err(RosError::NotInitialized);
+        }
+        
+        self.publishers.map.publish(map)?;
+        
+        Ok(())
+    }
+    
+    /// Publish a JSON system status report on `/eos/status`
+    pub fn publish_status(&self, status_json: &str) -> Result<(), RosError> {
+        if !self.is_initialized {
+            return Err(RosError::NotInitialized);
+        }
+        
+        let status_msg = r2r::std_msgs::msg::String {
+            data: status_json.to_string(),
+        };
+        self.publishers.status.publish(&status_msg)?;
+        
+        Ok(())
+    }
+    
     /// Get current ROS status
     pub fn get_status(&self) -> RosStatus {
         RosStatus {
             connected: self.is_initialized,
-            publishers_count: 3, // Fixed count for now
-            subscribers_count: 3, // Fixed count for now
+            publishers_count: 5, // Fixed count for now
+            subscribers_count: 5, // Fixed count for now (scan, IMU, odometry, TF, static TF)
             last_message_time: self.subscribers.laser_scan.get_last_message_time(),
         }
     }
     
     /// Shutdown the ROS interface
     pub fn shutdown(&mut self) -> Result<(), RosError> {
+        if !self.is_initialized {
+            return Err(RosError::NotInitialized);
+        }
+        
         log::info!("Shutting down ROS interface...");
         
-        // Note: r2r doesn't have explicit shutdown, so we just mark as not initialized
+        // r2r has no explicit shutdown; stop spinning so a later initialize starts cleanly
+        self.spinning.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.spin_thread.take() {
+            if handle.join().is_err() {
+                log::warn!("ROS spin thread panicked");
+            }
+        }
+        self.spun_async = false;
         self.is_initialized = false;
         
         log::info!("ROS interface shutdown complete");
@@ -185,17 +383,263 @@ impl RosInterface {
     }
     
     /// Get the current robot pose from odometry
-    pub fn get_current_pose(&self) -> Option<Pose2D> {
-        self.subscribers.odom.get_latest()
-            .ok()
-            .map(|odom| Pose2D {
-                x: odom.pose.pose.position.x as f32,
-                y: odom.pose.pose.position.y as f32,
-                theta: 2.0 * (odom.pose.pose.orientation.z as f32).atan2(odom.pose.pose.orientation.w as f32),
-            })
+    ///
+    /// With a pose frame configured, the odometry pose is composed with the transform
+    /// from that frame to odometry's; fails with `RosError::TransformError` while that
+    /// isn't available, and with `RosError::SubscribeError` before the first odometry.
+    pub fn get_current_pose(&self) -> Result<Pose2D, RosError> {
+        let odom = self.subscribers.odom.get_latest()?;
+        let pose = Pose2D {
+            x: odom.pose.pose.position.x as f32,
+            y: odom.pose.pose.position.y as f32,
+            theta: 2.0 * (odom.pose.pose.orientation.z as f32).atan2(odom.pose.pose.orientation.w as f32),
+        };
+        let Some(pose_frame) = &self.pose_frame else {
+            return Ok(pose);
+        };
+        let odom_frame = self.lookup_transform(pose_frame, odom_frame_id(&odom))?;
+        Ok(odom_frame.compose(&pose))
+    }
+    
+    /// Frame `get_current_pose` reports in
+    fn pose_frame_id(&self) -> String {
+        match &self.pose_frame {
+            Some(pose_frame) => pose_frame.clone(),
+            None => match self.subscribers.odom.get_latest() {
+                Ok(odom) => odom_frame_id(&odom).to_string(),
+                Err(_) => "odom".to_string(),
+            },
+        }
+    }
+    
+    /// Pose of frame `source` expressed in frame `target`, from `/tf` and `/tf_static`
+    ///
+    /// Fails with `RosError::TransformError` when a frame is unknown, the frames
+    /// aren't connected, or a dynamic transform between them has expired.
+    pub fn lookup_transform(&self, target: &str, source: &str) -> Result<Pose2D, RosError> {
+        self.tf.lookup_transform(target, source)
+    }
+}
+
+impl SensorSource for RosInterface {
+    fn get_sensor_data(&self) -> Result<SensorData, RosError> {
+        RosInterface::get_sensor_data(self)
+    }
+    
+    fn get_current_pose(&self) -> Result<Pose2D, RosError> {
+        RosInterface::get_current_pose(self)
+    }
+}
+
+impl CommandSink for RosInterface {
+    fn publish_command(&self, command: &MotionCommand) -> Result<(), RosError> {
+        RosInterface::publish_command(self, command)
+    }
+}
+
+impl RobotInterface for RosInterface {
+    fn initialize(&mut self) -> Result<(), RosError> {
+        RosInterface::initialize(self)
+    }
+    
+    fn publish_odometry(
+        &self,
+        pose: &Pose2D,
+        covariance: &Matrix3<f64>,
+        velocity: &MotionCommand,
+        stamp: Duration,
+    ) -> Result<(), RosError> {
+        RosInterface::publish_odometry(self, pose, covariance, velocity, stamp)
+    }
+    
+    fn publish_map(&self, map: &r2r::nav_msgs::msg::OccupancyGrid) -> Result<(), RosError> {
+        RosInterface::publish_map(self, map)
+    }
+    
+    fn publish_status(&self, status_json: &str) -> Result<(), RosError> {
+        RosInterface::publish_status(self, status_json)
+    }
+    
+    fn get_status(&self) -> RosStatus {
+        RosInterface::get_status(self)
+    }
+    
+    fn shutdown(&mut self) -> Result<(), RosError> {
+        RosInterface::shutdown(self)
+    }
+    
+    #[cfg(feature = "async")]
+    fn sensor_updates(&self) -> Option<tokio::sync::watch::Receiver<u64>> {
+        Some(RosInterface::sensor_updates(self))
+    }
+}
+
+/// Robot interface from a separate sensor source and command sink
+///
+/// For backends without ROS: odometry, maps and status reports are dropped, and the
+/// interface counts as connected once initialized.
+pub struct SplitInterface<S, C> {
+    source: S,
+    sink: C,
+    is_initialized: bool,
+}
+
+impl<S: SensorSource, C: CommandSink> SplitInterface<S, C> {
+    /// Combine a sensor source and a command sink
+    pub fn new(source: S, sink: C) -> Self {
+        SplitInterface {
+            source,
+            sink,
+            is_initialized: false,
+        }
+    }
+    
+    /// The sensor source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+    
+    /// The command sink
+    pub fn sink(&self) -> &C {
+        &self.sink
+    }
+}
+
+impl<S: SensorSource, C: CommandSink> SensorSource for SplitInterface<S, C> {
+    fn get_sensor_data(&self) -> Result<SensorData, RosError> {
+        self.source.get_sensor_data()
+    }
+    
+    fn get_current_pose(&self) -> Result<Pose2D, RosError> {
+        self.source.get_current_pose()
+    }
+}
+
+impl<S: SensorSource, C: CommandSink> CommandSink for SplitInterface<S, C> {
+    fn publish_command(&self, command: &MotionCommand) -> Result<(), RosError> {
+        self.sink.publish_command(command)
     }
 }
 
+impl<S: SensorSource, C: CommandSink> RobotInterface for SplitInterface<S, C> {
+    fn initialize(&mut self) -> Result<(), RosError> {
+        if self.is_initialized {
+            return Err(RosError::AlreadyInitialized);
+        }
+        self.is_initialized = true;
+        Ok(())
+    }
+    
+    fn publish_odometry(
+        &self,
+        _pose: &Pose2D,
+        _covariance: &Matrix3<f64>,
+        _velocity: &MotionCommand,
+        _stamp: Duration,
+    ) -> Result<(), RosError> {
+        Ok(())
+    }
+    
+    fn publish_map(&self, _map: &r2r::nav_msgs::msg::OccupancyGrid) -> Result<(), RosError> {
+        Ok(())
+    }
+    
+    fn publish_status(&self, _status_json: &str) -> Result<(), RosError> {
+        Ok(())
+    }
+    
+    fn get_status(&self) -> RosStatus {
+        RosStatus {
+            connected: self.is_initialized,
+            publishers_count: 0,
+            subscribers_count: 0,
+            last_message_time: None,
+        }
+    }
+    
+    fn shutdown(&mut self) -> Result<(), RosError> {
+        self.is_initialized = false;
+        Ok(())
+    }
+}
+
+/// Variance reported for the axes a planar estimate doesn't observe (z, roll, pitch)
+const UNOBSERVED_VARIANCE: f64 = 1e6;
+
+/// Row/column of x, y and yaw in a ROS 6x6 pose covariance
+const PLANAR_AXES: [usize; 3] = [0, 1, 5];
+
+/// Frame an odometry message's pose is in, "odom" when the header leaves it empty
+fn odom_frame_id(odom: &r2r::nav_msgs::msg::Odometry) -> &str {
+    match odom.header.frame_id.as_str() {
+        "" => "odom",
+        frame_id => frame_id,
+    }
+}
+
+/// Build an odometry message from a planar pose estimate in `frame_id` and velocity
+///
+/// `covariance` is over (x, y, theta), e.g. `PoseConfidence::covariance` from localization.
+pub fn odometry_message(
+    frame_id: &str,
+    pose: &Pose2D,
+    covariance: &Matrix3<f64>,
+    velocity: &MotionCommand,
+    stamp: Duration,
+) -> r2r::nav_msgs::msg::Odometry {
+    // Embed the planar covariance into the 6x6 row-major layout
+    let mut pose_covariance = vec![0.0; 36];
+    for axis in [2, 3, 4] {
+        pose_covariance[axis * 6 + axis] = UNOBSERVED_VARIANCE;
+    }
+    for (i, row) in PLANAR_AXES.iter().enumerate() {
+        for (j, col) in PLANAR_AXES.iter().enumerate() {
+            pose_covariance[row * 6 + col] = covariance[(i, j)];
+        }
+    }
+    
+    let half_theta = pose.theta as f64 / 2.0;
+    
+    r2r::nav_msgs::msg::Odometry {
+        header: r2r::std_msgs::msg::Header {
+            stamp: r2r::builtin_interfaces::msg::Time {
+                sec: stamp.as_secs() as i32,
+                nanosec: stamp.subsec_nanos(),
+            },
+            frame_id: frame_id.to_string(),
+        },
+        child_frame_id: "base_link".to_string(),
+        pose: r2r::geometry_msgs::msg::PoseWithCovariance {
+            pose: r2r::geometry_msgs::msg::Pose {
+                position: r2r::geometry_msgs::msg::Point {
+                    x: pose.x as f64,
+                    y: pose.y as f64,
+                    z: 0.0,
+                },
+                orientation: r2r::geometry_msgs::msg::Quaternion {
+                    x: 0.0,
+                    y: 0.0,
+                    z: half_theta.sin(),
+                    w: half_theta.cos(),
+                },
+            },
+            covariance: pose_covariance,
+        },
+        twist: r2r::geometry_msgs::msg::TwistWithCovariance {
+            twist: velocity.to_ros_message(),
+            covariance: vec![0.0; 36],
+        },
+    }
+}
+
+/// Extract the (x, y, theta) block of a ROS 6x6 pose covariance
+pub fn planar_covariance(covariance: &[f64]) -> Matrix3<f64> {
+    if covariance.len() < 36 {
+        return Matrix3::zeros();
+    }
+    Matrix3::from_fn(|i, j| covariance[PLANAR_AXES[i] * 6 + PLANAR_AXES[j]])
+}
+
 /// ROS error types
 #[derive(Debug)]
 pub enum RosError {
@@ -209,6 +653,10 @@ pub enum RosError {
     ConversionError(String),
     /// Interface not initialized
     NotInitialized,
+    /// Interface initialized twice
+    AlreadyInitialized,
+    /// Transform between frames missing or out of date
+    TransformError(String),
 }
 
 impl std::fmt::Display for RosError {
@@ -219,12 +667,20 @@ impl std::fmt::Display for RosError {
             RosError::SubscribeError(msg) => write!(f, "Subscribe error: {}", msg),
             RosError::ConversionError(msg) => write!(f, "Conversion error: {}", msg),
             RosError::NotInitialized => write!(f, "ROS interface not initialized"),
+            RosError::AlreadyInitialized => write!(f, "ROS interface already initialized"),
+            RosError::TransformError(msg) => write!(f, "Transform error: {}", msg),
         }
     }
 }
 
 impl std::error::Error for RosError {}
 
+impl From<r2r::Error> for RosError {
+    fn from(error: r2r::Error) -> Self {
+        RosError::InitError(error.to_string())
+    }
+}
+
 /// Sensor data collected from ROS
 #[derive(Debug, Clone)]
 pub struct SensorData {
@@ -237,7 +693,7 @@ pub struct SensorData {
 }
 
 /// Simple 2D pose representation
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct Pose2D {
     /// X position
     pub x: f32,
@@ -247,8 +703,21 @@ pub struct Pose2D {
     pub theta: f32,
 }
 
+impl Pose2D {
+    /// This pose followed by `other`, which is expressed in this pose's frame
+    pub fn compose(&self, other: &Pose2D) -> Pose2D {
+        let (sin, cos) = self.theta.sin_cos();
+        let theta = self.theta + other.theta;
+        Pose2D {
+            x: self.x + cos * other.x - sin * other.y,
+            y: self.y + sin * other.x + cos * other.y,
+            theta: theta.sin().atan2(theta.cos()),
+        }
+    }
+}
+
 /// Motion command for the robot
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct MotionCommand {
     /// Linear velocity (m/s)
     pub linear: f32,
@@ -257,19 +726,73 @@ pub struct MotionCommand {
 }
 
 impl MotionCommand {
+    /// Largest lateral, vertical, roll or pitch component still treated as zero
+    const UNSUPPORTED_AXIS_TOLERANCE: f64 = 1e-6;
+    
     /// Convert to ROS Twist message
     pub fn to_ros_message(&self) -> r2r::geometry_msgs::msg::Twist {
+        (*self).into()
+    }
+}
+
+impl From<(f32, f32)> for MotionCommand {
+    /// `(linear, angular)` velocities
+    fn from((linear, angular): (f32, f32)) -> Self {
+        MotionCommand { linear, angular }
+    }
+}
+
+impl From<MotionCommand> for r2r::geometry_msgs::msg::Twist {
+    fn from(command: MotionCommand) -> Self {
         r2r::geometry_msgs::msg::Twist {
             linear: r2r::geometry_msgs::msg::Vector3 {
-                x: self.linear as f64,
+                x: command.linear as f64,
                 y: 0.0,
                 z: 0.0,
             },
             angular: r2r::geometry_msgs::msg::Vector3 {
                 x: 0.0,
                 y: 0.0,
-                z: self.angular as f64,
+                z: command.angular as f64,
             },
         }
     }
+}
+
+impl TryFrom<r2r::geometry_msgs::msg::Twist> for MotionCommand {
+    type Error = RosError;
+    
+    /// Accepts only forward speed and yaw rate, the two axes a differential base can drive
+    ///
+    /// Every component must be finite, including the ignored ones, so a corrupt message
+    /// is rejected rather than half-followed.
+    fn try_from(twist: r2r::geometry_msgs::msg::Twist) -> Result<Self, RosError> {
+        let components = [
+            ("linear.x", twist.linear.x),
+            ("linear.y", twist.linear.y),
+            ("linear.z", twist.linear.z),
+            ("angular.x", twist.angular.x),
+            ("angular.y", twist.angular.y),
+            ("angular.z", twist.angular.z),
+        ];
+        // Speeds beyond f32 range would become infinite once converted
+        if let Some((axis, value)) = components.iter().find(|(_, value)| !(*value as f32).is_finite()) {
+            return Err(RosError::ConversionError(format!("non-finite velocity in twist: {} = {}", axis, value)));
+        }
+        let unsupported = [components[1], components[2], components[3], components[4]];
+        if let Some((axis, value)) = unsupported
+            .iter()
+            .find(|(_, value)| value.abs() > Self::UNSUPPORTED_AXIS_TOLERANCE)
+        {
+            return Err(RosError::ConversionError(format!(
+                "differential base cannot follow {} = {:.3}",
+                axis, value
+            )));
+        }
+        
+        Ok(MotionCommand {
+            linear: twist.linear.x as f32,
+            angular: twist.angular.z as f32,
+        })
+    }
 }
\ No newline at end of file
@@ -1,22 +1,30 @@
 use r2r::QosProfile;
 
+use super::RosError;
+
 // Generic ROS2 Publisher wrapper
 pub struct Publisher<T> {
     inner: r2r::Publisher<T>,
+    topic: String,
 }
 
 impl<T> Publisher<T>
 where
-    T: r2r::Message + 'static, // Message must implement ROS2 Message trait
+    T: r2r::WrappedTypesupport + 'static, // Message must implement ROS2 Message trait
 {
     // Create a new publisher on the given topic with specified QoS
     pub fn new(node: &mut r2r::Node, topic: &str, qos: QosProfile) -> Result<Self, r2r::Error> {
         let publisher = node.create_publisher(topic, qos)?;
-        Ok(Publisher { inner: publisher })
+        Ok(Publisher {
+            inner: publisher,
+            topic: topic.to_string(),
+        })
     }
 
     // Publish a message to the topic
-    pub fn publish(&self, message: T) -> Result<(), r2r::Error> {
-        self.inner.publish(message)
+    pub fn publish(&self, message: &T) -> Result<(), RosError> {
+        self.inner
+            .publish(message)
+            .map_err(|e| RosError::PublishError(format!("{}: {}", self.topic, e)))
     }
 }
@@ -0,0 +1,62 @@
+//! QoS policies from configuration
+//!
+//! Reliability and durability are configured as readable strings (`"reliable"`,
+//! `"transient_local"`) and mapped onto the r2r policies when the publishers and
+//! subscribers are created.
+
+use r2r::{QosDurabilityPolicy, QosProfile, QosReliabilityPolicy};
+use serde::{Deserialize, Serialize};
+
+/// Delivery guarantee for topics
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QosReliability {
+    /// Drop messages rather than retry; suits high-rate sensor data
+    #[default]
+    BestEffort,
+    /// Retry until delivered; needed to talk to reliable-only publishers
+    Reliable,
+    /// Whatever the middleware defaults to
+    SystemDefault,
+}
+
+/// Whether late subscribers receive messages published before they joined
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QosDurability {
+    /// Only messages published after subscribing
+    #[default]
+    Volatile,
+    /// Also the last messages published before subscribing (maps, static TF)
+    TransientLocal,
+    /// Whatever the middleware defaults to
+    SystemDefault,
+}
+
+impl From<QosReliability> for QosReliabilityPolicy {
+    fn from(reliability: QosReliability) -> Self {
+        match reliability {
+            QosReliability::BestEffort => QosReliabilityPolicy::BestEffort,
+            QosReliability::Reliable => QosReliabilityPolicy::Reliable,
+            QosReliability::SystemDefault => QosReliabilityPolicy::SystemDefault,
+        }
+    }
+}
+
+impl From<QosDurability> for QosDurabilityPolicy {
+    fn from(durability: QosDurability) -> Self {
+        match durability {
+            QosDurability::Volatile => QosDurabilityPolicy::Volatile,
+            QosDurability::TransientLocal => QosDurabilityPolicy::TransientLocal,
+            QosDurability::SystemDefault => QosDurabilityPolicy::SystemDefault,
+        }
+    }
+}
+
+/// QoS profile with the given history depth, reliability, and durability
+pub fn qos_profile(depth: usize, reliability: QosReliability, durability: QosDurability) -> QosProfile {
+    QosProfile::default()
+        .depth(depth)
+        .reliability(reliability.into())
+        .durability(durability.into())
+}
@@ -1,77 +1,96 @@
 // src/ros_interface/subscriber.rs
-// Subscribes to ROS 2 /scan topic to receive LIDAR data for Eos navigation.
+// Subscribes to a ROS 2 topic and keeps the latest message for the run loop to read.
 
-// Imports necessary dependencies and shared types.
-// - r2r: ROS 2 Rust bindings for topic subscription.
-// - SensorData: Defined in lib.rs for consistent data handling.
-use r2r::{QosProfile, Node};
-use crate::SensorData;
+use futures::{executor::block_on, future, StreamExt};
+use r2r::QosProfile;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
 
-/// Subscriber struct to manage ROS 2 subscription and store latest data.
-/// - `subscriber`: ROS 2 subscriber for LaserScan messages.
-/// - `latest_data`: Stores the most recent sensor data for processing.
-pub struct Subscriber {
-    subscriber: r2r::Subscriber<r2r::sensor_msgs::msg::LaserScan>,
-    latest_data: Option<SensorData>,
+use super::RosError;
+
+// Latest message and its arrival time, shared with the subscription callback
+type Latest<T> = Arc<Mutex<Option<(T, SystemTime)>>>;
+
+/// Subscribes to `topic` and hands each message to `callback`
+///
+/// r2r delivers messages as a stream that fills while the node spins; a dedicated
+/// thread drains it into the callback until the node is dropped and the stream ends.
+pub fn subscribe_with<T, F>(node: &mut r2r::Node, topic: &str, qos: QosProfile, mut callback: F) -> Result<(), r2r::Error>
+where
+    T: r2r::WrappedTypesupport + Send + 'static,
+    F: FnMut(T) + Send + 'static,
+{
+    let stream = node.subscribe::<T>(topic, qos)?;
+    thread::spawn(move || {
+        block_on(stream.for_each(|message| {
+            callback(message);
+            future::ready(())
+        }))
+    });
+    Ok(())
 }
 
-/// Implementation of Subscriber for creating and accessing data.
-impl Subscriber {
-    /// Creates a new subscriber for the given topic (e.g., `/scan`).
-    /// - `node`: ROS 2 node for subscription.
-    /// - `topic`: Topic name (e.g., `/scan` for LIDAR).
-    /// Returns a `Subscriber` instance or an error if subscription fails.
-    pub fn new(node: &mut Node, topic: &str) -> Result<Self, r2r::Error> {
-        let qos = QosProfile::default(); // Default QoS for reliable delivery
+/// Generic ROS 2 subscriber wrapper holding the most recent message
+pub struct Subscriber<T> {
+    topic: String,
+    latest: Latest<T>,
+}
+
+impl<T> Subscriber<T>
+where
+    T: r2r::WrappedTypesupport + Clone + Send + 'static,
+{
+    /// Subscribes to `topic`; each message received replaces the previous one
+    pub fn new(node: &mut r2r::Node, topic: &str, qos: QosProfile) -> Result<Self, r2r::Error> {
+        Self::with_notify(node, topic, qos, || {})
+    }
+
+    /// Like `new`, calling `notify` after each message is stored (e.g. to wake a waiting task)
+    pub fn with_notify<F>(node: &mut r2r::Node, topic: &str, qos: QosProfile, notify: F) -> Result<Self, r2r::Error>
+    where
+        F: Fn() + Send + 'static,
+    {
+        let latest: Latest<T> = Arc::new(Mutex::new(None));
+        let mut store = Self::store_into(latest.clone());
+        subscribe_with(node, topic, qos, move |message| {
+            store(message);
+            notify();
+        })?;
+        Ok(Subscriber {
+            topic: topic.to_string(),
+            latest,
+        })
+    }
 
-        // Initialize with a placeholder callback to allow setup
-        let mut subscriber = Self {
-            subscriber: node.create_subscription::<r2r::sensor_msgs::msg::LaserScan, _>(
-                topic,
-                qos,
-                |_| {}, // Temporary empty callback
-            )?,
-            latest_data: None,
-        };
+    /// Callback storing messages into this subscriber, as driven by the subscription
+    pub fn callback(&self) -> impl FnMut(T) + Send + 'static {
+        Self::store_into(self.latest.clone())
+    }
 
-        // Reassign subscriber with closure that updates `latest_data`
-        subscriber.subscriber = node.create_subscription::<r2r::sensor_msgs::msg::LaserScan, _>(
-            topic,
-            qos,
-            move |msg| {
-                // Convert ROS LaserScan to SensorData
-                subscriber.latest_data = Some(SensorData {
-                    ranges: msg.ranges.clone(),
-                });
-            },
-        )?;
+    /// Latest message, or an error if none has arrived yet
+    pub fn get_latest(&self) -> Result<T, RosError> {
+        let latest = self
+            .latest
+            .lock()
+            .map_err(|_| RosError::SubscribeError(format!("{} message lock poisoned", self.topic)))?;
+        latest
+            .as_ref()
+            .map(|(message, _)| message.clone())
+            .ok_or_else(|| RosError::SubscribeError(format!("no message received on {}", self.topic)))
+    }
 
-        Ok(subscriber)
+    /// When the latest message arrived, None before the first one
+    pub fn get_last_message_time(&self) -> Option<SystemTime> {
+        self.latest.lock().ok()?.as_ref().map(|(_, received)| *received)
     }
 
-    /// Returns the latest sensor data, if available.
-    /// Clones data to avoid ownership issues.
-    pub fn get_data(&self) -> Option<SensorData> {
-        self.latest_data.clone()
+    // Callback that stamps each message with its arrival time and keeps it
+    fn store_into(latest: Latest<T>) -> impl FnMut(T) + Send + 'static {
+        move |message| {
+            if let Ok(mut latest) = latest.lock() {
+                *latest = Some((message, SystemTime::now()));
+            }
+        }
     }
 }
-
-// SWOT Analysis
-// Strengths:
-// - Robust Integration: Uses r2r for reliable ROS 2 subscription, compatible with TurtleBot3’s /scan topic.
-// - Simplicity: Minimal code focused on LIDAR data, suitable for MVP demo in Gazebo.
-// - Reusability: Generic structure supports other sensor types (e.g., cameras) in future iterations.
-//
-// Weaknesses:
-// - Basic Callback: Simple closure may miss high-frequency data in real-world scenarios.
-// - No Preprocessing: Directly converts LaserScan to SensorData without noise filtering or validation.
-// - Error Handling: Minimal handling of subscription failures or invalid data.
-//
-// Opportunities:
-// - Extensibility: Can add support for multiple sensors (e.g., /camera/image_raw) to enhance sensory processing.
-// - Optimization: Async callbacks (e.g., tokio) could improve performance for real-time applications.
-// - Z Fellows Appeal: Demonstrates ROS 2 proficiency, aligning with robotics focus for $10B market.
-//
-// Threats:
-// - ROS 2 Dependency: Relies on r2r, which may have bugs or version incompatibilities.
-// - Scalability Limits: Single-threaded subscription struggles with high data rates in complex environments.
@@ -0,0 +1,202 @@
+//! Planar TF buffer
+//!
+//! Keeps the latest transform for every frame published on `/tf` and `/tf_static`
+//! and chains them through their common ancestor, so poses reported in one frame
+//! (e.g. odometry in `odom`) can be expressed in another (e.g. `map`).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use r2r::geometry_msgs::msg::TransformStamped;
+use r2r::tf2_msgs::msg::TFMessage;
+use r2r::{Node, QosProfile};
+
+use super::{qos_profile, subscribe_with, Pose2D, QosDurability, QosReliability, RosError};
+
+/// Age after which a dynamic transform no longer counts as current
+pub const DEFAULT_TF_MAX_AGE: Duration = Duration::from_secs(1);
+
+/// (x, y, theta) of a frame in another
+type PlanarPose = (f64, f64, f64);
+
+/// History depth for `/tf_static`, which latches every static transform publisher's message
+const TF_STATIC_DEPTH: usize = 100;
+
+/// Pose of a frame in itself
+const IDENTITY: PlanarPose = (0.0, 0.0, 0.0);
+
+/// Latest transform of a frame relative to its parent
+#[derive(Debug, Clone, Copy)]
+struct FrameTransform {
+    /// Pose of the frame in its parent
+    pose: PlanarPose,
+    /// When it was received
+    received: Instant,
+    /// Published on `/tf_static`; never expires
+    is_static: bool,
+}
+
+/// Latest transforms between frames, as a tree keyed by child frame
+#[derive(Debug, Clone)]
+pub struct TfBuffer {
+    frames: HashMap<String, (String, FrameTransform)>, // child -> (parent, transform)
+    max_age: Duration,
+}
+
+impl TfBuffer {
+    /// Empty buffer treating dynamic transforms older than `max_age` as expired
+    pub fn new(max_age: Duration) -> Self {
+        TfBuffer {
+            frames: HashMap::new(),
+            max_age,
+        }
+    }
+
+    /// Store every transform of a TF message received at `received`
+    pub fn insert_message(&mut self, message: &TFMessage, is_static: bool, received: Instant) {
+        for transform in &message.transforms {
+            self.insert(transform, is_static, received);
+        }
+    }
+
+    /// Store a transform, replacing the previous one for its child frame
+    pub fn insert(&mut self, transform: &TransformStamped, is_static: bool, received: Instant) {
+        let t = &transform.transform;
+        let q = &t.rotation;
+        let yaw = (2.0 * (q.w * q.z + q.x * q.y)).atan2(1.0 - 2.0 * (q.y * q.y + q.z * q.z));
+        self.frames.insert(
+            transform.child_frame_id.clone(),
+            (
+                transform.header.frame_id.clone(),
+                FrameTransform {
+                    pose: (t.translation.x, t.translation.y, yaw),
+                    received,
+                    is_static,
+                },
+            ),
+        );
+    }
+
+    /// Pose of `source` expressed in `target`, using transforms current at `now`
+    ///
+    /// Fails when either frame is unknown, the frames aren't connected, or a dynamic
+    /// transform on the way is older than the buffer's maximum age. Only the links up
+    /// to the frames' common ancestor count; a stale link above it isn't used.
+    pub fn lookup(&self, target: &str, source: &str, now: Instant) -> Result<Pose2D, RosError> {
+        for frame in [target, source] {
+            if !self.knows(frame) {
+                return Err(RosError::TransformError(format!("unknown frame '{}'", frame)));
+            }
+        }
+        let target_chain = self.chain_to_root(target)?;
+        let source_chain = self.chain_to_root(source)?;
+
+        // Closest frame both chains reach: target <- common <- source
+        let Some((source_index, target_index)) = source_chain.iter().enumerate().find_map(|(i, (frame, _))| {
+            target_chain.iter().position(|(other, _)| other == frame).map(|j| (i, j))
+        }) else {
+            return Err(RosError::TransformError(format!(
+                "no transform from '{}' to '{}': frames are not connected",
+                source, target
+            )));
+        };
+        for (child, _) in source_chain[..source_index].iter().chain(&target_chain[..target_index]) {
+            self.check_current(child, now)?;
+        }
+
+        let (x, y, theta) = compose(invert(target_chain[target_index].1), source_chain[source_index].1);
+        Ok(Pose2D {
+            x: x as f32,
+            y: y as f32,
+            theta: theta as f32,
+        })
+    }
+
+    /// Whether any transform mentions `frame`, as child or parent
+    fn knows(&self, frame: &str) -> bool {
+        self.frames.contains_key(frame) || self.frames.values().any(|(parent, _)| parent == frame)
+    }
+
+    /// Fails when the transform from `child` to its parent is dynamic and older than the maximum age
+    fn check_current(&self, child: &str, now: Instant) -> Result<(), RosError> {
+        let Some((parent, transform)) = self.frames.get(child) else {
+            return Ok(());
+        };
+        let age = now.saturating_duration_since(transform.received);
+        if !transform.is_static && age > self.max_age {
+            return Err(RosError::TransformError(format!(
+                "transform '{}' -> '{}' expired ({:.2} s old)",
+                parent,
+                child,
+                age.as_secs_f64()
+            )));
+        }
+        Ok(())
+    }
+
+    /// `frame` and each of its ancestors, with the pose of `frame` in that ancestor
+    fn chain_to_root(&self, frame: &str) -> Result<Vec<(String, PlanarPose)>, RosError> {
+        let mut chain = vec![(frame.to_string(), IDENTITY)];
+        let mut current = frame;
+        let mut pose = IDENTITY;
+        while let Some((parent, transform)) = self.frames.get(current) {
+            // A malformed tree could loop; it can't be longer than the number of frames
+            if chain.len() > self.frames.len() {
+                return Err(RosError::TransformError(format!("transform cycle through '{}'", frame)));
+            }
+            pose = compose(transform.pose, pose);
+            chain.push((parent.clone(), pose));
+            current = parent;
+        }
+        Ok(chain)
+    }
+}
+
+/// Pose of `b`'s frame in `a`'s parent, given `a` in its parent and `b` in `a`
+fn compose(a: PlanarPose, b: PlanarPose) -> PlanarPose {
+    let (sin, cos) = a.2.sin_cos();
+    let theta = (a.2 + b.2).sin().atan2((a.2 + b.2).cos());
+    (a.0 + cos * b.0 - sin * b.1, a.1 + sin * b.0 + cos * b.1, theta)
+}
+
+/// Pose of a parent frame in the child, given the child in the parent
+fn invert(a: PlanarPose) -> PlanarPose {
+    let (sin, cos) = a.2.sin_cos();
+    (-cos * a.0 - sin * a.1, sin * a.0 - cos * a.1, -a.2)
+}
+
+/// Keeps a `TfBuffer` filled from `/tf` and `/tf_static`
+pub struct TfListener {
+    buffer: Arc<Mutex<TfBuffer>>,
+}
+
+impl TfListener {
+    /// Subscribe to `/tf` with `qos` and to `/tf_static` as reliable and transient local
+    ///
+    /// Static transforms are published once, so a late subscriber needs them latched.
+    pub fn new(node: &mut Node, qos: &QosProfile, max_age: Duration) -> Result<Self, RosError> {
+        let buffer = Arc::new(Mutex::new(TfBuffer::new(max_age)));
+        let mut subscribe = |topic: &str, qos: QosProfile, is_static: bool| {
+            let buffer = Arc::clone(&buffer);
+            subscribe_with(node, topic, qos, move |message: TFMessage| {
+                if let Ok(mut buffer) = buffer.lock() {
+                    buffer.insert_message(&message, is_static, Instant::now());
+                }
+            })
+        };
+        subscribe("/tf", qos.clone(), false)?;
+        let static_qos = qos_profile(TF_STATIC_DEPTH, QosReliability::Reliable, QosDurability::TransientLocal);
+        subscribe("/tf_static", static_qos, true)?;
+
+        Ok(TfListener { buffer })
+    }
+
+    /// Pose of `source` expressed in `target` from the latest transforms
+    pub fn lookup_transform(&self, target: &str, source: &str) -> Result<Pose2D, RosError> {
+        self.buffer
+            .lock()
+            .map_err(|_| RosError::TransformError("TF buffer lock poisoned".to_string()))?
+            .lookup(target, source, Instant::now())
+    }
+}
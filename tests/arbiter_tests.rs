@@ -0,0 +1,52 @@
+use eos::core::{CommandArbiter, CommandPriority};
+use eos::ros_interface::MotionCommand;
+
+/// Command with the given velocities
+fn command(linear: f32, angular: f32) -> MotionCommand {
+    MotionCommand { linear, angular }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An emergency stop overrides a simultaneous nominal forward command
+    #[test]
+    fn test_emergency_overrides_nominal() {
+        let mut arbiter = CommandArbiter::new();
+        arbiter.submit("indoor_control", CommandPriority::Nominal, command(0.4, 0.0));
+        arbiter.submit("motion_controller", CommandPriority::Emergency, command(0.2, 0.1));
+
+        assert_eq!(arbiter.arbitrate(), command(0.0, 0.0));
+        assert_eq!(arbiter.pending(), 0);
+    }
+
+    // Higher priority wins, a stop wins over anything, and no candidates means stop
+    #[test]
+    fn test_priority_and_stop_precedence() {
+        let mut arbiter = CommandArbiter::new();
+        arbiter.submit("indoor_control", CommandPriority::Nominal, command(0.4, 0.0));
+        arbiter.submit("motion_controller", CommandPriority::SafetySlow, command(0.1, 0.0));
+        assert_eq!(arbiter.arbitrate(), command(0.1, 0.0));
+
+        arbiter.submit("indoor_control", CommandPriority::SafetySlow, command(0.3, 0.2));
+        arbiter.submit("motion_controller", CommandPriority::Nominal, command(0.0, 0.0));
+        assert_eq!(arbiter.arbitrate(), command(0.0, 0.0));
+
+        assert_eq!(arbiter.arbitrate(), command(0.0, 0.0));
+    }
+
+    // A safety slow-down only caps the nominal speed, never raises it, and scales the
+    // turn rate with it
+    #[test]
+    fn test_safety_slow_caps_nominal_speed() {
+        let mut arbiter = CommandArbiter::new();
+        arbiter.submit("indoor_control", CommandPriority::Nominal, command(0.2, 0.1));
+        arbiter.submit("motion_controller", CommandPriority::SafetySlow, command(0.5, 0.0));
+        assert_eq!(arbiter.arbitrate(), command(0.2, 0.1));
+
+        arbiter.submit("indoor_control", CommandPriority::Nominal, command(0.4, 0.2));
+        arbiter.submit("motion_controller", CommandPriority::SafetySlow, command(0.1, 0.0));
+        assert_eq!(arbiter.arbitrate(), command(0.1, 0.05));
+    }
+}
@@ -0,0 +1,72 @@
+use eos::apps::calibration::{
+    estimate_lidar_offset, load_calibration, save_calibration, CalibrationData, CalibrationError, IndoorCalibrationData,
+};
+use r2r::sensor_msgs::msg::LaserScan;
+
+/// Scan of a flat wall 2 m ahead, seen by a LiDAR mounted with the given yaw
+fn wall_scan(mount_yaw: f32) -> LaserScan {
+    let angle_min = -1.0;
+    let angle_increment = 0.02;
+    let ranges = (0..100)
+        .map(|i| {
+            let bearing = angle_min + i as f32 * angle_increment + mount_yaw;
+            2.0 / bearing.cos()
+        })
+        .collect();
+    LaserScan {
+        angle_min,
+        angle_max: angle_min + angle_increment * 100.0,
+        angle_increment,
+        range_min: 0.05,
+        range_max: 10.0,
+        ranges,
+        ..Default::default()
+    }
+}
+
+/// Calibration file path for the given test
+fn calibration_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("eos_calibration_{}.json", name)).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Calibration saved to disk loads back unchanged
+    #[test]
+    fn test_calibration_round_trip() {
+        let path = calibration_path("round_trip");
+        let calibration = CalibrationData {
+            lidar_calibration: [0.05, -0.02, 0.3, 0.0, 0.01, 0.12],
+            camera_calibration: [1.0, 0.0, 320.0, 0.0, 1.0, 240.0, 0.0, 0.0, 1.0],
+            imu_calibration: [0.25; 12],
+        };
+
+        save_calibration(&calibration, &path).unwrap();
+        let loaded: CalibrationData = load_calibration(&path).unwrap();
+        assert_eq!(loaded, calibration);
+
+        // Another robot's calibration layout is rejected rather than misread
+        assert!(matches!(load_calibration::<IndoorCalibrationData>(&path), Err(CalibrationError::Json(_))));
+        assert!(matches!(
+            load_calibration::<CalibrationData>(&calibration_path("missing")),
+            Err(CalibrationError::Io(_))
+        ));
+    }
+
+    // A wall that appears tilted reveals the LiDAR mount yaw
+    #[test]
+    fn test_lidar_offset_from_wall() {
+        let square = estimate_lidar_offset(&wall_scan(0.0));
+        assert!(square[5].abs() < 1e-3);
+
+        let yawed = estimate_lidar_offset(&wall_scan(0.1));
+        assert!((yawed[5] - 0.1).abs() < 1e-3, "yaw {}", yawed[5]);
+        assert_eq!(&yawed[..5], &[0.0; 5]);
+
+        // Nothing in front means nothing to calibrate against
+        let empty = LaserScan { ranges: vec![f32::INFINITY; 100], ..wall_scan(0.0) };
+        assert_eq!(estimate_lidar_offset(&empty), [0.0; 6]);
+    }
+}
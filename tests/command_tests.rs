@@ -0,0 +1,29 @@
+mod common;
+
+use eos::neural::snn::Snn;
+use eos::ros_interface::MotionCommand;
+
+use common::sensor_data;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A (linear, angular) pair converts field for field
+    #[test]
+    fn test_motion_command_from_tuple() {
+        let command = MotionCommand::from((0.45, -1.0));
+        assert_eq!(command, MotionCommand { linear: 0.45, angular: -1.0 });
+
+        let twist = command.to_ros_message();
+        assert_eq!((twist.linear.x, twist.angular.z), (0.45f32 as f64, -1.0));
+    }
+
+    // The simple Snn turns away from close obstacles and drives forward otherwise
+    #[test]
+    fn test_snn_returns_motion_commands() {
+        let snn = Snn::new(10);
+        assert_eq!(snn.process(&sensor_data(0.0, 0.0, vec![2.0, 0.3, 1.0])), MotionCommand { linear: 0.0, angular: 0.5 });
+        assert_eq!(snn.process(&sensor_data(0.0, 0.0, vec![2.0, 1.5])), MotionCommand { linear: 0.2, angular: 0.0 });
+    }
+}
@@ -0,0 +1,50 @@
+//! Helpers shared by the integration tests
+
+use std::time::{Duration, Instant};
+
+use eos::Clock;
+use eos::ros_interface::SensorData;
+use r2r::sensor_msgs::msg::LaserScan;
+
+/// Clock that only advances when slept on
+#[allow(dead_code)] // Only the loop timing tests use it
+pub struct FakeClock {
+    start: Instant,
+    elapsed: Duration,
+}
+
+#[allow(dead_code)]
+impl FakeClock {
+    /// Clock starting now, advancing only by the time slept
+    pub fn new() -> Self {
+        FakeClock { start: Instant::now(), elapsed: Duration::ZERO }
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.elapsed += duration;
+    }
+}
+
+/// Sensor data around a laser scan of `ranges` starting at `angle_min` (radians), with
+/// returns between 0.05 and 10 m counted
+pub fn sensor_data(angle_min: f32, angle_increment: f32, ranges: Vec<f32>) -> SensorData {
+    SensorData {
+        laser_scan: LaserScan {
+            angle_min,
+            angle_max: angle_min + angle_increment * ranges.len() as f32,
+            angle_increment,
+            range_min: 0.05,
+            range_max: 10.0,
+            ranges,
+            ..Default::default()
+        },
+        imu_data: Default::default(),
+        odom_data: Default::default(),
+    }
+}
@@ -0,0 +1,94 @@
+use eos::core::{Pid, PidGains};
+
+/// Gains with every term enabled
+fn gains(kp: f32, ki: f32, kd: f32) -> PidGains {
+    PidGains {
+        kp,
+        ki,
+        kd,
+        integral_limit: 1.0,
+        derivative_filter: 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A proportional-only loop scales the error and keeps no state
+    #[test]
+    fn test_proportional_output() {
+        let mut pid = Pid::new(PidGains::proportional(2.0));
+
+        assert!((pid.update(0.5, 0.1) - 1.0).abs() < 1e-6);
+        assert!((pid.update(-0.25, 0.1) + 0.5).abs() < 1e-6);
+        assert!((pid.update(0.0, 0.1)).abs() < 1e-6);
+    }
+
+    // A long-held error saturates the integral term at its limit, and the output
+    // recovers as soon as the error reverses instead of unwinding a huge integral
+    #[test]
+    fn test_integral_windup_clamp() {
+        let mut pid = Pid::new(gains(0.0, 0.5, 0.0));
+
+        let mut output = 0.0;
+        for _ in 0..1000 {
+            output = pid.update(1.0, 0.1);
+        }
+        assert!((output - 1.0).abs() < 1e-4, "integral term should saturate at the limit");
+        assert!((pid.integral_term() - 1.0).abs() < 1e-4);
+
+        // 2 s of opposite error undoes 1.0 / 0.5 = 2 error·s of integral
+        let mut reversed = 0;
+        while pid.update(-1.0, 0.1) > 0.0 {
+            reversed += 1;
+        }
+        assert!(reversed <= 21, "took {} steps to unwind", reversed);
+
+        pid.reset();
+        assert_eq!(pid.integral_term(), 0.0);
+    }
+
+    // A step in the error gives a single derivative spike, not a kick on the first update
+    #[test]
+    fn test_derivative_on_step() {
+        let mut pid = Pid::new(gains(0.0, 0.0, 1.0));
+
+        assert_eq!(pid.update(0.0, 0.1), 0.0);
+        assert!((pid.update(1.0, 0.1) - 10.0).abs() < 1e-4);
+        assert!(pid.update(1.0, 0.1).abs() < 1e-4);
+
+        // A fresh controller starting on the step has nothing to differentiate
+        let mut fresh = Pid::new(gains(0.0, 0.0, 1.0));
+        assert_eq!(fresh.update(1.0, 0.1), 0.0);
+    }
+
+    // On a ramp the filtered derivative settles at the slope, smoothing the step response
+    #[test]
+    fn test_derivative_on_ramp_with_filter() {
+        let mut pid = Pid::new(PidGains {
+            derivative_filter: 0.5,
+            ..gains(0.0, 0.0, 1.0)
+        });
+
+        // Error growing at 2 per second
+        let mut outputs = Vec::new();
+        for step in 0..30 {
+            outputs.push(pid.update(0.2 * step as f32, 0.1));
+        }
+        assert!((outputs[29] - 2.0).abs() < 1e-3);
+        // Filtering makes it approach the slope gradually rather than jumping there
+        assert!(outputs[1] < 2.0 && outputs[1] > 0.0);
+        assert!(outputs.windows(2).all(|pair| pair[1] >= pair[0] - 1e-4));
+    }
+
+    // A non-positive time step leaves the integral and derivative untouched
+    #[test]
+    fn test_zero_dt_is_proportional_only() {
+        let mut pid = Pid::new(gains(1.0, 1.0, 1.0));
+        pid.update(0.0, 0.1);
+
+        assert!((pid.update(0.5, 0.0) - 0.5).abs() < 1e-6);
+        assert_eq!(pid.integral_term(), 0.0);
+    }
+}
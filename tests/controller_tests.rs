@@ -0,0 +1,458 @@
+mod common;
+
+use eos::core::{CoreState, Mode, SemanticObject};
+use eos::navigation::{MotionController, NavigationConfig, NavigationPlanner, Obstacle, ObstacleCategory, SafetyEventReason};
+use eos::ros_interface::{MotionCommand, SensorData};
+use nalgebra::Vector2;
+
+use common::sensor_data;
+
+/// Forward command at the given speed
+fn forward(linear: f32) -> MotionCommand {
+    MotionCommand { linear, angular: 0.0 }
+}
+
+/// Sensor data with every beam at maximum range
+fn open_space() -> SensorData {
+    sensor_data(-1.5, 0.1, vec![10.0; 30])
+}
+
+/// Cruise speed reached toward a goal with the given semantic objects nearby
+fn cruise_speed_near(objects: &[SemanticObject]) -> f32 {
+    let config = NavigationConfig::default();
+    let mut planner = NavigationPlanner::new(&config);
+    let mut controller = MotionController::new(&config);
+    planner.set_goal(eos::ros_interface::Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+    planner.set_semantic_objects(objects);
+
+    let mut command = forward(0.0);
+    for _ in 0..50 {
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+        command = controller.execute_plan(&path).unwrap();
+    }
+    command.linear
+}
+
+/// Cruise speed reached in open space toward a goal the given distance ahead
+fn cruise_speed_toward(goal_distance: f32) -> f32 {
+    let config = NavigationConfig::default();
+    let mut planner = NavigationPlanner::new(&config);
+    let mut controller = MotionController::new(&config);
+    planner.set_goal(eos::ros_interface::Pose2D { x: goal_distance, y: 0.0, theta: 0.0 }).unwrap();
+
+    let mut command = forward(0.0);
+    for _ in 0..100 {
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+        command = controller.execute_plan(&path).unwrap();
+    }
+    command.linear
+}
+
+/// Default configuration with the dynamic window approach switched on
+fn dwa_config() -> NavigationConfig {
+    let mut config = NavigationConfig::default();
+    config.dwa.enabled = true;
+    config
+}
+
+/// Poses driven by the dynamic window approach toward a goal 3m ahead, with the given obstacles
+fn dwa_drive_around(obstacles: &[Obstacle]) -> Vec<eos::ros_interface::Pose2D> {
+    let config = dwa_config();
+    let mut planner = NavigationPlanner::new(&config);
+    let mut controller = MotionController::new(&config);
+    planner.set_goal(eos::ros_interface::Pose2D { x: 3.0, y: 0.0, theta: 0.0 }).unwrap();
+    for obstacle in obstacles {
+        planner.add_external_obstacle(obstacle.clone());
+    }
+
+    // Integrate each command over the 100ms cycle
+    let mut pose = eos::ros_interface::Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+    let mut poses = Vec::new();
+    for _ in 0..80 {
+        let path = planner.plan(&open_space(), &[], Some(pose)).unwrap();
+        let command = controller.execute_plan_around(&path, &planner).unwrap();
+        pose.x += command.linear * pose.theta.cos() * 0.1;
+        pose.y += command.linear * pose.theta.sin() * 0.1;
+        pose.theta += command.angular * 0.1;
+        poses.push(pose);
+    }
+    poses
+}
+
+/// Poses driven by the dynamic window approach from (1, 1) heading +y toward a goal 3m ahead,
+/// checking rollouts against the keep-out zones in `zones` (a YAML file), if any
+fn dwa_drive_toward_zone(zones: Option<&str>) -> Vec<eos::ros_interface::Pose2D> {
+    let config = dwa_config();
+    let mut planner = NavigationPlanner::new(&config);
+    let mut fenced = NavigationPlanner::new(&config);
+    let mut controller = MotionController::new(&config);
+    planner.set_goal(eos::ros_interface::Pose2D { x: 1.0, y: 4.0, theta: 0.0 }).unwrap();
+    if let Some(zones) = zones {
+        fenced.load_keepout_zones(zones).unwrap();
+    }
+
+    let mut pose = eos::ros_interface::Pose2D { x: 1.0, y: 1.0, theta: std::f32::consts::FRAC_PI_2 };
+    let mut poses = Vec::new();
+    for _ in 0..80 {
+        let path = planner.plan(&open_space(), &[], Some(pose)).unwrap();
+        let command = controller.execute_plan_around(&path, &fenced).unwrap();
+        pose.x += command.linear * pose.theta.cos() * 0.1;
+        pose.y += command.linear * pose.theta.sin() * 0.1;
+        pose.theta += command.angular * 0.1;
+        poses.push(pose);
+    }
+    poses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A square-wave target is smoothed into a bounded, lagging output
+    #[test]
+    fn test_output_smoothing_square_wave() {
+        let config = NavigationConfig {
+            output_smoothing_alpha: 0.5,
+            ..Default::default()
+        };
+        let mut controller = MotionController::new(&config);
+
+        let targets = [0.4, 0.4, 0.0, 0.0, 0.4, 0.4];
+        let outputs: Vec<f32> = targets
+            .iter()
+            .map(|&t| controller.smooth_output(forward(t)).linear)
+            .collect();
+
+        let expected = [0.2, 0.3, 0.15, 0.075, 0.2375, 0.31875];
+        for (output, expected) in outputs.iter().zip(expected) {
+            assert!((output - expected).abs() < 1e-6);
+        }
+        // Never exceeds the largest target
+        assert!(outputs.iter().all(|&o| (0.0..=0.4).contains(&o)));
+    }
+
+    // Alpha of zero passes commands through unchanged
+    #[test]
+    fn test_output_smoothing_disabled() {
+        let mut controller = MotionController::new(&NavigationConfig::default());
+
+        assert_eq!(controller.smooth_output(forward(0.4)), forward(0.4));
+        assert_eq!(controller.smooth_output(forward(0.0)), forward(0.0));
+    }
+
+    // Commanded motion with zero odometry trips the stuck condition after the timeout
+    #[test]
+    fn test_stuck_detection_drives_recovery() {
+        let config = NavigationConfig {
+            stuck_timeout: 1.0,
+            ..Default::default()
+        };
+        let mut controller = MotionController::new(&config);
+        let mut state = CoreState::new();
+
+        controller.smooth_output(forward(0.4));
+
+        // Still within the timeout
+        for _ in 0..4 {
+            assert!(!controller.check_stuck(forward(0.0), 0.25));
+        }
+        // Stalled past the timeout
+        assert!(controller.check_stuck(forward(0.0), 0.25));
+
+        state.report_stuck();
+        assert_eq!(state.get_mode(), Mode::Recovering);
+    }
+
+    // Measured motion keeps resetting the monitor
+    #[test]
+    fn test_moving_robot_is_not_stuck() {
+        let config = NavigationConfig {
+            stuck_timeout: 1.0,
+            ..Default::default()
+        };
+        let mut controller = MotionController::new(&config);
+        controller.smooth_output(forward(0.4));
+
+        for _ in 0..10 {
+            assert!(!controller.check_stuck(forward(0.35), 0.25));
+        }
+    }
+
+    // A low battery lowers the velocity cap and the speed actually reached
+    #[test]
+    fn test_energy_scale_lowers_velocity_cap() {
+        let config = NavigationConfig::default();
+        let mut full = MotionController::new(&config);
+        let mut low = MotionController::new(&config);
+
+        full.set_energy_scale(1.0);
+        low.set_energy_scale(0.2);
+
+        assert_eq!(full.velocity_limits().linear, config.max_linear_velocity);
+        assert!(low.velocity_limits().linear < full.velocity_limits().linear);
+        assert!(low.velocity_limits().angular < full.velocity_limits().angular);
+
+        // Drive both along the same open-space path until they settle
+        let mut planner = NavigationPlanner::new(&config);
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+        let (mut full_speed, mut low_speed) = (0.0, 0.0);
+        for _ in 0..50 {
+            full_speed = full.execute_plan(&path).unwrap().linear;
+            low_speed = low.execute_plan(&path).unwrap().linear;
+        }
+        assert!(low_speed < full_speed);
+        assert!(low_speed <= low.velocity_limits().linear);
+    }
+
+    // Braking removes more speed per cycle than accelerating adds
+    #[test]
+    fn test_deceleration_exceeds_acceleration() {
+        let config = NavigationConfig {
+            max_acceleration: 0.2,
+            max_deceleration: 0.6,
+            ..Default::default()
+        };
+        let mut controller = MotionController::new(&config);
+
+        let accel_step = controller.apply_motion_profile(forward(1.0)).linear;
+        for _ in 0..30 {
+            controller.apply_motion_profile(forward(0.5));
+        }
+        let cruising = controller.apply_motion_profile(forward(0.5)).linear;
+        let braking = controller.apply_motion_profile(forward(0.0)).linear;
+        let decel_step = cruising - braking;
+
+        assert!((accel_step - 0.02).abs() < 1e-6);
+        assert!((decel_step - 0.06).abs() < 1e-6);
+        assert!(decel_step > accel_step);
+    }
+
+    // A longer measured cycle allows a proportionally larger velocity step
+    #[test]
+    fn test_ramp_follows_cycle_time() {
+        let config = NavigationConfig {
+            max_acceleration: 0.2,
+            ..Default::default()
+        };
+        let mut controller = MotionController::new(&config);
+        controller.set_cycle_time(0.25);
+        assert!((controller.apply_motion_profile(forward(1.0)).linear - 0.05).abs() < 1e-6);
+
+        // A stall is capped instead of jumping straight to the target
+        controller.set_cycle_time(5.0);
+        assert!((controller.apply_motion_profile(forward(1.0)).linear - 0.15).abs() < 1e-6);
+    }
+
+    // A person 0.6 m away slows the robot while a wall at the same distance does not
+    #[test]
+    fn test_person_comfort_zone_slows_robot() {
+        let unobstructed = cruise_speed_near(&[]);
+        let near_wall = cruise_speed_near(&[SemanticObject::new(1, "wall", Vector2::new(0.0, 0.6))]);
+        let near_person = cruise_speed_near(&[SemanticObject::new(2, "person", Vector2::new(0.0, 0.6))]);
+
+        assert!(unobstructed > 0.0);
+        assert_eq!(near_wall, unobstructed);
+        assert!(near_person < 0.7 * unobstructed);
+        assert!(near_person > 0.0);
+    }
+
+    // Speed tapers inside the approach zone and creeps at the goal tolerance radius
+    #[test]
+    fn test_approach_speed_tapers_to_creep() {
+        let config = NavigationConfig::default();
+        let controller = MotionController::new(&config);
+        let far = cruise_speed_toward(1.5);
+
+        for distance in [0.8, 0.6, 0.4, 0.2] {
+            let bound = controller.approach_speed_limit(distance);
+            let speed = cruise_speed_toward(distance);
+            assert!(bound < far);
+            assert!(speed <= bound + 1e-4, "{speed} above taper {bound} at {distance} m");
+            assert!(speed > 0.0);
+        }
+
+        assert!((controller.approach_speed_limit(config.goal_tolerance) - config.creep_speed).abs() < 1e-6);
+        let creeping = cruise_speed_toward(config.goal_tolerance);
+        assert!((creeping - config.creep_speed).abs() < 1e-3);
+    }
+
+    // A battery drop below the current speed clamps the command and records the intervention
+    #[test]
+    fn test_velocity_clamp_records_safety_event() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+        planner.set_goal(eos::ros_interface::Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+
+        for _ in 0..50 {
+            let path = planner.plan(&open_space(), &[], None).unwrap();
+            controller.execute_plan(&path).unwrap();
+        }
+        let cruising = controller.last_command().linear;
+        assert!(!controller.safety_events().iter().any(|e| e.reason == SafetyEventReason::VelocityLimit));
+
+        // An empty battery lowers the limit well below the cruise speed
+        controller.set_energy_scale(0.0);
+        let limit = controller.velocity_limits().linear;
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+        let command = controller.execute_plan(&path).unwrap();
+
+        let event = controller.safety_events().last().copied().unwrap();
+        assert_eq!(event.reason, SafetyEventReason::VelocityLimit);
+        let braked = cruising - config.max_deceleration * 0.1;
+        assert!((event.original.linear - braked).abs() < 1e-5);
+        assert!((event.applied.linear - limit).abs() < 1e-6);
+        assert_eq!(command.linear, event.applied.linear);
+
+        // An emergency stop overriding motion is recorded too
+        controller.emergency_stop();
+        let stop = controller.safety_events().last().copied().unwrap();
+        assert_eq!(stop.reason, SafetyEventReason::EmergencyStop);
+        assert_eq!(stop.original.linear, limit);
+        assert_eq!(stop.applied.linear, 0.0);
+    }
+
+    // A ramp held back by the acceleration limits over many cycles is one event
+    #[test]
+    fn test_acceleration_limit_recorded_once_per_ramp() {
+        let config = NavigationConfig::default();
+        let mut controller = MotionController::new(&config);
+        let acceleration_events = |controller: &MotionController| {
+            controller.safety_events().iter().filter(|e| e.reason == SafetyEventReason::AccelerationLimit).count()
+        };
+
+        for _ in 0..5 {
+            controller.apply_motion_profile(MotionCommand { linear: 0.5, angular: 0.0 });
+        }
+        assert_eq!(acceleration_events(&controller), 1);
+
+        // Reaching the target ends the ramp; the next one is recorded again
+        for _ in 0..50 {
+            controller.apply_motion_profile(MotionCommand { linear: 0.5, angular: 0.0 });
+        }
+        controller.apply_motion_profile(MotionCommand { linear: 0.0, angular: 0.0 });
+        assert_eq!(acceleration_events(&controller), 2);
+    }
+
+    // A goal off to the side turns the robot toward it, within the angular limit
+    #[test]
+    fn test_heading_turns_toward_goal() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+
+        // Straight ahead needs no turning
+        planner.set_goal(eos::ros_interface::Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+        assert_eq!(controller.execute_plan(&path).unwrap().angular, 0.0);
+
+        // Goal to the left: turn counter-clockwise, growing toward the heading PID's output
+        planner.set_goal(eos::ros_interface::Pose2D { x: 0.0, y: 1.5, theta: 0.0 }).unwrap();
+        let mut angular = Vec::new();
+        for _ in 0..30 {
+            let path = planner.plan(&open_space(), &[], None).unwrap();
+            angular.push(controller.execute_plan(&path).unwrap().angular);
+        }
+        assert!(angular[0] > 0.0);
+        assert!(angular.iter().all(|w| *w > 0.0 && *w <= config.max_angular_velocity + 1e-6));
+        assert!(angular[29] > angular[0]);
+    }
+
+    // The dynamic window approach turns toward a goal off to the side
+    #[test]
+    fn test_dwa_turns_toward_goal() {
+        let config = dwa_config();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+        planner.set_goal(eos::ros_interface::Pose2D { x: 0.0, y: 1.5, theta: 0.0 }).unwrap();
+
+        let mut command = forward(0.0);
+        for _ in 0..20 {
+            let path = planner.plan(&open_space(), &[], None).unwrap();
+            command = controller.execute_plan(&path).unwrap();
+        }
+        assert!(command.angular > 0.0);
+        assert!(command.angular <= config.max_angular_velocity + 1e-6);
+    }
+
+    // An obstacle on the way slows the dynamic window approach and it follows the detour
+    // around it without entering the inflation
+    #[test]
+    fn test_dwa_steers_around_obstacle() {
+        let clear = dwa_drive_around(&[]);
+        assert!(clear.iter().all(|pose| pose.y.abs() < 1e-6));
+
+        let obstacle = Obstacle {
+            position: eos::ros_interface::Pose2D { x: 1.2, y: 0.0, theta: 0.0 },
+            radius: 0.1,
+            confidence: 1.0,
+            velocity: None,
+            category: ObstacleCategory::Small,
+        };
+        let swerved = dwa_drive_around(std::slice::from_ref(&obstacle));
+        assert!(swerved.iter().any(|pose| pose.y.abs() > 0.1));
+        assert!(swerved.iter().all(|pose| {
+            (pose.x - obstacle.position.x).hypot(pose.y - obstacle.position.y) > NavigationConfig::default().obstacle_inflation
+        }));
+        assert!(swerved.last().unwrap().x > obstacle.position.x);
+        assert!(swerved.last().unwrap().x < clear.last().unwrap().x);
+    }
+
+    // A plan reused across cycles is followed from where the robot is now, not where it was
+    // planned from: having drifted left and turned, it steers back right
+    #[test]
+    fn test_reused_plan_steers_from_current_pose() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+        planner.set_goal(eos::ros_interface::Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+        let path = planner.plan(&open_space(), &[], None).unwrap();
+
+        assert_eq!(controller.execute_plan(&path).unwrap().angular, 0.0);
+        controller.set_pose(eos::ros_interface::Pose2D { x: 0.3, y: 0.2, theta: 0.4 });
+        assert!(controller.execute_plan(&path).unwrap().angular < 0.0);
+    }
+
+    // A goal at 90 degrees turns the robot in place first, then drives once it faces the goal
+    #[test]
+    fn test_turns_in_place_before_driving() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+        planner.set_goal(eos::ros_interface::Pose2D { x: 0.0, y: 1.5, theta: 0.0 }).unwrap();
+
+        let mut pose = eos::ros_interface::Pose2D { x: 0.0, y: 0.0, theta: 0.0 };
+        let mut commands = Vec::new();
+        for _ in 0..150 {
+            let path = planner.plan(&open_space(), &[], Some(pose)).unwrap();
+            let command = controller.execute_plan(&path).unwrap();
+            pose.x += command.linear * pose.theta.cos() * 0.1;
+            pose.y += command.linear * pose.theta.sin() * 0.1;
+            pose.theta += command.angular * 0.1;
+            commands.push((command, pose.theta));
+        }
+
+        assert!(commands[0].0.angular > 0.0);
+        // Far from aligned the robot barely creeps forward
+        let unaligned = commands.iter().take_while(|(_, theta)| *theta < 0.6);
+        assert!(unaligned.clone().count() > 1);
+        assert!(unaligned.map(|(command, _)| command.linear).all(|linear| linear < 0.01));
+        // Once facing the goal it drives there
+        assert!(commands.iter().any(|(command, _)| command.linear > 0.1));
+        assert!(pose.x.hypot(pose.y - 1.5) < 0.2);
+    }
+
+    // Rollouts are checked against keep-out zones in the odometry frame, so a zone the
+    // path runs into stops the robot short of it
+    #[test]
+    fn test_dwa_stays_out_of_keepout_zone() {
+        let clear = dwa_drive_toward_zone(None);
+        assert!(clear.last().unwrap().y > 2.4);
+
+        let zones = std::env::temp_dir().join("eos_dwa_keepout.yaml");
+        std::fs::write(&zones, "- name: ramp\n  vertices: [[0.0, 2.0], [2.0, 2.0], [2.0, 2.4], [0.0, 2.4]]\n").unwrap();
+        let fenced = dwa_drive_toward_zone(Some(zones.to_str().unwrap()));
+        assert!(fenced.iter().all(|pose| pose.y < 2.0));
+        assert!(fenced.last().unwrap().y > 1.2);
+    }
+}
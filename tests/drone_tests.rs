@@ -0,0 +1,95 @@
+use eos::apps::eos_drone::{Axis, DroneError, DroneLimits, FrameLayout, MotorMixer};
+use r2r::geometry_msgs::msg::Twist;
+
+/// Twist commanding only a yaw rate
+fn yaw_command(rate: f64) -> Twist {
+    let mut cmd = Twist::default();
+    cmd.angular.z = rate;
+    cmd
+}
+
+/// Twist commanding only a linear velocity
+fn velocity_command(x: f64, y: f64, z: f64) -> Twist {
+    let mut cmd = Twist::default();
+    cmd.linear.x = x;
+    cmd.linear.y = y;
+    cmd.linear.z = z;
+    cmd
+}
+
+/// Writes drone limits JSON to a temporary file and returns its path
+fn limits_file(name: &str, json: &str) -> String {
+    let path = std::env::temp_dir().join(format!("eos_drone_limits_{}.json", name));
+    std::fs::write(&path, json).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pure yaw speeds up one diagonal pair and slows the other equally
+    #[test]
+    fn test_pure_yaw_diagonal_differential() {
+        let mixer = MotorMixer::new(FrameLayout::QuadX);
+
+        let [front_left, front_right, rear_right, rear_left] = mixer.mix(&yaw_command(0.5), (0.0, 0.0, 0.0));
+
+        assert_eq!(front_left, rear_right);
+        assert_eq!(front_right, rear_left);
+        assert!(front_left > front_right);
+        assert!((front_left - mixer.hover_thrust - 0.1).abs() < 1e-6);
+        assert!((mixer.hover_thrust - front_right - 0.1).abs() < 1e-6);
+    }
+
+    // Motor outputs stay normalized under extreme commands
+    #[test]
+    fn test_mixer_clamps_outputs() {
+        let mixer = MotorMixer::new(FrameLayout::QuadPlus);
+        let mut cmd = yaw_command(20.0);
+        cmd.linear.z = 10.0;
+
+        let motors = mixer.mix(&cmd, (0.0, 0.0, 0.0));
+
+        assert!(motors.iter().all(|m| (0.0..=1.0).contains(m)));
+    }
+
+    // Vertical commands beyond the configured climb and descent rates are clamped
+    #[test]
+    fn test_climb_rate_clamped() {
+        let limits = DroneLimits {
+            max_horizontal_speed: 3.0,
+            max_climb_rate: 1.5,
+            max_descent_rate: 0.8,
+        };
+        assert_eq!(limits.max_speed_for(Axis::Climb), 1.5);
+        assert_eq!(limits.max_speed_for(Axis::Descent), 0.8);
+
+        let climb = limits.clamp(&velocity_command(4.0, -1.0, 3.0));
+        assert_eq!((climb.linear.x, climb.linear.y, climb.linear.z), (3.0, -1.0, 1.5));
+
+        let descent = limits.clamp(&velocity_command(0.0, 0.0, -3.0));
+        assert!((descent.linear.z + 0.8).abs() < 1e-6);
+    }
+
+    // Negative or NaN limits are rejected at load instead of panicking in clamp
+    #[test]
+    fn test_invalid_limits_rejected_on_load() {
+        let valid = limits_file("valid", r#"{"max_horizontal_speed": 3.0, "max_climb_rate": 1.5, "max_descent_rate": 0.8}"#);
+        let limits = DroneLimits::load(&valid).unwrap();
+        assert_eq!(limits.max_speed_for(Axis::Descent), 0.8);
+
+        let negative = limits_file("negative", r#"{"max_horizontal_speed": 3.0, "max_climb_rate": -1.5, "max_descent_rate": 0.8}"#);
+        assert!(matches!(
+            DroneLimits::load(&negative),
+            Err(DroneError::InvalidLimit { name: "max_climb_rate", .. })
+        ));
+
+        let not_a_number = DroneLimits {
+            max_horizontal_speed: f32::NAN,
+            max_climb_rate: 1.5,
+            max_descent_rate: 0.8,
+        };
+        assert!(not_a_number.validate().is_err());
+    }
+}
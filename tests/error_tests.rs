@@ -0,0 +1,88 @@
+use eos::EosError;
+use eos::apps::eos_drone::DroneError;
+use eos::apps::eos_indoor::IndoorError;
+use eos::apps::eos_rover::RoverError;
+use eos::navigation::NavigationError;
+use eos::neural::NeuralError;
+use eos::ros_interface::RosError;
+
+/// Convert an app error with `?` the way EosOS callers would
+fn propagate<E>(error: E) -> Result<(), EosError>
+where
+    EosError: From<E>,
+{
+    Err(error)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Indoor errors keep their variant and message inside EosError
+    #[test]
+    fn test_indoor_error_converts() {
+        let err = propagate(IndoorError::SociallyUnacceptable { acceptability: 0.42 }).unwrap_err();
+
+        assert!(matches!(
+            err,
+            EosError::IndoorError(IndoorError::SociallyUnacceptable { .. })
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Indoor app error: Path is not socially acceptable (acceptability 0.42)"
+        );
+    }
+
+    // Drone errors carry the offending rule name through EosError
+    #[test]
+    fn test_drone_error_converts() {
+        let err = propagate(DroneError::RestrictedAirspace("airport".to_string())).unwrap_err();
+
+        assert!(matches!(err, EosError::DroneError(DroneError::RestrictedAirspace(ref name)) if name == "airport"));
+        assert_eq!(err.to_string(), "Drone app error: Restricted airspace: airport");
+    }
+
+    // Rover errors keep the energy figures
+    #[test]
+    fn test_rover_error_converts() {
+        let err = propagate(RoverError::InsufficientEnergy { required: 1.5, budget: 0.8 }).unwrap_err();
+
+        assert!(matches!(err, EosError::RoverError(RoverError::InsufficientEnergy { .. })));
+        assert_eq!(
+            err.to_string(),
+            "Rover app error: Insufficient energy for path: needs 1.50, budget 0.80"
+        );
+    }
+
+    // I/O failures stay reachable inside the app error and are printed once
+    #[test]
+    fn test_io_error_preserved() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "terrain.json missing");
+        let err = propagate(RoverError::ReadTerrainProfiles(io)).unwrap_err();
+
+        assert!(matches!(
+            err,
+            EosError::RoverError(RoverError::ReadTerrainProfiles(ref io)) if io.kind() == std::io::ErrorKind::NotFound
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Rover app error: Failed to read terrain profiles: terrain.json missing"
+        );
+        // Reporters that walk the source chain don't repeat what the message already says
+        assert!(std::error::Error::source(&err).is_none());
+    }
+
+    // Subsystem errors land in their EosError variant with the inner message
+    #[test]
+    fn test_subsystem_errors_convert() {
+        let ros = propagate(RosError::PublishError("cmd_vel".to_string())).unwrap_err();
+        assert!(matches!(ros, EosError::RosError(ref msg) if msg == &RosError::PublishError("cmd_vel".to_string()).to_string()));
+
+        let neural = propagate(NeuralError::NoModelError).unwrap_err();
+        assert!(matches!(neural, EosError::NeuralError(ref msg) if msg == &NeuralError::NoModelError.to_string()));
+
+        let navigation = propagate(NavigationError::InvalidGoal).unwrap_err();
+        assert!(matches!(navigation, EosError::NavigationError(ref msg) if msg == &NavigationError::InvalidGoal.to_string()));
+        assert_eq!(navigation.to_string(), format!("Navigation error: {}", NavigationError::InvalidGoal));
+    }
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use eos::{FallbackConfig, FallbackPolicy};
+
+use common::sensor_data;
+
+/// Fallback config for a policy with the default speeds
+fn fallback(policy: FallbackPolicy) -> FallbackConfig {
+    FallbackConfig {
+        policy,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Empty sensor data triggers the fallback even with plausible neural output
+    #[test]
+    fn test_empty_sensor_data_is_unusable() {
+        let config = FallbackConfig::default();
+        assert!(!config.inputs_usable(&sensor_data(0.0, 0.0, vec![]), &[0.4, 0.1]));
+
+        let mut data = sensor_data(0.0, 0.0, vec![]);
+        data.laser_scan.ranges = vec![f32::INFINITY, 2.0];
+        assert!(config.inputs_usable(&data, &[0.4, 0.1]));
+        assert!(!config.inputs_usable(&data, &[0.0, 0.0]));
+        assert!(!config.inputs_usable(&data, &[f32::NAN, 0.1]));
+    }
+
+    // Stop holds position
+    #[test]
+    fn test_stop_fallback() {
+        let config = fallback(FallbackPolicy::Stop);
+        assert!(!config.inputs_usable(&sensor_data(0.0, 0.0, vec![]), &[]));
+
+        let command = config.command();
+        assert_eq!((command.linear, command.angular), (0.0, 0.0));
+    }
+
+    // Creep forward keeps a slow forward speed and no turn
+    #[test]
+    fn test_creep_forward_fallback() {
+        let config = fallback(FallbackPolicy::CreepForward);
+        assert!(!config.inputs_usable(&sensor_data(0.0, 0.0, vec![]), &[]));
+
+        let command = config.command();
+        assert_eq!((command.linear, command.angular), (config.creep_speed, 0.0));
+        assert!(command.linear > 0.0);
+    }
+
+    // Rotate to search turns in place
+    #[test]
+    fn test_rotate_to_search_fallback() {
+        let config = fallback(FallbackPolicy::RotateToSearch);
+        assert!(!config.inputs_usable(&sensor_data(0.0, 0.0, vec![]), &[]));
+
+        let command = config.command();
+        assert_eq!((command.linear, command.angular), (0.0, config.search_turn_rate));
+        assert!(command.angular > 0.0);
+    }
+}
@@ -0,0 +1,61 @@
+use eos::ros_interface::{parse_imu, ImuAxisRemap, SignedAxis};
+use r2r::sensor_msgs::msg::Imu;
+
+/// IMU message lying flat and yawing left, with gravity on +z
+fn level_yawing_imu() -> Imu {
+    let mut msg = Imu::default();
+    msg.orientation.w = 1.0;
+    msg.angular_velocity.x = 0.1;
+    msg.angular_velocity.y = 0.2;
+    msg.angular_velocity.z = 0.5;
+    msg.linear_acceleration.x = 0.3;
+    msg.linear_acceleration.y = -0.4;
+    msg.linear_acceleration.z = 9.81;
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fields land in their named slots without remapping
+    #[test]
+    fn test_parse_imu_fields() {
+        let sample = parse_imu(&level_yawing_imu());
+
+        assert_eq!(sample.angular_velocity.z, 0.5);
+        assert_eq!(sample.linear_acceleration.x, 0.3);
+        assert_eq!(sample.linear_acceleration.z, 9.81);
+        assert!(sample.orientation.angle() < 1e-12);
+        assert_eq!(ImuAxisRemap::identity().parse(&level_yawing_imu()), sample);
+    }
+
+    // An upside-down IMU reads y and z reversed; the remap restores the base frame
+    #[test]
+    fn test_upside_down_remap_flips_axes() {
+        let remap: ImuAxisRemap = serde_yaml::from_str(r#"["+x", "-y", "-z"]"#).unwrap();
+        assert_eq!(remap, ImuAxisRemap::upside_down());
+
+        let sample = remap.parse(&level_yawing_imu());
+
+        assert_eq!(sample.angular_velocity.x, 0.1);
+        assert_eq!(sample.angular_velocity.y, -0.2);
+        assert_eq!(sample.angular_velocity.z, -0.5);
+        assert_eq!(sample.linear_acceleration.x, 0.3);
+        assert_eq!(sample.linear_acceleration.y, 0.4);
+        assert_eq!(sample.linear_acceleration.z, -9.81);
+
+        // A level IMU mounted upside down means the base is rolled by 180°
+        let (roll, pitch, _) = sample.orientation.euler_angles();
+        assert!((roll.abs() - std::f64::consts::PI).abs() < 1e-9);
+        assert!(pitch.abs() < 1e-9);
+    }
+
+    // Remaps that mirror the frame are rejected
+    #[test]
+    fn test_improper_remap_rejected() {
+        assert!(ImuAxisRemap::new(SignedAxis::PosX, SignedAxis::PosY, SignedAxis::NegZ).is_err());
+        assert!(ImuAxisRemap::new(SignedAxis::PosX, SignedAxis::PosX, SignedAxis::PosZ).is_err());
+        assert!(ImuAxisRemap::new(SignedAxis::PosY, SignedAxis::PosX, SignedAxis::NegZ).is_ok());
+    }
+}
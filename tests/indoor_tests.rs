@@ -0,0 +1,64 @@
+use eos::apps::eos_indoor::{HumanTracker, TrackedHuman};
+
+/// Feed one detection per frame along a straight walk, returning the last track
+fn walk(tracker: &mut HumanTracker, start: (f32, f32), velocity: (f32, f32), frames: usize, dt: f32) -> TrackedHuman {
+    let mut last = None;
+    for k in 0..frames {
+        let t = k as f32 * dt;
+        let position = (start.0 + velocity.0 * t, start.1 + velocity.1 * t);
+        last = tracker.update(&[position], dt).pop();
+    }
+    last.unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A human crossing in front of the robot gets a velocity estimate and a forward prediction
+    #[test]
+    fn test_moving_human_velocity_and_prediction() {
+        let mut tracker = HumanTracker::new();
+
+        let track = walk(&mut tracker, (2.0, -1.0), (0.0, 1.0), 10, 0.1);
+
+        assert_eq!(tracker.tracks().len(), 1);
+        assert!((track.velocity.0).abs() < 1e-3);
+        assert!((track.velocity.1 - 1.0).abs() < 0.01);
+
+        let predicted = track.predict(1.0);
+        assert!((predicted.0 - 2.0).abs() < 1e-3);
+        assert!((predicted.1 - (track.position.1 + 1.0)).abs() < 0.01);
+
+        let horizon = track.predicted_positions(2.0, 0.5);
+        assert_eq!(horizon.len(), 4);
+        for pair in horizon.windows(2) {
+            assert!(pair[1].1 > pair[0].1);
+        }
+    }
+
+    // Two humans keep their own identities when both move
+    #[test]
+    fn test_nearest_neighbor_keeps_identities() {
+        let mut tracker = HumanTracker::new();
+
+        let first = tracker.update(&[(1.0, 0.0), (3.0, 0.0)], 0.1);
+        let second = tracker.update(&[(3.0, 0.1), (1.1, 0.0)], 0.1);
+
+        assert_eq!(second[0].id, first[1].id);
+        assert_eq!(second[1].id, first[0].id);
+        assert!(second[1].velocity.0 > 0.0);
+        assert!(second[0].velocity.1 > 0.0);
+    }
+
+    // A standing human has no velocity and predicts in place
+    #[test]
+    fn test_standing_human_predicts_in_place() {
+        let mut tracker = HumanTracker::new();
+
+        let track = walk(&mut tracker, (1.5, 0.5), (0.0, 0.0), 5, 0.1);
+
+        assert_eq!(track.velocity, (0.0, 0.0));
+        assert_eq!(track.predict(2.0), track.position);
+    }
+}
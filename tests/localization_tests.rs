@@ -0,0 +1,338 @@
+use eos::core::{
+    fuse_pose_measurement, initial_covariance, integrate_imu, loop_closure_noise, predict_covariance, predict_state, scan_match,
+    scan_velocity, DriftEstimator, LaserSimConfig, LaserSimulator, Memory, OccupancyGrid, Pose, PoseConfidence, StateConfig,
+};
+use eos::ros_interface::{parse_imu, ImuAxisRemap, ImuSample};
+use nalgebra::{Matrix3, Vector6};
+use r2r::geometry_msgs::msg::Vector3;
+use r2r::sensor_msgs::msg::Imu;
+
+/// Features observed at a doorway
+fn doorway() -> Vec<String> {
+    vec!["doorway".to_string()]
+}
+
+/// Distance between two poses in the plane
+fn planar_distance(a: &Pose, b: &Pose) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+/// IMU message with the given forward acceleration and yaw rate
+fn imu_message(forward_acceleration: f64, yaw_rate: f64) -> Imu {
+    Imu {
+        angular_velocity: Vector3 { x: 0.0, y: 0.0, z: yaw_rate },
+        linear_acceleration: Vector3 { x: forward_acceleration, y: 0.0, z: 9.81 },
+        ..Default::default()
+    }
+}
+
+/// IMU sample with the given forward acceleration and yaw rate, from an IMU mounted in the base frame
+fn imu_reading(forward_acceleration: f64, yaw_rate: f64) -> ImuSample {
+    parse_imu(&imu_message(forward_acceleration, yaw_rate))
+}
+
+/// Centered 10 m grid with walls ahead, to the left and behind, plus a pillar to break symmetry
+fn room() -> OccupancyGrid {
+    let mut grid = OccupancyGrid::centered(100, 0.1, (0.0, 0.0));
+    for i in 0..100 {
+        grid.set_cell(75, i, 1); // x = 2.5
+        grid.set_cell(i, 70, 1); // y = 2.0
+        grid.set_cell(25, i, 1); // x = -2.5
+    }
+    for col in 60..63 {
+        for row in 35..38 {
+            grid.set_cell(col, row, 1); // Pillar near (1.1, -1.4)
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A drifted estimate revisiting a known node is pulled back toward the node's pose
+    #[test]
+    fn test_loop_closure_corrects_drift() {
+        let mut memory = Memory::new();
+        memory.add_map_node(Pose { x: 0.0, y: 0.0, theta: 0.0 }, vec![]);
+        let node = memory.add_map_node(Pose { x: 3.0, y: 1.0, theta: 0.5 }, vec!["doorway".to_string()]);
+
+        let drifted = PoseConfidence {
+            pose: Pose { x: 3.3, y: 0.8, theta: 0.55 },
+            covariance: Matrix3::from_diagonal_element(0.5),
+        };
+        let (matched, node_pose) = memory.loop_closure_pose(&drifted.pose, &doorway()).expect("loop closure expected");
+        assert_eq!(matched, node);
+
+        let corrected = fuse_pose_measurement(&drifted, &node_pose, 0.05);
+        assert!(planar_distance(&corrected.pose, &node_pose) < 0.2 * planar_distance(&drifted.pose, &node_pose));
+        assert!((corrected.pose.theta - node_pose.theta).abs() < (drifted.pose.theta - node_pose.theta).abs());
+        assert!(corrected.covariance[(0, 0)] < drifted.covariance[(0, 0)]);
+
+        // Far from every node there is nothing to correct with
+        assert!(memory.loop_closure_pose(&Pose { x: 10.0, y: 10.0, theta: 0.0 }, &doorway()).is_none());
+    }
+
+    // A revisit is confirmed when the observed features match the node's
+    #[test]
+    fn test_loop_closure_confirmed_by_features() {
+        let mut memory = Memory::new();
+        let features = vec!["doorway".to_string(), "corner".to_string(), "wall".to_string()];
+        let node = memory.add_map_node(Pose { x: 2.0, y: 0.0, theta: 0.0 }, features);
+
+        let observed = vec!["wall".to_string(), "doorway".to_string()];
+        let closure = memory
+            .check_loop_closure(&Pose { x: 2.3, y: 0.0, theta: 0.05 }, &observed)
+            .expect("loop closure expected");
+        assert_eq!(closure.node_id, node);
+        assert!((closure.geometric_error - 0.3).abs() < 1e-9);
+        assert!((closure.feature_overlap - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    // Being close to a node isn't enough when the surroundings look different
+    #[test]
+    fn test_loop_closure_rejects_feature_mismatch() {
+        let mut memory = Memory::new();
+        memory.add_map_node(Pose { x: 2.0, y: 0.0, theta: 0.0 }, vec!["doorway".to_string(), "corner".to_string()]);
+        let pose = Pose { x: 2.1, y: 0.0, theta: 0.0 };
+
+        assert!(memory.check_loop_closure(&pose, &["wall".to_string(), "corner".to_string()]).is_none());
+        assert!(memory.check_loop_closure(&pose, &[]).is_none());
+
+        // A lower threshold accepts the partial match
+        memory.set_min_feature_overlap(0.3);
+        let closure = memory.check_loop_closure(&pose, &["wall".to_string(), "corner".to_string()]);
+        assert!(closure.is_some_and(|c| (c.feature_overlap - 1.0 / 3.0).abs() < 1e-9));
+    }
+
+    // Repeated labels count, so the same kinds of feature in different numbers don't match
+    #[test]
+    fn test_loop_closure_counts_repeated_features() {
+        let labels = |labels: &[&str]| labels.iter().map(|label| label.to_string()).collect::<Vec<_>>();
+        let mut memory = Memory::new();
+        memory.add_map_node(Pose { x: 2.0, y: 0.0, theta: 0.0 }, labels(&["corner", "wall", "wall"]));
+        let pose = Pose { x: 2.1, y: 0.0, theta: 0.0 };
+
+        let closure = memory.check_loop_closure(&pose, &labels(&["wall", "corner"]));
+        assert!(closure.is_some_and(|c| (c.feature_overlap - 2.0 / 3.0).abs() < 1e-9));
+        assert!(memory
+            .check_loop_closure(&pose, &labels(&["corner", "corner", "corner", "wall"]))
+            .is_none());
+    }
+
+    // Heading corrections take the short way across the +-pi wrap-around
+    #[test]
+    fn test_pose_correction_wraps_heading() {
+        let estimate = PoseConfidence {
+            pose: Pose { x: 0.0, y: 0.0, theta: 3.1 },
+            covariance: Matrix3::identity(),
+        };
+        let measured = Pose { x: 0.0, y: 0.0, theta: -3.0 };
+
+        // Halfway round the short way crosses pi and comes back wrapped
+        let corrected = fuse_pose_measurement(&estimate, &measured, 1.0);
+        let expected = 3.1 + (2.0 * std::f64::consts::PI - 6.1) / 2.0 - 2.0 * std::f64::consts::PI;
+        assert!((corrected.pose.theta - expected).abs() < 1e-9, "theta {}", corrected.pose.theta);
+    }
+
+    // Lingering near a node closes the loop once; leaving and coming back closes it again
+    #[test]
+    fn test_loop_closure_applied_once_per_revisit() {
+        let mut memory = Memory::new();
+        let node = memory.add_map_node(Pose { x: 3.0, y: 1.0, theta: 3.1 }, doorway());
+        let near = Pose { x: 3.1, y: 1.0, theta: -3.13 }; // Within 0.1 rad across the wrap-around
+
+        let mut corrections = 0;
+        for pose in [&near, &near, &near] {
+            let closure = memory.loop_closure_pose(pose, &doorway());
+            assert_eq!(closure.as_ref().map(|(id, _)| *id), Some(node));
+            if memory.mark_loop_closure(closure.map(|(id, _)| id)) {
+                corrections += 1;
+            }
+        }
+        assert_eq!(corrections, 1);
+
+        assert!(!memory.mark_loop_closure(None));
+        assert!(memory.mark_loop_closure(Some(node)));
+    }
+
+    // Dead reckoning without corrections drifts at a positive rate that grows as the wheels slip
+    #[test]
+    fn test_prediction_only_drift_rate() {
+        let process_noise = Matrix3::from_diagonal_element(0.1);
+        let mut covariance = Matrix3::identity();
+        let mut drift = DriftEstimator::new(5);
+        assert_eq!(drift.drift_rate(), 0.0);
+
+        let mut rates = Vec::new();
+        for step in 0..30 {
+            let slip = step as f32 / 30.0;
+            covariance = predict_covariance(&covariance, &process_noise, slip, 0.1);
+            drift.update(&covariance, 0.1);
+            rates.push(drift.drift_rate());
+        }
+
+        assert!(rates[1..].iter().all(|rate| *rate > 0.0));
+        assert!(rates[5..].windows(2).all(|pair| pair[1] > pair[0]));
+
+        // Without slip three axes of 0.1 per second grow the trace by 0.3 per second,
+        // however the time is split into steps
+        let mut steady = DriftEstimator::new(5);
+        let mut covariance = Matrix3::identity();
+        for _ in 0..10 {
+            covariance = predict_covariance(&covariance, &process_noise, 0.0, 0.05);
+            steady.update(&covariance, 0.05);
+        }
+        assert!((steady.drift_rate() - 0.3).abs() < 1e-9);
+    }
+
+    // Constant IMU readings advance the predicted pose: accelerating from rest, then
+    // cruising at constant velocity, then turning at a constant rate
+    #[test]
+    fn test_imu_prediction_advances_pose() {
+        let mut state = Vector6::zeros();
+        for _ in 0..10 {
+            state = predict_state(&state, Some(&imu_reading(1.0, 0.0)), 0.1);
+        }
+        assert!((state[0] - 0.5).abs() < 1e-9);
+        assert!((state[3] - 1.0).abs() < 1e-9);
+
+        for _ in 0..10 {
+            state = predict_state(&state, Some(&imu_reading(0.0, 0.0)), 0.1);
+        }
+        assert!((state[0] - 1.5).abs() < 1e-9);
+        assert!(state[1].abs() < 1e-9);
+
+        // Turning without acceleration keeps the world-frame velocity
+        for _ in 0..10 {
+            state = predict_state(&state, Some(&imu_reading(0.0, 0.5)), 0.1);
+        }
+        assert!((state[2] - 0.5).abs() < 1e-9);
+        assert!((state[0] - 2.5).abs() < 1e-9);
+
+        // Without a reading the state coasts at the last rates
+        let coasted = predict_state(&state, None, 0.1);
+        assert!((coasted[0] - 2.6).abs() < 1e-9);
+        assert!((coasted[2] - 0.55).abs() < 1e-9);
+    }
+
+    // Every sample between updates counts, and an upside-down IMU is read through its remap
+    #[test]
+    fn test_imu_samples_integrated_in_base_frame() {
+        // Ten samples over one 0.1 s update match ten 0.01 s predictions
+        let samples = vec![imu_reading(1.0, 0.5); 10];
+        let mut stepped = Vector6::zeros();
+        for sample in &samples {
+            stepped = predict_state(&stepped, Some(sample), 0.01);
+        }
+        let integrated = integrate_imu(&Vector6::zeros(), &samples, 0.1);
+        assert!((integrated - stepped).norm() < 1e-12);
+        assert!((integrated[2] - 0.05).abs() < 1e-9);
+
+        // Mounted upside down, the raw gyro reads the turn reversed
+        let mut upside_down = imu_message(1.0, -0.5);
+        upside_down.linear_acceleration.z = -9.81;
+        let remapped = vec![ImuAxisRemap::upside_down().parse(&upside_down); 10];
+        let from_remapped = integrate_imu(&Vector6::zeros(), &remapped, 0.1);
+        assert!((from_remapped - integrated).norm() < 1e-12);
+
+        // No samples: coast at the current velocity
+        let moving = Vector6::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.2);
+        let coasted = integrate_imu(&moving, &[], 0.5);
+        assert!((coasted[0] - 0.5).abs() < 1e-9 && (coasted[2] - 0.1).abs() < 1e-9);
+    }
+
+    // The motion between two matched scans over the time between them gives the velocity
+    #[test]
+    fn test_scan_velocity() {
+        let previous = Pose { x: 1.0, y: 2.0, theta: 3.1 };
+        let current = Pose { x: 1.2, y: 1.9, theta: -3.1 };
+        let velocity = scan_velocity(&previous, &current, 0.2).unwrap();
+        assert!((velocity.x - 1.0).abs() < 1e-9);
+        assert!((velocity.y + 0.5).abs() < 1e-9);
+        // Across the ±π wrap the heading turned forward by 0.083 rad
+        assert!((velocity.z - (2.0 * std::f64::consts::PI - 6.2) / 0.2).abs() < 1e-9);
+        assert!(scan_velocity(&previous, &current, 0.0).is_none());
+    }
+
+    // Matching scans taken at two poses recovers the motion between them
+    #[test]
+    fn test_scan_match_recovers_motion() {
+        let mut sim = LaserSimulator::new(LaserSimConfig::default());
+        let before = sim.simulate(&room(), &Pose { x: 0.0, y: 0.0, theta: 0.0 });
+        let after = sim.simulate(&room(), &Pose { x: 0.12, y: 0.05, theta: 0.04 });
+
+        let delta = scan_match(&before, &after).expect("scans overlap");
+        assert!((delta.x - 0.12).abs() < 0.03);
+        assert!((delta.y - 0.05).abs() < 0.03);
+        assert!((delta.theta - 0.04).abs() < 0.02);
+
+        // An empty scan has nothing to match
+        let mut empty = after.clone();
+        empty.ranges = vec![empty.range_max; empty.ranges.len()];
+        assert!(scan_match(&before, &empty).is_none());
+    }
+
+    // Confidence is 1 for a certain pose, 0.5 at 1 m² of position or 0.25 rad² of heading
+    // variance, and falls toward 0 beyond
+    #[test]
+    fn test_confidence_scalar_from_variances() {
+        let with_variances = |position: f64, heading: f64| PoseConfidence {
+            pose: Pose { x: 0.0, y: 0.0, theta: 0.0 },
+            covariance: Matrix3::from_diagonal(&nalgebra::Vector3::new(position / 2.0, position / 2.0, heading)),
+        };
+
+        assert_eq!(with_variances(0.0, 0.0).confidence_scalar(), 1.0);
+        assert!((with_variances(1.0, 0.0).confidence_scalar() - 0.5).abs() < 1e-12);
+        assert!((with_variances(0.0, 0.25).confidence_scalar() - 0.5).abs() < 1e-12);
+        let falling: Vec<f64> = [0.1, 1.0, 10.0, 1000.0].iter().map(|v| with_variances(*v, *v).confidence_scalar()).collect();
+        assert!(falling.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(falling.iter().all(|c| (0.0..=1.0).contains(c)));
+        assert_eq!(with_variances(f64::INFINITY, 0.0).confidence_scalar(), 0.0);
+
+        // A fresh estimate is confident enough to navigate, not on the verge of Lost
+        let fresh = PoseConfidence {
+            pose: Pose { x: 0.0, y: 0.0, theta: 0.0 },
+            covariance: initial_covariance(),
+        };
+        assert!(fresh.confidence_scalar() > StateConfig::default().restore_threshold);
+    }
+
+    // Dead reckoning with biased IMU readings drifts off a straight run; closing the loop at a
+    // known node shrinks the error, more so the more familiar the node
+    #[test]
+    fn test_loop_closure_after_gyro_drift() {
+        let mut memory = Memory::new();
+        let node = memory.add_map_node(Pose { x: 3.0, y: 0.0, theta: 0.0 }, vec!["doorway".to_string()]);
+        let familiarity = memory.familiarity(node).unwrap();
+
+        // Drive 3 m straight at 1 m/s while the accelerometer and gyro read small biases
+        let process_noise = Matrix3::from_diagonal_element(0.1);
+        let mut state = Vector6::new(0.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+        let mut covariance = Matrix3::from_diagonal_element(0.01);
+        for _ in 0..30 {
+            state = predict_state(&state, Some(&imu_reading(0.05, 0.02)), 0.1);
+            covariance = predict_covariance(&covariance, &process_noise, 0.0, 0.1);
+        }
+        let drifted = PoseConfidence {
+            pose: Pose { x: state[0], y: state[1], theta: state[2] },
+            covariance,
+        };
+        let (matched, node_pose) = memory.loop_closure_pose(&drifted.pose, &doorway()).expect("loop closure expected");
+        assert_eq!(matched, node);
+        let drift = planar_distance(&drifted.pose, &node_pose);
+        assert!(drift > 0.05);
+
+        let corrected = fuse_pose_measurement(&drifted, &node_pose, loop_closure_noise(familiarity));
+        assert!(planar_distance(&corrected.pose, &node_pose) < 0.3 * drift);
+        assert!(corrected.pose.theta.abs() < drifted.pose.theta.abs());
+
+        // A barely familiar node only nudges the estimate
+        let nudged = fuse_pose_measurement(&drifted, &node_pose, loop_closure_noise(0.05));
+        let nudged_error = planar_distance(&nudged.pose, &node_pose);
+        assert!(nudged_error < drift);
+        assert!(nudged_error > planar_distance(&corrected.pose, &node_pose));
+        assert_eq!(loop_closure_noise(0.0), loop_closure_noise(0.05));
+    }
+}
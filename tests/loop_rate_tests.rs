@@ -0,0 +1,63 @@
+mod common;
+
+use std::time::Duration;
+
+use eos::navigation::{MotionController, NavigationConfig, NavigationPlanner, Path};
+use eos::ros_interface::SensorData;
+use eos::{Clock, LoopPacer, LoopRateConfig, MotionState};
+
+use common::{sensor_data, FakeClock};
+
+/// Sensor data with a single forward beam at the given range
+fn forward_return(range: f32) -> SensorData {
+    sensor_data(0.0, 0.1, vec![range])
+}
+
+/// Count control cycles executed during one second of fake time
+fn cycles_per_second(controller: &mut MotionController, path: &Path, clock: &mut FakeClock) -> usize {
+    let mut pacer = LoopPacer::new(LoopRateConfig::default());
+    let end = clock.now() + Duration::from_secs(1);
+    let mut cycles = 0;
+    while clock.now() < end {
+        controller.execute_plan(path).unwrap();
+        pacer.pace(clock, controller.motion_state());
+        cycles += 1;
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Moving into avoidance raises the effective loop rate
+    #[test]
+    fn test_rate_rises_when_avoiding() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut controller = MotionController::new(&config);
+        let mut clock = FakeClock::new();
+
+        let clear = planner.plan(&forward_return(9.0), &[], None).unwrap();
+        let moving_rate = cycles_per_second(&mut controller, &clear, &mut clock);
+        assert_eq!(controller.motion_state(), MotionState::Moving);
+
+        // An obstacle inside the safety margin still allows a path, but forces avoidance
+        let tight = planner.plan(&forward_return(0.7), &[], None).unwrap();
+        let avoiding_rate = cycles_per_second(&mut controller, &tight, &mut clock);
+        assert_eq!(controller.motion_state(), MotionState::Avoiding);
+
+        assert!(avoiding_rate > moving_rate);
+        assert!((9..=11).contains(&moving_rate));
+        assert!((19..=21).contains(&avoiding_rate));
+    }
+
+    // Stopped and emergency states map to their configured periods
+    #[test]
+    fn test_period_for_state() {
+        let rates = LoopRateConfig::default();
+
+        assert_eq!(rates.period_for(MotionState::Stopped), Duration::from_millis(500));
+        assert_eq!(rates.period_for(MotionState::EmergencyStopping), Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,164 @@
+use eos::core::{Memory, MemoryFormat, Pose};
+
+/// Path in the temp directory for a memory file of the given test
+fn memory_path(name: &str, extension: &str) -> String {
+    std::env::temp_dir().join(format!("eos_memory_{}.{}", name, extension)).to_string_lossy().into_owned()
+}
+
+/// Drives the trajectory in straight 0.1 m steps through the given waypoints
+fn drive(memory: &mut Memory, waypoints: &[(f64, f64)]) {
+    for leg in waypoints.windows(2) {
+        let ((x0, y0), (x1, y1)) = (leg[0], leg[1]);
+        let steps = ((x1 - x0).hypot(y1 - y0) / 0.1).ceil() as usize;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            memory.add_pose(Pose { x: x0 + t * (x1 - x0), y: y0 + t * (y1 - y0), theta: 0.0 });
+        }
+    }
+}
+
+/// Memory with `nodes` featured nodes, a full trajectory, and a few loop closures
+fn large_memory(nodes: u64) -> Memory {
+    let mut memory = Memory::new();
+    let labels = ["wall", "corner", "doorway", "corner"];
+    for i in 0..nodes {
+        let pose = Pose { x: i as f64 * 0.37, y: (i as f64 * 0.11).sin(), theta: (i as f64 * 0.013) % 3.0 };
+        let features = labels.iter().take(1 + (i % 4) as usize).map(|label| label.to_string()).collect();
+        memory.add_map_node(pose, features);
+        if i > 0 {
+            memory.add_edge(i - 1, i);
+        }
+    }
+    for i in 0..150 {
+        memory.add_pose(Pose { x: i as f64 * 0.1, y: 1.0 / 3.0, theta: -0.1 * i as f64 });
+    }
+    for i in (0..nodes).step_by(1000) {
+        memory.record_loop_closure(i, nodes - 1 - i);
+    }
+    memory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Driving between nodes links them, and routes follow the shortest chain of links
+    #[test]
+    fn test_trajectory_links_nodes_for_shortest_route() {
+        let mut memory = Memory::new();
+        let a = memory.add_map_node(Pose { x: 0.0, y: 0.0, theta: 0.0 }, vec![]);
+        let b = memory.add_map_node(Pose { x: 4.0, y: 0.0, theta: 0.0 }, vec![]);
+        let c = memory.add_map_node(Pose { x: 4.0, y: 4.0, theta: 0.0 }, vec![]);
+        let d = memory.add_map_node(Pose { x: 0.0, y: 5.0, theta: 0.0 }, vec![]);
+        let e = memory.add_map_node(Pose { x: 2.0, y: 2.0, theta: 0.0 }, vec![]);
+
+        // Nothing is linked before the robot has moved between nodes
+        assert_eq!(memory.find_route(a, c), None);
+
+        // A loop around the outside, then the diagonal from a to c through e
+        drive(&mut memory, &[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 5.0), (0.0, 0.0)]);
+        assert_eq!(memory.find_route(a, c), Some(vec![a, b, c]));
+        drive(&mut memory, &[(0.0, 0.0), (4.0, 4.0)]);
+        assert_eq!(memory.find_route(a, c), Some(vec![a, e, c]));
+        assert_eq!(memory.find_route(b, d), Some(vec![b, c, d]));
+    }
+
+    // Explicit edges give the shortest route by distance, not the fewest hops
+    #[test]
+    fn test_find_route_over_added_edges() {
+        let mut memory = Memory::new();
+        let a = memory.add_map_node(Pose { x: 0.0, y: 0.0, theta: 0.0 }, vec![]);
+        let b = memory.add_map_node(Pose { x: 1.0, y: 3.0, theta: 0.0 }, vec![]);
+        let c = memory.add_map_node(Pose { x: 1.0, y: 0.5, theta: 0.0 }, vec![]);
+        let d = memory.add_map_node(Pose { x: 2.0, y: 0.0, theta: 0.0 }, vec![]);
+        let e = memory.add_map_node(Pose { x: 4.0, y: 0.0, theta: 0.0 }, vec![]);
+        for (from, to) in [(a, b), (b, e), (a, c), (c, d), (d, e)] {
+            assert!(memory.add_edge(from, to));
+        }
+        assert!(!memory.add_edge(a, 99));
+
+        assert_eq!(memory.find_route(a, e), Some(vec![a, c, d, e]));
+        assert_eq!(memory.find_route(e, a), Some(vec![e, d, c, a]));
+        assert_eq!(memory.find_route(a, a), Some(vec![a]));
+        assert_eq!(memory.find_route(a, 99), None);
+    }
+
+    // The trajectory keeps only the configured number of most recent poses
+    #[test]
+    fn test_trajectory_capacity_is_configurable() {
+        let mut memory = Memory::with_capacity(500);
+        for i in 0..600 {
+            memory.add_pose(Pose { x: i as f64, y: 0.0, theta: 0.0 });
+        }
+        assert_eq!(memory.trajectory_len(), 500);
+        assert_eq!(memory.get_trajectory().iter().next().map(|pose| pose.x), Some(100.0));
+
+        // The default keeps 100
+        let mut memory = Memory::new();
+        for i in 0..150 {
+            memory.add_pose(Pose { x: i as f64, y: 0.0, theta: 0.0 });
+        }
+        assert_eq!(memory.trajectory_len(), 100);
+    }
+
+    // YAML written before the trajectory kept its capacity still loads
+    #[test]
+    fn test_load_legacy_yaml_trajectory() {
+        let path = memory_path("legacy", "yaml");
+        let legacy = "topological_map: {}\n\
+            trajectory:\n\
+            - x: 0.0\n  y: 0.0\n  theta: 0.0\n\
+            - x: 0.5\n  y: 0.0\n  theta: 0.1\n\
+            loop_closures: []\n\
+            node_counter: 0\n";
+        std::fs::write(&path, legacy).unwrap();
+
+        let memory = Memory::load(&path).unwrap();
+        assert_eq!(memory.trajectory_len(), 2);
+        assert_eq!(memory.get_trajectory().capacity(), 100);
+        assert_eq!(memory.get_trajectory().last(), Some(&Pose { x: 0.5, y: 0.0, theta: 0.1 }));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    // A write that fails only when the buffer is flushed is still reported
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_save_reports_failed_flush() {
+        assert!(Memory::new().save("/dev/full").is_err());
+    }
+
+    // Files ending in .bin are binary; everything else stays YAML
+    #[test]
+    fn test_memory_format_from_extension() {
+        assert_eq!(MemoryFormat::from_path("maps/office.bin"), MemoryFormat::Bincode);
+        assert_eq!(MemoryFormat::from_path("maps/office.yaml"), MemoryFormat::Yaml);
+        assert_eq!(MemoryFormat::from_path("maps/office"), MemoryFormat::Yaml);
+    }
+
+    // A 10k node map survives both formats unchanged, and the binary file is at least a third smaller
+    #[test]
+    fn test_binary_round_trip_is_lossless_and_smaller() {
+        let memory = large_memory(10_000);
+        let yaml_path = memory_path("large", "yaml");
+        let bin_path = memory_path("large", "bin");
+        memory.save(&yaml_path).unwrap();
+        memory.save(&bin_path).unwrap();
+
+        let from_yaml = Memory::load(&yaml_path).unwrap();
+        let from_bin = Memory::load(&bin_path).unwrap();
+        assert_eq!(from_bin, memory);
+        assert_eq!(from_yaml, memory);
+        assert_eq!(from_bin.get_topological_map().len(), 10_000);
+        assert_eq!(from_bin.trajectory_len(), 100);
+        assert_eq!(from_bin.loop_closures(), memory.loop_closures());
+        assert_eq!(from_bin.find_route(0, 3), Some(vec![0, 1, 2, 3]));
+
+        let yaml_size = std::fs::metadata(&yaml_path).unwrap().len();
+        let bin_size = std::fs::metadata(&bin_path).unwrap().len();
+        assert!(bin_size * 3 < yaml_size * 2, "binary {} bytes vs YAML {} bytes", bin_size, yaml_size);
+
+        std::fs::remove_file(yaml_path).ok();
+        std::fs::remove_file(bin_path).ok();
+    }
+}
@@ -1,23 +0,0 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    // Unit test for the PathPlanner
-    #[test]
-    fn test_path_planning() {
-        // Create a dummy or mock localizer
-        let localizer = Localizer::new(/* TODO: provide mock parameters */);
-
-        // Initialize spatial memory with capacity 100
-        let spatial_memory = SpatialMemory::new(100);
-
-        // Create a path planner with the localizer and spatial memory
-        let mut planner = PathPlanner::new(localizer, spatial_memory);
-
-        // Plan a path to a target position
-        let path = planner.plan_path((1.0, 1.0)).unwrap();
-
-        // Check that the resulting path has at least 2 points
-        assert!(path.len() >= 2);
-    }
-}
@@ -1,3 +1,44 @@
+mod common;
+
+use eos::neural::encoding::closeness;
+use eos::neural::{Activation, BeamSampling, NeuralConfig, NeuralError, NeuralOutput, SNNEngine, SpikeEncoding};
+
+use common::sensor_data;
+
+/// Initialized engine on a generated model with the given seed
+fn seeded_engine(seed: u64) -> SNNEngine {
+    let config = NeuralConfig {
+        seed: Some(seed),
+        ..Default::default()
+    };
+    let mut engine = SNNEngine::new(&config).unwrap();
+    engine.initialize().unwrap();
+    engine
+}
+
+/// Write a two-input, one-hidden-layer model using `activation` and return its path
+fn write_layered_model(activation: &str) -> String {
+    let model = serde_json::json!({
+        // Hidden layer passes both inputs straight through
+        "hidden_weights": [[[1.0, 0.0], [0.0, 1.0]]],
+        // Outputs: 5 * h1, -h1, 0.3 * h0
+        "weights": [[0.0, 0.0, 0.3], [5.0, -1.0, 0.0]],
+        "states": [0.0, 0.0],
+        "thresholds": [0.5, 0.5],
+        "metadata": {
+            "name": "layered",
+            "version": "1.0",
+            "trained_on": "hand_written",
+            "accuracy": 0.0,
+            "activation": activation,
+        },
+    });
+
+    let path = std::env::temp_dir().join(format!("eos_layered_{}.json", activation));
+    std::fs::write(&path, model.to_string()).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,19 +46,389 @@ mod tests {
     // Unit test for the SNNEngine
     #[test]
     fn test_snn_engine() {
-        // Use default config
-        let config = SNNConfig::default();
+        let mut engine = seeded_engine(1);
+
+        // Example sensor input, padded up to the configured input size
+        let result = engine.process(&sensor_data(0.0, 0.0, vec![0.1, 0.2, 0.3])).unwrap();
+
+        // Verify output has the configured size
+        assert_eq!(result.len(), NeuralConfig::default().output_size);
+        assert!(engine.get_status().model_loaded);
+    }
+
+    // Engines generated from the same seed agree on every input
+    #[test]
+    fn test_seeded_engines_match() {
+        let mut first = seeded_engine(42);
+        let mut second = seeded_engine(42);
+
+        for k in 1..5 {
+            let data = sensor_data(0.0, 0.0, (0..100).map(|j| ((j * k) % 9) as f32 * 0.5).collect());
+            assert_eq!(first.process(&data).unwrap(), second.process(&data).unwrap());
+        }
+    }
+
+    // Processing before initialization is refused
+    #[test]
+    fn test_process_requires_initialization() {
+        let mut engine = SNNEngine::new(&NeuralConfig::default()).unwrap();
+        assert!(engine.process(&sensor_data(0.0, 0.0, vec![1.0])).is_err());
+    }
+
+    // Input (3, -2) through the same weights under each hidden activation
+    #[test]
+    fn test_hidden_activations() {
+        let config = NeuralConfig {
+            input_size: 2,
+            output_size: 3,
+            ..Default::default()
+        };
+
+        // relu (3, 0), sigmoid (0.95, 0.12), tanh (0.995, -0.96), threshold (1, 0)
+        let cases = [
+            ("relu", vec![0.0, 0.0, 1.0]),
+            ("sigmoid", vec![1.0, 0.0, 0.0]),
+            ("tanh", vec![0.0, 1.0, 0.0]),
+            ("threshold", vec![0.0, 0.0, 0.0]),
+        ];
+        for (activation, expected) in cases {
+            let mut engine = SNNEngine::new(&config).unwrap();
+            engine.load_model(&write_layered_model(activation)).unwrap();
+            engine.initialize().unwrap();
+
+            // Without range limits the raw values pass through as network inputs
+            let mut data = sensor_data(0.0, 0.0, vec![3.0, -2.0]);
+            data.laser_scan.range_min = 0.0;
+            data.laser_scan.range_max = 0.0;
+            let output = engine.process(&data).unwrap();
+            assert_eq!(output, expected, "activation {}", activation);
+        }
+
+        assert_eq!(Activation::Relu.apply(-1.0, 0.5), 0.0);
+        assert_eq!(Activation::Threshold.apply(0.6, 0.5), 1.0);
+    }
+
+    // Both outputs spike, but the one driven far past threshold is the more confident
+    #[test]
+    fn test_strong_output_is_more_confident() {
+        let config = NeuralConfig {
+            input_size: 2,
+            output_size: 3,
+            ..Default::default()
+        };
+        let mut engine = SNNEngine::new(&config).unwrap();
+        engine.load_model(&write_layered_model("relu")).unwrap();
+        engine.initialize().unwrap();
+
+        // Potentials: 5.0 (strong), -1.0 (silent), 0.6 (marginal) against threshold 0.5
+        let NeuralOutput { values, confidence } =
+            engine.process_with_confidence(&sensor_data(0.0, 0.0, vec![2.0, 1.0])).unwrap();
+        assert_eq!(values, vec![1.0, 0.0, 1.0]);
+        assert!(confidence[0] > 0.9);
+        assert_eq!(confidence[1], 0.0);
+        assert!(confidence[2] > 0.0 && confidence[2] < 0.5);
+    }
+
+    // A checkpoint is written once the interval passes and reloads to the same model
+    #[test]
+    fn test_periodic_checkpoint() {
+        let path = std::env::temp_dir().join("eos_checkpoint_model.json").to_string_lossy().into_owned();
+        let _ = std::fs::remove_file(&path);
+        let interval = std::time::Duration::from_millis(50);
+        let mut engine = seeded_engine(11);
+        engine.enable_checkpointing(path.clone(), interval);
+
+        let start = std::time::Instant::now();
+        assert!(!engine.checkpoint_if_due(start).unwrap());
+        assert!(!std::path::Path::new(&path).exists());
+        assert!(engine.checkpoint_if_due(start + interval * 2).unwrap());
+        assert!(!engine.checkpoint_if_due(start + interval * 2).unwrap());
+
+        // Reloaded weights match the running engine, both saved and in use
+        let current = std::env::temp_dir().join("eos_checkpoint_current.json").to_string_lossy().into_owned();
+        engine.save_model(&current).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), std::fs::read_to_string(&current).unwrap());
+
+        let mut reloaded = SNNEngine::new(&NeuralConfig::default()).unwrap();
+        reloaded.load_model(&path).unwrap();
+        reloaded.initialize().unwrap();
+        let data = sensor_data(0.0, 0.0, vec![0.4, 1.2, 3.0, 0.8]);
+        assert_eq!(reloaded.process(&data).unwrap(), engine.process(&data).unwrap());
+    }
+
+    // A failing checkpoint waits twice as long before each retry instead of trying every cycle
+    #[test]
+    fn test_failed_checkpoint_backs_off() {
+        let path = std::env::temp_dir().join("eos_missing_dir").join("model.json").to_string_lossy().into_owned();
+        let interval = std::time::Duration::from_secs(10);
+        let mut engine = seeded_engine(11);
+        engine.enable_checkpointing(path, interval);
+        let start = std::time::Instant::now();
+        let at = |secs: u64| start + std::time::Duration::from_secs(secs);
+
+        assert!(engine.checkpoint_if_due(at(10)).is_err());
+        assert!(!engine.checkpoint_if_due(at(11)).unwrap());
+        assert!(!engine.checkpoint_if_due(at(29)).unwrap());
+        assert!(engine.checkpoint_if_due(at(30)).is_err());
+        assert!(!engine.checkpoint_if_due(at(69)).unwrap());
+        assert!(engine.checkpoint_if_due(at(70)).is_err());
+    }
+
+    // With a 360-beam scan and 100 inputs, an obstacle behind the robot still shows up
+    #[test]
+    fn test_rear_obstacle_reaches_input() {
+        let mut ranges = vec![8.0; 360];
+        ranges[350] = 0.4; // One beam near the end of the scan
+
+        let pooled = seeded_engine(3).preprocess_sensor_data(&sensor_data(0.0, 0.0, ranges.clone()));
+        assert_eq!(pooled.len(), 100);
+        assert_eq!(pooled[97], 0.4); // Bin covering beams 349..352
+        assert_eq!(pooled.iter().filter(|r| **r == 0.4).count(), 1);
+        assert!(pooled.iter().all(|r| *r > 0.0));
+
+        let truncating = SNNEngine::new(&NeuralConfig {
+            beam_sampling: BeamSampling::Truncate,
+            ..Default::default()
+        })
+        .unwrap();
+        let truncated = truncating.preprocess_sensor_data(&sensor_data(0.0, 0.0, ranges));
+        assert!(truncated.iter().all(|r| *r == 8.0));
+    }
+
+    // NaN, infinite and below-minimum readings aren't taken as the closest return in a bin
+    #[test]
+    fn test_min_pool_skips_invalid_readings() {
+        let mut ranges = vec![8.0; 360];
+        ranges[0] = f32::NAN;
+        ranges[1] = 0.0; // Below range_min: no return
+        ranges[7] = f32::NAN;
+        ranges[8] = 0.5;
+        ranges[10..14].fill(f32::INFINITY);
+
+        let pooled = seeded_engine(3).preprocess_sensor_data(&sensor_data(0.0, 0.0, ranges));
+        assert_eq!(pooled[0], 8.0); // Beams 0..3
+        assert_eq!(pooled[2], 0.5); // Beams 7..10
+        assert_eq!(pooled[3], 10.0); // Beams 10..14: nothing returned, so clear to range_max
+    }
+
+    // A second initialize is refused, and processing stops after shutdown
+    #[test]
+    fn test_double_init_and_use_after_shutdown() {
+        let mut engine = seeded_engine(5);
+        assert!(matches!(engine.initialize(), Err(NeuralError::AlreadyInitialized)));
+
+        engine.shutdown().unwrap();
+        assert!(matches!(engine.process(&sensor_data(0.0, 0.0, vec![1.0; 10])), Err(NeuralError::NotInitialized)));
+        assert!(matches!(engine.shutdown(), Err(NeuralError::NotInitialized)));
+
+        // The engine can be brought back up
+        engine.initialize().unwrap();
+        assert!(engine.process(&sensor_data(0.0, 0.0, vec![1.0; 10])).is_ok());
+    }
+
+    // A repeated input/output pairing strengthens its weight every cycle, within the
+    // configured range, and the learned weights survive a save and reload
+    #[test]
+    fn test_stdp_strengthens_correlated_weight() {
+        let model_path = write_layered_model("relu");
+        let config = NeuralConfig {
+            input_size: 2,
+            output_size: 3,
+            learning_rate: 0.1,
+            weight_range: (-1.0, 0.5),
+            ..Default::default()
+        };
+        let mut engine = SNNEngine::new(&config).unwrap();
+        engine.load_model(&model_path).unwrap();
+        engine.initialize().unwrap();
+        assert!(engine.apply_stdp(&[1.0], &[1.0, 0.0, 0.0]).is_err());
+
+        // Input 0 fires with output 0 every cycle; output 1 never fires
+        let mut correlated = vec![engine.output_weight(0, 0).unwrap()];
+        for _ in 0..8 {
+            engine.apply_stdp(&[1.0, 0.0], &[1.0, 0.0, 0.0]).unwrap();
+            correlated.push(engine.output_weight(0, 0).unwrap());
+        }
+        assert!(correlated.windows(2).all(|pair| pair[1] > pair[0] || pair[1] == 0.5), "{:?}", correlated);
+        assert!(correlated[8] > correlated[0]);
+        assert_eq!(engine.output_weight(0, 1), Some(0.0));
+        // The hand-written 5.0 weight is left alone while input 1 stays silent; an input
+        // spike that follows output 0's earlier spikes weakens it, clamped into range
+        assert_eq!(engine.output_weight(1, 0), Some(5.0));
+        engine.apply_stdp(&[0.0, 1.0], &[0.0, 1.0, 0.0]).unwrap();
+        assert_eq!(engine.output_weight(1, 0), Some(0.5));
+        engine.apply_stdp(&[0.0, 1.0], &[0.0, 1.0, 0.0]).unwrap();
+        assert!(engine.output_weight(1, 0).unwrap() < 0.5);
+
+        // Enough repetitions saturate at the top of the range
+        for _ in 0..50 {
+            engine.apply_stdp(&[1.0, 0.0], &[1.0, 0.0, 0.0]).unwrap();
+        }
+        assert_eq!(engine.output_weight(0, 0), Some(0.5));
+
+        let saved = std::env::temp_dir().join("eos_stdp_model.json").to_string_lossy().into_owned();
+        engine.save_model(&saved).unwrap();
+        let mut reloaded = SNNEngine::new(&config).unwrap();
+        reloaded.load_model(&saved).unwrap();
+        assert_eq!(reloaded.output_weight(0, 0), Some(0.5));
+        assert_eq!(reloaded.output_weight(1, 0), engine.output_weight(1, 0));
+    }
+
+    // Closer obstacles spike more often under rate coding and sooner under latency coding
+    #[test]
+    fn test_spike_encodings_favor_close_obstacles() {
+        let spikes = |train: &[f32]| train.iter().filter(|v| **v == 1.0).count();
+        let first = |train: &[f32]| train.iter().position(|v| *v == 1.0);
+        let near = closeness(1.0, 0.05, 10.0);
+        let far = closeness(8.0, 0.05, 10.0);
+        assert!(near > far);
+
+        let (near_rate, far_rate) = (SpikeEncoding::Rate.encode(near, 10), SpikeEncoding::Rate.encode(far, 10));
+        assert_eq!(near_rate.len(), 10);
+        assert!(spikes(&near_rate) > spikes(&far_rate));
+        assert_eq!(spikes(&SpikeEncoding::Rate.encode(1.0, 10)), 10);
+        assert_eq!(spikes(&SpikeEncoding::Rate.encode(0.0, 10)), 0);
+
+        let (near_latency, far_latency) = (SpikeEncoding::Latency.encode(near, 10), SpikeEncoding::Latency.encode(far, 10));
+        assert_eq!(spikes(&near_latency), 1);
+        assert!(first(&near_latency) < first(&far_latency));
+        assert_eq!(first(&SpikeEncoding::Latency.encode(0.0, 10)), None);
+
+        // Garbage readings are clamped into a valid train instead of spiking at random
+        assert_eq!(closeness(f32::NAN, 0.05, 10.0), 0.0);
+        assert_eq!(closeness(f32::INFINITY, 0.05, 10.0), 0.0);
+        assert_eq!(closeness(-3.0, 0.05, 10.0), 1.0);
+        assert_eq!(closeness(2.0, 0.0, 0.0), 0.0);
+        for encoding in [SpikeEncoding::Rate, SpikeEncoding::Latency] {
+            assert_eq!(spikes(&encoding.encode(f32::NAN, 10)), 0);
+            assert_eq!(encoding.encode(7.5, 10), encoding.encode(1.0, 10));
+            assert!(encoding.encode(-1.0, 10).iter().all(|v| *v == 0.0));
+        }
+    }
+
+    // A spike-encoded scan runs through the network as one frame per time step
+    #[test]
+    fn test_spike_encoded_processing() {
+        let config = NeuralConfig {
+            seed: Some(3),
+            encoding: SpikeEncoding::Rate,
+            time_steps: 8,
+            ..Default::default()
+        };
+        let mut engine = SNNEngine::new(&config).unwrap();
+        engine.initialize().unwrap();
 
-        // Create a new engine
-        let mut engine = SNNEngine::new(config);
+        let data = sensor_data(0.0, 0.0, vec![0.5, f32::NAN, 20.0, 3.0]);
+        let input = engine.preprocess_sensor_data(&data);
+        assert_eq!(input.len(), config.input_size);
+        assert!(input.iter().all(|v| (0.0..=1.0).contains(v)));
+
+        let frames = engine.encode_spike_trains(&input);
+        assert_eq!(frames.len(), 8);
+        assert!(frames.iter().all(|frame| frame.len() == config.input_size));
+        assert!(frames.iter().flatten().all(|v| *v == 0.0 || *v == 1.0));
+
+        let output = engine.process_with_confidence(&data).unwrap();
+        assert_eq!(output.values.len(), config.output_size);
+        assert!(output.values.iter().all(|v| *v == 0.0 || *v == 1.0));
+        assert!(output.confidence.iter().all(|c| c.is_finite() && (0.0..=1.0).contains(c)));
+    }
+
+    // A .bin model round-trips exactly and is smaller than the same model as JSON
+    #[test]
+    fn test_bincode_model_round_trip() {
+        let dir = std::env::temp_dir();
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        let engine = seeded_engine(21);
+        engine.save_model(&path("eos_roundtrip.bin")).unwrap();
+        engine.save_model(&path("eos_roundtrip.json")).unwrap();
+
+        let mut reloaded = SNNEngine::new(&NeuralConfig::default()).unwrap();
+        reloaded.load_model(&path("eos_roundtrip.bin")).unwrap();
+        reloaded.save_model(&path("eos_roundtrip_reloaded.json")).unwrap();
+
+        // Same weights and metadata, down to the last bit of every float
+        assert_eq!(
+            std::fs::read_to_string(path("eos_roundtrip.json")).unwrap(),
+            std::fs::read_to_string(path("eos_roundtrip_reloaded.json")).unwrap()
+        );
+        assert_eq!(reloaded.output_weight(3, 7), engine.output_weight(3, 7));
+
+        let size = |name: &str| std::fs::metadata(path(name)).unwrap().len();
+        assert!(size("eos_roundtrip.bin") < size("eos_roundtrip.json"));
+
+        // A JSON file named .bin is rejected rather than misread
+        std::fs::copy(path("eos_roundtrip.json"), path("eos_mislabeled.bin")).unwrap();
+        assert!(matches!(reloaded.load_model(&path("eos_mislabeled.bin")), Err(NeuralError::ParseError(_))));
+    }
+
+    // A model whose layers don't fit the configured input or output size is refused
+    #[test]
+    fn test_load_rejects_mismatched_dimensions() {
+        let model = write_layered_model("relu");
+        let load = |input_size: usize, output_size: usize| {
+            let mut engine = SNNEngine::new(&NeuralConfig { input_size, output_size, ..Default::default() }).unwrap();
+            engine.load_model(&model)
+        };
+
+        assert!(load(2, 3).is_ok());
+        assert!(matches!(load(100, 3), Err(NeuralError::DimensionMismatch { expected: 100, found: 2 })));
+        assert!(matches!(load(2, 10), Err(NeuralError::DimensionMismatch { expected: 10, found: 3 })));
+
+        // A ragged output layer is caught too
+        let ragged = serde_json::json!({
+            "weights": [[0.0, 0.0, 0.3], [5.0, -1.0]],
+            "states": [0.0, 0.0],
+            "thresholds": [0.5, 0.5],
+            "metadata": { "name": "ragged", "version": "2.0", "trained_on": "hand_written", "accuracy": 0.0 },
+        });
+        let path = std::env::temp_dir().join("eos_ragged_model.json");
+        std::fs::write(&path, ragged.to_string()).unwrap();
+        let mut engine = SNNEngine::new(&NeuralConfig { input_size: 2, output_size: 3, ..Default::default() }).unwrap();
+        let result = engine.load_model(&path.to_string_lossy());
+        assert!(matches!(result, Err(NeuralError::DimensionMismatch { expected: 3, found: 2 })));
+        assert!(!engine.get_status().model_loaded);
+    }
+
+    // Processing time is measured into the status and can be cleared for benchmarking
+    #[test]
+    fn test_processing_latency_is_reported() {
+        let mut engine = seeded_engine(2);
+        assert_eq!(engine.get_status().processing_latency, 0.0);
+
+        for _ in 0..5 {
+            engine.process(&sensor_data(0.0, 0.0, vec![0.5; 10])).unwrap();
+        }
+        let latency = engine.get_status().processing_latency;
+        assert!(latency > 0.0 && latency.is_finite());
+
+        engine.reset_latency_stats();
+        assert_eq!(engine.get_status().processing_latency, 0.0);
+    }
+
+    // Batch inference matches frame-by-frame processing and leaves the buffers alone
+    #[test]
+    fn test_batch_matches_per_frame() {
+        for encoding in [SpikeEncoding::Raw, SpikeEncoding::Rate] {
+            let config = NeuralConfig {
+                seed: Some(4),
+                encoding,
+                ..Default::default()
+            };
+            let mut engine = SNNEngine::new(&config).unwrap();
+            engine.initialize().unwrap();
 
-        // Example sensor input
-        let sensor_data = vec![0.1, 0.2, 0.3];
+            let frames: Vec<_> = (0..5).map(|i| sensor_data(0.0, 0.0, vec![0.5 + i as f32; 20])).collect();
+            let inputs: Vec<_> = frames.iter().map(|data| engine.preprocess_sensor_data(data)).collect();
+            let batch = engine.process_batch(&inputs).unwrap();
+            assert_eq!(engine.get_status().input_buffer_size, 0);
+            assert_eq!(engine.get_status().output_buffer_size, 0);
 
-        // Process the sensor data through the engine
-        let result = engine.process_sensor_data(&sensor_data).unwrap();
+            let per_frame: Vec<_> = frames.iter().map(|data| engine.process(data).unwrap()).collect();
+            assert_eq!(batch, per_frame);
+        }
 
-        // Verify output has expected size (2 outputs, e.g. linear + angular velocity)
-        assert_eq!(result.len(), 2);
+        let mut engine = seeded_engine(4);
+        assert!(matches!(engine.process_batch(&[vec![0.0; 3]]), Err(NeuralError::ProcessingError(_))));
     }
 }
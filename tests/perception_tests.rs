@@ -0,0 +1,370 @@
+use eos::core::{Memory, OccupancyGrid, Perception, Pose, SemanticObject, Snapshot, SnapshotDiff};
+use nalgebra::Vector2;
+use r2r::sensor_msgs::msg::{LaserScan, PointCloud2, PointField};
+
+/// Write a core config file for the given test, with extra YAML lines, and return its path
+fn write_config(name: &str, extra: &str) -> String {
+    let path = std::env::temp_dir().join(format!("eos_{}.yaml", name));
+    std::fs::write(
+        &path,
+        format!(
+            "lidar_topic: /scan\nimu_topic: /imu\ngrid_resolution: 0.1\ngrid_size: 100\nsensor_noise: 0.1\nprocess_noise: 0.01\n{}",
+            extra
+        ),
+    )
+    .unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+/// Create a perception module on a fresh ROS node
+fn perception(name: &str) -> Perception {
+    perception_with(name, "")
+}
+
+/// Create a perception module with extra config lines
+fn perception_with(name: &str, extra: &str) -> Perception {
+    let ctx = r2r::Context::create().unwrap();
+    let mut node = r2r::Node::create(ctx, name, "").unwrap();
+    Perception::new(&mut node, &write_config(name, extra)).unwrap()
+}
+
+/// Scan from the inside of a room corner formed by walls at x = 2 and y = 2
+fn corner_scan() -> LaserScan {
+    let increment = 0.02;
+    let ranges = (0..=78)
+        .map(|i| {
+            let angle = i as f32 * increment;
+            (2.0 / angle.cos().max(1e-6)).min(2.0 / angle.sin().max(1e-6))
+        })
+        .collect();
+    LaserScan {
+        angle_min: 0.0,
+        angle_max: 78.0 * increment,
+        angle_increment: increment,
+        range_min: 0.05,
+        range_max: 10.0,
+        ranges,
+        ..Default::default()
+    }
+}
+
+/// Scan of a single straight wall at x = 2 over ±0.6 rad
+fn wall_scan() -> LaserScan {
+    let increment = 0.02;
+    let ranges = (0..=60).map(|i| 2.0 / (-0.6 + i as f32 * increment).cos()).collect();
+    LaserScan {
+        angle_min: -0.6,
+        angle_max: 0.6,
+        angle_increment: increment,
+        range_min: 0.05,
+        range_max: 10.0,
+        ranges,
+        ..Default::default()
+    }
+}
+
+/// Unorganized float32 xyz cloud from a list of points
+fn xyz_cloud(points: &[(f32, f32, f32)]) -> PointCloud2 {
+    let field = |name: &str, offset: u32| PointField {
+        name: name.to_string(),
+        offset,
+        datatype: PointField::FLOAT32,
+        count: 1,
+    };
+    let data = points
+        .iter()
+        .flat_map(|(x, y, z)| [x.to_le_bytes(), y.to_le_bytes(), z.to_le_bytes()].concat())
+        .collect();
+    PointCloud2 {
+        height: 1,
+        width: points.len() as u32,
+        fields: vec![field("x", 0), field("y", 4), field("z", 8)],
+        is_bigendian: false,
+        point_step: 12,
+        row_step: 12 * points.len() as u32,
+        data,
+        is_dense: true,
+        ..Default::default()
+    }
+}
+
+/// Scan of 61 beams over ±0.3 rad with every beam returning at the given range
+fn flat_scan(range: f32) -> LaserScan {
+    LaserScan {
+        angle_min: -0.3,
+        angle_max: 0.3,
+        angle_increment: 0.01,
+        range_min: 0.05,
+        range_max: 10.0,
+        ranges: vec![range; 61],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A room corner yields a single corner and the two walls meeting there
+    #[test]
+    fn test_extract_corner_feature() {
+        let perception = perception("extract_corner_feature");
+
+        let features = perception.extract_features(&corner_scan());
+
+        assert_eq!(features.iter().filter(|f| *f == "corner").count(), 1);
+        assert_eq!(features.iter().filter(|f| *f == "wall").count(), 2);
+    }
+
+    // Features extracted at a remembered place confirm a revisit there, while a different
+    // view from the same pose doesn't
+    #[test]
+    fn test_extracted_features_confirm_loop_closure() {
+        let perception = perception("extracted_features_confirm_loop_closure");
+        let mut memory = Memory::new();
+        let pose = Pose { x: 1.0, y: 1.0, theta: 0.0 };
+
+        let node = memory
+            .remember_place(&pose, &perception.extract_features(&corner_scan()))
+            .expect("new place remembered");
+        assert!(memory.remember_place(&pose, &perception.extract_features(&corner_scan())).is_none());
+
+        let revisit = Pose { x: 1.2, y: 1.0, theta: 0.05 };
+        let closure = memory.check_loop_closure(&revisit, &perception.extract_features(&corner_scan()));
+        assert_eq!(closure.map(|c| c.node_id), Some(node));
+
+        let wall_only = perception.extract_features(&wall_scan());
+        assert_eq!(wall_only, vec!["wall".to_string()]);
+        assert!(memory.check_loop_closure(&revisit, &wall_only).is_none());
+    }
+
+    // A grid centered on the start pose represents obstacles behind and to the left
+    #[test]
+    fn test_centered_grid_maps_negative_coordinates() {
+        let corner = OccupancyGrid::new(100, 100, 0.1, (0.0, 0.0));
+        let centered = OccupancyGrid::centered(100, 0.1, (0.0, 0.0));
+
+        assert_eq!(corner.world_to_cell(-2.05, -3.05), None);
+        assert_eq!(centered.world_to_cell(-2.05, -3.05), Some((29, 19)));
+
+        let (x, y) = centered.cell_to_world(29, 19);
+        assert!((x + 2.05).abs() < 1e-9 && (y + 3.05).abs() < 1e-9);
+    }
+
+    // Points on or past any edge of an offset grid map to no cell
+    #[test]
+    fn test_world_to_cell_bounds() {
+        let grid = OccupancyGrid::new(20, 10, 0.5, (-2.0, 1.0)); // Covers x -2..8, y 1..6
+
+        assert_eq!(grid.world_to_cell(-2.0, 1.0), Some((0, 0)));
+        assert_eq!(grid.world_to_cell(7.99, 5.99), Some((19, 9)));
+        for (x, y) in [(-2.01, 3.0), (8.0, 3.0), (3.0, 0.99), (3.0, 6.0), (f64::NAN, 3.0), (3.0, f64::INFINITY)] {
+            assert_eq!(grid.world_to_cell(x, y), None, "({}, {}) should be outside", x, y);
+        }
+        assert_eq!(grid.get_cell(20, 0), None);
+        assert_eq!(grid.get_cell(0, 10), None);
+
+        // Every cell center maps back to its own cell
+        for (col, row) in [(0, 0), (19, 9), (7, 4)] {
+            let (x, y) = grid.cell_to_world(col, row);
+            assert_eq!(grid.world_to_cell(x, y), Some((col, row)));
+        }
+    }
+
+    // The ROS map carries the grid's layout and 0-100 probabilities with -1 for unknown cells
+    #[test]
+    fn test_to_ros_occupancy_grid() {
+        let mut grid = OccupancyGrid::new(4, 3, 0.25, (-1.0, 2.0));
+        grid.set_cell(1, 0, 0);
+        grid.set_cell(3, 2, 1);
+        grid.update_log_odds(2, 1, 3.5, -2.0, 3.5);
+
+        let map = grid.to_ros_occupancy_grid("map", std::time::Duration::from_millis(1500));
+        assert_eq!(map.header.frame_id, "map");
+        assert_eq!((map.header.stamp.sec, map.header.stamp.nanosec), (1, 500_000_000));
+        assert_eq!((map.info.width, map.info.height), (4, 3));
+        assert_eq!(map.info.resolution, 0.25);
+        assert_eq!((map.info.origin.position.x, map.info.origin.position.y), (-1.0, 2.0));
+        assert_eq!(map.info.origin.orientation.w, 1.0);
+
+        assert_eq!(map.data.len(), 12);
+        assert_eq!(map.data[0], -1);
+        assert_eq!(map.data[1], 30); // Free threshold
+        assert_eq!(map.data[2 * 4 + 3], 70); // Occupied threshold
+        assert_eq!(map.data[4 + 2], 97); // Clamped at the maximum log-odds
+    }
+
+    // One flipped cell and one moved object are the only reported changes
+    #[test]
+    fn test_snapshot_diff() {
+        let mut grid = OccupancyGrid::new(10, 10, 0.1, (0.0, 0.0));
+        grid.set_cell(2, 3, 0);
+        grid.set_cell(4, 4, 1);
+        let chair = SemanticObject::new(1, "chair", Vector2::new(1.0, 1.0));
+        let wall = SemanticObject::new(2, "wall", Vector2::new(0.0, 2.0));
+        let before = Snapshot::new(grid.clone(), vec![chair, wall.clone()]);
+
+        // The chair is pushed half a meter and lands on a previously free cell
+        grid.set_cell(2, 3, 1);
+        grid.set_cell(7, 7, 0); // Newly explored, not a change
+        let moved_chair = SemanticObject::new(1, "chair", Vector2::new(1.5, 1.0));
+        let after = Snapshot::new(grid, vec![moved_chair, wall]);
+
+        let diff = before.diff(&after);
+
+        assert_eq!(
+            diff,
+            SnapshotDiff {
+                flipped_cells: vec![(2, 3)],
+                appeared: vec![],
+                disappeared: vec![],
+                moved: vec![1],
+            }
+        );
+        assert!(before.diff(&before).is_empty());
+    }
+
+    // Points at obstacle height occupy cells, ground points free them, overhead points are ignored
+    #[test]
+    fn test_integrate_pointcloud() {
+        let mut perception = perception("integrate_pointcloud");
+        let pose = Pose { x: 2.0, y: 2.0, theta: std::f64::consts::FRAC_PI_2 };
+
+        let cloud = xyz_cloud(&[
+            (1.0, 0.0, 0.5),  // Box ahead -> world (2.0, 3.0)
+            (0.5, -0.5, 0.0), // Floor -> world (2.5, 2.5)
+            (0.0, 1.0, 2.5),  // Overhead lamp -> world (1.0, 2.0)
+            (1.0, 0.0, 0.0),  // Floor under the box, still occupied
+        ]);
+        perception.integrate_pointcloud(&cloud, &pose).unwrap();
+
+        let snapshot = perception.get_snapshot();
+        let grid = snapshot.grid();
+        let cell_at = |x: f64, y: f64| {
+            let (col, row) = grid.world_to_cell(x, y).unwrap();
+            grid.get_cell(col, row).unwrap()
+        };
+        assert_eq!(cell_at(2.05, 3.05), 1);
+        assert_eq!(cell_at(2.55, 2.55), 0);
+        assert_eq!(cell_at(1.05, 2.05), -1);
+    }
+
+    // Driving up to the edge of the 10 m grid enlarges it and keeps the cells already mapped
+    #[test]
+    fn test_grid_grows_on_edge() {
+        let mut perception = perception_with("grid_grows_on_edge", "grow_on_edge: true\n");
+        perception
+            .integrate_pointcloud(&xyz_cloud(&[(-3.0, -2.0, 0.5)]), &Pose { x: 5.0, y: 5.0, theta: 0.0 })
+            .unwrap();
+
+        // Box just past the +x edge at world (10.5, 5.0)
+        let pose = Pose { x: 9.5, y: 5.0, theta: 0.0 };
+        perception.integrate_pointcloud(&xyz_cloud(&[(1.0, 0.0, 0.5)]), &pose).unwrap();
+
+        let snapshot = perception.get_snapshot();
+        let grid = snapshot.grid();
+        assert!(grid.width() > 100);
+        assert_eq!(grid.height(), 100);
+        assert_eq!(grid.origin(), (0.0, 0.0));
+        let cell_at = |x: f64, y: f64| {
+            let (col, row) = grid.world_to_cell(x, y).unwrap();
+            grid.get_cell(col, row).unwrap()
+        };
+        assert_eq!(cell_at(2.05, 3.05), 1);
+        assert_eq!(cell_at(10.55, 5.05), 1);
+    }
+
+    // Growth stops at the maximum size, recycling the cells farthest behind the robot
+    #[test]
+    fn test_grid_growth_capped() {
+        let mut perception = perception_with("grid_growth_capped", "grow_on_edge: true\nmax_grid_size: 150\n");
+        let mark = |perception: &mut Perception, x: f64| {
+            perception
+                .integrate_pointcloud(&xyz_cloud(&[(1.0, 0.0, 0.5)]), &Pose { x, y: 5.0, theta: 0.0 })
+                .unwrap();
+        };
+        mark(&mut perception, 0.5);
+        mark(&mut perception, 9.5);
+        assert_eq!(perception.get_snapshot().grid().width(), 150);
+
+        mark(&mut perception, 14.5);
+        let snapshot = perception.get_snapshot();
+        let grid = snapshot.grid();
+        assert_eq!((grid.width(), grid.height()), (150, 100));
+        assert!(grid.world_to_cell(1.55, 5.05).is_none());
+        let cell_at = |x: f64, y: f64| {
+            let (col, row) = grid.world_to_cell(x, y).unwrap();
+            grid.get_cell(col, row).unwrap()
+        };
+        assert_eq!(cell_at(10.55, 5.05), 1);
+        assert_eq!(cell_at(15.55, 5.05), 1);
+    }
+
+    // Without growth, points beyond the edge are dropped and the grid keeps its size
+    #[test]
+    fn test_grid_fixed_without_growth() {
+        let mut perception = perception("grid_fixed_without_growth");
+        let pose = Pose { x: 9.5, y: 5.0, theta: 0.0 };
+        perception.integrate_pointcloud(&xyz_cloud(&[(1.0, 0.0, 0.5)]), &pose).unwrap();
+
+        let snapshot = perception.get_snapshot();
+        assert_eq!(snapshot.grid().width(), 100);
+        assert!(snapshot.grid().world_to_cell(10.55, 5.05).is_none());
+    }
+
+    // A return seen in several scans becomes occupied, cells along the beam become free,
+    // and a one-off return fades once later scans see through it
+    #[test]
+    fn test_scan_log_odds_updates() {
+        let mut perception = perception("scan_log_odds_updates");
+        let pose = Pose { x: 5.0, y: 5.0, theta: 0.0 };
+        let cell_at = |perception: &Perception, x: f64, y: f64| {
+            let snapshot = perception.get_snapshot();
+            let (col, row) = snapshot.grid().world_to_cell(x, y).unwrap();
+            (snapshot.grid().get_cell(col, row).unwrap(), snapshot.grid().probability(col, row).unwrap())
+        };
+
+        // A spurious return 1.5 m ahead
+        perception.integrate_scan(&flat_scan(1.55), &pose);
+        let (value, noise) = cell_at(&perception, 6.55, 5.05);
+        assert_eq!(value, -1); // One observation is not enough
+        assert!(noise > 0.5 && noise < 0.7);
+
+        // The wall 2 m ahead at world x = 7.0, seen through the spurious return
+        for _ in 0..5 {
+            perception.integrate_scan(&flat_scan(2.05), &pose);
+        }
+        let (value, wall) = cell_at(&perception, 7.05, 5.05);
+        assert_eq!(value, 1);
+        assert!(wall > 0.9);
+        assert!(wall < 0.98); // Clamped at the maximum log-odds
+        assert_eq!(cell_at(&perception, 6.55, 5.05).0, 0);
+        assert_eq!(cell_at(&perception, 6.05, 5.05).0, 0);
+        assert_eq!(cell_at(&perception, 7.55, 5.05).0, -1); // Behind the wall
+    }
+
+    // A person walking at (1.0, 0.5) m/s keeps one track whose velocity and prediction follow them
+    #[test]
+    fn test_tracks_moving_object() {
+        let mut perception = perception("tracks_moving_object");
+        let wall = SemanticObject::new(7, "wall", Vector2::new(4.0, 0.0));
+
+        for step in 0..3 {
+            let t = step as f64 * 0.5;
+            let person = SemanticObject::new(3, "person", Vector2::new(1.0 + t, 2.0 + 0.5 * t));
+            perception.track_objects(&[person, wall.clone()], 0.5);
+        }
+
+        let snapshot = perception.get_snapshot();
+        let tracked = snapshot.dynamic_objects();
+        assert_eq!(tracked.len(), 2);
+        let person = tracked.iter().find(|object| object.class == "person").unwrap();
+        assert!((person.position - Vector2::new(2.0, 2.5)).norm() < 0.05);
+        assert!((person.velocity - Vector2::new(1.0, 0.5)).norm() < 0.15);
+        assert!((person.predicted_position - Vector2::new(3.0, 3.0)).norm() < 0.2);
+        assert_eq!(person.missed_updates, 0);
+
+        let wall = tracked.iter().find(|object| object.class == "wall").unwrap();
+        assert!(wall.velocity.norm() < 1e-6);
+    }
+}
@@ -0,0 +1,658 @@
+mod common;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use eos::core::{LaserSimConfig, Mode, OccupancyGrid, Pose, PoseConfidence, SimulatedRobot};
+use eos::navigation::{MotionState, SafetyStatus};
+use eos::neural::{NeuralConfig, SNNEngine};
+use eos::ros_interface::{CommandSink, ImuAxisRemap, MotionCommand, Pose2D, RobotInterface, RosError, RosStatus, SensorData, SensorSource};
+use eos::{Clock, EosConfig, EosError, EosOS, LoopPacer, LoopRateConfig, PanicCause, RateDivisors};
+use nalgebra::Matrix3;
+
+use common::{sensor_data, FakeClock};
+
+/// Robot interface that replays scripted scans and records published commands
+struct ScriptedInterface {
+    scans: RefCell<VecDeque<SensorData>>,
+    commands: RefCell<Vec<MotionCommand>>,
+    statuses: RefCell<Vec<String>>,
+    odometry: RefCell<Vec<(Pose2D, Matrix3<f64>)>>,
+    maps: RefCell<Vec<r2r::nav_msgs::msg::OccupancyGrid>>,
+    /// Between initialize and shutdown, which fail out of order like the real interface
+    initialized: bool,
+    /// Signals new sensor data to awaiting cycles when set
+    #[cfg(feature = "async")]
+    updates: Option<tokio::sync::watch::Sender<u64>>,
+}
+
+impl ScriptedInterface {
+    fn new(scans: Vec<SensorData>) -> Self {
+        ScriptedInterface {
+            scans: RefCell::new(scans.into()),
+            commands: RefCell::new(Vec::new()),
+            statuses: RefCell::new(Vec::new()),
+            odometry: RefCell::new(Vec::new()),
+            maps: RefCell::new(Vec::new()),
+            initialized: false,
+            #[cfg(feature = "async")]
+            updates: None,
+        }
+    }
+}
+
+impl SensorSource for ScriptedInterface {
+    fn get_sensor_data(&self) -> Result<SensorData, RosError> {
+        self.scans
+            .borrow_mut()
+            .pop_front()
+            .ok_or_else(|| RosError::SubscribeError("script exhausted".to_string()))
+    }
+
+    fn get_current_pose(&self) -> Result<Pose2D, RosError> {
+        Ok(Pose2D { x: 0.0, y: 0.0, theta: 0.0 })
+    }
+}
+
+impl CommandSink for ScriptedInterface {
+    fn publish_command(&self, command: &MotionCommand) -> Result<(), RosError> {
+        self.commands.borrow_mut().push(*command);
+        Ok(())
+    }
+}
+
+impl RobotInterface for ScriptedInterface {
+    fn initialize(&mut self) -> Result<(), RosError> {
+        if self.initialized {
+            return Err(RosError::AlreadyInitialized);
+        }
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn publish_odometry(
+        &self,
+        pose: &Pose2D,
+        covariance: &Matrix3<f64>,
+        _velocity: &MotionCommand,
+        _stamp: Duration,
+    ) -> Result<(), RosError> {
+        self.odometry.borrow_mut().push((*pose, *covariance));
+        Ok(())
+    }
+
+    fn publish_map(&self, map: &r2r::nav_msgs::msg::OccupancyGrid) -> Result<(), RosError> {
+        self.maps.borrow_mut().push(map.clone());
+        Ok(())
+    }
+
+    fn publish_status(&self, status_json: &str) -> Result<(), RosError> {
+        self.statuses.borrow_mut().push(status_json.to_string());
+        Ok(())
+    }
+
+    fn get_status(&self) -> RosStatus {
+        RosStatus {
+            connected: true,
+            publishers_count: 0,
+            subscribers_count: 0,
+            last_message_time: None,
+        }
+    }
+
+    fn shutdown(&mut self) -> Result<(), RosError> {
+        if !self.initialized {
+            return Err(RosError::NotInitialized);
+        }
+        self.initialized = false;
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    fn sensor_updates(&self) -> Option<tokio::sync::watch::Receiver<u64>> {
+        self.updates.as_ref().map(|updates| updates.subscribe())
+    }
+}
+
+/// Forward-facing scan with every beam at the given range
+fn scan_at(range: f32) -> SensorData {
+    sensor_data(-1.5, 0.1, vec![range; 30])
+}
+
+/// Scan at the given range with odometry too uncertain to navigate on
+fn uncertain_scan_at(range: f32) -> SensorData {
+    let mut scan = scan_at(range);
+    let mut covariance = vec![0.0; 36];
+    for axis in [0, 7, 35] {
+        covariance[axis] = 100.0;
+    }
+    scan.odom_data.pose.covariance = covariance;
+    scan
+}
+
+/// Initialized system on a seeded default model and the given scan script
+fn scripted_system(scans: Vec<SensorData>) -> EosOS<ScriptedInterface> {
+    scripted_system_with_divisors(scans, RateDivisors::default())
+}
+
+/// Config for scripted systems: a seeded neural engine without a model file
+fn scripted_config(rate_divisors: RateDivisors) -> EosConfig {
+    EosConfig {
+        neural_config: NeuralConfig {
+            model_path: None,
+            seed: Some(7),
+            ..Default::default()
+        },
+        rate_divisors,
+        ..Default::default()
+    }
+}
+
+/// Initialized scripted system with the given perception and planning divisors
+fn scripted_system_with_divisors(scans: Vec<SensorData>, rate_divisors: RateDivisors) -> EosOS<ScriptedInterface> {
+    let config = scripted_config(rate_divisors);
+    let mut system = EosOS::with_interface(config, ScriptedInterface::new(scans)).unwrap();
+    system.initialize().unwrap();
+    system
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Open space ramps speed up; an obstacle ahead brings it down and the robot avoids
+    #[test]
+    fn test_run_cycle_open_space_then_obstacle() {
+        let mut scans = vec![scan_at(10.0); 10];
+        scans.extend(vec![scan_at(0.5); 10]);
+        let mut system = scripted_system(scans);
+
+        for _ in 0..20 {
+            system.run_cycle().unwrap();
+        }
+        assert!(system.run_cycle().is_err()); // Script exhausted
+
+        let commands = system.interface().commands.borrow().clone();
+        assert_eq!(commands.len(), 20);
+
+        // Acceleration-limited ramp in open space
+        for pair in commands[..10].windows(2) {
+            assert!(pair[1].linear > pair[0].linear);
+        }
+        assert!(commands.iter().all(|c| c.angular == 0.0));
+
+        // Half the safety margin left halves the speed cap, below the open-space peak
+        let peak = commands[9].linear;
+        assert!(commands[19].linear < peak);
+        assert!((commands[19].linear - 0.25).abs() < 1e-5);
+        for pair in commands[10..].windows(2) {
+            assert!(pair[1].linear <= pair[0].linear);
+        }
+
+        let status = system.get_status();
+        assert_eq!(status.navigation.motion_state, MotionState::Avoiding);
+        assert_eq!(status.navigation.safety_status, SafetyStatus::Warning);
+    }
+
+    // The same script and seed always produce the same commands
+    #[test]
+    fn test_run_cycle_is_deterministic() {
+        let script = || {
+            let mut scans = vec![scan_at(10.0); 5];
+            scans.extend(vec![scan_at(0.8); 5]);
+            scans
+        };
+
+        let mut first = scripted_system(script());
+        let mut second = scripted_system(script());
+        for _ in 0..10 {
+            first.run_cycle().unwrap();
+            second.run_cycle().unwrap();
+        }
+
+        assert_eq!(*first.interface().commands.borrow(), *second.interface().commands.borrow());
+    }
+
+    // A 20 Hz loop perceives at 10 Hz and plans at 2 Hz, controlling every cycle on the last plan
+    #[test]
+    fn test_planning_runs_at_divided_rate() {
+        let divisors = RateDivisors { perception: 2, planning: 10 };
+        let mut system = scripted_system_with_divisors(vec![scan_at(10.0); 10], divisors);
+
+        let mut clock = FakeClock::new();
+        let mut pacer = LoopPacer::new(LoopRateConfig {
+            stopped_hz: 20.0,
+            moving_hz: 20.0,
+            avoiding_hz: 20.0,
+            approaching_goal_hz: 20.0,
+            emergency_hz: 20.0,
+        });
+
+        // One second of fake time
+        pacer.pace(&mut clock, MotionState::Stopped);
+        let end = clock.now() + Duration::from_secs(1);
+        while clock.now() < end {
+            system.run_cycle().unwrap();
+            pacer.pace(&mut clock, MotionState::Moving);
+        }
+
+        let counts = system.cycle_counts();
+        assert_eq!(counts.cycles, 20);
+        assert_eq!(counts.perception_updates, 10);
+        assert_eq!(counts.plan_updates, 2);
+        assert_eq!(system.interface().commands.borrow().len(), 20);
+        assert!(system.interface().scans.borrow().is_empty());
+
+        // Commands between plan updates keep ramping along the reused plan
+        let commands = system.interface().commands.borrow();
+        for pair in commands[..10].windows(2) {
+            assert!(pair[1].linear > pair[0].linear);
+        }
+        drop(commands);
+
+        // An obstacle showing up between plan updates stops the robot without waiting to replan;
+        // odometry shows it moving so the stuck monitor stays out of it
+        let moving = |range| {
+            let mut scan = scan_at(range);
+            scan.odom_data.twist.twist.linear.x = 0.5;
+            scan
+        };
+        system.interface().scans.borrow_mut().extend([moving(10.0), moving(0.15)]);
+        system.run_cycle().unwrap();
+        system.run_cycle().unwrap();
+        let before = system.interface().commands.borrow().last().unwrap().linear;
+        let error = system.run_cycle().unwrap_err();
+        assert!(error.to_string().contains("Emergency stop engaged"));
+        assert_eq!(system.cycle_counts().plan_updates, 3);
+        assert!(system.interface().commands.borrow().last().unwrap().linear < before);
+    }
+
+    // Disabled navigation holds position whatever the scans show, then resumes when re-enabled
+    #[test]
+    fn test_disabled_navigation_publishes_zero_command() {
+        let mut scans = vec![scan_at(10.0), scan_at(0.5), scan_at(f32::NAN)];
+        scans.extend(vec![scan_at(10.0); 2]);
+        let mut system = scripted_system(scans);
+
+        system.set_navigation_enabled(false);
+        for _ in 0..3 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.cycle_counts().plan_updates, 0);
+        {
+            let commands = system.interface().commands.borrow();
+            assert_eq!(commands.len(), 3);
+            assert!(commands.iter().all(|c| c.linear == 0.0 && c.angular == 0.0));
+        }
+
+        system.set_navigation_enabled(true);
+        for _ in 0..2 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.cycle_counts().plan_updates, 2);
+        assert!(system.interface().commands.borrow()[4].linear > 0.0);
+    }
+
+    // Idle publishes zero and skips planning even with a reachable goal down a clear path
+    #[test]
+    fn test_idle_mode_publishes_zero_command() {
+        let mut system = scripted_system(vec![scan_at(10.0); 4]);
+        system.set_goal(Pose2D { x: 3.0, y: 0.0, theta: 0.0 }).unwrap();
+
+        system.set_mode(Mode::Idle);
+        for _ in 0..2 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.cycle_counts().plan_updates, 0);
+        assert!(system.interface().commands.borrow().iter().all(|c| c.linear == 0.0 && c.angular == 0.0));
+
+        system.set_mode(Mode::Navigating);
+        system.run_cycle().unwrap();
+        assert_eq!(system.cycle_counts().plan_updates, 1);
+        assert!(system.interface().commands.borrow()[2].linear > 0.0);
+    }
+
+    // Uncertain odometry makes the system Lost; it turns in place to relocalize instead of planning
+    #[test]
+    fn test_lost_mode_stops_and_relocalizes() {
+        let mut system = scripted_system(vec![uncertain_scan_at(10.0); 2]);
+        for _ in 0..2 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.mode(), Mode::Lost);
+
+        let counts = system.cycle_counts();
+        assert_eq!(counts.plan_updates, 0);
+        assert_eq!(counts.relocalization_attempts, 2);
+        let commands = system.interface().commands.borrow();
+        assert!(commands.iter().all(|c| c.linear == 0.0));
+        assert!(commands.last().unwrap().angular > 0.0);
+    }
+
+    // A confident fix moves Lost on to Recovering, which holds still, and then back to Navigating
+    #[test]
+    fn test_confidence_returning_resumes_navigation() {
+        let mut system = scripted_system(vec![uncertain_scan_at(10.0), scan_at(10.0), scan_at(10.0)]);
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Lost);
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Recovering);
+        assert_eq!(system.cycle_counts().plan_updates, 0);
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Navigating);
+        assert_eq!(system.cycle_counts().plan_updates, 1);
+    }
+
+    // Driving with odometry showing no motion past the stuck timeout sends the system Recovering
+    #[test]
+    fn test_stalled_odometry_reports_stuck() {
+        let mut system = scripted_system(vec![scan_at(10.0); 40]);
+        system.set_goal(Pose2D { x: 20.0, y: 0.0, theta: 0.0 }).unwrap();
+        let mut stuck_cycle = None;
+        for cycle in 0..40 {
+            system.run_cycle().unwrap();
+            if system.mode() == Mode::Recovering {
+                stuck_cycle = Some(cycle);
+                break;
+            }
+        }
+
+        // 2 s stuck timeout at the default 0.1 s cycle time
+        let stuck_cycle = stuck_cycle.expect("never reported stuck");
+        assert!(stuck_cycle > 20, "stuck after {} cycles", stuck_cycle);
+        let commands = system.interface().commands.borrow();
+        let last = commands[commands.len() - 1].linear;
+        assert!(last < commands[commands.len() - 2].linear);
+    }
+
+    // Odometry goes out with the fused estimate once localization provides one
+    #[test]
+    fn test_publishes_fused_pose_estimate() {
+        let mut scan = scan_at(10.0);
+        scan.odom_data.pose.covariance = vec![0.0; 36];
+        scan.odom_data.pose.covariance[0] = 0.01;
+        let mut system = scripted_system(vec![scan; 2]);
+        system.run_cycle().unwrap();
+        let (pose, covariance) = system.interface().odometry.borrow()[0];
+        assert_eq!((pose.x, pose.y), (0.0, 0.0));
+        assert_eq!(covariance[(0, 0)], 0.01);
+
+        let covariance = Matrix3::from_diagonal_element(0.02);
+        system.set_pose_estimate(PoseConfidence { pose: Pose { x: 1.0, y: 2.0, theta: 0.5 }, covariance });
+        system.run_cycle().unwrap();
+        let (pose, published) = system.interface().odometry.borrow()[1];
+        assert_eq!((pose.x, pose.y, pose.theta), (1.0, 2.0, 0.5));
+        assert_eq!(published, covariance);
+    }
+
+    // A map set from perception is published once, in the pose frame
+    #[test]
+    fn test_publishes_map_once() {
+        let mut system = scripted_system(vec![scan_at(10.0); 3]);
+        system.run_cycle().unwrap();
+        assert!(system.interface().maps.borrow().is_empty());
+
+        let mut grid = OccupancyGrid::new(4, 3, 0.25, (-1.0, 2.0));
+        grid.set_cell(1, 2, 1);
+        system.set_map(grid);
+        system.run_cycle().unwrap();
+        system.run_cycle().unwrap();
+        let maps = system.interface().maps.borrow();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].header.frame_id, "odom");
+        assert_eq!((maps[0].info.width, maps[0].info.height), (4, 3));
+        assert!(maps[0].data[2 * 4 + 1] > 50);
+    }
+
+    // A low battery sends the system Charging, where it stops; once charged it waits Idle
+    #[test]
+    fn test_low_battery_enters_charging() {
+        let mut system = scripted_system(vec![scan_at(10.0); 3]);
+        system.set_goal(Pose2D { x: 3.0, y: 0.0, theta: 0.0 }).unwrap();
+        system.set_battery_level(0.1);
+        for _ in 0..2 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.mode(), Mode::Charging);
+        assert_eq!(system.cycle_counts().plan_updates, 0);
+        assert!(system.interface().commands.borrow().iter().all(|c| c.linear == 0.0 && c.angular == 0.0));
+
+        system.set_battery_level(0.95);
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Idle);
+    }
+
+    // Losing localization at speed brakes over several cycles instead of stopping dead
+    #[test]
+    fn test_lost_mode_decelerates_to_stop() {
+        let mut scans = vec![scan_at(10.0); 10];
+        scans.extend(vec![uncertain_scan_at(10.0); 30]);
+        let mut system = scripted_system(scans);
+        system.set_goal(Pose2D { x: 20.0, y: 0.0, theta: 0.0 }).unwrap();
+        for _ in 0..10 {
+            system.run_cycle().unwrap();
+        }
+        let cruising = system.interface().commands.borrow().last().unwrap().linear;
+        assert!(cruising > 0.2);
+
+        for _ in 0..30 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.mode(), Mode::Lost);
+        let commands = system.interface().commands.borrow();
+        let braking: Vec<f32> = commands[10..].iter().map(|c| c.linear).collect();
+        assert!(braking[0] > 0.0 && braking[0] < cruising);
+        assert!(braking.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert_eq!(*braking.last().unwrap(), 0.0);
+        let stopping_cycles = braking.iter().filter(|v| **v > 0.0).count();
+        assert!(stopping_cycles > 3, "stopped in {} cycles", stopping_cycles);
+        assert_eq!(system.cycle_counts().relocalization_attempts as usize, braking.len() - stopping_cycles);
+    }
+
+    // A simulated robot stands in for ROS as both sensor source and command sink
+    #[test]
+    fn test_run_cycle_on_simulated_robot() {
+        let mut grid = OccupancyGrid::centered(100, 0.1, (0.0, 0.0));
+        for row in 0..100 {
+            grid.set_cell(90, row, 1); // Wall 4 m ahead
+        }
+        let robot = SimulatedRobot::new(LaserSimConfig::default(), grid, Pose { x: 0.0, y: 0.0, theta: 0.0 }, 0.1);
+        let config = EosConfig {
+            neural_config: NeuralConfig {
+                model_path: None,
+                seed: Some(7),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut system = EosOS::with_backends(config, &robot, &robot).unwrap();
+        system.initialize().unwrap();
+
+        for _ in 0..5 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.cycle_counts().plan_updates, 5);
+        assert!(robot.pose().x > 0.0);
+        assert!(system.get_status().ros.connected);
+    }
+
+    // The status JSON carries the operational flag and motion state for remote monitoring
+    #[test]
+    fn test_status_json_reports_operational_and_mode() {
+        let mut system = scripted_system(vec![scan_at(10.0)]);
+        system.run_cycle().unwrap();
+
+        let status: serde_json::Value = serde_json::from_str(&system.status_json()).unwrap();
+        assert_eq!(status["operational"], true);
+        assert_eq!(status["navigation"]["motion_state"], "moving");
+        assert_eq!(status["ros"]["connected"], true);
+
+        system.publish_status().unwrap();
+        assert_eq!(*system.interface().statuses.borrow(), vec![system.status_json()]);
+    }
+
+    // Double initialize is a config error; calls after shutdown report NotInitialized
+    #[test]
+    fn test_double_init_and_use_after_shutdown() {
+        let mut system = scripted_system(vec![scan_at(10.0); 2]);
+        match system.initialize() {
+            Err(EosError::ConfigError(msg)) => assert_eq!(msg, "already initialized"),
+            other => panic!("expected ConfigError, got {:?}", other),
+        }
+        system.run_cycle().unwrap();
+
+        system.shutdown().unwrap();
+        assert!(matches!(system.run_cycle(), Err(EosError::NotInitialized)));
+        assert!(matches!(system.publish_status(), Err(EosError::NotInitialized)));
+        assert!(matches!(system.shutdown(), Err(EosError::NotInitialized)));
+        assert!(!system.get_status().operational);
+        assert_eq!(system.interface().commands.borrow().len(), 1);
+    }
+
+    // Losing the sensor with an obstacle in contact latches Panic; motion requests are
+    // ignored and only zero is published until an operator reset
+    #[test]
+    fn test_sensor_loss_and_contact_enter_panic() {
+        let mut scans = vec![scan_at(0.08)];
+        scans.extend(vec![scan_at(f32::INFINITY); 3]);
+        scans.extend(vec![scan_at(10.0); 2]);
+        let mut system = scripted_system(scans);
+        system.set_goal(Pose2D { x: 3.0, y: 0.0, theta: 0.0 }).unwrap();
+
+        // Contact alone is a stop or a back-up away from it, not a panic
+        assert!(system.run_cycle().is_err());
+        assert_eq!(system.mode(), Mode::Navigating);
+        assert!(system.interface().commands.borrow()[0].linear <= 0.0);
+
+        // The sensor goes quiet with the obstacle still last seen in contact
+        for _ in 0..3 {
+            system.run_cycle().unwrap();
+        }
+        assert_eq!(system.mode(), Mode::Panic);
+        assert_eq!(system.panic_causes(), &[PanicCause::SensorLoss, PanicCause::ObstacleContact]);
+        assert!(!system.get_status().operational);
+
+        // Motion requests are refused or ignored; every cycle commands zero
+        assert!(matches!(system.set_goal(Pose2D { x: 1.0, y: 0.0, theta: 0.0 }), Err(EosError::Panicked)));
+        system.set_mode(Mode::Navigating);
+        assert_eq!(system.mode(), Mode::Panic);
+        let before = system.interface().commands.borrow().len();
+        for _ in 0..3 {
+            system.run_cycle().unwrap();
+        }
+        {
+            let commands = system.interface().commands.borrow();
+            assert_eq!(commands.len(), before + 3);
+            assert!(commands[1..].iter().all(|c| c.linear == 0.0 && c.angular == 0.0));
+        }
+        assert_eq!(system.cycle_counts().perception_updates, 4);
+
+        // The operator reset leaves the system Idle, ready to be sent on again
+        assert!(system.reset_panic());
+        assert_eq!(system.mode(), Mode::Idle);
+        assert!(system.panic_causes().is_empty());
+        system.set_mode(Mode::Navigating);
+        assert!(system.set_goal(Pose2D { x: 1.0, y: 0.0, theta: 0.0 }).is_ok());
+    }
+
+    // Tipping over enters Panic on its own
+    #[test]
+    fn test_over_tilt_enters_panic() {
+        let mut tipped = scan_at(10.0);
+        // Rolled 90 degrees onto its side
+        tipped.imu_data.orientation.x = std::f64::consts::FRAC_1_SQRT_2;
+        tipped.imu_data.orientation.w = std::f64::consts::FRAC_1_SQRT_2;
+        let mut system = scripted_system(vec![scan_at(10.0), tipped]);
+        system.set_goal(Pose2D { x: 3.0, y: 0.0, theta: 0.0 }).unwrap();
+
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Navigating);
+        system.run_cycle().unwrap();
+        assert_eq!(system.mode(), Mode::Panic);
+        assert_eq!(system.panic_causes(), &[PanicCause::OverTilt]);
+        assert_eq!(system.interface().commands.borrow().last().unwrap().linear, 0.0);
+    }
+
+    // An IMU mounted upside down reads as rolled over unless its axis remap is configured
+    #[test]
+    fn test_tilt_uses_imu_axis_remap() {
+        let mut level = scan_at(10.0);
+        // Level robot, IMU rolled 180 degrees about the forward axis
+        level.imu_data.orientation.x = 1.0;
+        level.imu_data.orientation.w = 0.0;
+
+        let mut config = scripted_config(RateDivisors::default());
+        config.ros_config.imu_axis_remap = ImuAxisRemap::upside_down();
+        let mut system = EosOS::with_interface(config, ScriptedInterface::new(vec![level.clone()])).unwrap();
+        system.initialize().unwrap();
+        system.run_cycle().unwrap();
+        assert_ne!(system.mode(), Mode::Panic);
+
+        let mut unmapped = scripted_system(vec![level]);
+        unmapped.run_cycle().unwrap();
+        assert_eq!(unmapped.panic_causes(), &[PanicCause::OverTilt]);
+    }
+
+    // The awaitable cycle behaves like the blocking one, and the async loop keeps cycling
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_run_loop() {
+        let mut system = scripted_system(vec![scan_at(10.0); 50]);
+        for _ in 0..3 {
+            system.run_cycle_async().await.unwrap();
+        }
+        assert_eq!(system.interface().commands.borrow().len(), 3);
+
+        // Cycles until shutdown, so cancel it after a few periods
+        let timed_out = tokio::time::timeout(Duration::from_millis(350), system.run_async()).await;
+        assert!(timed_out.is_err());
+        let cycles = system.cycle_counts().cycles;
+        assert!(cycles > 4, "{} cycles", cycles);
+        assert!(system.interface().statuses.borrow().len() as u64 >= cycles - 3);
+    }
+
+    // A model that fails to load leaves nothing half started, so initialize can be retried
+    #[test]
+    fn test_failed_initialize_can_be_retried() {
+        let model_path = std::env::temp_dir().join("eos_retry_model.json").to_string_lossy().into_owned();
+        let _ = std::fs::remove_file(&model_path);
+        let mut config = scripted_config(RateDivisors::default());
+        config.neural_config.model_path = Some(model_path.clone());
+        let mut system = EosOS::with_interface(config, ScriptedInterface::new(vec![scan_at(10.0)])).unwrap();
+
+        assert!(system.initialize().is_err());
+        assert!(matches!(system.run_cycle(), Err(EosError::NotInitialized)));
+
+        let mut engine = SNNEngine::new(&NeuralConfig { seed: Some(7), ..Default::default() }).unwrap();
+        engine.initialize().unwrap();
+        engine.save_model(&model_path).unwrap();
+        system.initialize().unwrap();
+        system.run_cycle().unwrap();
+        system.shutdown().unwrap();
+    }
+
+    // With an interface that signals sensor data, an awaited cycle waits for new data
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_async_cycle_awaits_sensor_data() {
+        let (updates, _) = tokio::sync::watch::channel(0);
+        let interface = ScriptedInterface {
+            updates: Some(updates.clone()),
+            ..ScriptedInterface::new(vec![scan_at(10.0); 5])
+        };
+        let mut system = EosOS::with_interface(scripted_config(RateDivisors::default()), interface).unwrap();
+        system.initialize().unwrap();
+
+        // Nothing new has arrived, so the cycle is still waiting
+        let waiting = tokio::time::timeout(Duration::from_millis(20), system.run_cycle_async()).await;
+        assert!(waiting.is_err());
+        assert!(system.interface().commands.borrow().is_empty());
+
+        updates.send_modify(|count| *count += 1);
+        let started = std::time::Instant::now();
+        system.run_cycle_async().await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(20));
+        assert_eq!(system.interface().commands.borrow().len(), 1);
+    }
+}
@@ -0,0 +1,968 @@
+mod common;
+
+use eos::navigation::{
+    MapBounds, NavigationConfig, NavigationError, NavigationOutcome, NavigationPlanner, Obstacle, ObstacleCategory, ObstacleIndex, Path,
+    RecoveryBehavior, RecoveryState, SafetyMarginModel, SafetyStatus,
+};
+use eos::core::{Memory, Pose};
+use eos::ros_interface::{MotionCommand, Pose2D};
+
+use common::sensor_data;
+
+/// Write a keep-out zone file for the given test and return its path
+fn write_zones(name: &str, contents: &str) -> String {
+    let path = std::env::temp_dir().join(format!("eos_{}", name));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+/// Build a point obstacle at the given position, as certain as a scan return
+fn obstacle_at(x: f32, y: f32) -> Obstacle {
+    Obstacle {
+        position: Pose2D { x, y, theta: 0.0 },
+        radius: 0.3,
+        confidence: 1.0,
+        velocity: None,
+        category: ObstacleCategory::Large,
+    }
+}
+
+/// Deterministic pseudo-random coordinates in [-range, range)
+fn scatter(count: usize, range: f32, seed: u64) -> Vec<Obstacle> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 * range - range
+    };
+    (0..count).map(|_| { let x = next(); obstacle_at(x, next()) }).collect()
+}
+
+/// Brute-force nearest obstacle within a radius
+fn nearest_brute_force(obstacles: &[Obstacle], x: f32, y: f32, radius: f32) -> Option<(f32, f32)> {
+    obstacles
+        .iter()
+        .map(|o| (o, ((o.position.x - x).powi(2) + (o.position.y - y).powi(2)).sqrt()))
+        .filter(|(_, d)| *d <= radius)
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(o, _)| (o.position.x, o.position.y))
+}
+
+/// Nearest obstacle within a radius using the spatial index
+fn nearest_indexed(index: &ObstacleIndex, obstacles: &[Obstacle], x: f32, y: f32, radius: f32) -> Option<(f32, f32)> {
+    index
+        .query(obstacles, x, y, radius)
+        .map(|o| (o, ((o.position.x - x).powi(2) + (o.position.y - y).powi(2)).sqrt()))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(o, _)| (o.position.x, o.position.y))
+}
+
+/// Distance from a point to the segment a-b
+fn segment_distance(p: Pose2D, a: Pose2D, b: Pose2D) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_sq = dx * dx + dy * dy;
+    let t = if length_sq > 0.0 { (((p.x - a.x) * dx + (p.y - a.y) * dy) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    ((p.x - a.x - t * dx).powi(2) + (p.y - a.y - t * dy).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The index returns exactly the obstacles inside the query radius
+    #[test]
+    fn test_obstacle_index_small_case() {
+        let obstacles = vec![
+            obstacle_at(0.5, 0.0),
+            obstacle_at(-0.4, 0.2),
+            obstacle_at(3.0, 3.0),
+            obstacle_at(-2.5, -0.1),
+        ];
+        let mut index = ObstacleIndex::new(0.5);
+        index.rebuild(&obstacles);
+
+        let mut found: Vec<(f32, f32)> = index
+            .query(&obstacles, 0.0, 0.0, 1.0)
+            .map(|o| (o.position.x, o.position.y))
+            .collect();
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        assert_eq!(found, vec![(-0.4, 0.2), (0.5, 0.0)]);
+        assert_eq!(index.max_radius(), 0.3);
+    }
+
+    // On a dense map the indexed lookup agrees with brute force
+    #[test]
+    fn test_obstacle_index_matches_brute_force_large_n() {
+        let obstacles = scatter(5000, 20.0, 42);
+        let mut index = ObstacleIndex::new(0.5);
+        index.rebuild(&obstacles);
+
+        for probe in scatter(200, 20.0, 7) {
+            let (x, y) = (probe.position.x, probe.position.y);
+            for radius in [0.25, 1.0, 3.0] {
+                assert_eq!(
+                    nearest_indexed(&index, &obstacles, x, y, radius),
+                    nearest_brute_force(&obstacles, x, y, radius),
+                );
+            }
+        }
+    }
+
+    // A forward beam from a LiDAR rotated 90° lands on the base +y axis
+    #[test]
+    fn test_lidar_mount_rotation() {
+        let config = NavigationConfig {
+            lidar_mount: Pose2D { x: 0.0, y: 0.0, theta: std::f32::consts::FRAC_PI_2 },
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+
+        // Single beam straight ahead of the sensor at 2 m
+        let _ = planner.plan(&sensor_data(0.0, 0.01, vec![2.0]), &[], None);
+
+        assert_eq!(planner.obstacles_near(0.0, 2.0, 0.05).count(), 1);
+        assert_eq!(planner.obstacles_near(2.0, 0.0, 0.05).count(), 0);
+    }
+
+    // A distant goal is only planned up to the horizon each cycle
+    #[test]
+    fn test_planning_horizon_truncates_path() {
+        let config = NavigationConfig {
+            planning_horizon: 3.0,
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+        planner.set_goal(Pose2D { x: 50.0, y: 0.0, theta: 0.0 }).unwrap();
+
+        // Open space: every beam at maximum range
+        let path = planner.plan(&sensor_data(-1.5, 0.1, vec![10.0; 30]), &[], None).unwrap();
+
+        assert!(path.total_length() <= config.planning_horizon + 1e-4);
+        assert!(path.total_length() > 0.0);
+    }
+
+    // A goal blocked right in front of the robot records the blocking obstacle
+    #[test]
+    fn test_blocked_goal_records_rejection() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+
+        // Single return 0.2 m straight ahead
+        let result = planner.plan(&sensor_data(0.0, 0.1, vec![0.2]), &[], None);
+        assert!(result.is_err());
+
+        let rejection = planner.last_rejection().expect("rejection should be recorded");
+        let obstacle = rejection.obstacle.expect("blocking obstacle should be known");
+        assert!((obstacle.x - 0.2).abs() < 1e-5);
+        assert!(obstacle.y.abs() < 1e-5);
+        assert!(rejection.safety_score < 0.3);
+        assert_eq!(rejection.segment_index, 0);
+    }
+
+    // An external obstacle keeps blocking even when a fresh scan doesn't see it
+    #[test]
+    fn test_external_obstacle_persists_across_scans() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+        planner.add_external_obstacle(obstacle_at(0.2, 0.0));
+
+        // The scan sees only open space
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        assert!(planner.plan(&open_scan, &[], None).is_err());
+        assert!(planner.plan(&open_scan, &[], None).is_err());
+
+        // Once cleared the same scan yields a path
+        planner.clear_external_obstacles();
+        assert!(planner.plan(&open_scan, &[], None).is_ok());
+    }
+
+    // The planner keeps the last successful path available for rendering
+    #[test]
+    fn test_current_path_matches_plan() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        assert!(planner.current_path().is_none());
+        planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+
+        let path = planner.plan(&sensor_data(-1.5, 0.1, vec![10.0; 30]), &[], None).unwrap();
+        let current = planner.current_path().expect("path should be stored");
+
+        assert_eq!(current.total_length(), path.total_length());
+        assert_eq!(current.overall_safety(), path.overall_safety());
+        assert_eq!(current.segments().len(), path.segments().len());
+        for (stored, planned) in current.segments().iter().zip(path.segments()) {
+            assert_eq!((stored.start().x, stored.start().y), (planned.start().x, planned.start().y));
+            assert_eq!((stored.end().x, stored.end().y), (planned.end().x, planned.end().y));
+            assert_eq!(stored.safety_score(), planned.safety_score());
+        }
+    }
+
+    // A keep-out zone turns the straight line into a detour around it
+    #[test]
+    fn test_keepout_zone_rejects_crossing_path() {
+        let zones = write_zones(
+            "keepout_display.yaml",
+            "- name: display\n  vertices: [[0.8, -0.5], [1.2, -0.5], [1.2, 0.5], [0.8, 0.5]]\n",
+        );
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.load_keepout_zones(&zones).unwrap();
+        assert_eq!(planner.keepout_zones().len(), 1);
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+
+        // Straight through the display: the route goes around instead, never entering it
+        planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+        let path = planner.plan(&open_scan, &[], None).unwrap();
+        assert!(path.segments().len() > 1);
+        let inside = |x: f32, y: f32| (0.8..=1.2).contains(&x) && (-0.5..=0.5).contains(&y);
+        for segment in path.segments() {
+            for t in 0..=20 {
+                let t = t as f32 / 20.0;
+                let x = segment.start().x + t * (segment.end().x - segment.start().x);
+                let y = segment.start().y + t * (segment.end().y - segment.start().y);
+                assert!(!inside(x, y), "{:?} enters the zone at ({}, {})", segment, x, y);
+            }
+        }
+
+        // Same length, passing beside it: a single straight segment
+        planner.set_goal(Pose2D { x: 1.5, y: 1.0, theta: 0.0 }).unwrap();
+        assert_eq!(planner.plan(&open_scan, &[], None).unwrap().segments().len(), 1);
+    }
+
+    // JSON files load the same zones, and malformed polygons are refused
+    #[test]
+    fn test_keepout_zone_file_formats() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+
+        let json = write_zones(
+            "keepout_stairs.json",
+            r#"[{"name": "stairs", "vertices": [[2.0, 2.0], [3.0, 2.0], [3.0, 3.0]]}]"#,
+        );
+        planner.load_keepout_zones(&json).unwrap();
+        assert_eq!(planner.keepout_zones()[0].name, "stairs");
+
+        let degenerate = write_zones(
+            "keepout_line.json",
+            r#"[{"name": "line", "vertices": [[0.0, 0.0], [1.0, 0.0]]}]"#,
+        );
+        assert!(planner.load_keepout_zones(&degenerate).is_err());
+    }
+
+    // Halfway along a straight path reports ~50% progress and a matching ETA
+    #[test]
+    fn test_progress_halfway_along_path() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.set_goal(Pose2D { x: 2.0, y: 0.0, theta: 0.0 }).unwrap();
+        planner.plan(&sensor_data(-1.5, 0.1, vec![10.0; 30]), &[], None).unwrap();
+
+        planner.update_pose(Pose2D { x: 1.0, y: 0.05, theta: 0.0 });
+        let status = planner.get_status();
+
+        assert!((status.progress_fraction - 0.5).abs() < 1e-3);
+        assert!((status.distance_to_goal - 1.0).abs() < 0.01);
+        let eta = planner.eta(0.5).expect("moving robot should have an ETA");
+        assert!((eta - 2.0).abs() < 1e-3);
+        assert_eq!(planner.eta(0.0), None);
+    }
+
+    // An isolated return is a point while a contiguous arc is a wall
+    #[test]
+    fn test_obstacle_categories_from_clusters() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+
+        // Beam 10 hits debris at 1 m; beams 40-99 trace an arc at 2 m
+        let mut ranges = vec![10.0; 100];
+        ranges[10] = 1.0;
+        for range in &mut ranges[40..] {
+            *range = 2.0;
+        }
+        let _ = planner.plan(&sensor_data(-0.5, 0.01, ranges), &[], None);
+
+        let debris_angle: f32 = -0.5 + 10.0 * 0.01;
+        let debris = planner
+            .obstacles_near(debris_angle.cos(), debris_angle.sin(), 0.05)
+            .next()
+            .expect("debris should be mapped");
+        assert_eq!(debris.category, ObstacleCategory::Point);
+
+        let wall_angle: f32 = -0.5 + 70.0 * 0.01;
+        let wall = planner
+            .obstacles_near(2.0 * wall_angle.cos(), 2.0 * wall_angle.sin(), 0.01)
+            .next()
+            .expect("wall should be mapped");
+        assert_eq!(wall.category, ObstacleCategory::Large);
+    }
+
+    // A failed plan clears the stored path instead of leaving a stale one to render
+    #[test]
+    fn test_current_path_cleared_when_planning_fails() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+        planner.plan(&sensor_data(-1.5, 0.1, vec![10.0; 30]), &[], None).unwrap();
+        assert!(planner.current_path().is_some());
+
+        let close_wall = sensor_data(-0.3, 0.02, vec![0.1; 30]);
+        assert!(planner.plan(&close_wall, &[], None).is_err());
+        assert!(planner.current_path().is_none());
+    }
+
+    // A tripped emergency stop holds after the obstacle recedes until it is cleared
+    #[test]
+    fn test_emergency_stop_latch() {
+        let config = NavigationConfig {
+            emergency_stop_distance: 0.4,
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+        let close_wall = sensor_data(-0.3, 0.02, vec![0.35; 30]);
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        // Away from the odometry origin, so clearance must be measured from the robot
+        let pose = Some(Pose2D { x: 5.0, y: -3.0, theta: 1.0 });
+
+        // Trip it, and refuse to clear while the wall is still close
+        assert!(planner.plan(&close_wall, &[], pose).is_err());
+        assert!(planner.is_emergency_stopped());
+        assert!(planner.clear_emergency_stop().is_err());
+
+        // The wall recedes but the stop stays latched
+        assert!(planner.plan(&open_scan, &[], pose).is_err());
+        assert!(planner.is_emergency_stopped());
+        assert_eq!(planner.get_status().safety_status, SafetyStatus::EmergencyStop);
+
+        // Clearing succeeds now that nothing is critically close
+        planner.clear_emergency_stop().unwrap();
+        assert!(!planner.is_emergency_stopped());
+        assert!(planner.plan(&open_scan, &[], pose).is_ok());
+    }
+
+    // Recovery holds still while the wall is critically close, releases the stop once it
+    // recedes, then turns, backs up and clears the map until planning succeeds again
+    #[test]
+    fn test_recovery_behaviors_after_emergency_stop() {
+        let config = NavigationConfig {
+            emergency_stop_distance: 0.4,
+            recovery_rotation: 0.25,
+            recovery_backup_distance: 0.245,
+            ..Default::default()
+        };
+        let close_wall = sensor_data(-0.3, 0.02, vec![0.35; 30]);
+        let near_wall = sensor_data(-0.3, 0.02, vec![0.5; 30]);
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        let run_recovery = |planner: &mut NavigationPlanner| {
+            let mut steps = Vec::new();
+            while let Some(command) = planner.recover(0.1) {
+                steps.push((planner.recovery_state(), command));
+            }
+            steps
+        };
+        let moved = |steps: &[(RecoveryState, MotionCommand)], behavior| {
+            steps
+                .iter()
+                .filter(|(state, _)| *state == RecoveryState::Running(behavior))
+                .fold((0.0, 0.0), |(linear, angular), (_, command)| (linear + command.linear * 0.1, angular + command.angular * 0.1))
+        };
+
+        // A wall that stays inside the emergency stop distance: turning could drag it
+        // along, so recovery backs straight away from it while the rear is clear
+        let mut planner = NavigationPlanner::new(&config);
+        assert!(planner.plan(&close_wall, &[], None).is_err());
+        assert_eq!(planner.get_status().recovery_state, RecoveryState::Idle);
+        assert!(planner.is_emergency_stopped());
+        let steps = run_recovery(&mut planner);
+        assert_eq!(steps[0].0, RecoveryState::Running(RecoveryBehavior::BackUp));
+        assert!(steps[0].1.linear < 0.0);
+        assert_eq!(planner.recovery_state(), RecoveryState::Exhausted);
+        assert_eq!(moved(&steps, RecoveryBehavior::RotateInPlace), (0.0, 0.0));
+        let (reversed, _) = moved(&steps, RecoveryBehavior::BackUp);
+        assert!((reversed + 0.245).abs() < 0.011);
+        assert_eq!(steps.last().unwrap().0, RecoveryState::Running(RecoveryBehavior::ClearObstacleMap));
+        assert_eq!(planner.get_status().obstacle_count, 0);
+        assert!(!planner.is_emergency_stopped());
+
+        // Planning succeeding again ends the recovery
+        assert!(planner.plan(&open_scan, &[], None).is_ok());
+        assert_eq!(planner.recovery_state(), RecoveryState::Idle);
+
+        // A wall blocking the way but outside the stop distance gets every behavior in order
+        let mut planner = NavigationPlanner::new(&NavigationConfig {
+            safety_distance: 1.0,
+            ..config.clone()
+        });
+        assert!(planner.plan(&near_wall, &[], None).is_err());
+        assert!(!planner.is_emergency_stopped());
+        let steps = run_recovery(&mut planner);
+        assert_eq!(steps[0].0, RecoveryState::Running(RecoveryBehavior::RotateInPlace));
+        let (_, turned) = moved(&steps, RecoveryBehavior::RotateInPlace);
+        assert!((0.25..0.35).contains(&turned));
+        let (reversed, _) = moved(&steps, RecoveryBehavior::BackUp);
+        assert!((reversed + 0.245).abs() < 0.011);
+        assert_eq!(steps.last().unwrap().0, RecoveryState::Running(RecoveryBehavior::ClearObstacleMap));
+    }
+
+    // Boxed in front and behind, recovery holds still through every behavior and gives up
+    #[test]
+    fn test_recovery_holds_when_boxed_in() {
+        let config = NavigationConfig {
+            emergency_stop_distance: 0.4,
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+        let surrounded = sensor_data(-std::f32::consts::PI, 0.05, vec![0.3; 126]);
+        assert!(planner.plan(&surrounded, &[], None).is_err());
+
+        let mut commands = Vec::new();
+        while let Some(command) = planner.recover(0.1) {
+            commands.push(command);
+            assert!(commands.len() < 100, "recovery never gave up");
+        }
+        assert!(commands.iter().all(|c| c.linear == 0.0 && c.angular == 0.0));
+        assert_eq!(planner.recovery_state(), RecoveryState::Exhausted);
+    }
+
+    // Behaviors run for their time at any cycle rate, so the rotation turns the same angle
+    #[test]
+    fn test_recovery_follows_cycle_time() {
+        let config = NavigationConfig::default();
+        for dt in [0.05, 0.2] {
+            let mut planner = NavigationPlanner::new(&config);
+            let mut turned = 0.0;
+            while let Some(command) = planner.recover(dt) {
+                turned += command.angular * dt;
+            }
+            assert!(turned >= config.recovery_rotation - 1e-4, "dt {}: turned {}", dt, turned);
+            assert!(turned < config.recovery_rotation + config.max_angular_velocity * dt, "dt {}: turned {}", dt, turned);
+        }
+    }
+
+    // Queued goals are pursued in order, advancing as each is reached
+    #[test]
+    fn test_goal_queue_advances_on_arrival() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        let first = Pose2D { x: 1.0, y: 0.0, theta: 0.0 };
+        let second = Pose2D { x: 1.0, y: 1.0, theta: 0.0 };
+        let third = Pose2D { x: 0.0, y: 1.0, theta: 0.0 };
+        for goal in [first, second, third] {
+            planner.push_goal(goal).unwrap();
+        }
+        assert_eq!(planner.get_status().goals_remaining, 3);
+        let path = planner.plan(&open_scan, &[], None).unwrap();
+        assert!((path.distance_to_goal().unwrap() - 1.0).abs() < 1e-4);
+
+        // Arriving at the first goal moves straight on to the second
+        let path = planner.plan(&open_scan, &[], Some(first)).unwrap();
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::Reached));
+        assert_eq!(planner.get_status().goals_remaining, 2);
+        assert!(planner.get_status().has_goal);
+        assert!((path.distance_to_goal().unwrap() - 1.0).abs() < 1e-4);
+
+        // Setting a goal replaces the whole queue
+        planner.set_goal(third).unwrap();
+        assert_eq!(planner.get_status().goals_remaining, 1);
+        planner.plan(&open_scan, &[], Some(third)).unwrap();
+        assert_eq!(planner.get_status().goals_remaining, 0);
+        assert!(!planner.get_status().has_goal);
+
+        // Clearing drops queued goals too
+        planner.push_goal(first).unwrap();
+        planner.push_goal(second).unwrap();
+        planner.clear_goals();
+        assert_eq!(planner.get_status().goals_remaining, 0);
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::Cleared));
+    }
+
+    // An obstacle that moves between scans gets a velocity in the direction it moved
+    #[test]
+    fn test_obstacle_velocity_from_consecutive_scans() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        let scan_with_return = |beam: usize, range: f32| {
+            let mut ranges = vec![10.0; 30];
+            ranges[beam] = range;
+            sensor_data(-0.3, 0.02, ranges)
+        };
+
+        // First sighting: nothing to compare against yet
+        planner.plan(&scan_with_return(14, 2.0), &[], None).unwrap();
+        assert!(planner.obstacles().iter().all(|obstacle| obstacle.velocity.is_none()));
+
+        // Closer and further left one (default 0.1s) scan later
+        planner.plan(&scan_with_return(16, 1.9), &[], None).unwrap();
+        let moving: Vec<_> = planner.obstacles().iter().filter_map(|obstacle| obstacle.velocity).collect();
+        assert_eq!(moving.len(), 1);
+        let (vx, vy) = moving[0];
+        assert!((vx + 1.0).abs() < 0.1);
+        assert!((vy - 0.8).abs() < 0.1);
+    }
+
+    // Without latching the stop clears itself once nothing is critically close, and
+    // resetting the violations brings the status back to Normal
+    #[test]
+    fn test_unlatched_emergency_stop_clears_itself() {
+        let config = NavigationConfig {
+            emergency_stop_distance: 0.4,
+            latch_emergency_stop: false,
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+        let close_wall = sensor_data(-0.3, 0.02, vec![0.35; 30]);
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+
+        assert!(planner.plan(&close_wall, &[], None).is_err());
+        assert!(planner.is_emergency_stopped());
+        assert!(planner.plan(&close_wall, &[], None).is_err());
+        assert!(planner.is_emergency_stopped());
+
+        // The wall recedes: the next cycle clears the stop and plans again
+        assert!(planner.plan(&open_scan, &[], None).is_ok());
+        assert!(!planner.is_emergency_stopped());
+        assert!(planner.safety_violations() > 0);
+        assert_eq!(planner.get_status().safety_status, SafetyStatus::Warning);
+
+        planner.reset_safety_violations();
+        assert_eq!(planner.safety_violations(), 0);
+        assert_eq!(planner.get_status().safety_status, SafetyStatus::Normal);
+    }
+
+    // An obstacle seen once keeps fading cost after it leaves view, then disappears
+    #[test]
+    fn test_obstacle_persists_then_fades() {
+        let config = NavigationConfig {
+            obstacle_persistence: 0.5,
+            ..Default::default()
+        };
+        let mut planner = NavigationPlanner::new(&config);
+        let post = sensor_data(-0.05, 0.1, vec![1.0]);
+        let looking_away = sensor_data(2.0, 0.1, vec![10.0; 5]);
+
+        let _ = planner.plan(&post, &[], None);
+        let mut costs = vec![planner.obstacle_cost(1.0, 0.0)];
+        for _ in 0..6 {
+            let _ = planner.plan(&looking_away, &[], None);
+            costs.push(planner.obstacle_cost(1.0, 0.0));
+        }
+
+        // Test scans carry no stamps or scan period, so each counts as the nominal 0.1 s
+        assert!(costs[..4].iter().all(|c| *c > 0.0), "{:?}", costs);
+        for pair in costs[..4].windows(2) {
+            assert!(pair[1] < pair[0]);
+        }
+        assert_eq!(costs[6], 0.0);
+    }
+
+    // A beam passing through a remembered obstacle clears it right away
+    #[test]
+    fn test_remembered_obstacle_cleared_by_free_beam() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+
+        let _ = planner.plan(&sensor_data(-0.05, 0.1, vec![1.0]), &[], None);
+        let _ = planner.plan(&sensor_data(-0.05, 0.1, vec![10.0]), &[], None);
+
+        assert_eq!(planner.obstacle_cost(1.0, 0.0), 0.0);
+    }
+
+    // Required clearance grows with speed: a slow robot proceeds where a fast one must stop
+    #[test]
+    fn test_safety_margin_grows_with_speed() {
+        let config = NavigationConfig::default();
+        let mut planner = NavigationPlanner::new(&config);
+        let mut margins = Vec::new();
+        for speed in [0.0, 0.1, 0.3, 0.5] {
+            planner.set_current_speed(speed);
+            margins.push(planner.speed_margin());
+        }
+        assert_eq!(margins[0], 0.0);
+        assert!(margins.windows(2).all(|pair| pair[1] > pair[0]));
+        assert!((margins[3] - 0.5f32.powi(2) / (2.0 * config.max_deceleration)).abs() < 1e-6);
+
+        let wall = sensor_data(-0.3, 0.02, vec![0.4; 30]);
+
+        let mut slow = NavigationPlanner::new(&config);
+        slow.set_current_speed(0.1);
+        assert!(slow.plan(&wall, &[], None).is_ok());
+
+        let mut fast = NavigationPlanner::new(&config);
+        fast.set_current_speed(0.5);
+        assert!(matches!(fast.plan(&wall, &[], None), Err(NavigationError::EmergencyStop)));
+
+        // The fixed model ignores speed
+        let mut fixed = NavigationPlanner::new(&NavigationConfig {
+            safety_margin_model: SafetyMarginModel::Fixed,
+            ..config
+        });
+        fixed.set_current_speed(0.5);
+        assert_eq!(fixed.speed_margin(), 0.0);
+        assert!(fixed.plan(&wall, &[], None).is_ok());
+    }
+
+    // A gap the center fits through but the corners of a wide footprint don't is rejected
+    #[test]
+    fn test_rectangular_footprint_rejects_narrow_gap() {
+        let rectangle = vec![(0.3, 0.4), (-0.3, 0.4), (-0.3, -0.4), (0.3, -0.4)];
+        let goal = Pose2D { x: 2.0, y: 0.0, theta: 0.0 };
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+
+        let plan_through_gap = |footprint: Vec<(f32, f32)>, half_gap: f32| {
+            let mut planner = NavigationPlanner::new(&NavigationConfig {
+                footprint,
+                ..Default::default()
+            });
+            planner.set_goal(goal).unwrap();
+            planner.add_external_obstacle(obstacle_at(1.2, half_gap));
+            planner.add_external_obstacle(obstacle_at(1.2, -half_gap));
+            planner.plan(&open_scan, &[], None)
+        };
+
+        // 0.6 m gap: a point robot passes, the 0.8 m wide rectangle does not
+        assert!(plan_through_gap(Vec::new(), 0.3).is_ok());
+        assert!(matches!(
+            plan_through_gap(rectangle.clone(), 0.3),
+            Err(NavigationError::UnsafePath(_))
+        ));
+
+        // A gap wide enough for the corners is accepted
+        assert!(plan_through_gap(rectangle, 0.9).is_ok());
+    }
+
+    // A single 10 m segment resampled at 1 m becomes 11 evenly spaced waypoints
+    #[test]
+    fn test_path_resample_uniform_spacing() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig {
+            planning_horizon: 20.0,
+            ..Default::default()
+        });
+        planner.set_goal(Pose2D { x: 6.0, y: 8.0, theta: 0.0 }).unwrap();
+        let path = planner.plan(&sensor_data(-1.5, 0.1, vec![10.0; 30]), &[], None).unwrap();
+        assert_eq!(path.segments().len(), 1);
+        assert!((path.total_length() - 10.0).abs() < 1e-5);
+
+        let resampled = path.resample(1.0);
+        let waypoints = resampled.waypoints();
+        assert_eq!(waypoints.len(), 11);
+        for (k, waypoint) in waypoints.iter().enumerate() {
+            assert!((waypoint.x - 0.6 * k as f32).abs() < 1e-4, "waypoint {k}: {waypoint:?}");
+            assert!((waypoint.y - 0.8 * k as f32).abs() < 1e-4, "waypoint {k}: {waypoint:?}");
+        }
+        assert!(resampled.segments().iter().all(|segment| (segment.length() - 1.0).abs() < 1e-4));
+        assert_eq!(resampled.total_length(), path.total_length());
+        assert_eq!(resampled.segments()[0].safety_score(), path.segments()[0].safety_score());
+    }
+
+    // Goals inside an obstacle or outside the known map are refused when set
+    #[test]
+    fn test_infeasible_goal_rejected_at_set_time() {
+        let mut planner = NavigationPlanner::new(&NavigationConfig {
+            map_bounds: Some(MapBounds { min_x: -5.0, min_y: -5.0, max_x: 5.0, max_y: 5.0 }),
+            ..Default::default()
+        });
+
+        // A scan with a single return 1 m straight ahead populates the obstacle map
+        let scan = sensor_data(0.0, 0.1, vec![1.0]);
+        assert!(planner.plan(&scan, &[], None).is_ok());
+
+        let inside_obstacle = Pose2D { x: 1.0, y: 0.0, theta: 0.0 };
+        let inflated = Pose2D { x: 1.2, y: 0.0, theta: 0.0 };
+        assert!(!planner.is_goal_feasible(inside_obstacle));
+        assert!(matches!(planner.set_goal(inside_obstacle), Err(NavigationError::InvalidGoal)));
+        assert!(matches!(planner.set_goal(inflated), Err(NavigationError::InvalidGoal)));
+        assert!(matches!(
+            planner.set_goal(Pose2D { x: 8.0, y: 0.0, theta: 0.0 }),
+            Err(NavigationError::InvalidGoal)
+        ));
+        assert!(!planner.get_status().has_goal);
+
+        let free = Pose2D { x: 0.0, y: 2.0, theta: 0.0 };
+        assert!(planner.is_goal_feasible(free));
+        assert!(planner.set_goal(free).is_ok());
+    }
+
+    // Only returns inside the field of interest reach the obstacle map
+    #[test]
+    fn test_fov_excludes_rear_beams() {
+        use std::f32::consts::FRAC_PI_2;
+
+        // Returns 1 m ahead and 1 m behind
+        let scan = sensor_data(0.0, FRAC_PI_2, vec![1.0, 10.0, 1.0, 10.0]);
+
+        let mut full = NavigationPlanner::new(&NavigationConfig::default());
+        full.plan(&scan, &[], None).unwrap();
+        assert!(full.obstacle_cost(1.0, 0.0) > 0.0);
+        assert!(full.obstacle_cost(-1.0, 0.0) > 0.0);
+
+        let mut forward = NavigationPlanner::new(&NavigationConfig {
+            fov_min: -FRAC_PI_2,
+            fov_max: FRAC_PI_2,
+            ..Default::default()
+        });
+        forward.plan(&scan, &[], None).unwrap();
+        assert!(forward.obstacle_cost(1.0, 0.0) > 0.0);
+        assert_eq!(forward.obstacle_cost(-1.0, 0.0), 0.0);
+        assert_eq!(forward.obstacles_near(-1.0, 0.0, 0.5).count(), 0);
+        assert_eq!(forward.obstacles_near(1.0, 0.0, 0.5).count(), 1);
+    }
+
+    // A full window written in a config file with a rounded pi still keeps the beam straight behind
+    #[test]
+    fn test_rounded_full_fov_keeps_rear_beam() {
+        let scan = sensor_data(0.0, std::f32::consts::FRAC_PI_2, vec![10.0, 10.0, 1.0, 10.0]);
+
+        let config: NavigationConfig = serde_yaml::from_str("fov_min: -3.14159\nfov_max: 3.14159\n").unwrap();
+        let mut planner = NavigationPlanner::new(&config);
+        planner.plan(&scan, &[], None).unwrap();
+        assert_eq!(planner.obstacles_near(-1.0, 0.0, 0.5).count(), 1);
+    }
+
+    // Each way a goal can end is recorded as its outcome and reported in the status
+    #[test]
+    fn test_goal_outcomes() {
+        let goal = |x: f32| Pose2D { x, y: 0.0, theta: 0.0 };
+        let at = |x: f32| Some(goal(x));
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+
+        // Reached: within tolerance the goal resolves and the planner holds position
+        let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+        planner.set_goal(goal(2.0)).unwrap();
+        assert!(planner.plan(&open_scan, &[], at(0.0)).is_ok());
+        assert_eq!(planner.last_outcome(), None);
+        let hold = planner.plan(&open_scan, &[], at(1.95)).unwrap();
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::Reached));
+        assert!(hold.segments().is_empty());
+        assert!(!planner.get_status().has_goal);
+
+        // Preempted by a new goal, then cleared
+        planner.set_goal(goal(3.0)).unwrap();
+        planner.set_goal(goal(4.0)).unwrap();
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::Preempted));
+        planner.clear_goal();
+        assert_eq!(planner.get_status().last_outcome, Some(NavigationOutcome::Cleared));
+
+        // Aborted on timeout after 0.6 s of scans against a 0.5 s limit
+        let mut planner = NavigationPlanner::new(&NavigationConfig {
+            goal_timeout: Some(0.5),
+            ..Default::default()
+        });
+        let mut timed_scan = open_scan.clone();
+        timed_scan.laser_scan.scan_time = 0.2;
+        planner.set_goal(goal(3.0)).unwrap();
+        for _ in 0..2 {
+            planner.plan(&timed_scan, &[], at(0.0)).unwrap();
+        }
+        assert_eq!(planner.last_outcome(), None);
+        planner.push_goal(goal(4.0)).unwrap();
+        planner.plan(&timed_scan, &[], at(0.0)).unwrap();
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::AbortedTimeout));
+
+        // The aborted mission's queued goals are dropped, so a new one starts right away
+        assert_eq!(planner.get_status().goals_remaining, 0);
+        planner.push_goal(goal(5.0)).unwrap();
+        assert_eq!(planner.get_status().goals_remaining, 1);
+        let path = planner.plan(&timed_scan, &[], at(0.0)).unwrap();
+        assert!((path.distance_to_goal().unwrap() - 5.0).abs() < 1e-4);
+
+        // A stop that clears leaves the goal to be resumed
+        let mut planner = NavigationPlanner::new(&NavigationConfig {
+            emergency_stop_distance: 0.4,
+            unsafe_abort_timeout: 0.5,
+            latch_emergency_stop: false,
+            ..Default::default()
+        });
+        planner.set_goal(goal(3.0)).unwrap();
+        let mut close_wall = sensor_data(-0.3, 0.02, vec![0.35; 30]);
+        close_wall.laser_scan.scan_time = 0.2;
+        for _ in 0..2 {
+            assert!(matches!(planner.plan(&close_wall, &[], at(0.0)), Err(NavigationError::EmergencyStop)));
+        }
+        assert_eq!(planner.last_outcome(), None);
+        assert!(planner.plan(&timed_scan, &[], at(0.0)).is_ok());
+        assert!(planner.get_status().has_goal);
+
+        // Aborted as unsafe once an emergency stop holds for 0.6 s against a 0.5 s limit
+        for _ in 0..2 {
+            assert!(planner.plan(&close_wall, &[], at(0.0)).is_err());
+        }
+        assert_eq!(planner.last_outcome(), None);
+        assert!(planner.plan(&close_wall, &[], at(0.0)).is_err());
+        assert_eq!(planner.last_outcome(), Some(NavigationOutcome::AbortedUnsafe));
+        assert!(!planner.get_status().has_goal);
+    }
+
+    // A likely-noise obstacle below the confidence threshold doesn't block; a certain one does
+    #[test]
+    fn test_low_confidence_obstacle_does_not_block() {
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        let plan_past = |confidence: f32| {
+            let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+            planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+            planner.add_external_obstacle(Obstacle { confidence, ..obstacle_at(0.28, 0.0) });
+            planner.plan(&open_scan, &[], None)
+        };
+
+        let noise = plan_past(0.2).unwrap();
+        assert_eq!(noise.overall_safety(), 1.0);
+        assert!(matches!(plan_past(1.0), Err(NavigationError::UnsafePath(_))));
+
+        // Above the threshold lower confidence never lets the robot through
+        assert!(matches!(plan_past(0.5), Err(NavigationError::UnsafePath(_))));
+    }
+
+    // Lower confidence widens the margin kept from an obstacle beside the path
+    #[test]
+    fn test_uncertain_obstacle_widens_margin() {
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        let safety_beside = |confidence: f32| {
+            let mut planner = NavigationPlanner::new(&NavigationConfig::default());
+            planner.set_goal(Pose2D { x: 1.5, y: 0.0, theta: 0.0 }).unwrap();
+            planner.add_external_obstacle(Obstacle { confidence, ..obstacle_at(0.75, 0.5) });
+            planner.plan(&open_scan, &[], None).unwrap().overall_safety()
+        };
+
+        // Distance from the start against a margin of twice the 0.5 m safety distance,
+        // half as wide again at half confidence
+        let distance = 0.75f32.hypot(0.5);
+        let certain = safety_beside(1.0);
+        assert!((certain - distance).abs() < 1e-4);
+        let uncertain = safety_beside(0.5);
+        assert!(uncertain < certain);
+        assert!((uncertain - distance / 1.5).abs() < 1e-4);
+    }
+
+    // A goal next to a remembered node follows the remembered route instead of a straight line
+    #[test]
+    fn test_goal_near_node_uses_remembered_route() {
+        let mut memory = Memory::new();
+        let start = memory.add_map_node(Pose { x: 0.0, y: 0.0, theta: 0.0 }, vec![]);
+        let corner = memory.add_map_node(Pose { x: 2.0, y: 2.0, theta: 0.0 }, vec!["doorway".to_string()]);
+        let end = memory.add_map_node(Pose { x: 4.0, y: 0.0, theta: 0.0 }, vec![]);
+        assert!(memory.add_edge(start, corner));
+        assert!(memory.add_edge(corner, end));
+        assert_eq!(memory.find_route(start, end), Some(vec![start, corner, end]));
+
+        let config = NavigationConfig { planning_horizon: 10.0, ..Default::default() };
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+        let goal = Pose2D { x: 4.3, y: 0.2, theta: 0.0 };
+        let waypoints = |planner: &mut NavigationPlanner| -> Vec<(f32, f32)> {
+            planner.set_goal(goal).unwrap();
+            let path = planner.plan(&open_scan, &[], None).unwrap();
+            path.waypoints().iter().map(|p| (p.x, p.y)).collect()
+        };
+
+        // Without memory the planner heads straight for the goal
+        let mut planner = NavigationPlanner::new(&config);
+        assert_eq!(waypoints(&mut planner), vec![(0.0, 0.0), (4.3, 0.2)]);
+
+        // With memory it goes through the remembered nodes
+        planner.set_memory(&memory);
+        assert_eq!(waypoints(&mut planner), vec![(0.0, 0.0), (2.0, 2.0), (4.0, 0.0), (4.3, 0.2)]);
+
+        // A goal away from every node is planned directly
+        planner.set_goal(Pose2D { x: 3.0, y: -3.0, theta: 0.0 }).unwrap();
+        assert_eq!(planner.plan(&open_scan, &[], None).unwrap().segments().len(), 1);
+    }
+
+    // The top-level config carries the navigation module's own config straight to the planner
+    #[test]
+    fn test_eos_config_builds_planner() {
+        let config = eos::EosConfig::default();
+        let mut planner = NavigationPlanner::new(&config.navigation_config);
+        assert!(planner.set_goal(Pose2D { x: 1.0, y: 0.0, theta: 0.0 }).is_ok());
+
+        // Basic limits from older configs map onto the full config
+        let legacy = eos::NavigationConfig {
+            max_velocity: 0.8,
+            max_acceleration: 0.4,
+            safety_distance: 0.6,
+            goal_tolerance: 0.2,
+        };
+        let converted = NavigationConfig::from(legacy);
+        let defaults = NavigationConfig::default();
+        assert_eq!(converted.max_linear_velocity, 0.8);
+        assert_eq!(converted.max_acceleration, 0.4);
+        assert_eq!(converted.safety_distance, 0.6);
+        assert_eq!(converted.goal_tolerance, 0.2);
+        assert_eq!(converted.max_angular_velocity, defaults.max_angular_velocity);
+        assert_eq!(converted.obstacle_inflation, defaults.obstacle_inflation);
+    }
+
+    // Scan obstacles are relative to the robot, so they block its path wherever it is in odometry
+    #[test]
+    fn test_scan_obstacles_follow_robot_pose() {
+        let config = NavigationConfig::default();
+        let mut ranges = vec![10.0; 30];
+        ranges[15] = 1.0; // Straight ahead
+        let scan = sensor_data(-1.5, 0.1, ranges);
+
+        // Facing +y from (5, 0), the return lies at (5, 1) in odometry, on the way to the goal
+        let pose = Pose2D { x: 5.0, y: 0.0, theta: std::f32::consts::FRAC_PI_2 };
+        let mut planner = NavigationPlanner::new(&config);
+        planner.set_goal(Pose2D { x: 5.0, y: 3.0, theta: 0.0 }).unwrap();
+        let path = planner.plan(&scan, &[], Some(pose)).unwrap();
+        assert!((path.segments()[0].end().x - 5.0).abs() > 0.1, "should steer off the straight line");
+        let obstacle = Pose2D { x: 5.0, y: 1.0, theta: 0.0 };
+        for segment in path.segments() {
+            assert!(segment_distance(obstacle, segment.start(), segment.end()) >= config.obstacle_inflation);
+        }
+    }
+
+    // A U-shaped wall opening toward the robot forces a detour around one of its arms
+    #[test]
+    fn test_astar_detours_around_u_wall() {
+        let config = NavigationConfig::default();
+        let goal = Pose2D { x: 3.0, y: 0.0, theta: 0.0 };
+        let open_scan = sensor_data(-1.5, 0.1, vec![10.0; 30]);
+
+        // Back wall across the straight line at x = 1.5, arms reaching back toward the robot
+        let mut wall: Vec<Obstacle> = (0..=20).map(|i| obstacle_at(1.5, -1.0 + i as f32 * 0.1)).collect();
+        for i in 0..10 {
+            let x = 0.5 + i as f32 * 0.1;
+            wall.push(obstacle_at(x, 1.0));
+            wall.push(obstacle_at(x, -1.0));
+        }
+        let walled_planner = |config: &NavigationConfig| {
+            let mut planner = NavigationPlanner::new(config);
+            planner.set_goal(goal).unwrap();
+            for obstacle in &wall {
+                planner.add_external_obstacle(obstacle.clone());
+            }
+            planner
+        };
+        let assert_clear = |path: &Path| {
+            // Every leg keeps the wall outside its inflation
+            for segment in path.segments() {
+                for obstacle in &wall {
+                    let clearance = segment_distance(obstacle.position, segment.start(), segment.end());
+                    assert!(clearance >= config.obstacle_inflation, "{:?} passes {:?}", segment, obstacle.position);
+                }
+            }
+        };
+
+        // The default horizon point (2, 0) lies in the wall's inflation; the route still
+        // leads out of the U and is only clipped to the horizon afterwards
+        let path = walled_planner(&config).plan(&open_scan, &[], None).unwrap();
+        assert!(path.segments().len() > 1);
+        assert!((path.total_length() - config.planning_horizon).abs() < 1e-3);
+        assert_clear(&path);
+
+        // With the whole route inside the horizon it ends at the goal
+        let far_sighted = NavigationConfig {
+            planning_horizon: 10.0,
+            ..Default::default()
+        };
+        let path = walled_planner(&far_sighted).plan(&open_scan, &[], None).unwrap();
+        let waypoints = path.waypoints();
+        let end = waypoints.last().unwrap();
+        assert!((end.x - goal.x).abs() < 1e-5 && (end.y - goal.y).abs() < 1e-5);
+        assert!(path.total_length() > 4.0, "should leave the U, got {}", path.total_length());
+        assert_clear(&path);
+
+        // A closed ring around the goal cuts it off entirely
+        let ring: Vec<Obstacle> = (0..63)
+            .map(|i| {
+                let angle = i as f32 * 0.1;
+                obstacle_at(goal.x + angle.cos(), goal.y + angle.sin())
+            })
+            .collect();
+        let mut enclosed = NavigationPlanner::new(&config);
+        enclosed.set_goal(goal).unwrap();
+        for obstacle in ring {
+            enclosed.add_external_obstacle(obstacle);
+        }
+        assert!(matches!(enclosed.plan(&open_scan, &[], None), Err(NavigationError::NoPathError(_))));
+    }
+}
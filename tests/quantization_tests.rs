@@ -0,0 +1,84 @@
+mod common;
+
+use eos::neural::{NeuralConfig, SNNEngine};
+use eos::ros_interface::SensorData;
+
+use common::sensor_data;
+
+/// Write a model with deterministic weights and return its path
+fn write_model(name: &str, config: &NeuralConfig) -> String {
+    let weights: Vec<Vec<f32>> = (0..config.input_size)
+        .map(|j| {
+            (0..config.output_size)
+                .map(|i| ((j * 7 + i * 13) as f32).sin() * 0.8)
+                .collect()
+        })
+        .collect();
+    let model = serde_json::json!({
+        "weights": weights,
+        "states": vec![0.0; config.input_size],
+        "thresholds": vec![config.spike_threshold; config.input_size],
+        "metadata": {
+            "name": name,
+            "version": "1.0",
+            "trained_on": "synthetic",
+            "accuracy": 0.0,
+        },
+    });
+
+    let path = std::env::temp_dir().join(format!("eos_{}.json", name));
+    std::fs::write(&path, model.to_string()).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The i8 model agrees with the float model on the same inputs
+    #[test]
+    fn test_quantized_output_matches_float() {
+        let config = NeuralConfig::default();
+        let model = write_model("quantized_model", &config);
+        let mut engine = SNNEngine::new(&config).unwrap();
+        engine.load_model(&model).unwrap();
+        engine.initialize().unwrap();
+        assert!(!engine.is_quantized());
+
+        let inputs: Vec<SensorData> = (0..5)
+            .map(|k| sensor_data(0.0, 0.0, (0..100).map(|j| 0.5 + ((j * (k + 3)) % 11) as f32 * 0.4).collect()))
+            .collect();
+        let float_outputs: Vec<Vec<f32>> = inputs.iter().map(|d| engine.process(d).unwrap()).collect();
+
+        engine.quantize_model().unwrap();
+        assert!(engine.is_quantized());
+
+        let mut differing = 0;
+        let mut total = 0;
+        for (data, expected) in inputs.iter().zip(&float_outputs) {
+            let output = engine.process(data).unwrap();
+            assert_eq!(output.len(), expected.len());
+            differing += output.iter().zip(expected).filter(|(a, b)| (*a - *b).abs() > 1e-6).count();
+            total += output.len();
+        }
+
+        // Spikes may only flip for activations within rounding error of the threshold
+        assert!(differing as f32 / total as f32 <= 0.05, "{} of {} outputs differ", differing, total);
+    }
+
+    // The config flag quantizes whatever model gets loaded
+    #[test]
+    fn test_quantized_config_quantizes_on_load() {
+        let config = NeuralConfig {
+            quantized: true,
+            ..Default::default()
+        };
+        let model = write_model("quantized_config", &config);
+        let mut engine = SNNEngine::new(&config).unwrap();
+
+        engine.initialize().unwrap();
+        assert!(engine.is_quantized());
+        engine.load_model(&model).unwrap();
+        assert!(engine.is_quantized());
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::Duration;
+
+use eos::ros_interface::{
+    odometry_message, planar_covariance, MotionCommand, Pose2D, QosDurability, QosReliability, RosError, Subscriber,
+    TfBuffer,
+};
+use eos::RosConfig;
+use r2r::{QosDurabilityPolicy, QosReliabilityPolicy};
+use r2r::geometry_msgs::msg::Twist;
+use r2r::geometry_msgs::msg::TransformStamped;
+use r2r::sensor_msgs::msg::Imu;
+use nalgebra::Matrix3;
+
+/// Planar transform placing `child` at (x, y, theta) in `parent`
+fn transform(parent: &str, child: &str, x: f64, y: f64, theta: f64) -> TransformStamped {
+    let mut transform = TransformStamped::default();
+    transform.header.frame_id = parent.to_string();
+    transform.child_frame_id = child.to_string();
+    transform.transform.translation.x = x;
+    transform.transform.translation.y = y;
+    transform.transform.rotation.z = (theta / 2.0).sin();
+    transform.transform.rotation.w = (theta / 2.0).cos();
+    transform
+}
+
+/// Whether two poses agree to within a millimeter / milliradian
+fn poses_close(a: &Pose2D, b: &Pose2D) -> bool {
+    (a.x - b.x).abs() < 1e-3 && (a.y - b.y).abs() < 1e-3 && (a.theta - b.theta).abs() < 1e-3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pose, covariance, twist and stamp all land in the right odometry fields
+    #[test]
+    fn test_odometry_message_fields() {
+        let pose = Pose2D { x: 1.5, y: -2.0, theta: std::f32::consts::FRAC_PI_2 };
+        let covariance = Matrix3::new(
+            0.04, 0.01, 0.0,
+            0.01, 0.09, 0.0,
+            0.0, 0.0, 0.02,
+        );
+        let velocity = MotionCommand { linear: 0.3, angular: -0.1 };
+
+        let odom = odometry_message("map", &pose, &covariance, &velocity, Duration::new(12, 500));
+
+        assert_eq!((odom.header.stamp.sec, odom.header.stamp.nanosec), (12, 500));
+        assert_eq!(odom.header.frame_id, "map");
+        assert_eq!(odom.child_frame_id, "base_link");
+
+        let position = &odom.pose.pose.position;
+        assert_eq!((position.x, position.y, position.z), (1.5, -2.0, 0.0));
+        let orientation = &odom.pose.pose.orientation;
+        assert!((orientation.z - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert!((orientation.w - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+
+        // x, y and yaw live at rows/columns 0, 1 and 5 of the 6x6 matrix
+        let cov = &odom.pose.covariance;
+        assert_eq!(cov.len(), 36);
+        assert_eq!((cov[0], cov[1], cov[6], cov[7], cov[35]), (0.04, 0.01, 0.01, 0.09, 0.02));
+        assert!(cov[14] > 1e3); // z is unobserved
+        assert_eq!(planar_covariance(cov), covariance);
+
+        assert!((odom.twist.twist.linear.x - 0.3).abs() < 1e-6);
+        assert!((odom.twist.twist.angular.z + 0.1).abs() < 1e-6);
+    }
+
+    // A command survives the round trip through a Twist
+    #[test]
+    fn test_motion_command_twist_round_trip() {
+        let command = MotionCommand { linear: 0.4, angular: -0.25 };
+
+        let twist = Twist::from(command);
+        assert_eq!((twist.linear.x, twist.linear.y, twist.linear.z), (0.4f32 as f64, 0.0, 0.0));
+        assert_eq!((twist.angular.x, twist.angular.y, twist.angular.z), (0.0, 0.0, -0.25));
+        assert_eq!(command.to_ros_message(), twist);
+
+        assert_eq!(MotionCommand::try_from(twist).unwrap(), command);
+    }
+
+    // Sideways or vertical motion can't be driven by a differential base
+    #[test]
+    fn test_twist_with_lateral_motion_rejected() {
+        let mut strafing = Twist::default();
+        strafing.linear.x = 0.3;
+        strafing.linear.y = 0.2;
+        assert!(matches!(MotionCommand::try_from(strafing), Err(RosError::ConversionError(_))));
+
+        let mut climbing = Twist::default();
+        climbing.linear.z = 1.0;
+        assert!(matches!(MotionCommand::try_from(climbing), Err(RosError::ConversionError(_))));
+
+        let mut diverging = Twist::default();
+        diverging.linear.x = f64::NAN;
+        assert!(MotionCommand::try_from(diverging).is_err());
+    }
+
+    // Non-finite values are rejected in the ignored axes too, and speeds too large for f32
+    #[test]
+    fn test_twist_with_non_finite_component_rejected() {
+        for set in [
+            |twist: &mut Twist| twist.linear.y = f64::NAN,
+            |twist: &mut Twist| twist.linear.z = f64::NAN,
+            |twist: &mut Twist| twist.angular.x = f64::NAN,
+            |twist: &mut Twist| twist.angular.y = f64::NAN,
+            |twist: &mut Twist| twist.angular.z = f64::INFINITY,
+            |twist: &mut Twist| twist.linear.x = 1e300,
+        ] {
+            let mut twist = Twist::default();
+            set(&mut twist);
+            assert!(matches!(MotionCommand::try_from(twist), Err(RosError::ConversionError(_))));
+        }
+    }
+
+    // A subscriber holds the latest message delivered to its callback, for any message type
+    #[test]
+    fn test_generic_subscriber_keeps_latest_message() {
+        let context = r2r::Context::create().unwrap();
+        let mut node = r2r::Node::create(context, "subscriber_test", "").unwrap();
+        let subscriber: Subscriber<Imu> = Subscriber::new(&mut node, "/imu", r2r::QosProfile::default()).unwrap();
+
+        // Nothing received yet
+        assert!(matches!(subscriber.get_latest(), Err(RosError::SubscribeError(_))));
+        assert!(subscriber.get_last_message_time().is_none());
+
+        let mut imu = Imu::default();
+        imu.angular_velocity.z = 0.25;
+        let before = std::time::SystemTime::now();
+        let mut callback = subscriber.callback();
+        callback(imu);
+
+        assert_eq!(subscriber.get_latest().unwrap().angular_velocity.z, 0.25);
+        assert!(subscriber.get_last_message_time().is_some_and(|time| time >= before));
+    }
+
+    // Reliability and durability read as strings from the config and map onto the r2r policies
+    #[test]
+    fn test_qos_policies_from_config() {
+        let config: RosConfig = serde_yaml::from_str(
+            "domain_id: 0\nnode_name: eos\nqos_depth: 5\nqos_reliability: reliable\nqos_durability: transient_local\n",
+        )
+        .unwrap();
+        assert_eq!(config.qos_reliability, QosReliability::Reliable);
+        assert_eq!(config.qos_durability, QosDurability::TransientLocal);
+        assert_eq!(QosReliabilityPolicy::from(config.qos_reliability), QosReliabilityPolicy::Reliable);
+        assert_eq!(QosDurabilityPolicy::from(config.qos_durability), QosDurabilityPolicy::TransientLocal);
+
+        // Older configs keep the previous best-effort, volatile behavior
+        let config: RosConfig = serde_yaml::from_str("domain_id: 0\nnode_name: eos\nqos_depth: 5\n").unwrap();
+        assert_eq!(QosReliabilityPolicy::from(config.qos_reliability), QosReliabilityPolicy::BestEffort);
+        assert_eq!(QosDurabilityPolicy::from(config.qos_durability), QosDurabilityPolicy::Volatile);
+        assert!(serde_yaml::to_string(&config).unwrap().contains("qos_reliability: best_effort"));
+
+        let typo = "domain_id: 0\nnode_name: eos\nqos_depth: 5\nqos_reliability: reliabel\n";
+        assert!(serde_yaml::from_str::<RosConfig>(typo).is_err());
+    }
+
+    // Lookups chain transforms through their common frame, in either direction
+    #[test]
+    fn test_tf_lookup_chains_transforms() {
+        let now = std::time::Instant::now();
+        let mut buffer = TfBuffer::new(Duration::from_secs(1));
+        buffer.insert(&transform("map", "odom", 2.0, 1.0, std::f64::consts::FRAC_PI_2), false, now);
+        buffer.insert(&transform("odom", "base_link", 1.0, 0.0, 0.0), false, now);
+        buffer.insert(&transform("base_link", "laser", 0.2, 0.0, std::f64::consts::PI), true, now);
+
+        // Odometry's x axis points along the map's y axis
+        let base_in_map = buffer.lookup("map", "base_link", now).unwrap();
+        assert!(poses_close(&base_in_map, &Pose2D { x: 2.0, y: 2.0, theta: std::f32::consts::FRAC_PI_2 }));
+        let odom_in_map = buffer.lookup("map", "odom", now).unwrap();
+        let base_in_odom = Pose2D { x: 1.0, y: 0.0, theta: 0.0 };
+        assert!(poses_close(&odom_in_map.compose(&base_in_odom), &base_in_map));
+
+        // The reverse lookup is the inverse
+        let map_in_base = buffer.lookup("base_link", "map", now).unwrap();
+        assert!(poses_close(&base_in_map.compose(&map_in_base), &Pose2D { x: 0.0, y: 0.0, theta: 0.0 }));
+
+        // Sibling branches meet at their common ancestor
+        buffer.insert(&transform("odom", "dock", 1.0, 1.0, 0.0), false, now);
+        let dock_in_laser = buffer.lookup("laser", "dock", now).unwrap();
+        assert!(poses_close(&dock_in_laser, &Pose2D { x: 0.2, y: -1.0, theta: std::f32::consts::PI })
+            || poses_close(&dock_in_laser, &Pose2D { x: 0.2, y: -1.0, theta: -std::f32::consts::PI }));
+    }
+
+    // Missing frames, disconnected trees and stale transforms are reported, static ones never expire
+    #[test]
+    fn test_tf_lookup_errors() {
+        let now = std::time::Instant::now();
+        let mut buffer = TfBuffer::new(Duration::from_millis(500));
+        buffer.insert(&transform("map", "odom", 1.0, 0.0, 0.0), false, now);
+        buffer.insert(&transform("base_link", "laser", 0.2, 0.0, 0.0), true, now);
+
+        let error = buffer.lookup("map", "camera", now).unwrap_err();
+        assert!(matches!(&error, RosError::TransformError(msg) if msg.contains("unknown frame 'camera'")));
+        let error = buffer.lookup("map", "laser", now).unwrap_err();
+        assert!(matches!(&error, RosError::TransformError(msg) if msg.contains("not connected")));
+
+        let later = now + Duration::from_secs(2);
+        let error = buffer.lookup("map", "odom", later).unwrap_err();
+        assert!(matches!(&error, RosError::TransformError(msg) if msg.contains("expired")));
+        assert!(buffer.lookup("base_link", "laser", later).is_ok());
+
+        // A fresh transform replaces the stale one
+        buffer.insert(&transform("map", "odom", 1.5, 0.0, 0.0), false, later);
+        assert!(poses_close(&buffer.lookup("map", "odom", later).unwrap(), &Pose2D { x: 1.5, y: 0.0, theta: 0.0 }));
+        // A stale link above the common ancestor isn't used, so it doesn't fail the lookup
+        buffer.insert(&transform("odom", "base_link", 0.5, 0.0, 0.0), false, later);
+        let much_later = later + Duration::from_millis(400);
+        buffer.insert(&transform("odom", "base_link", 0.6, 0.0, 0.0), false, much_later);
+        let even_later = much_later + Duration::from_millis(300);
+        assert!(buffer.lookup("map", "base_link", even_later).is_err());
+        let laser_in_odom = buffer.lookup("odom", "laser", even_later).unwrap();
+        assert!(poses_close(&laser_in_odom, &Pose2D { x: 0.8, y: 0.0, theta: 0.0 }));
+    }
+}
@@ -0,0 +1,62 @@
+use eos::apps::eos_rover::rover_control::RoverControl;
+use eos::apps::eos_rover::rover_navigation::RoverNavigation;
+use eos::apps::eos_rover::{apply_slip, SlipLimiter};
+use eos::core::{estimate_slip, Localization, Memory};
+
+/// Rover navigation on a localizer built from a minimal config
+fn rover_navigation(name: &str) -> RoverNavigation {
+    let path = std::env::temp_dir().join(format!("eos_rover_{}.yaml", name));
+    std::fs::write(&path, "imu_topic: /imu\nlidar_topic: /scan\nsensor_noise: 0.1\nprocess_noise: 0.01\n").unwrap();
+    let ctx = r2r::Context::create().unwrap();
+    let mut node = r2r::Node::create(ctx, name, "").unwrap();
+    let localizer = Localization::new(&mut node, &path.to_string_lossy()).unwrap();
+    RoverNavigation::new(localizer, Memory::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wheels claim a hard turn the gyro doesn't see: high slip and a slower rover
+    #[test]
+    fn test_odom_imu_disagreement_reduces_speed() {
+        let slip = estimate_slip(0.8, 0.1);
+        assert!(slip > 0.8, "slip {}", slip);
+
+        let limiter = SlipLimiter::default();
+        let scale = limiter.speed_scale(slip);
+        assert!(scale < 0.5, "scale {}", scale);
+        assert!(scale >= limiter.min_speed_scale);
+    }
+
+    // One slip update slows the rover and makes localization grow its uncertainty faster
+    #[test]
+    fn test_slip_update_slows_rover_and_inflates_covariance() {
+        let mut control = RoverControl::new();
+        let mut slipping = rover_navigation("slipping");
+        let mut gripping = rover_navigation("gripping");
+
+        let slip = apply_slip(&mut control, &mut slipping, 0.8, 0.1);
+        assert_eq!(control.slip_estimate(), slip);
+        assert!(control.slip_speed_scale() < 0.5, "scale {}", control.slip_speed_scale());
+
+        let slipping = slipping.update_localization().unwrap();
+        let gripping = gripping.update_localization().unwrap();
+        assert_eq!(slipping.slip, slip);
+        assert!(slipping.covariance_trace > gripping.covariance_trace);
+
+        apply_slip(&mut control, &mut rover_navigation("recovered"), 0.5, 0.48);
+        assert_eq!(control.slip_speed_scale(), 1.0);
+    }
+
+    // Agreeing or near-zero yaw rates don't read as slip
+    #[test]
+    fn test_agreement_keeps_full_speed() {
+        let limiter = SlipLimiter::default();
+
+        assert!(estimate_slip(0.5, 0.48) < 0.05);
+        assert_eq!(limiter.speed_scale(estimate_slip(0.5, 0.48)), 1.0);
+        assert!(estimate_slip(0.005, -0.005) < limiter.slip_threshold);
+        assert_eq!(estimate_slip(0.0, 0.0), 0.0);
+    }
+}
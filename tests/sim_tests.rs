@@ -0,0 +1,45 @@
+use eos::core::{LaserSimConfig, LaserSimulator, OccupancyGrid, Pose};
+
+/// Centered 10 m grid with a wall spanning x = 2.0 .. 2.2
+fn grid_with_wall_ahead() -> OccupancyGrid {
+    let mut grid = OccupancyGrid::centered(100, 0.1, (0.0, 0.0));
+    for row in 0..100 {
+        for col in 70..72 {
+            grid.set_cell(col, row, 1);
+        }
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Forward beams hit the wall at ~2 m while rear beams see open space
+    #[test]
+    fn test_wall_ahead_gives_short_forward_ranges() {
+        let mut sim = LaserSimulator::new(LaserSimConfig::default());
+        let pose = Pose { x: 0.0, y: 0.0, theta: 0.0 };
+
+        let scan = sim.simulate(&grid_with_wall_ahead(), &pose);
+
+        let forward = ((0.0 - scan.angle_min) / scan.angle_increment).round() as usize;
+        assert!((scan.ranges[forward] - 2.0).abs() <= 0.05);
+        assert_eq!(scan.ranges[0], scan.range_max); // Straight behind: nothing to hit
+    }
+
+    // Noise perturbs ranges but is reproducible for a fixed seed
+    #[test]
+    fn test_noise_is_seeded() {
+        let config = LaserSimConfig { noise_stddev: 0.02, seed: 7, ..Default::default() };
+        let pose = Pose { x: 0.0, y: 0.0, theta: 0.0 };
+        let grid = grid_with_wall_ahead();
+
+        let first = LaserSimulator::new(config.clone()).simulate(&grid, &pose);
+        let second = LaserSimulator::new(config).simulate(&grid, &pose);
+
+        assert_eq!(first.ranges, second.ranges);
+        let forward = ((0.0 - first.angle_min) / first.angle_increment).round() as usize;
+        assert!((first.ranges[forward] - 2.0).abs() < 0.2);
+    }
+}
@@ -0,0 +1,446 @@
+use std::time::{Duration, Instant};
+
+use eos::core::{
+    BehaviorTree, BehaviorTreeError, CoreState, Mode, OccupancyGrid, Pose, PoseConfidence, Snapshot, StateConfig, StateEvent,
+};
+use nalgebra::{Matrix3, Vector3};
+
+/// Fully charged battery level
+const FULL: f64 = 1.0;
+
+/// Pose confidence whose scalar confidence is the given value
+fn confidence(value: f64) -> PoseConfidence {
+    confidence_at(value, Pose { x: 0.0, y: 0.0, theta: 0.0 })
+}
+
+/// Pose confidence at the given pose whose scalar confidence is the given value
+fn confidence_at(value: f64, pose: Pose) -> PoseConfidence {
+    // Invert confidence = 1 / (1 + position variance / 1 m²), split over x and y with a certain heading
+    let variance = (1.0 - value) / value / 2.0;
+    PoseConfidence {
+        pose,
+        covariance: Matrix3::from_diagonal(&Vector3::new(variance, variance, 0.0)),
+    }
+}
+
+/// Snapshot of an unexplored grid with no obstacles
+fn empty_snapshot() -> Snapshot {
+    Snapshot::new(OccupancyGrid::new(10, 10, 0.1, (0.0, 0.0)), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A brief confidence dip is ignored; a sustained one switches to Lost after the dwell
+    #[test]
+    fn test_dwell_time_suppresses_brief_dip() {
+        let mut state = CoreState::with_config(StateConfig {
+            min_mode_dwell: Duration::from_secs(1),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        // Brief dip below the threshold
+        state.update_at(&confidence(0.9), &snapshot, FULL, at(0)).unwrap();
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(100)).unwrap();
+        state.update_at(&confidence(0.9), &snapshot, FULL, at(200)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+
+        // Sustained dip: held until the dwell time has passed
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(300)).unwrap();
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(800)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(1300)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+    }
+
+    // Between the enter and exit thresholds a Lost robot stays Lost
+    #[test]
+    fn test_hysteresis_band_holds_mode() {
+        let mut state = CoreState::new();
+        let snapshot = empty_snapshot();
+
+        state.update(&confidence(0.4), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+
+        state.update(&confidence(0.6), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+
+        state.update(&confidence(0.85), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Recovering);
+    }
+
+    // Fast-growing uncertainty sends a navigating robot to relocalize
+    #[test]
+    fn test_high_drift_rate_triggers_recovering() {
+        let mut state = CoreState::with_config(StateConfig {
+            max_drift_rate: 0.5,
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+
+        // Only navigation is interrupted
+        state.report_drift_rate(2.0);
+        assert_eq!(state.get_mode(), Mode::Idle);
+
+        for value in [0.4, 0.85, 0.95] {
+            state.update(&confidence(value), &snapshot, FULL).unwrap();
+        }
+        assert_eq!(state.get_mode(), Mode::Navigating);
+
+        state.report_drift_rate(0.2);
+        assert_eq!(state.get_mode(), Mode::Navigating);
+        state.report_drift_rate(2.0);
+        assert_eq!(state.get_mode(), Mode::Recovering);
+    }
+
+    // Panic survives confidence changes and overrides until an operator resets it to Idle
+    #[test]
+    fn test_panic_latches_until_reset() {
+        let mut state = CoreState::new();
+        let snapshot = empty_snapshot();
+        assert!(!state.reset_panic());
+
+        state.enter_panic("test");
+        for value in [0.4, 0.85, 0.95] {
+            state.update(&confidence(value), &snapshot, FULL).unwrap();
+        }
+        state.report_stuck();
+        state.emergency_stop();
+        assert_eq!(state.get_mode(), Mode::Panic);
+
+        assert!(state.reset_panic());
+        assert_eq!(state.get_mode(), Mode::Idle);
+    }
+
+    // Obstacle proximity is measured from the robot in the map frame, not from the grid corner
+    #[test]
+    fn test_obstacle_distance_uses_grid_origin_and_pose() {
+        // 2 m grid anchored at (4, 4) with one occupied cell centered on (5.15, 5.05)
+        let mut grid = OccupancyGrid::new(20, 20, 0.1, (4.0, 4.0));
+        let (col, row) = grid.world_to_cell(5.15, 5.05).unwrap();
+        grid.set_cell(col, row, 1);
+        let snapshot = Snapshot::new(grid, vec![]);
+        let navigating = || {
+            let mut state = CoreState::new();
+            for value in [0.4, 0.85, 0.95] {
+                state.update(&confidence(value), &empty_snapshot(), FULL).unwrap();
+            }
+            assert_eq!(state.get_mode(), Mode::Navigating);
+            state
+        };
+
+        // Cell indices times resolution would put the obstacle 1.8 m from the origin
+        let mut far = navigating();
+        far.update(&confidence_at(0.95, Pose { x: 0.0, y: 0.0, theta: 0.0 }), &snapshot, FULL).unwrap();
+        assert_eq!(far.get_mode(), Mode::Navigating);
+
+        let mut near = navigating();
+        near.update(&confidence_at(0.95, Pose { x: 5.0, y: 5.0, theta: 0.0 }), &snapshot, FULL).unwrap();
+        assert_eq!(near.get_mode(), Mode::Recovering);
+    }
+
+    // The standard tree switches modes exactly like the built-in FSM
+    #[test]
+    fn test_standard_behavior_tree_matches_fsm() {
+        let config = StateConfig::default();
+        let mut fsm = CoreState::with_config(config.clone());
+        let mut tree = CoreState::with_behavior_tree(config.clone(), BehaviorTree::standard(&config));
+        let snapshot = empty_snapshot();
+
+        let steps = [(0.9, FULL), (0.4, FULL), (0.6, FULL), (0.85, FULL), (0.88, FULL), (0.95, FULL), (0.95, 0.1), (0.95, 0.95), (0.7, FULL), (0.3, FULL)];
+        for (value, battery) in steps {
+            fsm.update(&confidence(value), &snapshot, battery).unwrap();
+            tree.update(&confidence(value), &snapshot, battery).unwrap();
+            assert_eq!(tree.get_mode(), fsm.get_mode(), "after confidence {} at battery {}", value, battery);
+        }
+        assert_eq!(tree.get_mode(), Mode::Lost);
+
+        // Overrides and the Panic latch still apply
+        tree.enter_panic("test");
+        tree.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(tree.get_mode(), Mode::Panic);
+    }
+
+    // With a dwell time, the standard tree still stops for an obstacle at once but holds confidence changes
+    #[test]
+    fn test_behavior_tree_safety_action_skips_dwell() {
+        let config = StateConfig {
+            min_mode_dwell: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let mut state = CoreState::with_behavior_tree(config.clone(), BehaviorTree::standard(&config));
+        let mut grid = OccupancyGrid::new(20, 20, 0.1, (4.0, 4.0));
+        let (col, row) = grid.world_to_cell(5.15, 5.05).unwrap();
+        grid.set_cell(col, row, 1);
+        let obstacle = Snapshot::new(grid, vec![]);
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        state.override_mode(Mode::Navigating);
+        state.update_at(&confidence(0.4), &empty_snapshot(), FULL, at(0)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Navigating);
+
+        let near = confidence_at(0.95, Pose { x: 5.0, y: 5.0, theta: 0.0 });
+        state.update_at(&near, &obstacle, FULL, at(100)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Recovering);
+    }
+
+    // A custom tree can time out of Lost into Mapping, which the FSM can't express
+    #[test]
+    fn test_custom_behavior_tree_with_timeout() {
+        let tree = BehaviorTree::builder()
+            .selector()
+                .sequence()
+                    .condition("lost too long", |c| c.mode == Mode::Lost && c.time_in_mode >= Duration::from_secs(5))
+                    .action(Mode::Mapping)
+                .end()
+                .sequence()
+                    .condition("low confidence", |c| c.confidence < 0.5 && c.mode != Mode::Mapping)
+                    .action(Mode::Lost)
+                .end()
+            .end()
+            .build()
+            .unwrap();
+        let mut state = CoreState::with_behavior_tree(StateConfig::default(), tree);
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(0)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(3)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(5)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Mapping);
+
+        // No leaf runs at good confidence, so the mode holds
+        state.update_at(&confidence(0.95), &snapshot, FULL, at(6)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Mapping);
+    }
+
+    // Malformed trees are rejected when built
+    #[test]
+    fn test_behavior_tree_builder_errors() {
+        assert_eq!(BehaviorTree::builder().build().unwrap_err(), BehaviorTreeError::Empty);
+        assert_eq!(BehaviorTree::builder().selector().action(Mode::Idle).build().unwrap_err(), BehaviorTreeError::Unclosed(1));
+        assert_eq!(BehaviorTree::builder().action(Mode::Idle).end().build().unwrap_err(), BehaviorTreeError::UnmatchedEnd);
+        assert_eq!(BehaviorTree::builder().sequence().end().build().unwrap_err(), BehaviorTreeError::EmptyComposite("sequence"));
+        assert_eq!(
+            BehaviorTree::builder().action(Mode::Idle).action(Mode::Mapping).build().unwrap_err(),
+            BehaviorTreeError::MultipleRoots(2)
+        );
+    }
+
+    // A low battery sends any active mode to Charging, which ends in Idle once charged
+    #[test]
+    fn test_low_battery_enters_charging() {
+        let mut state = CoreState::new();
+        let snapshot = empty_snapshot();
+        for value in [0.4, 0.85, 0.95] {
+            state.update(&confidence(value), &snapshot, FULL).unwrap();
+        }
+        assert_eq!(state.get_mode(), Mode::Navigating);
+
+        state.update(&confidence(0.95), &snapshot, 0.25).unwrap();
+        assert_eq!(state.get_mode(), Mode::Navigating);
+        state.update(&confidence(0.95), &snapshot, 0.15).unwrap();
+        assert_eq!(state.get_mode(), Mode::Charging);
+
+        // Confidence doesn't matter while docked, and partial charge isn't enough
+        state.update(&confidence(0.3), &snapshot, 0.5).unwrap();
+        assert_eq!(state.get_mode(), Mode::Charging);
+        state.update(&confidence(0.95), &snapshot, 0.92).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+
+        // Lost robots charge too
+        state.update(&confidence(0.4), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        state.update(&confidence(0.4), &snapshot, 0.1).unwrap();
+        assert_eq!(state.get_mode(), Mode::Charging);
+    }
+
+    // An idle robot without a goal starts exploring after the timeout; a goal ends it
+    #[test]
+    fn test_idle_without_goal_starts_exploring() {
+        let mut state = CoreState::with_config(StateConfig {
+            explore_timeout: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+
+        // A goal keeps the robot from exploring however long it idles
+        state.set_goal_active(true);
+        std::thread::sleep(Duration::from_millis(60));
+        state.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+
+        // The timeout counts from when the goal was cleared
+        state.set_goal_active(false);
+        state.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+        std::thread::sleep(Duration::from_millis(60));
+        state.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Exploring);
+
+        state.set_goal_active(true);
+        assert_eq!(state.get_mode(), Mode::Navigating);
+    }
+
+    // Exploring loses confidence like Navigating, and emergency stop forces Idle from the new modes
+    #[test]
+    fn test_new_modes_lost_and_emergency_stop() {
+        let mut state = CoreState::with_config(StateConfig {
+            explore_timeout: Duration::ZERO,
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+
+        state.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Exploring);
+        state.update(&confidence(0.4), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+
+        state.update(&confidence(0.4), &snapshot, 0.1).unwrap();
+        assert_eq!(state.get_mode(), Mode::Charging);
+        state.emergency_stop();
+        assert_eq!(state.get_mode(), Mode::Idle);
+
+        state.update(&confidence(0.95), &snapshot, FULL).unwrap();
+        assert_eq!(state.get_mode(), Mode::Exploring);
+        state.emergency_stop();
+        assert_eq!(state.get_mode(), Mode::Idle);
+    }
+
+    // Events queued between updates are all applied, in order, by the next update
+    #[test]
+    fn test_queued_events_processed_in_order() {
+        let mut state = CoreState::new();
+        let snapshot = empty_snapshot();
+        for value in [0.4, 0.85, 0.95] {
+            state.update(&confidence(value), &snapshot, FULL).unwrap();
+        }
+        assert_eq!(state.get_mode(), Mode::Navigating);
+        let before = state.recent_transitions().len();
+
+        // Queued from another module's thread through a cloned handle
+        let sender = state.event_sender();
+        let planner = sender.clone();
+        std::thread::spawn(move || {
+            planner.send(StateEvent::GoalReached).unwrap();
+            planner.send(StateEvent::LocalizationLost).unwrap();
+        })
+        .join()
+        .unwrap();
+        sender.send(StateEvent::Stuck).unwrap();
+        sender.send(StateEvent::ObstacleDetected { distance: 0.1 }).unwrap();
+        assert_eq!(state.get_mode(), Mode::Navigating); // Nothing applied before the update
+
+        state.update(&confidence(0.85), &snapshot, FULL).unwrap();
+        assert_eq!(
+            state.recent_transitions()[before..],
+            [
+                (Mode::Navigating, Mode::Idle),
+                (Mode::Idle, Mode::Lost),
+                (Mode::Lost, Mode::Recovering),
+            ]
+        );
+        assert_eq!(state.get_mode(), Mode::Recovering);
+    }
+
+    // Recovering that never reaches Navigating gives up to Idle after the timeout
+    #[test]
+    fn test_recovery_timeout_returns_to_idle() {
+        let mut state = CoreState::with_config(StateConfig {
+            recovery_timeout: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(0)).unwrap();
+        state.update_at(&confidence(0.85), &snapshot, FULL, at(2)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Recovering);
+
+        // Entered at 2 s, so still recovering just before 12 s
+        state.update_at(&confidence(0.85), &snapshot, FULL, at(11)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Recovering);
+        state.update_at(&confidence(0.85), &snapshot, FULL, at(12)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+    }
+
+    // Staying Lost past the timeout triggers an emergency stop
+    #[test]
+    fn test_lost_timeout_triggers_emergency_stop() {
+        let mut state = CoreState::with_config(StateConfig {
+            lost_timeout: Duration::from_secs(20),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        state.update_at(&confidence(0.4), &snapshot, FULL, at(0)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        state.update_at(&confidence(0.6), &snapshot, FULL, at(19)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        state.update_at(&confidence(0.6), &snapshot, FULL, at(20)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Idle);
+        assert_eq!(state.recent_transitions().last(), Some(&(Mode::Lost, Mode::Idle)));
+    }
+
+    // The stop after the Lost timeout holds while confidence stays low instead of cycling back to Lost
+    #[test]
+    fn test_lost_timeout_stop_latches_until_relocalized() {
+        let mut state = CoreState::with_config(StateConfig {
+            lost_timeout: Duration::from_secs(20),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        state.update_at(&confidence(0.2), &snapshot, FULL, at(0)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+        for secs in 20..40 {
+            state.update_at(&confidence(0.2), &snapshot, FULL, at(secs)).unwrap();
+            assert_eq!(state.get_mode(), Mode::Idle);
+            assert!(state.lost_stop_latched());
+        }
+        assert_eq!(
+            state.recent_transitions(),
+            vec![(Mode::Idle, Mode::Lost), (Mode::Lost, Mode::Idle)]
+        );
+
+        // Good confidence releases the stop, so losing it again is reported as Lost
+        state.update_at(&confidence(0.9), &snapshot, FULL, at(40)).unwrap();
+        assert!(!state.lost_stop_latched());
+        state.update_at(&confidence(0.2), &snapshot, FULL, at(41)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Lost);
+    }
+
+    // An operator override releases the stop, and the timeout still lets a low battery through
+    #[test]
+    fn test_lost_timeout_stop_released_by_operator() {
+        let mut state = CoreState::with_config(StateConfig {
+            lost_timeout: Duration::from_secs(20),
+            ..Default::default()
+        });
+        let snapshot = empty_snapshot();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+
+        state.update_at(&confidence(0.2), &snapshot, FULL, at(0)).unwrap();
+        state.update_at(&confidence(0.2), &snapshot, 0.05, at(20)).unwrap();
+        assert_eq!(state.get_mode(), Mode::Charging);
+        assert!(state.lost_stop_latched());
+
+        state.override_mode(Mode::Navigating);
+        assert!(!state.lost_stop_latched());
+        assert_eq!(state.get_mode(), Mode::Navigating);
+    }
+}
@@ -0,0 +1,61 @@
+use eos::core::{BoundedHistory, CycleTimer};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Mean and max only look at the most recent window
+    #[test]
+    fn test_window_mean_and_max() {
+        let mut history = BoundedHistory::new(10);
+        for value in [9.0f32, 1.0, 2.0, 3.0, 6.0] {
+            history.push(value);
+        }
+
+        assert_eq!(history.mean(3), Some((2.0 + 3.0 + 6.0) / 3.0));
+        assert_eq!(history.max(3), Some(6.0));
+        assert_eq!(history.max(5), Some(9.0));
+        assert_eq!(history.mean(50), Some(21.0 / 5.0)); // Window larger than the history
+        assert_eq!(history.mean_of(2, |v| (*v as f64) * 2.0), Some(9.0));
+
+        let empty: BoundedHistory<f32> = BoundedHistory::new(4);
+        assert_eq!(empty.mean(3), None);
+        assert_eq!(empty.max(3), None);
+    }
+
+    // A full history evicts its oldest entry
+    #[test]
+    fn test_capacity_eviction() {
+        let mut history = BoundedHistory::new(3);
+        for value in 1..=5u32 {
+            history.push(value);
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.capacity(), 3);
+        assert_eq!(history.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(history.recent(2).copied().collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(history.last(), Some(&5));
+
+        history.clear();
+        assert!(history.is_empty());
+    }
+
+    // Update steps follow the timestamps, with a cap after a stall
+    #[test]
+    fn test_cycle_timer_measures_and_caps_dt() {
+        let mut timer = CycleTimer::new(0.1, 0.5);
+        assert_eq!(timer.tick(10.0), 0.1); // No previous update yet
+        assert!((timer.tick(10.2) - 0.2).abs() < 1e-9);
+        assert_eq!(timer.tick(13.0), 0.5); // Stalled for 2.8 s
+        assert_eq!(timer.tick(12.0), 0.1); // Clock went backwards
+
+        let mut steady = CycleTimer::new(0.1, 0.5);
+        for step in 0..30 {
+            steady.tick(step as f64 * 0.05);
+        }
+        let rate = steady.rate();
+        assert!((rate.last_dt - 0.05).abs() < 1e-9);
+        assert!((rate.hz - 20.0).abs() < 1e-6);
+    }
+}